@@ -0,0 +1,5 @@
+-- Store the inputs of the MEMO_HASH commitment recorded on each sharing transaction
+-- Rows created before this migration keep NULL values and are verified with the legacy memo hash
+ALTER TABLE process_shares ADD COLUMN memo_salt TEXT;
+ALTER TABLE process_shares ADD COLUMN content_digest TEXT;
+ALTER TABLE process_shares ADD COLUMN memo_commitment TEXT;
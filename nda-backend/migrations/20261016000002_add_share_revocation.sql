@@ -0,0 +1,6 @@
+-- Track revocation of process shares
+-- A revoked share keeps its row for auditing but no longer grants access
+ALTER TABLE process_shares ADD COLUMN revoked_at TEXT;
+ALTER TABLE process_shares ADD COLUMN revoked_by TEXT;
+ALTER TABLE process_shares ADD COLUMN revocation_reason TEXT;
+ALTER TABLE process_shares ADD COLUMN revocation_transaction_hash TEXT;
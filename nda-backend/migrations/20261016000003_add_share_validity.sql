@@ -0,0 +1,16 @@
+-- Optional access window of process shares
+-- expired_at is set by the share expiry task once expires_at has passed
+ALTER TABLE process_shares ADD COLUMN valid_from TEXT;
+ALTER TABLE process_shares ADD COLUMN expires_at TEXT;
+ALTER TABLE process_shares ADD COLUMN expired_at TEXT;
+
+-- Events reported to users, such as expired shares
+CREATE TABLE IF NOT EXISTS notifications (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    process_id TEXT NOT NULL,
+    share_id TEXT,
+    message TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
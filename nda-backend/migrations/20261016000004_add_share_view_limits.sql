@@ -0,0 +1,6 @@
+-- Optional number of times a partner may view a shared process
+ALTER TABLE process_shares ADD COLUMN max_accesses INTEGER;
+
+-- Link each access to the share that granted it, so view limits can be counted per share
+ALTER TABLE process_accesses ADD COLUMN share_id TEXT;
+CREATE INDEX IF NOT EXISTS idx_process_accesses_share ON process_accesses (share_id, partner_id);
@@ -0,0 +1,3 @@
+-- Envelope encryption: encryption_key holds the data key wrapped by a key-encryption key
+-- NULL key_id marks legacy rows with a plaintext key; the server wraps them at startup
+ALTER TABLE processes ADD COLUMN key_id TEXT;
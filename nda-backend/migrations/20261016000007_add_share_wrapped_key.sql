@@ -0,0 +1,3 @@
+-- Zero-knowledge shares: data key sealed to the partner's Stellar key (X25519 sealed box)
+-- NULL for regular shares, whose content is decrypted by the server on access
+ALTER TABLE process_shares ADD COLUMN wrapped_key TEXT;
@@ -0,0 +1,15 @@
+-- Encrypted process attachments; file contents are stored in ATTACHMENTS_DIR as <id>.enc
+CREATE TABLE IF NOT EXISTS process_attachments (
+    id TEXT PRIMARY KEY,
+    process_id TEXT NOT NULL,
+    filename TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    size_bytes INTEGER NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (process_id) REFERENCES processes (id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_process_attachments_process ON process_attachments (process_id);
+
+-- Attachment downloads are logged like text access, with the downloaded file
+ALTER TABLE process_accesses ADD COLUMN attachment_id TEXT;
@@ -0,0 +1,7 @@
+-- Process content is encrypted with associated data binding it to its row:
+-- process ID, client ID and content version (see crypto::process_aad).
+ALTER TABLE processes ADD COLUMN content_version INTEGER NOT NULL DEFAULT 1;
+
+-- Binding existing rows requires the key-encryption keys, so content is
+-- re-encrypted in place at startup by tasks::reencrypt_processes before the
+-- server accepts requests.
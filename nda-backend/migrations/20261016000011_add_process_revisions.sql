@@ -0,0 +1,21 @@
+-- Every version of a process's content is kept as a revision; the current
+-- one is mirrored in processes.encrypted_content / processes.content_version.
+CREATE TABLE IF NOT EXISTS process_revisions (
+    id TEXT PRIMARY KEY,
+    process_id TEXT NOT NULL,
+    version INTEGER NOT NULL,
+    encrypted_content TEXT NOT NULL,
+    author_id TEXT NOT NULL,
+    change_note TEXT,
+    created_at TEXT NOT NULL,
+    UNIQUE (process_id, version),
+    FOREIGN KEY (process_id) REFERENCES processes (id)
+);
+
+-- Existing content becomes the first revision, authored by the owner
+INSERT OR IGNORE INTO process_revisions (id, process_id, version, encrypted_content, author_id, change_note, created_at)
+SELECT lower(hex(randomblob(16))), id, content_version, encrypted_content, client_id, NULL, created_at
+FROM processes;
+
+-- Revision each partner access saw (NULL for accesses recorded before revisions)
+ALTER TABLE process_accesses ADD COLUMN content_version INTEGER;
@@ -0,0 +1,25 @@
+-- processes.status follows a lifecycle: draft, active, suspended, closed, archived.
+-- Free-form statuses from before the lifecycle are mapped onto it.
+UPDATE processes SET status = 'closed' WHERE status = 'completed';
+UPDATE processes SET status = 'active'
+WHERE status NOT IN ('draft', 'active', 'suspended', 'closed', 'archived');
+
+-- Every status change, with the user who made it and why
+CREATE TABLE IF NOT EXISTS process_transitions (
+    id TEXT PRIMARY KEY,
+    process_id TEXT NOT NULL,
+    from_status TEXT,
+    to_status TEXT NOT NULL,
+    actor_id TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (process_id) REFERENCES processes (id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_process_transitions_process ON process_transitions (process_id);
+
+-- Existing processes get their creation as first transition, made by the owner
+INSERT INTO process_transitions (id, process_id, from_status, to_status, actor_id, reason, created_at)
+SELECT lower(hex(randomblob(16))), p.id, NULL, p.status, p.client_id, 'Process created', p.created_at
+FROM processes p
+WHERE NOT EXISTS (SELECT 1 FROM process_transitions t WHERE t.process_id = p.id);
@@ -0,0 +1,16 @@
+-- Partners sign a canonical digest of a process's terms (ed25519, Stellar key)
+-- before its content is released; see src/terms.rs for the digest format.
+CREATE TABLE IF NOT EXISTS terms_signatures (
+    id TEXT PRIMARY KEY,
+    process_id TEXT NOT NULL,
+    partner_id TEXT NOT NULL,
+    partner_public_key TEXT NOT NULL,
+    content_version INTEGER NOT NULL,
+    terms_digest TEXT NOT NULL,
+    signature TEXT NOT NULL,
+    signed_at TEXT NOT NULL,
+    anchor_transaction_hash TEXT,            -- Stellar transaction anchoring the signature
+    UNIQUE (process_id, partner_id, terms_digest),
+    FOREIGN KEY (process_id) REFERENCES processes (id),
+    FOREIGN KEY (partner_id) REFERENCES users (id)
+);
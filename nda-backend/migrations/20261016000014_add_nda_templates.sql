@@ -0,0 +1,31 @@
+-- Reusable NDA templates of a client; the current version number is kept on the template
+CREATE TABLE IF NOT EXISTS nda_templates (
+    id TEXT PRIMARY KEY,
+    owner_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    current_version INTEGER NOT NULL DEFAULT 1,
+    created_at TEXT NOT NULL,
+    archived_at TEXT,
+    FOREIGN KEY (owner_id) REFERENCES users (id)
+);
+
+-- Every version of a template; placeholders is a JSON array of typed declarations
+CREATE TABLE IF NOT EXISTS nda_template_versions (
+    id TEXT PRIMARY KEY,
+    template_id TEXT NOT NULL,
+    version INTEGER NOT NULL,
+    body TEXT NOT NULL,
+    placeholders TEXT NOT NULL,
+    author_id TEXT NOT NULL,
+    change_note TEXT,
+    created_at TEXT NOT NULL,
+    UNIQUE (template_id, version),
+    FOREIGN KEY (template_id) REFERENCES nda_templates (id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_nda_templates_owner ON nda_templates (owner_id);
+
+-- Template version each process was rendered from (NULL for free-form content)
+ALTER TABLE processes ADD COLUMN template_id TEXT;
+ALTER TABLE processes ADD COLUMN template_version INTEGER;
@@ -0,0 +1,3 @@
+-- Ledger the sharing transaction was included in, listed on NDA certificates
+-- (NULL for shares recorded before it was kept)
+ALTER TABLE process_shares ADD COLUMN stellar_ledger INTEGER;
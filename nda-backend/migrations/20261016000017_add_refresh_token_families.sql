@@ -0,0 +1,23 @@
+-- Refresh token families: every token issued from one login, revoked together
+CREATE TABLE IF NOT EXISTS refresh_token_families (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    revoked_at TEXT,
+    revocation_reason TEXT,
+    FOREIGN KEY (user_id) REFERENCES users (id)
+);
+
+-- Refresh tokens of a family; parent_jti is the token rotated into this one
+-- (NULL for the token issued at login), rotated_at is set once it is exchanged
+CREATE TABLE IF NOT EXISTS refresh_tokens (
+    jti TEXT PRIMARY KEY,
+    family_id TEXT NOT NULL,
+    parent_jti TEXT,
+    issued_at TEXT NOT NULL,
+    expires_at INTEGER NOT NULL,
+    rotated_at TEXT,
+    FOREIGN KEY (family_id) REFERENCES refresh_token_families (id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_refresh_tokens_family ON refresh_tokens (family_id);
@@ -0,0 +1,15 @@
+-- Login sessions shown to users; a session is active while its refresh token
+-- family is not revoked and was refreshed within the refresh token lifetime
+CREATE TABLE IF NOT EXISTS sessions (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    family_id TEXT UNIQUE NOT NULL,
+    user_agent TEXT,
+    ip_address TEXT,
+    created_at TEXT NOT NULL,
+    last_refreshed_at TEXT,
+    FOREIGN KEY (user_id) REFERENCES users (id),
+    FOREIGN KEY (family_id) REFERENCES refresh_token_families (id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_sessions_user ON sessions (user_id);
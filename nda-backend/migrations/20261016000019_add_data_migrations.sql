@@ -0,0 +1,7 @@
+-- One-time data migrations that need key material and run from the server
+-- at startup (e.g. binding process content to its process); a row records
+-- that a migration has been applied and must not run again
+CREATE TABLE IF NOT EXISTS data_migrations (
+    name TEXT PRIMARY KEY,
+    applied_at TEXT NOT NULL
+);
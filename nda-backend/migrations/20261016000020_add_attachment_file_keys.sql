@@ -0,0 +1,3 @@
+-- Data key rotation: file key of an attachment encrypted under the current data key
+-- NULL for files whose key is still derived from the current data key
+ALTER TABLE process_attachments ADD COLUMN file_key TEXT;
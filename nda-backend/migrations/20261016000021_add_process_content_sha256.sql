@@ -0,0 +1,4 @@
+-- SHA-256 of the plaintext of the current revision, computed when it is written,
+-- so NDA terms are built without decrypting the content
+-- NULL for rows from older versions until the server computes it at startup
+ALTER TABLE processes ADD COLUMN content_sha256 TEXT;
@@ -0,0 +1,612 @@
+//! # Attachments Module
+//!
+//! This module stores process attachments (PDFs, spreadsheets, CAD files, ...)
+//! encrypted on disk. Files are encrypted and decrypted in fixed-size chunks
+//! while they are streamed, so a large file never sits fully in memory.
+//!
+//! ## Encryption
+//!
+//! Attachments are encrypted with a per-file key derived from the process
+//! data key, using chunked AES-256-GCM (the STREAM construction):
+//!
+//! 1. A random 32-byte salt is written as the file header
+//! 2. `file_key = HKDF-SHA256(ikm = data_key, salt, info = "nda-manager attachment v1")`
+//! 3. The plaintext is split into 64 KiB chunks, each encrypted separately with
+//!    the nonce `0x00 * 7 || chunk counter (u32 BE) || last-chunk flag`
+//!
+//! ```text
+//! salt (32) || chunk_0 (64 KiB + 16) || ... || last chunk (< 64 KiB + 16)
+//! ```
+//!
+//! The last chunk is always shorter than a full chunk (possibly empty) and is
+//! the only one encrypted with the last-chunk flag set. Reordered, duplicated,
+//! truncated or extended files therefore fail authentication.
+//!
+//! ## File Keys After Data Key Rotation
+//!
+//! When the data key of a process is rotated, files are not re-encrypted.
+//! Instead, each file key derived from the previous data key is stored in
+//! `process_attachments.file_key`, encrypted under the new data key with the
+//! content envelope of [`crate::crypto`] and [`file_key_aad`] as associated
+//! data (see [`wrap_file_key`]). Files without a stored file key derive it
+//! from the current data key as above.
+//!
+//! ## Configuration
+//!
+//! - `ATTACHMENTS_DIR`: Directory holding encrypted attachments (default `./attachments`)
+//! - `MAX_ATTACHMENT_BYTES`: Maximum size of an uploaded file (default 100 MiB)
+
+use std::path::PathBuf;
+
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Nonce,
+};
+use axum::body::Bytes;
+use base64::{engine::general_purpose, Engine as _};
+use futures_util::{Stream, StreamExt};
+use hkdf::Hkdf;
+use rand::Rng;
+use sha2::Sha256;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+
+use crate::crypto::{decrypt_content, encrypt_content_with, ContentCipher, CryptoError};
+
+/// Size of a plaintext chunk.
+pub const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Default maximum size of an uploaded file (100 MiB).
+pub const DEFAULT_MAX_ATTACHMENT_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Length of the AES-GCM authentication tag appended to each chunk.
+const TAG_LEN: usize = 16;
+
+/// Length of the per-file salt written as the file header.
+const SALT_LEN: usize = 32;
+
+/// Size of an encrypted full chunk.
+const ENCRYPTED_CHUNK_SIZE: usize = CHUNK_SIZE + TAG_LEN;
+
+/// HKDF info string binding file keys to this format.
+const HKDF_INFO: &[u8] = b"nda-manager attachment v1";
+
+/// Errors raised while storing or reading attachments.
+#[derive(Debug)]
+pub enum AttachmentError {
+    /// The uploaded file exceeds the configured maximum size
+    TooLarge(u64),
+    /// The upload stream failed (e.g. malformed multipart body)
+    Upload(String),
+    /// Reading or writing the encrypted file failed
+    Io(std::io::Error),
+    /// Encryption failed, or the stored file does not authenticate
+    Crypto(CryptoError),
+}
+
+impl std::fmt::Display for AttachmentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            AttachmentError::TooLarge(max) => write!(f, "Attachment exceeds {} bytes", max),
+            AttachmentError::Upload(e) => write!(f, "Upload failed: {}", e),
+            AttachmentError::Io(e) => write!(f, "Attachment storage error: {}", e),
+            AttachmentError::Crypto(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for AttachmentError {}
+
+impl From<std::io::Error> for AttachmentError {
+    fn from(error: std::io::Error) -> Self {
+        AttachmentError::Io(error)
+    }
+}
+
+impl From<CryptoError> for AttachmentError {
+    fn from(error: CryptoError) -> Self {
+        AttachmentError::Crypto(error)
+    }
+}
+
+/// Key an attachment file is encrypted with.
+#[derive(Debug, Clone, Copy)]
+pub enum FileKey<'a> {
+    /// Derived from the process data key and the file's salt
+    Derived(&'a str),
+    /// File key stored in `process_attachments.file_key`, already unwrapped
+    Stored([u8; 32]),
+}
+
+/// Derives the key of a file from the process data key and the file's salt.
+fn derive_file_key(data_key: &str, salt: &[u8]) -> Result<[u8; 32], CryptoError> {
+    let key_bytes = general_purpose::STANDARD
+        .decode(data_key)
+        .map_err(|e| CryptoError::new(format!("Failed to decode key: {}", e)))?;
+
+    let mut file_key = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(salt), &key_bytes)
+        .expand(HKDF_INFO, &mut file_key)
+        .map_err(|e| CryptoError::new(format!("Key derivation failed: {}", e)))?;
+    Ok(file_key)
+}
+
+/// Builds the AES-256-GCM cipher of a file.
+fn file_cipher(key: FileKey, salt: &[u8]) -> Result<Aes256Gcm, CryptoError> {
+    let file_key = match key {
+        FileKey::Derived(data_key) => derive_file_key(data_key, salt)?,
+        FileKey::Stored(file_key) => file_key,
+    };
+
+    Aes256Gcm::new_from_slice(&file_key).map_err(|e| CryptoError::new(format!("Invalid file key: {}", e)))
+}
+
+/// Associated data binding a wrapped file key to its attachment.
+pub fn file_key_aad(attachment_id: &str) -> Vec<u8> {
+    format!("nda-manager attachment key v1:{}", attachment_id).into_bytes()
+}
+
+/// Encrypts a file key under a process data key for `process_attachments.file_key`.
+pub fn wrap_file_key(
+    file_key: &[u8; 32],
+    data_key: &str,
+    cipher: ContentCipher,
+    attachment_id: &str,
+) -> Result<String, CryptoError> {
+    encrypt_content_with(&general_purpose::STANDARD.encode(file_key), data_key, cipher, &file_key_aad(attachment_id))
+        .map_err(|e| CryptoError::new(format!("Failed to wrap file key: {}", e)))
+}
+
+/// Decrypts a file key stored by [`wrap_file_key`].
+pub fn unwrap_file_key(wrapped: &str, data_key: &str, attachment_id: &str) -> Result<[u8; 32], CryptoError> {
+    let encoded = decrypt_content(wrapped, data_key, &file_key_aad(attachment_id))
+        .map_err(|e| CryptoError::new(format!("Failed to unwrap file key: {}", e)))?;
+    general_purpose::STANDARD
+        .decode(encoded)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| CryptoError::new("Invalid file key"))
+}
+
+/// Builds the nonce of a chunk from its position in the file.
+fn chunk_nonce(counter: u32, last: bool) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[7..11].copy_from_slice(&counter.to_be_bytes());
+    nonce[11] = last as u8;
+    nonce
+}
+
+/// Incremental encryptor producing the attachment file format.
+pub struct ChunkEncryptor {
+    cipher: Aes256Gcm,
+    counter: u32,
+    buffer: Vec<u8>,
+}
+
+impl ChunkEncryptor {
+    /// Creates an encryptor for a new file.
+    ///
+    /// Returns the encryptor and the file header, which must be written
+    /// before any chunk.
+    pub fn new(data_key: &str) -> Result<(Self, [u8; SALT_LEN]), CryptoError> {
+        let salt: [u8; SALT_LEN] = rand::thread_rng().gen();
+        let encryptor = Self {
+            cipher: file_cipher(FileKey::Derived(data_key), &salt)?,
+            counter: 0,
+            buffer: Vec::with_capacity(CHUNK_SIZE),
+        };
+        Ok((encryptor, salt))
+    }
+
+    fn encrypt_chunk(&mut self, chunk: &[u8], last: bool) -> Result<Vec<u8>, CryptoError> {
+        let nonce = chunk_nonce(self.counter, last);
+        self.counter = self
+            .counter
+            .checked_add(1)
+            .ok_or_else(|| CryptoError::new("Attachment has too many chunks"))?;
+
+        self.cipher
+            .encrypt(Nonce::from_slice(&nonce), chunk)
+            .map_err(|e| CryptoError::new(format!("Encryption failed: {:?}", e)))
+    }
+
+    /// Feeds plaintext, returning the encrypted chunks completed by it.
+    pub fn update(&mut self, data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        self.buffer.extend_from_slice(data);
+
+        let mut output = Vec::new();
+        while self.buffer.len() >= CHUNK_SIZE {
+            let chunk: Vec<u8> = self.buffer.drain(..CHUNK_SIZE).collect();
+            output.extend(self.encrypt_chunk(&chunk, false)?);
+        }
+        Ok(output)
+    }
+
+    /// Encrypts the remaining plaintext as the last chunk.
+    pub fn finish(mut self) -> Result<Vec<u8>, CryptoError> {
+        let remaining = std::mem::take(&mut self.buffer);
+        self.encrypt_chunk(&remaining, true)
+    }
+}
+
+/// Decryptor for files written by [`ChunkEncryptor`].
+pub struct ChunkDecryptor {
+    cipher: Aes256Gcm,
+    counter: u32,
+    finished: bool,
+}
+
+impl ChunkDecryptor {
+    /// Creates a decryptor from the file header.
+    pub fn new(key: FileKey, header: &[u8]) -> Result<Self, CryptoError> {
+        if header.len() != SALT_LEN {
+            return Err(CryptoError::new("Invalid attachment header"));
+        }
+        Ok(Self {
+            cipher: file_cipher(key, header)?,
+            counter: 0,
+            finished: false,
+        })
+    }
+
+    /// Checks if the last chunk has been decrypted.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Decrypts the next encrypted chunk.
+    ///
+    /// A full-size block is a regular chunk; a shorter one must be the last.
+    pub fn decrypt_chunk(&mut self, block: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        if self.finished {
+            return Err(CryptoError::new("Data after the last attachment chunk"));
+        }
+        if block.len() < TAG_LEN {
+            return Err(CryptoError::new("Attachment is truncated"));
+        }
+
+        let last = block.len() < ENCRYPTED_CHUNK_SIZE;
+        let nonce = chunk_nonce(self.counter, last);
+        let plaintext = self
+            .cipher
+            .decrypt(Nonce::from_slice(&nonce), block)
+            .map_err(|_| CryptoError::new("Attachment chunk failed authentication"))?;
+
+        self.counter = self
+            .counter
+            .checked_add(1)
+            .ok_or_else(|| CryptoError::new("Attachment has too many chunks"))?;
+        self.finished = last;
+        Ok(plaintext)
+    }
+}
+
+/// Reads until `buf` is full or the reader is exhausted.
+async fn read_block<R: AsyncRead + Unpin>(reader: &mut R, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]).await? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
+
+/// Reads and decrypts the next chunk, checking that the file ends after the last one.
+async fn next_plaintext_chunk<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    decryptor: &mut ChunkDecryptor,
+) -> Result<Bytes, AttachmentError> {
+    let mut block = vec![0u8; ENCRYPTED_CHUNK_SIZE];
+    let read = read_block(reader, &mut block).await?;
+    let plaintext = decryptor.decrypt_chunk(&block[..read])?;
+
+    if decryptor.is_finished() && read_block(reader, &mut [0u8; 1]).await? != 0 {
+        return Err(CryptoError::new("Data after the last attachment chunk").into());
+    }
+    Ok(Bytes::from(plaintext))
+}
+
+/// Normalizes an uploaded filename to a bare file name.
+///
+/// Drops directory components and control characters, so the name is safe
+/// to echo in `Content-Disposition` headers.
+pub fn sanitize_filename(filename: &str) -> String {
+    let name = filename.rsplit(['/', '\\']).next().unwrap_or_default();
+    let name: String = name.chars().filter(|c| !c.is_control()).take(255).collect();
+    let name = name.trim();
+
+    if name.is_empty() || name == "." || name == ".." {
+        "attachment".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// Builds a `Content-Disposition` value for downloading `filename`.
+///
+/// Includes an ASCII fallback and the RFC 5987 encoded UTF-8 name.
+pub fn content_disposition(filename: &str) -> String {
+    let fallback: String = filename
+        .chars()
+        .map(|c| if (c.is_ascii_graphic() && c != '"' && c != '\\') || c == ' ' { c } else { '_' })
+        .collect();
+
+    let encoded: String = filename
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect();
+
+    format!("attachment; filename=\"{}\"; filename*=UTF-8''{}", fallback, encoded)
+}
+
+/// Directory of encrypted attachment files.
+///
+/// Files are named after the attachment ID; metadata such as the original
+/// filename and content type lives in the `process_attachments` table.
+#[derive(Debug, Clone)]
+pub struct AttachmentStore {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+impl AttachmentStore {
+    /// Creates a store in `dir` accepting files up to `max_bytes`.
+    pub fn new(dir: impl Into<PathBuf>, max_bytes: u64) -> Self {
+        Self {
+            dir: dir.into(),
+            max_bytes,
+        }
+    }
+
+    /// Creates a store from `ATTACHMENTS_DIR` and `MAX_ATTACHMENT_BYTES`.
+    pub fn from_env() -> Result<Self, String> {
+        let dir = std::env::var("ATTACHMENTS_DIR").unwrap_or_else(|_| "./attachments".to_string());
+        let max_bytes = match std::env::var("MAX_ATTACHMENT_BYTES") {
+            Ok(value) => match value.trim().parse::<u64>() {
+                Ok(bytes) if bytes > 0 => bytes,
+                _ => {
+                    return Err(format!(
+                        "Invalid MAX_ATTACHMENT_BYTES '{}' (expected a positive number of bytes)",
+                        value
+                    ))
+                }
+            },
+            Err(_) => DEFAULT_MAX_ATTACHMENT_BYTES,
+        };
+
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create ATTACHMENTS_DIR '{}': {}", dir, e))?;
+
+        Ok(Self::new(dir, max_bytes))
+    }
+
+    /// Maximum size of an uploaded file, in bytes.
+    pub fn max_bytes(&self) -> u64 {
+        self.max_bytes
+    }
+
+    fn path(&self, attachment_id: &str) -> PathBuf {
+        self.dir.join(format!("{}.enc", attachment_id))
+    }
+
+    /// Encrypts a plaintext stream into the file of `attachment_id`.
+    ///
+    /// The file is written under a temporary name and only moved in place
+    /// once complete; on failure nothing is left behind.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result` containing:
+    /// - `Ok(u64)` - Plaintext size in bytes
+    /// - `Err(AttachmentError)` - Upload, size limit, encryption or storage failure
+    pub async fn write<S, E>(&self, attachment_id: &str, data_key: &str, stream: S) -> Result<u64, AttachmentError>
+    where
+        S: Stream<Item = Result<Bytes, E>>,
+        E: std::fmt::Display,
+    {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let temp_path = self.dir.join(format!("{}.part", attachment_id));
+
+        let result = self.write_to(&temp_path, data_key, stream).await;
+        match result {
+            Ok(size) => {
+                tokio::fs::rename(&temp_path, self.path(attachment_id)).await?;
+                Ok(size)
+            }
+            Err(e) => {
+                let _ = tokio::fs::remove_file(&temp_path).await;
+                Err(e)
+            }
+        }
+    }
+
+    async fn write_to<S, E>(&self, path: &std::path::Path, data_key: &str, stream: S) -> Result<u64, AttachmentError>
+    where
+        S: Stream<Item = Result<Bytes, E>>,
+        E: std::fmt::Display,
+    {
+        let mut stream = std::pin::pin!(stream);
+        let (mut encryptor, header) = ChunkEncryptor::new(data_key)?;
+        let mut file = tokio::fs::File::create(path).await?;
+        file.write_all(&header).await?;
+
+        let mut size: u64 = 0;
+        while let Some(bytes) = stream.next().await {
+            let bytes = bytes.map_err(|e| AttachmentError::Upload(e.to_string()))?;
+            size += bytes.len() as u64;
+            if size > self.max_bytes {
+                return Err(AttachmentError::TooLarge(self.max_bytes));
+            }
+            file.write_all(&encryptor.update(&bytes)?).await?;
+        }
+
+        file.write_all(&encryptor.finish()?).await?;
+        file.sync_all().await?;
+        Ok(size)
+    }
+
+    /// Opens an attachment as a stream of decrypted chunks.
+    ///
+    /// The first chunk is decrypted before returning, so a missing file or
+    /// wrong key is reported as an error instead of a broken download. Later
+    /// failures end the stream with an error.
+    pub async fn open_decrypted(
+        &self,
+        attachment_id: &str,
+        key: FileKey<'_>,
+    ) -> Result<impl Stream<Item = std::io::Result<Bytes>>, AttachmentError> {
+        let mut file = tokio::fs::File::open(self.path(attachment_id)).await?;
+
+        let mut header = [0u8; SALT_LEN];
+        if read_block(&mut file, &mut header).await? != SALT_LEN {
+            return Err(CryptoError::new("Attachment is truncated").into());
+        }
+        let mut decryptor = ChunkDecryptor::new(key, &header)?;
+        let first = next_plaintext_chunk(&mut file, &mut decryptor).await?;
+
+        let state = (file, decryptor, Some(first));
+        Ok(futures_util::stream::unfold(state, |(mut file, mut decryptor, pending)| async move {
+            if let Some(chunk) = pending {
+                return Some((Ok(chunk), (file, decryptor, None)));
+            }
+            if decryptor.is_finished() {
+                return None;
+            }
+            match next_plaintext_chunk(&mut file, &mut decryptor).await {
+                Ok(chunk) => Some((Ok(chunk), (file, decryptor, None))),
+                Err(e) => {
+                    tracing::error!("Attachment download aborted: {}", e);
+                    // Stop after reporting the error
+                    decryptor.finished = true;
+                    Some((Err(std::io::Error::other(e.to_string())), (file, decryptor, None)))
+                }
+            }
+        }))
+    }
+
+    /// Deletes an encrypted file whose record could not be stored.
+    pub async fn remove(&self, attachment_id: &str) -> std::io::Result<()> {
+        tokio::fs::remove_file(self.path(attachment_id)).await
+    }
+
+    /// Derives the key of a file without a stored file key from the data key.
+    ///
+    /// Used when the data key of its process is rotated, so the file key can
+    /// be stored under the new data key.
+    pub async fn derived_file_key(&self, attachment_id: &str, data_key: &str) -> Result<[u8; 32], AttachmentError> {
+        let mut file = tokio::fs::File::open(self.path(attachment_id)).await?;
+
+        let mut header = [0u8; SALT_LEN];
+        if read_block(&mut file, &mut header).await? != SALT_LEN {
+            return Err(CryptoError::new("Attachment is truncated").into());
+        }
+        Ok(derive_file_key(data_key, &header)?)
+    }
+
+    /// Opens an attachment as a stream of its encrypted bytes.
+    ///
+    /// Used for zero-knowledge shares, where the partner decrypts locally.
+    ///
+    /// # Returns
+    ///
+    /// The encrypted file size and a stream of its contents.
+    pub async fn open_encrypted(
+        &self,
+        attachment_id: &str,
+    ) -> Result<(u64, impl Stream<Item = std::io::Result<Bytes>>), AttachmentError> {
+        let file = tokio::fs::File::open(self.path(attachment_id)).await?;
+        let size = file.metadata().await?.len();
+
+        let stream = futures_util::stream::unfold(Some(file), |file| async move {
+            let mut file = file?;
+            let mut buf = vec![0u8; CHUNK_SIZE];
+            match file.read(&mut buf).await {
+                Ok(0) => None,
+                Ok(n) => {
+                    buf.truncate(n);
+                    Some((Ok(Bytes::from(buf)), Some(file)))
+                }
+                Err(e) => Some((Err(e), None)),
+            }
+        });
+        Ok((size, stream))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::generate_key;
+
+    async fn collect<S: Stream<Item = std::io::Result<Bytes>>>(stream: S) -> std::io::Result<Vec<u8>> {
+        let mut stream = std::pin::pin!(stream);
+        let mut output = Vec::new();
+        while let Some(chunk) = stream.next().await {
+            output.extend_from_slice(&chunk?);
+        }
+        Ok(output)
+    }
+
+    #[tokio::test]
+    async fn test_chunked_encryption_round_trip_and_tampering() {
+        let dir = std::env::temp_dir().join(format!("nda-attachments-{}", uuid::Uuid::new_v4()));
+        let store = AttachmentStore::new(&dir, 1024 * 1024);
+        let key = generate_key();
+
+        // Sizes around chunk boundaries, including an empty file
+        for size in [0, 1, CHUNK_SIZE - 1, CHUNK_SIZE, 2 * CHUNK_SIZE + 7] {
+            let data: Vec<u8> = (0..size).map(|i| (i % 251) as u8).collect();
+            let parts: Vec<Result<Bytes, std::io::Error>> =
+                data.chunks(10_000).map(|part| Ok(Bytes::copy_from_slice(part))).collect();
+
+            let id = format!("file-{}", size);
+            let written = store.write(&id, &key, futures_util::stream::iter(parts)).await.unwrap();
+            assert_eq!(written, size as u64);
+
+            let stream = store.open_decrypted(&id, FileKey::Derived(&key)).await.unwrap();
+            assert_eq!(collect(stream).await.unwrap(), data);
+        }
+
+        // Wrong key is detected before streaming starts
+        assert!(store.open_decrypted("file-1", FileKey::Derived(&generate_key())).await.is_err());
+
+        // After a data key rotation the file key is stored under the new data key
+        let file_key = store.derived_file_key("file-1", &key).await.unwrap();
+        let rotated = generate_key();
+        let wrapped = wrap_file_key(&file_key, &rotated, ContentCipher::default(), "file-1").unwrap();
+        assert!(unwrap_file_key(&wrapped, &rotated, "file-0").is_err());
+        let unwrapped = unwrap_file_key(&wrapped, &rotated, "file-1").unwrap();
+        let stream = store.open_decrypted("file-1", FileKey::Stored(unwrapped)).await.unwrap();
+        assert_eq!(collect(stream).await.unwrap(), vec![0u8]);
+
+        // Truncation at a chunk boundary fails authentication
+        let path = store.path(&format!("file-{}", 2 * CHUNK_SIZE + 7));
+        let encrypted = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &encrypted[..SALT_LEN + ENCRYPTED_CHUNK_SIZE]).unwrap();
+        let stream = store.open_decrypted(&format!("file-{}", 2 * CHUNK_SIZE + 7), FileKey::Derived(&key)).await.unwrap();
+        assert!(collect(stream).await.is_err());
+
+        // Oversized uploads are rejected and leave no file behind
+        let small = AttachmentStore::new(&dir, 10);
+        let parts = futures_util::stream::iter(vec![Ok::<_, std::io::Error>(Bytes::from(vec![0u8; 11]))]);
+        assert!(matches!(small.write("big", &key, parts).await, Err(AttachmentError::TooLarge(10))));
+        assert!(!small.path("big").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_filename_headers() {
+        assert_eq!(sanitize_filename("../../etc/passwd"), "passwd");
+        assert_eq!(sanitize_filename("C:\\docs\\NDA v2.pdf"), "NDA v2.pdf");
+        assert_eq!(sanitize_filename(".."), "attachment");
+        assert_eq!(
+            content_disposition("Plan \"é\".pdf"),
+            "attachment; filename=\"Plan ___.pdf\"; filename*=UTF-8''Plan%20%22%C3%A9%22.pdf"
+        );
+    }
+}
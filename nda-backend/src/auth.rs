@@ -16,7 +16,7 @@ impl Auth {
     /// 
     /// # Examples
     /// 
-    /// ```rust
+    /// ```rust,ignore
     /// let hashed = Auth::hash_password("my_secure_password").unwrap();
     /// ```
     pub fn hash_password(password: &str) -> Result<String, bcrypt::BcryptError> {
@@ -36,7 +36,7 @@ impl Auth {
     /// 
     /// # Examples
     /// 
-    /// ```rust
+    /// ```rust,ignore
     /// let is_valid = Auth::verify_password("my_password", &hashed_password).unwrap();
     /// ```
     pub fn verify_password(password: &str, hash: &str) -> Result<bool, bcrypt::BcryptError> {
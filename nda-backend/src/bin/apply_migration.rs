@@ -1,11 +1,10 @@
 use sqlx::sqlite::SqlitePool;
-use tokio;
 use std::fs;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let database_url = "sqlite:./stellar_mvp.db";
-    let pool = SqlitePool::connect(&database_url).await?;
+    let pool = SqlitePool::connect(database_url).await?;
     
     println!("🔄 Aplicando migração para adicionar coluna description...");
     
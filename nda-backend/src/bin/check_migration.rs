@@ -1,10 +1,9 @@
 use sqlx::sqlite::SqlitePool;
-use tokio;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let database_url = "sqlite:./stellar_mvp.db";
-    let pool = SqlitePool::connect(&database_url).await?;
+    let pool = SqlitePool::connect(database_url).await?;
     
     println!("🔍 Verificando estrutura da tabela users...");
     
@@ -0,0 +1,78 @@
+//! Re-wraps every process data key under a new key-encryption key.
+//!
+//! The current key ring is read from `KEK_FILE` or `KEK` like the server does.
+//! Content stays untouched: only `processes.encryption_key` and `key_id` change.
+//! When `SECRET_KEY_FILE` is not set, Stellar secret keys are sealed under the
+//! key-encryption keys too, so they are re-sealed under the new key as well.
+//!
+//! ```bash
+//! # Generate a new key and rotate to it
+//! KEK_FILE=kek.key cargo run --bin rotate_kek -- --generate new-kek.key
+//!
+//! # Rotate to an existing key
+//! KEK_FILE=kek.key cargo run --bin rotate_kek -- new-kek.key
+//! ```
+//!
+//! Afterwards, restart the server with the new key first in the key ring.
+
+use nda_backend::database::init_database;
+use nda_backend::key_provider::{reseal_secrets, LocalFileKeyProvider};
+use nda_backend::keys::{rewrap_process_keys, KeyEncryptionKey, KeyRing};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv::dotenv().ok();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (generate, path) = match args.as_slice() {
+        [flag, path] if flag == "--generate" => (true, path.clone()),
+        [path] => (false, path.clone()),
+        _ => {
+            eprintln!("Usage: rotate_kek [--generate] <new-kek-file>");
+            std::process::exit(2);
+        }
+    };
+
+    let current = KeyRing::from_env()?;
+
+    let new_key = if generate {
+        if std::path::Path::new(&path).exists() {
+            return Err(format!("Refusing to overwrite existing key file '{}'", path).into());
+        }
+        let key = KeyEncryptionKey::generate();
+        std::fs::write(&path, format!("{}\n", key.to_base64()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+        }
+        println!("🔑 Generated new key-encryption key in {}", path);
+        key
+    } else {
+        let contents = std::fs::read_to_string(&path)?;
+        KeyRing::parse(&contents)?.active().clone()
+    };
+
+    let target = KeyRing::new(new_key.clone());
+    let current = current.with_previous(new_key);
+
+    let pool = init_database().await?;
+    println!("🔄 Re-wrapping data keys under {}...", target.active().id());
+    let count = rewrap_process_keys(&pool, &current, &target, false).await?;
+
+    println!("✅ Re-wrapped {} data keys", count);
+
+    if std::env::var("SECRET_KEY_FILE").is_err() {
+        let resealed = reseal_secrets(
+            &pool,
+            &LocalFileKeyProvider::new(current),
+            &LocalFileKeyProvider::new(target),
+        )
+        .await?;
+        println!("✅ Re-sealed {} Stellar secret keys", resealed);
+    }
+
+    println!("➡️  Restart the server with KEK_FILE={} (or KEK set to its contents)", path);
+
+    Ok(())
+}
@@ -2,7 +2,7 @@
 use nda_backend::stellar_real::StellarClient;  // ← Mudança aqui
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     println!("🌟 Testando Integração Stellar Testnet");
     println!("=====================================");
     
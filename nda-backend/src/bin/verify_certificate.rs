@@ -0,0 +1,33 @@
+//! Verifies the platform signature of an NDA certificate offline.
+//!
+//! The public key is returned by `GET /api/certificates/signing-key`; no
+//! connection to the server, the database or the Stellar network is needed.
+//!
+//! ```bash
+//! cargo run --bin verify_certificate -- nda-certificate.pdf GABC...
+//! ```
+//!
+//! Exits with status 1 when the certificate was modified or signed by
+//! another key.
+
+use nda_backend::certificate::verify_pdf;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let [path, public_key] = args.as_slice() else {
+        eprintln!("Usage: verify_certificate <certificate.pdf> <platform-public-key>");
+        std::process::exit(2);
+    };
+
+    let pdf = std::fs::read(path)?;
+    match verify_pdf(&pdf, public_key) {
+        Ok(()) => {
+            println!("✅ {} is a valid certificate signed by {}", path, public_key);
+            Ok(())
+        }
+        Err(e) => {
+            eprintln!("❌ {} failed verification: {}", path, e);
+            std::process::exit(1);
+        }
+    }
+}
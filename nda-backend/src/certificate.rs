@@ -0,0 +1,558 @@
+//! # NDA Certificate Module
+//!
+//! Auditors get a single PDF per process listing the parties, the Stellar
+//! transactions that shared it, every partner access and a digest of the
+//! current content. The PDF is written by hand (no external services or PDF
+//! libraries) and signed with a platform ed25519 key, so it can be verified
+//! offline with nothing but the file and the platform public key.
+//!
+//! ## Signature
+//!
+//! The signature dictionary follows the layout of PDF digital signatures:
+//!
+//! ```text
+//! << /Type /Sig /Filter /NDA.Ed25519 /SubFilter /ed25519.detached
+//!    /ByteRange [0 a b c] /Contents <128 hex digits> >>
+//! ```
+//!
+//! The ed25519 signature covers every byte of the file except the hex digits
+//! of `/Contents` (angle brackets included), i.e. the ranges `[0, a)` and
+//! `[b, b + c)`. The byte range must reach the end of the file, so appended
+//! incremental updates invalidate the certificate. [`verify_pdf`] implements
+//! the check; the `verify_certificate` binary wraps it for auditors:
+//!
+//! ```bash
+//! cargo run --bin verify_certificate -- nda-certificate.pdf GABC...
+//! ```
+//!
+//! ## Configuration
+//!
+//! - `CERTIFICATE_SIGNING_KEY`: Stellar secret seed (`S...`) of the platform
+//!   signing key. Without it the key is derived from the active key-encryption
+//!   key, so it changes when the key-encryption key is rotated; set it to keep
+//!   certificates verifiable with the same public key across rotations.
+
+use base64::{engine::general_purpose, Engine as _};
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signature, Signer};
+use hkdf::Hkdf;
+use sha2::Sha256;
+
+use crate::crypto::CryptoError;
+use crate::keys::KeyRing;
+
+/// HKDF info string of the signing key derived from the key-encryption key.
+const SIGNING_KEY_INFO: &[u8] = b"NDA_CERTIFICATE_SIGNING_V1";
+
+/// Number of hex digits reserved for the signature in `/Contents`.
+const SIGNATURE_HEX_LEN: usize = 128;
+
+/// Byte range placeholder, overwritten once the offsets are known.
+const BYTE_RANGE_PLACEHOLDER: &str = "[0000000000 0000000000 0000000000 0000000000]";
+
+/// A4 page size in points.
+const PAGE_WIDTH: u32 = 595;
+const PAGE_HEIGHT: u32 = 842;
+const MARGIN: u32 = 50;
+const LINE_HEIGHT: u32 = 14;
+const LINES_PER_PAGE: usize = 52;
+const WRAP_COLUMNS: usize = 90;
+
+/// Platform key signing NDA certificates.
+pub struct CertificateSigner {
+    keypair: Keypair,
+}
+
+impl CertificateSigner {
+    fn from_seed(seed: &[u8; 32]) -> Result<Self, CryptoError> {
+        let secret = SecretKey::from_bytes(seed)
+            .map_err(|e| CryptoError::new(format!("Invalid ed25519 secret key: {}", e)))?;
+        let public = PublicKey::from(&secret);
+        Ok(Self {
+            keypair: Keypair { secret, public },
+        })
+    }
+
+    /// Creates a signer from a Stellar secret seed (`S...`).
+    pub fn from_secret(secret_key: &str) -> Result<Self, CryptoError> {
+        let seed = stellar_strkey::ed25519::PrivateKey::from_string(secret_key.trim())
+            .map_err(|e| CryptoError::new(format!("Invalid Stellar secret key: {:?}", e)))?;
+        Self::from_seed(&seed.0)
+    }
+
+    /// Derives a signer from the active key-encryption key of `keyring`.
+    pub fn derive(keyring: &KeyRing) -> Result<Self, CryptoError> {
+        let kek = general_purpose::STANDARD
+            .decode(keyring.active().to_base64())
+            .map_err(|e| CryptoError::new(format!("Failed to decode key: {}", e)))?;
+
+        let mut seed = [0u8; 32];
+        Hkdf::<Sha256>::new(None, &kek)
+            .expand(SIGNING_KEY_INFO, &mut seed)
+            .map_err(|e| CryptoError::new(format!("Key derivation failed: {}", e)))?;
+
+        Self::from_seed(&seed)
+    }
+
+    /// Creates a signer from `CERTIFICATE_SIGNING_KEY`, falling back to a key
+    /// derived from `keyring`.
+    pub fn from_env(keyring: &KeyRing) -> Result<Self, String> {
+        match std::env::var("CERTIFICATE_SIGNING_KEY") {
+            Ok(secret_key) => Self::from_secret(&secret_key)
+                .map_err(|e| format!("Invalid CERTIFICATE_SIGNING_KEY: {}", e)),
+            Err(_) => Self::derive(keyring).map_err(|e| e.to_string()),
+        }
+    }
+
+    /// Stellar encoding (`G...`) of the public key verifying certificates.
+    pub fn public_key(&self) -> String {
+        stellar_strkey::ed25519::PublicKey(self.keypair.public.to_bytes()).to_string()
+    }
+
+    /// Renders a certificate as a signed PDF.
+    pub fn sign_pdf(&self, certificate: &Certificate) -> Vec<u8> {
+        let mut pdf = PdfWriter::write(certificate, &self.public_key());
+
+        let contents_end = pdf.contents_start + SIGNATURE_HEX_LEN + 2;
+        let byte_range = format!(
+            "[{:010} {:010} {:010} {:010}]",
+            0,
+            pdf.contents_start,
+            contents_end,
+            pdf.bytes.len() - contents_end
+        );
+        pdf.bytes[pdf.byte_range_start..pdf.byte_range_start + byte_range.len()]
+            .copy_from_slice(byte_range.as_bytes());
+
+        let mut message = pdf.bytes[..pdf.contents_start].to_vec();
+        message.extend_from_slice(&pdf.bytes[contents_end..]);
+        let signature = hex::encode_upper(self.keypair.sign(&message).to_bytes());
+        pdf.bytes[pdf.contents_start + 1..contents_end - 1].copy_from_slice(signature.as_bytes());
+
+        pdf.bytes
+    }
+}
+
+/// User named in a certificate.
+#[derive(Debug, Clone)]
+pub struct CertificateParty {
+    pub name: String,
+    pub stellar_public_key: String,
+}
+
+/// Share of the process recorded on the Stellar network.
+#[derive(Debug, Clone)]
+pub struct CertificateShare {
+    pub partner: CertificateParty,
+    pub transaction_hash: String,
+    pub ledger: Option<i64>,
+    pub shared_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// Partner access to the content or to an attachment.
+#[derive(Debug, Clone)]
+pub struct CertificateAccess {
+    pub partner_name: String,
+    pub accessed_at: DateTime<Utc>,
+    pub content_version: Option<i64>,
+    pub attachment_id: Option<String>,
+}
+
+/// Everything an NDA certificate attests.
+#[derive(Debug, Clone)]
+pub struct Certificate {
+    pub process_id: String,
+    pub title: String,
+    pub status: String,
+    pub content_version: i64,
+    /// Hex SHA-256 of the plaintext content of the current version
+    pub content_sha256: String,
+    pub owner: CertificateParty,
+    pub shares: Vec<CertificateShare>,
+    pub accesses: Vec<CertificateAccess>,
+    pub generated_at: DateTime<Utc>,
+}
+
+fn timestamp(at: &DateTime<Utc>) -> String {
+    at.format("%Y-%m-%d %H:%M:%S UTC").to_string()
+}
+
+/// Text line of the certificate with its font (`F1` regular, `F2` bold) and size.
+struct Line {
+    font: &'static str,
+    size: u32,
+    text: String,
+}
+
+impl Certificate {
+    fn lines(&self, public_key: &str) -> Vec<Line> {
+        let mut lines = Vec::new();
+        let mut push = |font: &'static str, size: u32, text: String| {
+            for text in wrap(&text) {
+                lines.push(Line { font, size, text });
+            }
+        };
+
+        push("F2", 16, "NDA Certificate".to_string());
+        push("F1", 10, format!("Generated at: {}", timestamp(&self.generated_at)));
+        push("F1", 10, String::new());
+
+        push("F2", 11, "Process".to_string());
+        push("F1", 10, format!("ID: {}", self.process_id));
+        push("F1", 10, format!("Title: {}", self.title));
+        push("F1", 10, format!("Status: {}", self.status));
+        push("F1", 10, format!("Content version: {}", self.content_version));
+        push("F1", 10, format!("Content SHA-256: {}", self.content_sha256));
+        push("F1", 10, String::new());
+
+        push("F2", 11, "Parties".to_string());
+        push("F1", 10, format!("Owner: {} ({})", self.owner.name, self.owner.stellar_public_key));
+        let mut partners: Vec<&CertificateParty> = Vec::new();
+        for share in &self.shares {
+            if !partners.iter().any(|p| p.stellar_public_key == share.partner.stellar_public_key) {
+                partners.push(&share.partner);
+            }
+        }
+        for partner in partners {
+            push("F1", 10, format!("Partner: {} ({})", partner.name, partner.stellar_public_key));
+        }
+        push("F1", 10, String::new());
+
+        push("F2", 11, "Shares".to_string());
+        if self.shares.is_empty() {
+            push("F1", 10, "The process has not been shared.".to_string());
+        }
+        for (index, share) in self.shares.iter().enumerate() {
+            push("F1", 10, format!("{}. Shared with {} at {}", index + 1, share.partner.name, timestamp(&share.shared_at)));
+            push("F1", 10, format!("   Transaction hash: {}", share.transaction_hash));
+            let ledger = share.ledger.map_or_else(|| "not recorded".to_string(), |ledger| ledger.to_string());
+            push("F1", 10, format!("   Ledger: {}", ledger));
+            if let Some(revoked_at) = &share.revoked_at {
+                push("F1", 10, format!("   Revoked at: {}", timestamp(revoked_at)));
+            }
+        }
+        push("F1", 10, String::new());
+
+        push("F2", 11, "Accesses".to_string());
+        if self.accesses.is_empty() {
+            push("F1", 10, "No partner has accessed the process.".to_string());
+        }
+        for access in &self.accesses {
+            let target = match (&access.attachment_id, access.content_version) {
+                (Some(attachment_id), _) => format!("attachment {}", attachment_id),
+                (None, Some(version)) => format!("content version {}", version),
+                (None, None) => "content".to_string(),
+            };
+            push("F1", 10, format!("{} - {} - {}", timestamp(&access.accessed_at), access.partner_name, target));
+        }
+        push("F1", 10, String::new());
+
+        push("F2", 11, "Verification".to_string());
+        push("F1", 10, format!("Signed with the platform ed25519 key {}.", public_key));
+        push(
+            "F1",
+            10,
+            "The signature covers every byte of this file except the signature value; see /ByteRange \
+             in the signature dictionary."
+                .to_string(),
+        );
+
+        lines
+    }
+}
+
+/// Wraps text at word boundaries, splitting words longer than a line.
+fn wrap(text: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+
+    for word in text.split(' ') {
+        let mut word: Vec<char> = word.chars().collect();
+        while word.len() > WRAP_COLUMNS {
+            if !current.is_empty() {
+                lines.push(std::mem::take(&mut current));
+            }
+            lines.push(word.drain(..WRAP_COLUMNS).collect());
+        }
+        let word: String = word.into_iter().collect();
+        if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > WRAP_COLUMNS {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(&word);
+    }
+    lines.push(current);
+
+    lines
+}
+
+/// Encodes text as a PDF hex string in WinAnsi encoding.
+///
+/// Hex strings need no escaping, so user-provided text can never be mistaken
+/// for PDF syntax. Characters outside Latin-1 are replaced with `?`.
+fn pdf_string(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len() * 2 + 2);
+    encoded.push('<');
+    for c in text.chars() {
+        let byte = match c as u32 {
+            code @ (0x20..=0x7E | 0xA0..=0xFF) => code as u8,
+            _ => b'?',
+        };
+        encoded.push_str(&format!("{:02X}", byte));
+    }
+    encoded.push('>');
+    encoded
+}
+
+/// Unsigned PDF with the offsets of the signature placeholders.
+struct PdfWriter {
+    bytes: Vec<u8>,
+    offsets: Vec<usize>,
+    byte_range_start: usize,
+    contents_start: usize,
+}
+
+impl PdfWriter {
+    fn object(&mut self, body: &str) {
+        self.offsets.push(self.bytes.len());
+        self.bytes
+            .extend_from_slice(format!("{} 0 obj\n{}\nendobj\n", self.offsets.len(), body).as_bytes());
+    }
+
+    fn write(certificate: &Certificate, public_key: &str) -> Self {
+        let lines = certificate.lines(public_key);
+        let pages: Vec<&[Line]> = lines.chunks(LINES_PER_PAGE).collect();
+        let date = certificate.generated_at.format("D:%Y%m%d%H%M%SZ");
+
+        let mut pdf = Self {
+            bytes: b"%PDF-1.7\n%\xE2\xE3\xCF\xD3\n".to_vec(),
+            offsets: Vec::new(),
+            byte_range_start: 0,
+            contents_start: 0,
+        };
+
+        // 1-6: catalog, page tree, fonts, signature and document information;
+        // each page then takes two objects (page and content stream)
+        let kids: Vec<String> = (0..pages.len()).map(|i| format!("{} 0 R", 7 + 2 * i)).collect();
+        pdf.object("<< /Type /Catalog /Pages 2 0 R /NDACertificateSignature 5 0 R >>");
+        pdf.object(&format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), pages.len()));
+        pdf.object("<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>");
+        pdf.object("<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>");
+
+        pdf.offsets.push(pdf.bytes.len());
+        let signature = format!(
+            "5 0 obj\n<< /Type /Sig /Filter /NDA.Ed25519 /SubFilter /ed25519.detached /Name {} /M ({}) /ByteRange ",
+            pdf_string(public_key),
+            date
+        );
+        pdf.bytes.extend_from_slice(signature.as_bytes());
+        pdf.byte_range_start = pdf.bytes.len();
+        pdf.bytes.extend_from_slice(BYTE_RANGE_PLACEHOLDER.as_bytes());
+        pdf.bytes.extend_from_slice(b" /Contents ");
+        pdf.contents_start = pdf.bytes.len();
+        pdf.bytes.extend_from_slice(format!("<{}>", "0".repeat(SIGNATURE_HEX_LEN)).as_bytes());
+        pdf.bytes.extend_from_slice(b" >>\nendobj\n");
+
+        pdf.object(&format!(
+            "<< /Title {} /Producer (NDA Manager) /CreationDate ({}) >>",
+            pdf_string(&format!("NDA Certificate - {}", certificate.title)),
+            date
+        ));
+
+        for (index, page) in pages.iter().enumerate() {
+            let mut stream = String::new();
+            let mut y = PAGE_HEIGHT - MARGIN;
+            for line in page.iter() {
+                if !line.text.is_empty() {
+                    stream.push_str(&format!(
+                        "BT /{} {} Tf {} {} Td {} Tj ET\n",
+                        line.font,
+                        line.size,
+                        MARGIN,
+                        y,
+                        pdf_string(&line.text)
+                    ));
+                }
+                y -= LINE_HEIGHT;
+            }
+            stream.push_str(&format!(
+                "BT /F1 8 Tf {} {} Td {} Tj ET\n",
+                MARGIN,
+                MARGIN / 2,
+                pdf_string(&format!("Process {} - page {} of {}", certificate.process_id, index + 1, pages.len()))
+            ));
+
+            pdf.object(&format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+                 /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                PAGE_WIDTH,
+                PAGE_HEIGHT,
+                8 + 2 * index
+            ));
+            pdf.object(&format!("<< /Length {} >>\nstream\n{}endstream", stream.len(), stream));
+        }
+
+        let xref_offset = pdf.bytes.len();
+        let mut xref = format!("xref\n0 {}\n0000000000 65535 f \n", pdf.offsets.len() + 1);
+        for offset in &pdf.offsets {
+            xref.push_str(&format!("{:010} 00000 n \n", offset));
+        }
+        xref.push_str(&format!(
+            "trailer\n<< /Size {} /Root 1 0 R /Info 6 0 R >>\nstartxref\n{}\n%%EOF\n",
+            pdf.offsets.len() + 1,
+            xref_offset
+        ));
+        pdf.bytes.extend_from_slice(xref.as_bytes());
+
+        pdf
+    }
+}
+
+#[allow(dead_code)]
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+/// Verifies the platform signature of a certificate PDF.
+///
+/// # Parameters
+///
+/// * `pdf` - Certificate file as downloaded
+/// * `public_key` - Stellar public key (`G...`) of the platform signing key
+///
+/// # Returns
+///
+/// Returns `Ok(())` if the file is unchanged since it was signed, or a
+/// `CryptoError` for malformed files, byte ranges that do not cover the whole
+/// file and signatures that do not verify.
+#[allow(dead_code)]
+pub fn verify_pdf(pdf: &[u8], public_key: &str) -> Result<(), CryptoError> {
+    const MARKER: &[u8] = b"/ByteRange [";
+
+    let start = find(pdf, MARKER).ok_or_else(|| CryptoError::new("Certificate has no signature"))? + MARKER.len();
+    if find(&pdf[start..], MARKER).is_some() {
+        return Err(CryptoError::new("Certificate has more than one signature"));
+    }
+    let end = start
+        + pdf[start..]
+            .iter()
+            .position(|&byte| byte == b']')
+            .ok_or_else(|| CryptoError::new("Malformed byte range"))?;
+    let range: Vec<usize> = std::str::from_utf8(&pdf[start..end])
+        .ok()
+        .and_then(|range| range.split_whitespace().map(|value| value.parse().ok()).collect())
+        .ok_or_else(|| CryptoError::new("Malformed byte range"))?;
+
+    let (contents_start, contents_end) = match range.as_slice() {
+        [0, a, b, c] if a.checked_add(SIGNATURE_HEX_LEN + 2) == Some(*b) && b.checked_add(*c) == Some(pdf.len()) => {
+            (*a, *b)
+        }
+        _ => return Err(CryptoError::new("Byte range does not cover the whole certificate")),
+    };
+    if pdf[contents_start] != b'<' || pdf[contents_end - 1] != b'>' {
+        return Err(CryptoError::new("Malformed signature contents"));
+    }
+
+    let signature = hex::decode(&pdf[contents_start + 1..contents_end - 1])
+        .map_err(|e| CryptoError::new(format!("Invalid signature encoding: {}", e)))?;
+    let signature = Signature::from_bytes(&signature)
+        .map_err(|e| CryptoError::new(format!("Invalid signature: {}", e)))?;
+    let key = stellar_strkey::ed25519::PublicKey::from_string(public_key.trim())
+        .map_err(|e| CryptoError::new(format!("Invalid Stellar public key: {:?}", e)))?;
+    let key = PublicKey::from_bytes(&key.0)
+        .map_err(|e| CryptoError::new(format!("Invalid ed25519 public key: {}", e)))?;
+
+    let mut message = pdf[..contents_start].to_vec();
+    message.extend_from_slice(&pdf[contents_end..]);
+    key.verify_strict(&message, &signature)
+        .map_err(|_| CryptoError::new("Certificate signature does not match its contents"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::KeyEncryptionKey;
+    use crate::stellar_real::StellarClient;
+
+    fn certificate(accesses: usize) -> Certificate {
+        let owner = CertificateParty {
+            name: "Ana Souza".to_string(),
+            stellar_public_key: StellarClient::generate_keypair().unwrap().public_key,
+        };
+        let partner = CertificateParty {
+            name: "Zoë Müller".to_string(),
+            stellar_public_key: StellarClient::generate_keypair().unwrap().public_key,
+        };
+        let now = Utc::now();
+
+        Certificate {
+            process_id: "process-1".to_string(),
+            title: "Supplier NDA (draft [2]) /ByteRange [0 1 2 3]".to_string(),
+            status: "active".to_string(),
+            content_version: 2,
+            content_sha256: "ab".repeat(32),
+            owner,
+            shares: vec![CertificateShare {
+                partner: partner.clone(),
+                transaction_hash: "cd".repeat(32),
+                ledger: Some(123456),
+                shared_at: now,
+                revoked_at: None,
+            }],
+            accesses: (0..accesses)
+                .map(|i| CertificateAccess {
+                    partner_name: partner.name.clone(),
+                    accessed_at: now,
+                    content_version: Some(2),
+                    attachment_id: (i % 2 == 1).then(|| format!("attachment-{}", i)),
+                })
+                .collect(),
+            generated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_signed_certificate_verifies_only_unchanged() {
+        let keypair = StellarClient::generate_keypair().unwrap();
+        let signer = CertificateSigner::from_secret(&keypair.secret_key).unwrap();
+        assert_eq!(signer.public_key(), keypair.public_key);
+
+        let certificate = certificate(120);
+        let pages = certificate.lines(&signer.public_key()).len().div_ceil(LINES_PER_PAGE);
+        assert!(pages > 1);
+
+        let pdf = signer.sign_pdf(&certificate);
+        assert!(pdf.starts_with(b"%PDF-1.7"));
+        assert!(pdf.ends_with(b"%%EOF\n"));
+        assert!(find(&pdf, format!("/Count {} ", pages).as_bytes()).is_some());
+        verify_pdf(&pdf, &signer.public_key()).unwrap();
+
+        let other = StellarClient::generate_keypair().unwrap();
+        assert!(verify_pdf(&pdf, &other.public_key).is_err());
+
+        // Any change to the signed bytes, including appended updates, is detected
+        let mut tampered = pdf.clone();
+        let digest = find(&tampered, hex::encode_upper("ab".repeat(32)).as_bytes()).unwrap();
+        tampered[digest] = b'5';
+        assert!(verify_pdf(&tampered, &signer.public_key()).is_err());
+
+        let mut appended = pdf.clone();
+        appended.extend_from_slice(b"1 0 obj\n<< >>\nendobj\n");
+        assert!(verify_pdf(&appended, &signer.public_key()).is_err());
+    }
+
+    #[test]
+    fn test_derived_signer_follows_the_active_key() {
+        let kek = KeyEncryptionKey::generate();
+        let signer = CertificateSigner::derive(&KeyRing::new(kek.clone())).unwrap();
+        let again = CertificateSigner::derive(&KeyRing::new(kek)).unwrap();
+        let rotated = CertificateSigner::derive(&KeyRing::new(KeyEncryptionKey::generate())).unwrap();
+
+        assert_eq!(signer.public_key(), again.public_key());
+        assert_ne!(signer.public_key(), rotated.public_key());
+        assert!(signer.public_key().starts_with('G'));
+    }
+}
@@ -0,0 +1,147 @@
+//! # Share Commitment Module
+//!
+//! This module computes the 32-byte commitments recorded as `MEMO_HASH` on
+//! sharing transactions. A commitment binds a share to the exact document
+//! version that was shared without publishing any internal identifier.
+//!
+//! ## Commitment Format
+//!
+//! ```text
+//! commitment = SHA-256(
+//!     "NDA_SHARE_V1"
+//!     || len(process_id) || process_id
+//!     || len(partner_public_key) || partner_public_key
+//!     || salt                      (32 bytes, random per share)
+//!     || content_digest            (32 bytes, SHA-256 of the encrypted content)
+//! )
+//! ```
+//!
+//! Variable-length fields are prefixed with their length as a big-endian
+//! `u32`, so different inputs can never produce the same byte string.
+//!
+//! ## Privacy
+//!
+//! The per-share salt makes the commitment unlinkable to the process ID:
+//! observers of the ledger cannot confirm a guessed process ID without the
+//! salt, which is only stored in `process_shares`. Together with the stored
+//! inputs the commitment proves which encrypted document version was shared.
+//!
+//! ## Legacy Shares
+//!
+//! Shares created before commitments were introduced carry
+//! `SHA-256("NDA_SHARE:" || process_id)` as their memo hash; see
+//! [`legacy_memo_hash`].
+//!
+//! ## Signature Anchors
+//!
+//! Partners' signatures of NDA terms (see [`crate::terms`]) can be anchored
+//! with a transaction from the partner to the process owner carrying
+//!
+//! ```text
+//! anchor = SHA-256("NDA_SIGNATURE_V1" || terms_digest (32 bytes) || signature (64 bytes))
+//! ```
+
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+/// Domain separation tag of the current commitment format.
+const COMMITMENT_DOMAIN: &[u8] = b"NDA_SHARE_V1";
+
+/// Domain separation tag of signature anchors.
+const SIGNATURE_ANCHOR_DOMAIN: &[u8] = b"NDA_SIGNATURE_V1";
+
+/// Prefix of the memo text hashed by shares created before commitments.
+const LEGACY_MEMO_PREFIX: &str = "NDA_SHARE:";
+
+/// Generates a random 32-byte salt for a new share.
+pub fn generate_salt() -> [u8; 32] {
+    rand::thread_rng().gen()
+}
+
+/// Computes the SHA-256 digest of a process's stored encrypted content.
+///
+/// The digest covers the Base64 string exactly as stored in the database.
+pub fn content_digest(encrypted_content: &str) -> [u8; 32] {
+    Sha256::digest(encrypted_content.as_bytes()).into()
+}
+
+/// Computes the memo commitment for a share.
+///
+/// # Parameters
+///
+/// * `process_id` - ID of the shared process
+/// * `partner_public_key` - Stellar public key of the partner
+/// * `salt` - Random salt generated for this share
+/// * `content_digest` - Digest of the encrypted content at share time
+///
+/// # Returns
+///
+/// The 32-byte value attached to the sharing transaction as `MEMO_HASH`.
+pub fn share_commitment(
+    process_id: &str,
+    partner_public_key: &str,
+    salt: &[u8; 32],
+    content_digest: &[u8; 32],
+) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(COMMITMENT_DOMAIN);
+    for field in [process_id, partner_public_key] {
+        hasher.update((field.len() as u32).to_be_bytes());
+        hasher.update(field.as_bytes());
+    }
+    hasher.update(salt);
+    hasher.update(content_digest);
+    hasher.finalize().into()
+}
+
+/// Computes the memo anchoring a partner's signature of NDA terms.
+///
+/// The anchor commits to the signed terms digest and the ed25519 signature,
+/// so the on-chain record proves which terms were accepted.
+pub fn signature_anchor(terms_digest: &[u8; 32], signature: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(SIGNATURE_ANCHOR_DOMAIN);
+    hasher.update(terms_digest);
+    hasher.update(signature);
+    hasher.finalize().into()
+}
+
+/// Memo hash used by shares recorded before commitments were introduced.
+pub fn legacy_memo_hash(process_id: &str) -> [u8; 32] {
+    Sha256::digest(format!("{}{}", LEGACY_MEMO_PREFIX, process_id).as_bytes()).into()
+}
+
+/// Decodes a hex-encoded 32-byte value stored in the database.
+pub fn decode_hash(value: &str) -> Option<[u8; 32]> {
+    hex::decode(value).ok()?.try_into().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commitment_binds_every_input() {
+        let salt = [7u8; 32];
+        let digest = content_digest("ciphertext-v1");
+        let commitment = share_commitment("process-1", "GPARTNER", &salt, &digest);
+
+        assert_eq!(commitment, share_commitment("process-1", "GPARTNER", &salt, &digest));
+        assert_ne!(commitment, share_commitment("process-2", "GPARTNER", &salt, &digest));
+        assert_ne!(commitment, share_commitment("process-1", "GOTHER", &salt, &digest));
+        assert_ne!(commitment, share_commitment("process-1", "GPARTNER", &[8u8; 32], &digest));
+        assert_ne!(
+            commitment,
+            share_commitment("process-1", "GPARTNER", &salt, &content_digest("ciphertext-v2"))
+        );
+
+        // Length prefixes keep field boundaries unambiguous
+        assert_ne!(
+            share_commitment("ab", "c", &salt, &digest),
+            share_commitment("a", "bc", &salt, &digest)
+        );
+
+        assert_eq!(decode_hash(&hex::encode(commitment)), Some(commitment));
+        assert_eq!(decode_hash("abcd"), None);
+    }
+}
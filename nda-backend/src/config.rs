@@ -0,0 +1,272 @@
+//! # Configuration Module
+//!
+//! This module loads the Stellar network configuration used by [`StellarClient`].
+//! Instead of hard-coding testnet endpoints, the Horizon URL, network passphrase
+//! and Friendbot URL are resolved from a named preset, an optional JSON config
+//! file and environment variables, in that order of precedence (lowest first).
+//!
+//! ## Presets
+//!
+//! Selected with `STELLAR_NETWORK` (default `testnet`):
+//!
+//! - **testnet**: SDF test network with the public Friendbot
+//! - **mainnet**: Public network, no Friendbot (accounts must be funded externally)
+//! - **standalone**: Local quickstart container on `http://localhost:8000`
+//! - **custom**: No defaults, every value must be configured explicitly
+//!
+//! ## Environment Variables
+//!
+//! - `STELLAR_NETWORK`: Preset name (see above)
+//! - `STELLAR_CONFIG_FILE`: Path to a JSON file with any of the fields below
+//! - `STELLAR_HORIZON_URL`: Horizon API base URL
+//! - `STELLAR_NETWORK_PASSPHRASE`: Network passphrase used for transaction signing
+//! - `STELLAR_FRIENDBOT_URL`: Friendbot endpoint for account funding (empty disables it)
+//! - `STELLAR_HISTORY_MAX_PAGES`: Pages of 200 transactions searched when verifying
+//!   shares from history (default 50, `0` for no limit)
+//!
+//! ## Config File Example
+//!
+//! ```json
+//! {
+//!   "network": "standalone",
+//!   "horizon_url": "http://stellar:8000",
+//!   "friendbot_url": "http://stellar:8000/friendbot"
+//! }
+//! ```
+//!
+//! The configured passphrase is checked against Horizon's root resource at
+//! startup (see [`StellarClient::verify_network`]), so a client pointed at the
+//! wrong network fails fast instead of signing transactions that never validate.
+//!
+//! [`StellarClient`]: crate::stellar_real::StellarClient
+//! [`StellarClient::verify_network`]: crate::stellar_real::StellarClient::verify_network
+
+use serde::Deserialize;
+
+/// Horizon URL of the SDF test network.
+pub const TESTNET_HORIZON_URL: &str = "https://horizon-testnet.stellar.org";
+/// Network passphrase of the SDF test network.
+pub const TESTNET_PASSPHRASE: &str = "Test SDF Network ; September 2015";
+/// Public Friendbot of the SDF test network.
+pub const TESTNET_FRIENDBOT_URL: &str = "https://friendbot.stellar.org";
+
+/// Horizon URL of the public network.
+pub const MAINNET_HORIZON_URL: &str = "https://horizon.stellar.org";
+/// Network passphrase of the public network.
+pub const MAINNET_PASSPHRASE: &str = "Public Global Stellar Network ; September 2015";
+
+/// Horizon URL exposed by a local quickstart container.
+pub const STANDALONE_HORIZON_URL: &str = "http://localhost:8000";
+/// Network passphrase of a quickstart standalone network.
+pub const STANDALONE_PASSPHRASE: &str = "Standalone Network ; February 2017";
+/// Friendbot exposed by a local quickstart container.
+pub const STANDALONE_FRIENDBOT_URL: &str = "http://localhost:8000/friendbot";
+
+/// Connection settings for a Stellar network.
+///
+/// # Fields
+///
+/// * `network` - Name of the preset the settings were derived from
+/// * `horizon_url` - Horizon API base URL (no trailing slash)
+/// * `network_passphrase` - Passphrase transactions are signed for
+/// * `friendbot_url` - Friendbot endpoint, `None` when the network has no faucet
+/// * `history_max_pages` - Page limit for transaction history searches, `None` for no limit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StellarConfig {
+    pub network: String,
+    pub horizon_url: String,
+    pub network_passphrase: String,
+    pub friendbot_url: Option<String>,
+    pub history_max_pages: Option<usize>,
+}
+
+/// Default page limit for transaction history searches (10,000 transactions).
+pub const DEFAULT_HISTORY_MAX_PAGES: usize = 50;
+
+/// Optional overrides read from the JSON config file.
+#[derive(Debug, Default, Deserialize)]
+struct StellarConfigFile {
+    network: Option<String>,
+    horizon_url: Option<String>,
+    network_passphrase: Option<String>,
+    friendbot_url: Option<String>,
+    history_max_pages: Option<usize>,
+}
+
+impl StellarConfig {
+    /// Settings for the SDF test network.
+    pub fn testnet() -> Self {
+        Self {
+            network: "testnet".to_string(),
+            horizon_url: TESTNET_HORIZON_URL.to_string(),
+            network_passphrase: TESTNET_PASSPHRASE.to_string(),
+            friendbot_url: Some(TESTNET_FRIENDBOT_URL.to_string()),
+            history_max_pages: Some(DEFAULT_HISTORY_MAX_PAGES),
+        }
+    }
+
+    /// Settings for the public network.
+    pub fn mainnet() -> Self {
+        Self {
+            network: "mainnet".to_string(),
+            horizon_url: MAINNET_HORIZON_URL.to_string(),
+            network_passphrase: MAINNET_PASSPHRASE.to_string(),
+            friendbot_url: None,
+            history_max_pages: Some(DEFAULT_HISTORY_MAX_PAGES),
+        }
+    }
+
+    /// Settings for a local quickstart standalone network.
+    pub fn standalone() -> Self {
+        Self {
+            network: "standalone".to_string(),
+            horizon_url: STANDALONE_HORIZON_URL.to_string(),
+            network_passphrase: STANDALONE_PASSPHRASE.to_string(),
+            friendbot_url: Some(STANDALONE_FRIENDBOT_URL.to_string()),
+            history_max_pages: Some(DEFAULT_HISTORY_MAX_PAGES),
+        }
+    }
+
+    /// Returns the preset with the given name.
+    ///
+    /// `custom` yields empty settings that must be filled by the config file
+    /// or environment variables.
+    pub fn preset(network: &str) -> Result<Self, String> {
+        match network.trim().to_ascii_lowercase().as_str() {
+            "testnet" => Ok(Self::testnet()),
+            "mainnet" | "public" => Ok(Self::mainnet()),
+            "standalone" | "local" => Ok(Self::standalone()),
+            "custom" => Ok(Self {
+                network: "custom".to_string(),
+                horizon_url: String::new(),
+                network_passphrase: String::new(),
+                friendbot_url: None,
+                history_max_pages: Some(DEFAULT_HISTORY_MAX_PAGES),
+            }),
+            other => Err(format!(
+                "Invalid STELLAR_NETWORK '{}' (expected testnet, mainnet, standalone or custom)",
+                other
+            )),
+        }
+    }
+
+    /// Loads the configuration from the process environment.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result` containing:
+    /// - `Ok(StellarConfig)` - Resolved and validated settings
+    /// - `Err(String)` - Unknown preset, unreadable config file or missing values
+    pub fn from_env() -> Result<Self, String> {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Loads the configuration using `var` to look up environment variables.
+    ///
+    /// Precedence (lowest first): preset, config file, individual variables.
+    pub fn from_vars<F>(var: F) -> Result<Self, String>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let file = match var("STELLAR_CONFIG_FILE") {
+            Some(path) => {
+                let contents = std::fs::read_to_string(&path)
+                    .map_err(|e| format!("Failed to read Stellar config file '{}': {}", path, e))?;
+                serde_json::from_str::<StellarConfigFile>(&contents)
+                    .map_err(|e| format!("Invalid Stellar config file '{}': {}", path, e))?
+            }
+            None => StellarConfigFile::default(),
+        };
+
+        let network = var("STELLAR_NETWORK")
+            .or(file.network)
+            .unwrap_or_else(|| "testnet".to_string());
+        let mut config = Self::preset(&network)?;
+
+        if let Some(horizon_url) = var("STELLAR_HORIZON_URL").or(file.horizon_url) {
+            config.horizon_url = horizon_url;
+        }
+        if let Some(passphrase) = var("STELLAR_NETWORK_PASSPHRASE").or(file.network_passphrase) {
+            config.network_passphrase = passphrase;
+        }
+        if let Some(friendbot_url) = var("STELLAR_FRIENDBOT_URL").or(file.friendbot_url) {
+            // An empty value disables funding on networks that have a default Friendbot
+            config.friendbot_url = Some(friendbot_url).filter(|url| !url.trim().is_empty());
+        }
+        if let Some(max_pages) = var("STELLAR_HISTORY_MAX_PAGES").or(file.history_max_pages.map(|n| n.to_string())) {
+            let max_pages = max_pages.trim().parse::<usize>()
+                .map_err(|e| format!("Invalid STELLAR_HISTORY_MAX_PAGES '{}': {}", max_pages, e))?;
+            // Zero removes the limit and follows the history to its start
+            config.history_max_pages = Some(max_pages).filter(|n| *n > 0);
+        }
+
+        config.horizon_url = config.horizon_url.trim_end_matches('/').to_string();
+
+        if config.horizon_url.is_empty() {
+            return Err("STELLAR_HORIZON_URL must be set for a custom network".to_string());
+        }
+        if config.network_passphrase.is_empty() {
+            return Err("STELLAR_NETWORK_PASSPHRASE must be set for a custom network".to_string());
+        }
+
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn load(vars: &[(&str, &str)]) -> Result<StellarConfig, String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        StellarConfig::from_vars(|name| vars.get(name).cloned())
+    }
+
+    #[test]
+    fn test_presets_and_overrides() {
+        assert_eq!(load(&[]).unwrap(), StellarConfig::testnet());
+        assert_eq!(load(&[("STELLAR_NETWORK", "mainnet")]).unwrap().friendbot_url, None);
+
+        let config = load(&[
+            ("STELLAR_NETWORK", "standalone"),
+            ("STELLAR_HORIZON_URL", "http://quickstart:8000/"),
+            ("STELLAR_FRIENDBOT_URL", ""),
+            ("STELLAR_HISTORY_MAX_PAGES", "0"),
+        ])
+        .unwrap();
+        assert_eq!(config.horizon_url, "http://quickstart:8000");
+        assert_eq!(config.network_passphrase, STANDALONE_PASSPHRASE);
+        assert_eq!(config.friendbot_url, None);
+        assert_eq!(config.history_max_pages, None);
+
+        assert!(load(&[("STELLAR_NETWORK", "custom")]).is_err());
+        assert!(load(&[("STELLAR_HISTORY_MAX_PAGES", "many")]).is_err());
+        assert!(load(&[("STELLAR_NETWORK", "devnet")]).is_err());
+    }
+
+    #[test]
+    fn test_config_file_is_overridden_by_env() {
+        let path = std::env::temp_dir().join(format!("stellar-config-{}.json", uuid::Uuid::new_v4()));
+        std::fs::write(
+            &path,
+            r#"{"network": "custom", "horizon_url": "http://file:8000", "network_passphrase": "File Network"}"#,
+        )
+        .unwrap();
+
+        let config = load(&[
+            ("STELLAR_CONFIG_FILE", path.to_str().unwrap()),
+            ("STELLAR_HORIZON_URL", "http://env:8000"),
+        ])
+        .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(config.network, "custom");
+        assert_eq!(config.horizon_url, "http://env:8000");
+        assert_eq!(config.network_passphrase, "File Network");
+        assert_eq!(config.friendbot_url, None);
+    }
+}
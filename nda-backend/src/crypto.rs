@@ -1,23 +1,53 @@
 //! # Crypto Module
 //! 
 //! This module provides cryptographic functionality for the NDA backend system.
-//! It implements AES-256-GCM encryption for secure content protection with the following features:
+//! It implements authenticated encryption for secure content protection with the following features:
 //! 
-//! - **Encryption Algorithm**: AES-256-GCM (Galois/Counter Mode)
+//! - **Encryption Algorithms**: AES-256-GCM (default) and XChaCha20-Poly1305
 //! - **Key Size**: 256-bit keys generated using cryptographically secure random number generation
-//! - **Authentication**: Built-in authentication and integrity verification via GCM mode
-//! - **Nonce**: 96-bit (12 bytes) random nonce generated for each encryption operation
+//! - **Authentication**: Built-in authentication and integrity verification (AEAD)
+//! - **Nonce**: Random nonce generated for each encryption operation (96-bit for AES-GCM,
+//!   192-bit for XChaCha20-Poly1305)
 //! 
 //! ## Security Model
 //! 
 //! - Keys are generated using OS-provided cryptographically secure random number generator
 //! - Each encryption operation uses a unique, randomly generated nonce
-//! - The nonce is prepended to the ciphertext for storage/transmission
+//! - Ciphertexts are stored in a self-describing envelope, so the cipher can change
+//!   without breaking existing data
 //! - Base64 encoding is used for safe text representation of binary data
 //! 
+//! ## Ciphertext Envelope
+//! 
+//! ```text
+//! base64( "NDAE" || version (1) || algorithm (1) || key_id_len (1) || key_id || nonce || ciphertext + tag )
+//! ```
+//! 
+//! - `version`: Envelope format version ([`ENVELOPE_VERSION`])
+//! - `algorithm`: [`ContentCipher`] identifier (`1` AES-256-GCM, `2` XChaCha20-Poly1305)
+//! - `key_id`: Fingerprint of the encryption key ([`key_fingerprint`]), checked before decrypting
+//! 
+//! Values written before the envelope existed are `base64(nonce (12) || ciphertext + tag)`
+//! under AES-256-GCM. [`decrypt_content`] accepts both formats; the re-encryption job in
+//! [`crate::tasks`] upgrades legacy process rows.
+//! 
+//! ## Associated Data
+//! 
+//! Version 2 envelopes authenticate associated data (AAD) together with the
+//! ciphertext. Process content is bound to its process with [`process_aad`]
+//! (process ID, client ID and content version), so a ciphertext moved to
+//! another row fails with an [`IntegrityError`] instead of decrypting under
+//! the wrong process. Version 1 and legacy values carry no AAD and are only
+//! accepted when the caller passes empty associated data.
+//! 
+//! ## Configuration
+//! 
+//! - `CONTENT_CIPHER`: Cipher for new process content (`aes-256-gcm` or
+//!   `xchacha20-poly1305`, default `aes-256-gcm`)
+//! 
 //! ## Usage Example
 //! 
-//! ```rust
+//! ```rust,ignore
 //! use crate::crypto::{generate_key, encrypt_content, decrypt_content};
 //! 
 //! // Generate a new encryption key
@@ -37,11 +67,28 @@
 //! Common error scenarios include invalid keys, corrupted data, and encoding issues.
 
 use aes_gcm::{
-    aead::{Aead, KeyInit, OsRng},
+    aead::{Aead, KeyInit, OsRng, Payload},
     Aes256Gcm, Nonce,
 };
 use base64::{engine::general_purpose, Engine as _};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
 use rand::Rng;
+use sha2::{Digest, Sha256};
+
+/// Magic bytes opening every ciphertext envelope.
+const ENVELOPE_MAGIC: &[u8; 4] = b"NDAE";
+
+/// Current version of the ciphertext envelope (authenticates associated data).
+pub const ENVELOPE_VERSION: u8 = 2;
+
+/// First envelope version, written without associated data.
+const UNBOUND_ENVELOPE_VERSION: u8 = 1;
+
+/// Domain separation prefix of the process content AAD.
+const PROCESS_AAD_CONTEXT: &[u8] = b"nda-manager process content";
+
+/// Nonce length of legacy (pre-envelope) AES-256-GCM ciphertexts.
+const LEGACY_NONCE_LEN: usize = 12;
 
 /// Represents cryptographic operation errors.
 /// 
@@ -58,7 +105,7 @@ use rand::Rng;
 /// 
 /// # Error Handling Best Practices
 /// 
-/// ```rust
+/// ```rust,ignore
 /// match encrypt_content("data", &key) {
 ///     Ok(encrypted) => {
 ///         // Handle successful encryption
@@ -74,6 +121,13 @@ use rand::Rng;
 #[derive(Debug)]
 pub struct CryptoError(String);
 
+impl CryptoError {
+    /// Creates an error with the given description.
+    pub fn new(message: impl Into<String>) -> Self {
+        Self(message.into())
+    }
+}
+
 impl std::fmt::Display for CryptoError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "Cryptographic operation failed: {}", self.0)
@@ -82,6 +136,28 @@ impl std::fmt::Display for CryptoError {
 
 impl std::error::Error for CryptoError {}
 
+/// Ciphertext that does not belong to the data it was checked against.
+/// 
+/// Returned by [`decrypt_content`] when associated data was given and the
+/// value either authenticates different associated data (e.g. content moved
+/// to another process, or tampered with) or was never bound to any.
+/// Callers can tell it apart from other failures with [`is_integrity_error`].
+#[derive(Debug)]
+pub struct IntegrityError(String);
+
+impl std::fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Integrity check failed: {}", self.0)
+    }
+}
+
+impl std::error::Error for IntegrityError {}
+
+/// Checks if a decryption error is an [`IntegrityError`].
+pub fn is_integrity_error(error: &(dyn std::error::Error + 'static)) -> bool {
+    error.is::<IntegrityError>()
+}
+
 /// Generates a new cryptographically secure 256-bit AES key.
 /// 
 /// This function creates a new encryption key using the OS-provided cryptographically 
@@ -94,7 +170,7 @@ impl std::error::Error for CryptoError {}
 /// 
 /// # Examples
 /// 
-/// ```rust
+/// ```rust,ignore
 /// let encryption_key = generate_key();
 /// // The key is now ready for use with encrypt_content() and decrypt_content()
 /// ```
@@ -110,27 +186,212 @@ pub fn generate_key() -> String {
     general_purpose::STANDARD.encode(key)
 }
 
-/// Encrypts text content using AES-256-GCM encryption.
+/// Authenticated cipher used to encrypt content.
+///
+/// The discriminant is the algorithm identifier stored in the ciphertext
+/// envelope and must never change for an existing variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ContentCipher {
+    /// AES-256-GCM with a 96-bit random nonce
+    #[default]
+    Aes256Gcm = 1,
+    /// XChaCha20-Poly1305 with a 192-bit random nonce
+    XChaCha20Poly1305 = 2,
+}
+
+impl ContentCipher {
+    /// Algorithm identifier stored in the envelope.
+    pub fn id(self) -> u8 {
+        self as u8
+    }
+
+    /// Looks up a cipher by its envelope identifier.
+    pub fn from_id(id: u8) -> Option<Self> {
+        match id {
+            1 => Some(Self::Aes256Gcm),
+            2 => Some(Self::XChaCha20Poly1305),
+            _ => None,
+        }
+    }
+
+    /// Configuration name of the cipher (as used in `CONTENT_CIPHER`).
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Aes256Gcm => "aes-256-gcm",
+            Self::XChaCha20Poly1305 => "xchacha20-poly1305",
+        }
+    }
+
+    /// Parses a configuration name (case-insensitive).
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "aes-256-gcm" => Some(Self::Aes256Gcm),
+            "xchacha20-poly1305" => Some(Self::XChaCha20Poly1305),
+            _ => None,
+        }
+    }
+
+    /// Reads the cipher for new content from `CONTENT_CIPHER`.
+    pub fn from_env() -> Result<Self, String> {
+        match std::env::var("CONTENT_CIPHER") {
+            Ok(value) => Self::parse(&value).ok_or_else(|| {
+                format!(
+                    "Invalid CONTENT_CIPHER '{}' (expected 'aes-256-gcm' or 'xchacha20-poly1305')",
+                    value
+                )
+            }),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    /// Length of the random nonce generated for each encryption.
+    pub fn nonce_len(self) -> usize {
+        match self {
+            Self::Aes256Gcm => 12,
+            Self::XChaCha20Poly1305 => 24,
+        }
+    }
+
+    fn encrypt(self, key: &[u8], nonce: &[u8], plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let payload = Payload { msg: plaintext, aad };
+        let result = match self {
+            Self::Aes256Gcm => Aes256Gcm::new_from_slice(key)
+                .map_err(|_| CryptoError::new("Invalid key length"))?
+                .encrypt(Nonce::from_slice(nonce), payload),
+            Self::XChaCha20Poly1305 => XChaCha20Poly1305::new_from_slice(key)
+                .map_err(|_| CryptoError::new("Invalid key length"))?
+                .encrypt(XNonce::from_slice(nonce), payload),
+        };
+        result.map_err(|e| CryptoError(format!("Encryption failed: {:?}", e)))
+    }
+
+    fn decrypt(self, key: &[u8], nonce: &[u8], ciphertext: &[u8], aad: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let payload = Payload { msg: ciphertext, aad };
+        let result = match self {
+            Self::Aes256Gcm => Aes256Gcm::new_from_slice(key)
+                .map_err(|_| CryptoError::new("Invalid key length"))?
+                .decrypt(Nonce::from_slice(nonce), payload),
+            Self::XChaCha20Poly1305 => XChaCha20Poly1305::new_from_slice(key)
+                .map_err(|_| CryptoError::new("Invalid key length"))?
+                .decrypt(XNonce::from_slice(nonce), payload),
+        };
+        result.map_err(|e| CryptoError(format!("Decryption failed: {:?}", e)))
+    }
+}
+
+/// Header of a ciphertext envelope.
+///
+/// # Fields
+///
+/// * `version` - Envelope format version
+/// * `cipher` - Cipher that produced the ciphertext
+/// * `key_id` - Fingerprint of the encryption key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvelopeHeader {
+    pub version: u8,
+    pub cipher: ContentCipher,
+    pub key_id: String,
+}
+
+/// Derives the identifier recorded in envelopes from raw key bytes.
+///
+/// The first 16 hex digits of the key's SHA-256: enough to tell keys apart,
+/// without revealing anything useful about the key.
+pub fn key_fingerprint(key: &[u8]) -> String {
+    hex::encode(Sha256::digest(key))[..16].to_string()
+}
+
+/// Splits a decoded envelope into header, nonce and ciphertext.
+///
+/// Returns `None` if the data is not a well-formed envelope (legacy values).
+fn parse_envelope(data: &[u8]) -> Option<(EnvelopeHeader, &[u8], &[u8])> {
+    let rest = data.strip_prefix(ENVELOPE_MAGIC.as_slice())?;
+    let (&version, rest) = rest.split_first()?;
+    if version != ENVELOPE_VERSION && version != UNBOUND_ENVELOPE_VERSION {
+        return None;
+    }
+    let (&algorithm, rest) = rest.split_first()?;
+    let cipher = ContentCipher::from_id(algorithm)?;
+    let (&key_id_len, rest) = rest.split_first()?;
+    let key_id_len = key_id_len as usize;
+    if rest.len() < key_id_len + cipher.nonce_len() {
+        return None;
+    }
+    let (key_id, rest) = rest.split_at(key_id_len);
+    let key_id = std::str::from_utf8(key_id).ok()?.to_string();
+    let (nonce, ciphertext) = rest.split_at(cipher.nonce_len());
+
+    Some((EnvelopeHeader { version, cipher, key_id }, nonce, ciphertext))
+}
+
+/// Reads the envelope header of an encrypted value.
+///
+/// # Returns
+///
+/// - `Some(EnvelopeHeader)` - The value uses the envelope format
+/// - `None` - Legacy value (or not valid Base64)
+pub fn envelope_header(encrypted_content: &str) -> Option<EnvelopeHeader> {
+    let data = general_purpose::STANDARD.decode(encrypted_content).ok()?;
+    parse_envelope(&data).map(|(header, _, _)| header)
+}
+
+/// Builds the associated data binding content to its process.
+///
+/// The fields are length-prefixed, so no two different field combinations
+/// produce the same bytes.
+///
+/// # Parameters
+///
+/// * `process_id` - ID of the process owning the content
+/// * `client_id` - ID of the client owning the process
+/// * `content_version` - Version of the content within the process
+pub fn process_aad(process_id: &str, client_id: &str, content_version: i64) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(PROCESS_AAD_CONTEXT.len() + process_id.len() + client_id.len() + 16);
+    for field in [PROCESS_AAD_CONTEXT, process_id.as_bytes(), client_id.as_bytes()] {
+        aad.extend_from_slice(&(field.len() as u32).to_be_bytes());
+        aad.extend_from_slice(field);
+    }
+    aad.extend_from_slice(&content_version.to_be_bytes());
+    aad
+}
+
+/// Checks if a value authenticates associated data (version 2 envelope).
+///
+/// Version 1 envelopes and legacy values are not bound to anything.
+pub fn is_bound(encrypted_content: &str) -> bool {
+    envelope_header(encrypted_content).is_some_and(|header| header.version >= ENVELOPE_VERSION)
+}
+
+/// Decodes a Base64-encoded key into raw bytes.
+fn decode_key(key: &str) -> Result<Vec<u8>, CryptoError> {
+    general_purpose::STANDARD
+        .decode(key)
+        .map_err(|e| CryptoError(format!("Failed to decode key: {}", e)))
+}
+
+/// Encrypts text content with the default cipher (AES-256-GCM).
 /// 
-/// This function takes plain text content and encrypts it using the provided key with
-/// AES-256-GCM algorithm. Each encryption operation uses a unique random nonce for security.
+/// This function takes plain text content and encrypts it using the provided key.
+/// Each encryption operation uses a unique random nonce for security.
 /// 
 /// # Parameters
 /// 
 /// * `content` - The plain text content to encrypt. Must be valid UTF-8.
 /// * `key` - Base64-encoded encryption key (must be generated using `generate_key()` or equivalent)
+/// * `aad` - Associated data authenticated with the ciphertext (e.g. [`process_aad`]),
+///   empty when the value is not bound to anything
 /// 
 /// # Returns
 /// 
 /// Returns a `Result` containing:
-/// - `Ok(String)` - Base64-encoded encrypted data (nonce + ciphertext + authentication tag)
+/// - `Ok(String)` - Base64-encoded ciphertext envelope
 /// - `Err(Box<dyn std::error::Error>)` - Encryption error (invalid key, encryption failure, etc.)
 /// 
 /// # Examples
 /// 
-/// ```rust
+/// ```rust,ignore
 /// let key = generate_key();
-/// let encrypted = encrypt_content("Sensitive information", &key)?;
+/// let encrypted = encrypt_content("Sensitive information", &key, &process_aad(&id, &client_id, 1))?;
 /// // The encrypted string can now be safely stored or transmitted
 /// ```
 /// 
@@ -138,62 +399,91 @@ pub fn generate_key() -> String {
 /// 
 /// - Uses AES-256-GCM which provides both confidentiality and authenticity
 /// - A random 96-bit nonce is generated for each encryption operation
-/// - The nonce is prepended to the ciphertext for storage
 /// - The same plaintext will produce different ciphertext on each encryption
 /// 
 /// # Errors
 /// 
 /// This function may return errors for:
 /// - Invalid or corrupted Base64 key format
+/// - Keys that are not 256 bits long
 /// - Encryption algorithm failures
-/// - Memory allocation issues
-pub fn encrypt_content(content: &str, key: &str) -> Result<String, Box<dyn std::error::Error>> {
-    // Decode the Base64-encoded key into raw bytes
-    let key_bytes = general_purpose::STANDARD.decode(key)
-        .map_err(|e| CryptoError(format!("Failed to decode key: {}", e)))?;
-    
-    // Create AES-256-GCM cipher with the decoded key
-    let key = aes_gcm::Key::<Aes256Gcm>::from_slice(&key_bytes);
-    let cipher = Aes256Gcm::new(key);
-
-    // Generate a random 96-bit (12 bytes) nonce for this encryption operation
-    let nonce_bytes: [u8; 12] = rand::thread_rng().gen();
-    let nonce = Nonce::from_slice(&nonce_bytes);
-
-    // Encrypt the content using AES-256-GCM
-    let ciphertext = cipher.encrypt(nonce, content.as_bytes())
-        .map_err(|e| CryptoError(format!("Encryption failed: {:?}", e)))?;
-    
-    // Combine nonce + ciphertext for storage (nonce is needed for decryption)
-    let mut encrypted_data = nonce_bytes.to_vec();
-    encrypted_data.extend_from_slice(&ciphertext);
-    
-    // Encode the combined data as Base64 for safe text representation
-    Ok(general_purpose::STANDARD.encode(encrypted_data))
+pub fn encrypt_content(content: &str, key: &str, aad: &[u8]) -> Result<String, Box<dyn std::error::Error>> {
+    encrypt_content_with(content, key, ContentCipher::default(), aad)
+}
+
+/// Encrypts text content with the given cipher.
+/// 
+/// Produces a ciphertext envelope recording the envelope version, the cipher
+/// and the key fingerprint, followed by a fresh random nonce and the
+/// ciphertext with its authentication tag.
+/// 
+/// # Parameters
+/// 
+/// * `content` - The plain text content to encrypt
+/// * `key` - Base64-encoded 256-bit encryption key
+/// * `cipher` - Cipher to encrypt with
+/// * `aad` - Associated data authenticated with the ciphertext
+/// 
+/// # Returns
+/// 
+/// Returns a `Result` containing:
+/// - `Ok(String)` - Base64-encoded ciphertext envelope
+/// - `Err(Box<dyn std::error::Error>)` - Invalid key or encryption failure
+pub fn encrypt_content_with(
+    content: &str,
+    key: &str,
+    cipher: ContentCipher,
+    aad: &[u8],
+) -> Result<String, Box<dyn std::error::Error>> {
+    let key_bytes = decode_key(key)?;
+    let key_id = key_fingerprint(&key_bytes);
+
+    // Generate a random nonce of the length required by the cipher
+    let mut nonce = vec![0u8; cipher.nonce_len()];
+    rand::thread_rng().fill(nonce.as_mut_slice());
+
+    let ciphertext = cipher.encrypt(&key_bytes, &nonce, content.as_bytes(), aad)?;
+
+    // Header, then nonce + ciphertext (the nonce is needed for decryption)
+    let mut envelope = Vec::with_capacity(7 + key_id.len() + nonce.len() + ciphertext.len());
+    envelope.extend_from_slice(ENVELOPE_MAGIC);
+    envelope.push(ENVELOPE_VERSION);
+    envelope.push(cipher.id());
+    envelope.push(key_id.len() as u8);
+    envelope.extend_from_slice(key_id.as_bytes());
+    envelope.extend_from_slice(&nonce);
+    envelope.extend_from_slice(&ciphertext);
+
+    // Encode the envelope as Base64 for safe text representation
+    Ok(general_purpose::STANDARD.encode(envelope))
 }
 
 /// Decrypts content that was encrypted using `encrypt_content()`.
 /// 
 /// This function takes Base64-encoded encrypted content and decrypts it using the provided key.
-/// It verifies the authenticity and integrity of the data using AES-256-GCM's built-in authentication.
+/// It accepts both the ciphertext envelope and the legacy `nonce || ciphertext` format, and
+/// verifies the authenticity and integrity of the data using the cipher's built-in authentication.
 /// 
 /// # Parameters
 /// 
 /// * `encrypted_content` - Base64-encoded encrypted data (produced by `encrypt_content()`)
 /// * `key` - Base64-encoded decryption key (same key used for encryption)
+/// * `aad` - Associated data given at encryption; when not empty, unbound
+///   (version 1 or legacy) values are refused
 /// 
 /// # Returns
 /// 
 /// Returns a `Result` containing:
 /// - `Ok(String)` - The original plain text content
-/// - `Err(Box<dyn std::error::Error>)` - Decryption error (invalid key, corrupted data, etc.)
+/// - `Err(Box<dyn std::error::Error>)` - Decryption error (invalid key, corrupted data, etc.),
+///   an [`IntegrityError`] if the value does not match `aad`
 /// 
 /// # Examples
 /// 
-/// ```rust
+/// ```rust,ignore
 /// let key = generate_key();
-/// let encrypted = encrypt_content("Secret message", &key)?;
-/// let decrypted = decrypt_content(&encrypted, &key)?;
+/// let encrypted = encrypt_content("Secret message", &key, b"")?;
+/// let decrypted = decrypt_content(&encrypted, &key, b"")?;
 /// assert_eq!(decrypted, "Secret message");
 /// ```
 /// 
@@ -201,8 +491,8 @@ pub fn encrypt_content(content: &str, key: &str) -> Result<String, Box<dyn std::
 /// 
 /// - Automatically verifies data authenticity and integrity
 /// - Will fail if the data has been tampered with or corrupted
-/// - Will fail if an incorrect key is used
-/// - The nonce is automatically extracted from the encrypted data
+/// - Will fail if an incorrect key is used (detected from the key ID for envelopes)
+/// - The cipher and nonce are automatically extracted from the encrypted data
 /// 
 /// # Errors
 /// 
@@ -212,33 +502,151 @@ pub fn encrypt_content(content: &str, key: &str) -> Result<String, Box<dyn std::
 /// - Corrupted or tampered encrypted data
 /// - Data too short to contain valid nonce
 /// - UTF-8 conversion errors in the decrypted content
-pub fn decrypt_content(encrypted_content: &str, key: &str) -> Result<String, Box<dyn std::error::Error>> {
-    // Decode the Base64-encoded key into raw bytes
-    let key_bytes = general_purpose::STANDARD.decode(key)
-        .map_err(|e| CryptoError(format!("Failed to decode key: {}", e)))?;
-    
-    // Create AES-256-GCM cipher with the decoded key
-    let key = aes_gcm::Key::<Aes256Gcm>::from_slice(&key_bytes);
-    let cipher = Aes256Gcm::new(key);
+pub fn decrypt_content(encrypted_content: &str, key: &str, aad: &[u8]) -> Result<String, Box<dyn std::error::Error>> {
+    let key_bytes = decode_key(key)?;
 
     // Decode the Base64-encoded encrypted data
     let encrypted_data = general_purpose::STANDARD.decode(encrypted_content)
         .map_err(|e| CryptoError(format!("Failed to decode encrypted data: {}", e)))?;
-    
-    // Ensure the encrypted data is long enough to contain a nonce (12 bytes minimum)
-    if encrypted_data.len() < 12 {
-        return Err(Box::new(CryptoError("Invalid encrypted data: insufficient length".to_string())));
-    }
 
-    // Split the encrypted data into nonce (first 12 bytes) and ciphertext (remaining bytes)
-    let (nonce_bytes, ciphertext) = encrypted_data.split_at(12);
-    let nonce = Nonce::from_slice(nonce_bytes);
+    let unbound = || IntegrityError("Content is not bound to its associated data".to_string());
+
+    let plaintext = match parse_envelope(&encrypted_data) {
+        Some((header, nonce, ciphertext)) if header.key_id == key_fingerprint(&key_bytes) => {
+            if header.version == ENVELOPE_VERSION {
+                header.cipher.decrypt(&key_bytes, nonce, ciphertext, aad).map_err(|e| {
+                    // The key is right, so a failure means the data does not belong here
+                    if aad.is_empty() {
+                        Box::new(e) as Box<dyn std::error::Error>
+                    } else {
+                        Box::new(IntegrityError("Content does not match its associated data".to_string()))
+                    }
+                })?
+            } else if aad.is_empty() {
+                header.cipher.decrypt(&key_bytes, nonce, ciphertext, b"")?
+            } else {
+                return Err(Box::new(unbound()));
+            }
+        }
+        // A legacy nonce may start with the envelope magic by chance, so only
+        // report a key mismatch once the legacy format has been ruled out
+        Some((header, _, _)) => match decrypt_legacy(&encrypted_data, &key_bytes) {
+            Ok(_) if !aad.is_empty() => return Err(Box::new(unbound())),
+            Ok(plaintext) => plaintext,
+            Err(_) => {
+                return Err(Box::new(CryptoError(format!(
+                    "Key mismatch: content was encrypted under key {}",
+                    header.key_id
+                ))))
+            }
+        },
+        None => {
+            let plaintext = decrypt_legacy(&encrypted_data, &key_bytes)?;
+            if !aad.is_empty() {
+                return Err(Box::new(unbound()));
+            }
+            plaintext
+        }
+    };
 
-    // Decrypt the ciphertext using the nonce and verify authenticity
-    let plaintext = cipher.decrypt(nonce, ciphertext)
-        .map_err(|e| CryptoError(format!("Decryption failed: {:?}", e)))?;
-    
     // Convert the decrypted bytes back to a UTF-8 string
     String::from_utf8(plaintext)
         .map_err(|e| Box::new(CryptoError(format!("UTF-8 conversion error: {}", e))) as Box<dyn std::error::Error>)
-}
\ No newline at end of file
+}
+
+/// Decrypts a legacy `nonce (12) || ciphertext` value under AES-256-GCM.
+fn decrypt_legacy(encrypted_data: &[u8], key: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    // Ensure the encrypted data is long enough to contain a nonce (12 bytes minimum)
+    if encrypted_data.len() < LEGACY_NONCE_LEN {
+        return Err(CryptoError("Invalid encrypted data: insufficient length".to_string()));
+    }
+
+    let (nonce, ciphertext) = encrypted_data.split_at(LEGACY_NONCE_LEN);
+    ContentCipher::Aes256Gcm.decrypt(key, nonce, ciphertext, b"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encrypts in the format written before the envelope existed.
+    fn encrypt_legacy(content: &str, key: &str) -> String {
+        let key_bytes = decode_key(key).unwrap();
+        let nonce: [u8; LEGACY_NONCE_LEN] = rand::thread_rng().gen();
+        let mut data = nonce.to_vec();
+        data.extend(ContentCipher::Aes256Gcm.encrypt(&key_bytes, &nonce, content.as_bytes(), b"").unwrap());
+        general_purpose::STANDARD.encode(data)
+    }
+
+    #[test]
+    fn test_envelope_round_trip_and_legacy_format() {
+        let key = generate_key();
+        let other = generate_key();
+        let key_id = key_fingerprint(&decode_key(&key).unwrap());
+
+        for cipher in [ContentCipher::Aes256Gcm, ContentCipher::XChaCha20Poly1305] {
+            let encrypted = encrypt_content_with("Confidential", &key, cipher, b"").unwrap();
+            let header = envelope_header(&encrypted).unwrap();
+            assert_eq!(header.version, ENVELOPE_VERSION);
+            assert_eq!(header.cipher, cipher);
+            assert_eq!(header.key_id, key_id);
+            assert_eq!(decrypt_content(&encrypted, &key, b"").unwrap(), "Confidential");
+
+            let error = decrypt_content(&encrypted, &other, b"").unwrap_err().to_string();
+            assert!(error.contains("Key mismatch"), "{}", error);
+        }
+        assert_eq!(
+            envelope_header(&encrypt_content("x", &key, b"").unwrap()).unwrap().cipher,
+            ContentCipher::Aes256Gcm
+        );
+
+        // Values written before the envelope still decrypt
+        let legacy = encrypt_legacy("Old content", &key);
+        assert!(envelope_header(&legacy).is_none());
+        assert_eq!(decrypt_content(&legacy, &key, b"").unwrap(), "Old content");
+        assert!(decrypt_content(&legacy, &other, b"").is_err());
+
+        // Tampering is detected
+        let mut data = general_purpose::STANDARD
+            .decode(encrypt_content_with("Confidential", &key, ContentCipher::XChaCha20Poly1305, b"").unwrap())
+            .unwrap();
+        let last = data.len() - 1;
+        data[last] ^= 1;
+        assert!(decrypt_content(&general_purpose::STANDARD.encode(data), &key, b"").is_err());
+
+        assert!(encrypt_content("x", &general_purpose::STANDARD.encode([0u8; 16]), b"").is_err());
+        assert_eq!(ContentCipher::parse("XChaCha20-Poly1305"), Some(ContentCipher::XChaCha20Poly1305));
+        assert_eq!(ContentCipher::parse("des"), None);
+    }
+
+    #[test]
+    fn test_process_aad_binds_content_to_its_process() {
+        let key = generate_key();
+        let aad = process_aad("process-1", "client-1", 1);
+        let encrypted = encrypt_content("Confidential", &key, &aad).unwrap();
+        assert!(is_bound(&encrypted));
+        assert_eq!(decrypt_content(&encrypted, &key, &aad).unwrap(), "Confidential");
+
+        // Another process, owner or content version is an integrity error
+        for other in [
+            process_aad("process-2", "client-1", 1),
+            process_aad("process-1", "client-2", 1),
+            process_aad("process-1", "client-1", 2),
+        ] {
+            let error = decrypt_content(&encrypted, &key, &other).unwrap_err();
+            assert!(is_integrity_error(error.as_ref()), "{}", error);
+        }
+        // Length prefixes keep field boundaries apart
+        assert_ne!(process_aad("ab", "c", 1), process_aad("a", "bc", 1));
+
+        // Unbound values are refused once associated data is expected
+        let unbound = encrypt_legacy("Old content", &key);
+        assert!(!is_bound(&unbound));
+        assert!(is_integrity_error(decrypt_content(&unbound, &key, &aad).unwrap_err().as_ref()));
+        assert_eq!(decrypt_content(&unbound, &key, b"").unwrap(), "Old content");
+
+        // A wrong key is not reported as an integrity error
+        let error = decrypt_content(&encrypted, &generate_key(), &aad).unwrap_err();
+        assert!(!is_integrity_error(error.as_ref()));
+    }
+}
@@ -13,7 +13,7 @@
 //! - `id`: Unique identifier (UUID)
 //! - `username`: Unique username for authentication
 //! - `stellar_public_key`: Stellar network public key
-//! - `stellar_secret_key`: Encrypted Stellar network secret key
+//! - `stellar_secret_key`: Stellar secret key sealed by the key provider (`sealed:...`)
 //! - `roles`: User roles as JSON array: `["client"]`, `["partner"]`, or `["client","partner"]`
 //! - `created_at`: Account creation timestamp
 //! 
@@ -23,9 +23,14 @@
 //! - `client_id`: Reference to the owning client user
 //! - `title`: Process title/name
 //! - `description`: Detailed process description
-//! - `encrypted_content`: AES-256-GCM encrypted process content
-//! - `encryption_key`: Base64-encoded encryption key
-//! - `status`: Process status ('active', 'completed', etc.)
+//! - `encrypted_content`: Encrypted process content, bound to the process ID,
+//!   client ID and content version as associated data (see [`crate::crypto`])
+//! - `encryption_key`: Data key wrapped by a key-encryption key (see [`crate::keys`])
+//! - `key_id`: ID of the key-encryption key, `NULL` for legacy plaintext keys
+//! - `content_version`: Version of the content, part of its associated data
+//! - `status`: Lifecycle status (`draft`, `active`, `suspended`, `closed` or `archived`)
+//! - `template_id`: NDA template the content was rendered from, `NULL` for free-form content
+//! - `template_version`: Version of that template
 //! - `created_at`: Process creation timestamp
 //! 
 //! ### Process Shares Table
@@ -34,6 +39,7 @@
 //! - `process_id`: Reference to the shared process
 //! - `partner_public_key`: Stellar public key of the recipient
 //! - `stellar_transaction_hash`: Blockchain transaction hash
+//! - `stellar_ledger`: Ledger the transaction was included in, `NULL` when unknown
 //! - `shared_at`: Share timestamp
 //! 
 //! ### Process Accesses Table
@@ -42,10 +48,67 @@
 //! - `process_id`: Reference to the accessed process
 //! - `partner_id`: Reference to the accessing partner
 //! - `accessed_at`: Access timestamp
+//! - `attachment_id`: Downloaded attachment, `NULL` for access to the text content
+//! - `content_version`: Content revision current when the partner accessed the process
+//! 
+//! ### Process Revisions Table
+//! Every version of the process content (the current one is mirrored in `processes`):
+//! - `id`: Unique revision identifier (UUID)
+//! - `process_id`: Reference to the process
+//! - `version`: Content version, starting at 1 (unique per process)
+//! - `encrypted_content`: Content of this version, encrypted under the process data key
+//! - `author_id`: User who wrote this version
+//! - `change_note`: Optional description of the change
+//! - `created_at`: Revision timestamp
+//! 
+//! ### Process Transitions Table
+//! Every change of the lifecycle status of a process:
+//! - `id`: Unique transition identifier (UUID)
+//! - `process_id`: Reference to the process
+//! - `from_status`: Previous status, `NULL` for the creation of the process
+//! - `to_status`: New status
+//! - `actor_id`: User who changed the status
+//! - `reason`: Reason given for the change
+//! - `created_at`: Transition timestamp
+//! 
+//! ### Terms Signatures Table
+//! Partners' ed25519 signatures of the terms of a process (see [`crate::terms`]):
+//! - `id`: Unique signature identifier (UUID)
+//! - `process_id`: Reference to the process
+//! - `partner_id`: Reference to the signing partner
+//! - `partner_public_key`: Stellar public key the signature verifies under
+//! - `content_version`: Content revision covered by the signed terms
+//! - `terms_digest`: Hex-encoded digest that was signed
+//! - `signature`: Base64-encoded signature
+//! - `signed_at`: Signature timestamp
+//! - `anchor_transaction_hash`: Stellar transaction anchoring the signature, if any
+//! 
+//! ### NDA Templates Tables
+//! Reusable NDA templates of a client (see [`crate::templates`]); `nda_templates`
+//! holds the template and its current version number, `nda_template_versions`
+//! every version:
+//! - `id`: Unique template (or version) identifier (UUID)
+//! - `owner_id`: Client who owns the template
+//! - `name`, `description`: Template name and description
+//! - `current_version`: Latest version of the template
+//! - `archived_at`: When the template was deleted, `NULL` while in use
+//! - `version`: Version number, starting at 1 (unique per template)
+//! - `body`: Template body with `{{name}}` placeholders
+//! - `placeholders`: JSON array of typed placeholder declarations
+//! - `author_id`, `change_note`, `created_at`: Who wrote the version, why and when
+//! 
+//! ### Process Attachments Table
+//! Metadata of encrypted files attached to processes (see [`crate::attachments`]):
+//! - `id`: Unique attachment identifier (UUID), also naming the encrypted file
+//! - `process_id`: Reference to the owning process
+//! - `filename`: Original filename
+//! - `content_type`: MIME type given at upload
+//! - `size_bytes`: Plaintext size in bytes
+//! - `created_at`: Upload timestamp
 //! 
 //! ## Usage Example
 //! 
-//! ```rust
+//! ```rust,ignore
 //! use crate::database::{init_database, queries};
 //! 
 //! // Initialize database with migrations
@@ -63,10 +126,14 @@
 //! // Create a process
 //! let process = queries::create_process(
 //!     &pool,
+//!     &process_id,
 //!     &user.id,
 //!     "Confidential Agreement",
+//!     "Agreement details",
 //!     "encrypted_content",
-//!     "encryption_key"
+//!     &content_sha256,
+//!     &wrapped.wrapped_key,
+//!     Some(&wrapped.key_id)
 //! ).await?;
 //! ```
 //! 
@@ -104,7 +171,7 @@ use chrono::{DateTime, Utc};
 /// 
 /// # Examples
 /// 
-/// ```rust
+/// ```rust,ignore
 /// use crate::database::init_database;
 /// 
 /// let pool = init_database().await?;
@@ -146,7 +213,7 @@ pub async fn init_database() -> Result<SqlitePool, Box<dyn Error>> {
 
 /// Executes database migrations to create all required tables.
 /// 
-/// This internal function creates the database schema by executing DDL statements
+/// This function creates the database schema by executing DDL statements
 /// for all required tables. It's designed to be idempotent - running it multiple
 /// times is safe and will not cause errors.
 /// 
@@ -164,7 +231,20 @@ pub async fn init_database() -> Result<SqlitePool, Box<dyn Error>> {
 /// - **processes**: Encrypted NDA process content and metadata
 /// - **process_shares**: Blockchain sharing records with transaction hashes
 /// - **process_accesses**: Access audit logs for compliance tracking
-async fn run_migrations(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+/// 
+/// # Data Upgrades
+/// 
+/// Upgrades that need key material are not schema migrations and run from
+/// `main` instead:
+/// 
+/// - Plaintext `users.stellar_secret_key` values are sealed at startup by
+///   [`crate::key_provider::seal_plaintext_secrets`]
+/// - Process content and revisions from before content binding are bound to
+///   their process once by [`crate::tasks::bind_process_content`], which
+///   records itself in `data_migrations`
+/// - Bound process content under another cipher, and data keys wrapped in
+///   the legacy format, are upgraded by [`crate::tasks::reencrypt_processes`]
+pub async fn run_migrations(pool: &SqlitePool) -> Result<(), sqlx::Error> {
     println!("🔄 Running migrations...");
     
     // Create users table with new roles system
@@ -236,9 +316,15 @@ async fn run_migrations(pool: &SqlitePool) -> Result<(), sqlx::Error> {
             id TEXT PRIMARY KEY,
             client_id TEXT NOT NULL,
             title TEXT NOT NULL,
+            description TEXT NOT NULL DEFAULT '',
             encrypted_content TEXT NOT NULL,
             encryption_key TEXT NOT NULL,
+            key_id TEXT,
+            content_version INTEGER NOT NULL DEFAULT 1,
+            content_sha256 TEXT,
             status TEXT NOT NULL DEFAULT 'active',
+            template_id TEXT,
+            template_version INTEGER,
             created_at TEXT NOT NULL
         )
         "#,
@@ -246,6 +332,26 @@ async fn run_migrations(pool: &SqlitePool) -> Result<(), sqlx::Error> {
     .execute(pool)
     .await?;
 
+    // Migration: Add description column if it doesn't exist (for existing databases)
+    let _ = sqlx::query("ALTER TABLE processes ADD COLUMN description TEXT NOT NULL DEFAULT ''")
+        .execute(pool)
+        .await;
+
+    // Migration: Add key-encryption key ID column if it doesn't exist (NULL marks legacy plaintext keys)
+    let _ = sqlx::query("ALTER TABLE processes ADD COLUMN key_id TEXT")
+        .execute(pool)
+        .await;
+
+    // Migration: Add content version column (part of the content's associated data)
+    let _ = sqlx::query("ALTER TABLE processes ADD COLUMN content_version INTEGER NOT NULL DEFAULT 1")
+        .execute(pool)
+        .await;
+
+    // Migration: Plaintext digest of the current revision, so NDA terms are built without decrypting
+    let _ = sqlx::query("ALTER TABLE processes ADD COLUMN content_sha256 TEXT")
+        .execute(pool)
+        .await;
+
     // Create process shares table
     sqlx::query(
         r#"
@@ -254,13 +360,52 @@ async fn run_migrations(pool: &SqlitePool) -> Result<(), sqlx::Error> {
             process_id TEXT NOT NULL,
             partner_public_key TEXT NOT NULL,
             stellar_transaction_hash TEXT NOT NULL,
-            shared_at TEXT NOT NULL
+            stellar_ledger INTEGER,
+            shared_at TEXT NOT NULL,
+            memo_salt TEXT,
+            content_digest TEXT,
+            memo_commitment TEXT,
+            revoked_at TEXT,
+            revoked_by TEXT,
+            revocation_reason TEXT,
+            revocation_transaction_hash TEXT,
+            valid_from TEXT,
+            expires_at TEXT,
+            expired_at TEXT,
+            max_accesses INTEGER,
+            wrapped_key TEXT
         )
         "#,
     )
     .execute(pool)
     .await?;
 
+    // Migration: Add share commitment, revocation and validity columns if they don't exist (for existing databases)
+    for column in [
+        "memo_salt",
+        "content_digest",
+        "memo_commitment",
+        "revoked_at",
+        "revoked_by",
+        "revocation_reason",
+        "revocation_transaction_hash",
+        "valid_from",
+        "expires_at",
+        "expired_at",
+        "wrapped_key",
+    ] {
+        let _ = sqlx::query(&format!("ALTER TABLE process_shares ADD COLUMN {} TEXT", column))
+            .execute(pool)
+            .await;
+    }
+    let _ = sqlx::query("ALTER TABLE process_shares ADD COLUMN max_accesses INTEGER")
+        .execute(pool)
+        .await;
+    // Ledger of the sharing transaction (NULL for shares recorded before it was kept)
+    let _ = sqlx::query("ALTER TABLE process_shares ADD COLUMN stellar_ledger INTEGER")
+        .execute(pool)
+        .await;
+
     // Create process accesses table
     sqlx::query(
         r#"
@@ -268,7 +413,302 @@ async fn run_migrations(pool: &SqlitePool) -> Result<(), sqlx::Error> {
             id TEXT PRIMARY KEY,
             process_id TEXT NOT NULL,
             partner_id TEXT NOT NULL,
-            accessed_at TEXT NOT NULL
+            accessed_at TEXT NOT NULL,
+            share_id TEXT,
+            attachment_id TEXT,
+            content_version INTEGER
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Migration: Link accesses to the share that granted them (for existing databases)
+    let _ = sqlx::query("ALTER TABLE process_accesses ADD COLUMN share_id TEXT")
+        .execute(pool)
+        .await;
+    let _ = sqlx::query("ALTER TABLE process_accesses ADD COLUMN attachment_id TEXT")
+        .execute(pool)
+        .await;
+    // Content revision seen by the partner (NULL for accesses recorded before revisions existed)
+    let _ = sqlx::query("ALTER TABLE process_accesses ADD COLUMN content_version INTEGER")
+        .execute(pool)
+        .await;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_process_accesses_share ON process_accesses (share_id, partner_id)")
+        .execute(pool)
+        .await?;
+
+    // Create notifications table
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS notifications (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            process_id TEXT NOT NULL,
+            share_id TEXT,
+            message TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Create process attachments table (encrypted file contents live in ATTACHMENTS_DIR)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS process_attachments (
+            id TEXT PRIMARY KEY,
+            process_id TEXT NOT NULL,
+            filename TEXT NOT NULL,
+            content_type TEXT NOT NULL,
+            size_bytes INTEGER NOT NULL,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (process_id) REFERENCES processes (id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_process_attachments_process ON process_attachments (process_id)")
+        .execute(pool)
+        .await?;
+
+    // Migration: File keys stored under the current data key after it was rotated
+    let _ = sqlx::query("ALTER TABLE process_attachments ADD COLUMN file_key TEXT")
+        .execute(pool)
+        .await;
+
+    // Create process revisions table
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS process_revisions (
+            id TEXT PRIMARY KEY,
+            process_id TEXT NOT NULL,
+            version INTEGER NOT NULL,
+            encrypted_content TEXT NOT NULL,
+            author_id TEXT NOT NULL,
+            change_note TEXT,
+            created_at TEXT NOT NULL,
+            UNIQUE (process_id, version),
+            FOREIGN KEY (process_id) REFERENCES processes (id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Migration: Record the content of processes created before revisions existed as their first revision
+    sqlx::query(
+        r#"
+        INSERT OR IGNORE INTO process_revisions (id, process_id, version, encrypted_content, author_id, change_note, created_at)
+        SELECT lower(hex(randomblob(16))), id, content_version, encrypted_content, client_id, NULL, created_at
+        FROM processes
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Migration: Map free-form statuses from before the lifecycle onto it
+    sqlx::query("UPDATE processes SET status = 'closed' WHERE status = 'completed'")
+        .execute(pool)
+        .await?;
+    sqlx::query(
+        "UPDATE processes SET status = 'active' WHERE status NOT IN ('draft', 'active', 'suspended', 'closed', 'archived')",
+    )
+    .execute(pool)
+    .await?;
+
+    // Create process transitions table
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS process_transitions (
+            id TEXT PRIMARY KEY,
+            process_id TEXT NOT NULL,
+            from_status TEXT,
+            to_status TEXT NOT NULL,
+            actor_id TEXT NOT NULL,
+            reason TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (process_id) REFERENCES processes (id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_process_transitions_process ON process_transitions (process_id)")
+        .execute(pool)
+        .await?;
+
+    // Migration: Record the creation of processes created before transitions were tracked
+    sqlx::query(
+        r#"
+        INSERT INTO process_transitions (id, process_id, from_status, to_status, actor_id, reason, created_at)
+        SELECT lower(hex(randomblob(16))), p.id, NULL, p.status, p.client_id, 'Process created', p.created_at
+        FROM processes p
+        WHERE NOT EXISTS (SELECT 1 FROM process_transitions t WHERE t.process_id = p.id)
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Create terms signatures table
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS terms_signatures (
+            id TEXT PRIMARY KEY,
+            process_id TEXT NOT NULL,
+            partner_id TEXT NOT NULL,
+            partner_public_key TEXT NOT NULL,
+            content_version INTEGER NOT NULL,
+            terms_digest TEXT NOT NULL,
+            signature TEXT NOT NULL,
+            signed_at TEXT NOT NULL,
+            anchor_transaction_hash TEXT,
+            UNIQUE (process_id, partner_id, terms_digest),
+            FOREIGN KEY (process_id) REFERENCES processes (id),
+            FOREIGN KEY (partner_id) REFERENCES users (id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Create NDA templates tables (the current version is mirrored in nda_templates)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS nda_templates (
+            id TEXT PRIMARY KEY,
+            owner_id TEXT NOT NULL,
+            name TEXT NOT NULL,
+            description TEXT NOT NULL DEFAULT '',
+            current_version INTEGER NOT NULL DEFAULT 1,
+            created_at TEXT NOT NULL,
+            archived_at TEXT,
+            FOREIGN KEY (owner_id) REFERENCES users (id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS nda_template_versions (
+            id TEXT PRIMARY KEY,
+            template_id TEXT NOT NULL,
+            version INTEGER NOT NULL,
+            body TEXT NOT NULL,
+            placeholders TEXT NOT NULL,
+            author_id TEXT NOT NULL,
+            change_note TEXT,
+            created_at TEXT NOT NULL,
+            UNIQUE (template_id, version),
+            FOREIGN KEY (template_id) REFERENCES nda_templates (id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_nda_templates_owner ON nda_templates (owner_id)")
+        .execute(pool)
+        .await?;
+
+    // Migration: Record the template version processes were created from (NULL for free-form content)
+    let _ = sqlx::query("ALTER TABLE processes ADD COLUMN template_id TEXT")
+        .execute(pool)
+        .await;
+    let _ = sqlx::query("ALTER TABLE processes ADD COLUMN template_version INTEGER")
+        .execute(pool)
+        .await;
+
+    // Create revoked tokens table (JWT blacklist shared by every instance)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS revoked_tokens (
+            jti TEXT PRIMARY KEY,
+            expires_at INTEGER NOT NULL,
+            revoked_at TEXT NOT NULL
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_revoked_tokens_expires_at ON revoked_tokens (expires_at)")
+        .execute(pool)
+        .await?;
+
+    // Create refresh token family tables (rotation chains of each login)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS refresh_token_families (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            revoked_at TEXT,
+            revocation_reason TEXT,
+            FOREIGN KEY (user_id) REFERENCES users (id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS refresh_tokens (
+            jti TEXT PRIMARY KEY,
+            family_id TEXT NOT NULL,
+            parent_jti TEXT,
+            issued_at TEXT NOT NULL,
+            expires_at INTEGER NOT NULL,
+            rotated_at TEXT,
+            FOREIGN KEY (family_id) REFERENCES refresh_token_families (id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_refresh_tokens_family ON refresh_tokens (family_id)")
+        .execute(pool)
+        .await?;
+
+    // Create sessions table (one per login, tied to its refresh token family)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS sessions (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL,
+            family_id TEXT UNIQUE NOT NULL,
+            user_agent TEXT,
+            ip_address TEXT,
+            created_at TEXT NOT NULL,
+            last_refreshed_at TEXT,
+            FOREIGN KEY (user_id) REFERENCES users (id),
+            FOREIGN KEY (family_id) REFERENCES refresh_token_families (id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_sessions_user ON sessions (user_id)")
+        .execute(pool)
+        .await?;
+
+    // Create data migrations table (one-time upgrades run from main, see below)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS data_migrations (
+            name TEXT PRIMARY KEY,
+            applied_at TEXT NOT NULL
         )
         "#,
     )
@@ -320,10 +760,45 @@ fn string_to_datetime(s: &str) -> Result<DateTime<Utc>, chrono::ParseError> {
 /// prepared statements for SQL injection prevention.
 pub mod queries {
     use super::*;
+    use crate::key_provider::SealedSecret;
     use crate::models::*;
     use uuid::Uuid;
     use sqlx::Row;
 
+    /// Columns selected for every [`ProcessShare`] query.
+    const PROCESS_SHARE_COLUMNS: &str = "id, process_id, partner_public_key, stellar_transaction_hash, stellar_ledger, shared_at, \
+        memo_salt, content_digest, memo_commitment, \
+        revoked_at, revoked_by, revocation_reason, revocation_transaction_hash, \
+        valid_from, expires_at, expired_at, max_accesses, wrapped_key";
+
+    /// A process moved to a fresh data key, prepared by the caller.
+    /// 
+    /// Everything the previous data key opened is re-encrypted or re-sealed
+    /// under the new one, so nobody holding only the previous key (e.g. a
+    /// revoked zero-knowledge partner) can read what is written afterwards.
+    /// 
+    /// # Fields
+    /// 
+    /// * `previous_key` - Wrapped data key the rotation was prepared from
+    /// * `content_version` - Content version of the process the rotation was prepared at
+    /// * `encryption_key` - New data key, wrapped by a key-encryption key
+    /// * `key_id` - ID of the key-encryption key wrapping `encryption_key`
+    /// * `revisions` - Content version and content of every revision under the new key
+    /// * `file_keys` - Attachment ID and file key encrypted under the new key
+    ///   (see [`crate::attachments::wrap_file_key`])
+    /// * `sealed_keys` - Share ID and new key sealed to the partner, for every
+    ///   zero-knowledge share that is not revoked
+    #[derive(Debug, Clone)]
+    pub struct DataKeyRotation {
+        pub previous_key: String,
+        pub content_version: i64,
+        pub encryption_key: String,
+        pub key_id: String,
+        pub revisions: Vec<(i64, String)>,
+        pub file_keys: Vec<(String, String)>,
+        pub sealed_keys: Vec<(String, String)>,
+    }
+
     /// Creates a new user account in the database.
     /// 
     /// This function creates a new user with Stellar blockchain integration,
@@ -335,7 +810,8 @@ pub mod queries {
     /// * `username` - Unique username for the account
     /// * `name` - Full name or display name of the user
     /// * `stellar_public_key` - User's Stellar network public key
-    /// * `stellar_secret_key` - Encrypted Stellar network secret key
+    /// * `stellar_secret_key` - Stellar secret key sealed by a [`crate::key_provider::KeyProvider`]
+    /// * `password_hash` - Bcrypt hash of the user's password
     /// * `roles` - User roles as JSON string: `["client"]`, `["partner"]`, or `["client","partner"]`
     ///
     /// # Returns
@@ -346,13 +822,14 @@ pub mod queries {
     ///
     /// # Examples
     ///
-    /// ```rust
+    /// ```rust,ignore
     /// let user = queries::create_user(
     ///     &pool,
     ///     "john_doe",
     ///     "John Doe",
     ///     "GCKFBEIYTKP...",
-    ///     "encrypted_secret",
+    ///     &state.key_provider.seal(&secret_key).await?,
+    ///     &password_hash,
     ///     r#"["client"]"#
     /// ).await?;
     /// ```
@@ -369,7 +846,7 @@ pub async fn create_user(
     username: &str,
     name: &str,
     stellar_public_key: &str,
-    stellar_secret_key: &str,
+    stellar_secret_key: &SealedSecret,
     password_hash: &str,
     roles: &str,
 ) -> Result<User, sqlx::Error> {
@@ -387,7 +864,7 @@ pub async fn create_user(
     .bind(username)
     .bind(name)
     .bind(stellar_public_key)
-    .bind(stellar_secret_key)
+    .bind(stellar_secret_key.as_str())
     .bind(password_hash)
     .bind(roles)
     .bind(&created_at_str)
@@ -399,7 +876,7 @@ pub async fn create_user(
         username: username.to_string(),
         name: name.to_string(),
         stellar_public_key: stellar_public_key.to_string(),
-        stellar_secret_key: stellar_secret_key.to_string(),
+        stellar_secret_key: stellar_secret_key.as_str().to_string(),
         password_hash: password_hash.to_string(),
         roles: roles.to_string(),
         created_at,
@@ -424,7 +901,7 @@ pub async fn create_user(
     /// 
     /// # Examples
     /// 
-    /// ```rust
+    /// ```rust,ignore
     /// match queries::find_user_by_username(&pool, "john_doe").await? {
     ///     Some(user) => println!("Found user: {}", user.username),
     ///     None => println!("User not found"),
@@ -483,7 +960,7 @@ pub async fn create_user(
     /// 
     /// # Examples
     /// 
-    /// ```rust
+    /// ```rust,ignore
     /// match queries::find_user_by_id(&pool, &user_id).await? {
     ///     Some(user) => println!("Found user: {}", user.username),
     ///     None => println!("User not found"),
@@ -522,19 +999,55 @@ pub async fn create_user(
         }
     }
 
+    /// Finds a user by their Stellar public key.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pool` - Database connection pool
+    /// * `stellar_public_key` - Stellar public key (`G...`) to search for
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(Some(User))` - User owning the key
+    /// - `Ok(None)` - No user registered with that key
+    /// - `Err(sqlx::Error)` - Database error or datetime parsing failure
+    pub async fn find_user_by_stellar_public_key(
+        pool: &SqlitePool,
+        stellar_public_key: &str,
+    ) -> Result<Option<User>, sqlx::Error> {
+        let id: Option<String> = sqlx::query_scalar("SELECT id FROM users WHERE stellar_public_key = ?1")
+            .bind(stellar_public_key)
+            .fetch_optional(pool)
+            .await?;
+
+        match id {
+            Some(id) => find_user_by_id(pool, &id).await,
+            None => Ok(None),
+        }
+    }
+
     /// Creates a new NDA process with encrypted content.
     /// 
     /// This function creates a new process owned by a client user, with all
-    /// sensitive content encrypted using AES-256-GCM. The process starts
-    /// in 'active' status and gets a unique UUID identifier.
+    /// sensitive content encrypted beforehand. The process starts in `status`
+    /// (`active`, or `draft` for processes not ready to be shared) at content
+    /// version 1, which is also recorded as its first revision (authored by
+    /// the client). The creation is recorded as the first status transition.
     /// 
     /// # Parameters
     /// 
     /// * `pool` - Database connection pool
+    /// * `id` - UUID of the process, generated by the caller because the
+    ///   content is encrypted with the process ID as associated data
     /// * `client_id` - ID of the client user creating the process
     /// * `title` - Human-readable title for the process
     /// * `encrypted_content` - Base64-encoded encrypted process content
-    /// * `encryption_key` - Base64-encoded encryption key for the content
+    /// * `content_sha256` - Hex SHA-256 of the plaintext content (see [`crate::terms::content_sha256`])
+    /// * `encryption_key` - Data key of the content, wrapped by a key-encryption key
+    /// * `key_id` - ID of the key-encryption key (`None` only for plaintext data keys)
+    /// * `status` - Initial lifecycle status
+    /// * `template` - Template version the content was rendered from, if any
     /// 
     /// # Returns
     /// 
@@ -544,13 +1057,19 @@ pub async fn create_user(
     /// 
     /// # Examples
     /// 
-    /// ```rust
+    /// ```rust,ignore
     /// let process = queries::create_process(
     ///     &pool,
+    ///     &process_id,
     ///     &user.id,
     ///     "Software Development NDA",
+    ///     "Confidential project details",
     ///     "base64_encrypted_content",
-    ///     "base64_encryption_key"
+    ///     &hex::encode(terms::content_sha256(&content)),
+    ///     &wrapped.wrapped_key,
+    ///     Some(&wrapped.key_id),
+    ///     ProcessStatus::Active,
+    ///     None
     /// ).await?;
     /// ```
     /// 
@@ -558,45 +1077,65 @@ pub async fn create_user(
     /// 
     /// - Content must be pre-encrypted before calling this function
     /// - Encryption keys should be generated using cryptographically secure methods
-    /// - Consider the encryption key storage and access patterns carefully
+    /// - Data keys should be wrapped with [`crate::keys::KeyRing::wrap_key`] first
+    #[allow(clippy::too_many_arguments)]
     pub async fn create_process(
         pool: &SqlitePool,
+        id: &str,
         client_id: &str,
         title: &str,
         description: &str,
         encrypted_content: &str,
+        content_sha256: &str,
         encryption_key: &str,
+        key_id: Option<&str>,
+        status: ProcessStatus,
+        template: Option<&NdaTemplateVersion>,
     ) -> Result<Process, sqlx::Error> {
-        let id = Uuid::new_v4().to_string();
         let created_at = Utc::now();
         let created_at_str = datetime_to_string(&created_at);
-        let status = "active".to_string();
+
+        let mut tx = pool.begin().await?;
 
         sqlx::query(
             r#"
-            INSERT INTO processes (id, client_id, title, description, encrypted_content, encryption_key, status, created_at)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            INSERT INTO processes (id, client_id, title, description, encrypted_content, content_sha256, encryption_key, key_id, content_version, status, template_id, template_version, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, 1, ?9, ?10, ?11, ?12)
             "#,
         )
-        .bind(&id)
+        .bind(id)
         .bind(client_id)
         .bind(title)
         .bind(description)
         .bind(encrypted_content)
+        .bind(content_sha256)
         .bind(encryption_key)
-        .bind(&status)
+        .bind(key_id)
+        .bind(status)
+        .bind(template.map(|t| &t.template_id))
+        .bind(template.map(|t| t.version))
         .bind(&created_at_str)
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
 
+        insert_process_revision(&mut tx, id, 1, encrypted_content, client_id, None, &created_at_str).await?;
+        insert_process_transition(&mut tx, id, None, status, client_id, "Process created", &created_at_str).await?;
+
+        tx.commit().await?;
+
         Ok(Process {
-            id,
+            id: id.to_string(),
             client_id: client_id.to_string(),
             title: title.to_string(),
             description: description.to_string(),
             encrypted_content: encrypted_content.to_string(),
             encryption_key: encryption_key.to_string(),
+            key_id: key_id.map(str::to_string),
+            content_version: 1,
+            content_sha256: Some(content_sha256.to_string()),
             status,
+            template_id: template.map(|t| t.template_id.clone()),
+            template_version: template.map(|t| t.version),
             created_at,
         })
     }
@@ -621,7 +1160,7 @@ pub async fn create_user(
     /// 
     /// # Examples
     /// 
-    /// ```rust
+    /// ```rust,ignore
     /// if let Some(process) = queries::find_process_by_id(&pool, &process_id).await? {
     ///     println!("Process title: {}", process.title);
     /// }
@@ -651,7 +1190,12 @@ pub async fn create_user(
                     description: row.get("description"),
                     encrypted_content: row.get("encrypted_content"),
                     encryption_key: row.get("encryption_key"),
-                    status: row.get("status"),
+                    key_id: row.get("key_id"),
+                    content_version: row.get("content_version"),
+                    content_sha256: row.get("content_sha256"),
+                    status: row.try_get("status")?,
+                    template_id: row.get("template_id"),
+                    template_version: row.get("template_version"),
                     created_at,
                 }))
             },
@@ -678,7 +1222,7 @@ pub async fn create_user(
     /// 
     /// # Examples
     /// 
-    /// ```rust
+    /// ```rust,ignore
     /// let processes = queries::list_processes_by_client(&pool, &client_id).await?;
     /// for process in processes {
     ///     println!("Process: {} ({})", process.title, process.status);
@@ -709,7 +1253,12 @@ pub async fn create_user(
                 description: row.get("description"),
                 encrypted_content: row.get("encrypted_content"),
                 encryption_key: row.get("encryption_key"),
-                status: row.get("status"),
+                key_id: row.get("key_id"),
+                content_version: row.get("content_version"),
+                content_sha256: row.get("content_sha256"),
+                status: row.try_get("status")?,
+                template_id: row.get("template_id"),
+                template_version: row.get("template_version"),
                 created_at,
             });
         }
@@ -717,33 +1266,1015 @@ pub async fn create_user(
         Ok(processes)
     }
 
-    /// Records a process sharing event on the Stellar blockchain.
+    async fn insert_process_revision(
+        tx: &mut sqlx::Transaction<'_, Sqlite>,
+        process_id: &str,
+        version: i64,
+        encrypted_content: &str,
+        author_id: &str,
+        change_note: Option<&str>,
+        created_at: &str,
+    ) -> Result<String, sqlx::Error> {
+        let id = Uuid::new_v4().to_string();
+        sqlx::query(
+            r#"
+            INSERT INTO process_revisions (id, process_id, version, encrypted_content, author_id, change_note, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            "#,
+        )
+        .bind(&id)
+        .bind(process_id)
+        .bind(version)
+        .bind(encrypted_content)
+        .bind(author_id)
+        .bind(change_note)
+        .bind(created_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(id)
+    }
+
+    /// Replaces the content of a process with a new revision.
     /// 
-    /// This function creates a record when a process is shared with a partner
-    /// via the Stellar network. It stores the blockchain transaction hash for
-    /// audit and verification purposes.
+    /// The process row is only updated while its content is still at
+    /// `rotation.content_version`, so concurrent edits cannot overwrite each other
+    /// or reuse a version number. Previous revisions are kept.
+    /// 
+    /// The new revision is encrypted under a fresh data key, which the
+    /// `rotation` moves the rest of the process to (see [`DataKeyRotation`]).
     /// 
     /// # Parameters
     /// 
     /// * `pool` - Database connection pool
-    /// * `process_id` - ID of the process being shared
-    /// * `partner_public_key` - Stellar public key of the recipient partner
-    /// * `stellar_transaction_hash` - Hash of the blockchain transaction
+    /// * `process_id` - Process to update
+    /// * `encrypted_content` - Content of the new revision, encrypted for the
+    ///   version after `rotation.content_version`
+    /// * `content_sha256` - Hex SHA-256 of the plaintext of the new revision
+    /// * `author_id` - User writing the revision
+    /// * `change_note` - Optional description of the change
+    /// * `rotation` - Existing revisions, file keys and sealed keys under the new data key
     /// 
     /// # Returns
     /// 
     /// Returns `Result` containing:
-    /// - `Ok(ProcessShare)` - Created share record with generated ID and timestamp
-    /// - `Err(sqlx::Error)` - Database error or foreign key constraint failure
-    /// 
-    /// # Examples
-    /// 
-    /// ```rust
-    /// let share = queries::create_process_share(
-    ///     &pool,
+    /// - `Ok(Some(ProcessRevision))` - The new revision
+    /// - `Ok(None)` - The content version, data key, revisions, attachments or
+    ///   shares of the process changed since `rotation` was prepared (or it does not exist)
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn update_process_content(
+        pool: &SqlitePool,
+        process_id: &str,
+        encrypted_content: &str,
+        content_sha256: &str,
+        author_id: &str,
+        change_note: Option<&str>,
+        rotation: &DataKeyRotation,
+    ) -> Result<Option<ProcessRevision>, sqlx::Error> {
+        let version = rotation.content_version + 1;
+        let created_at = Utc::now();
+        let created_at_str = datetime_to_string(&created_at);
+
+        let mut tx = pool.begin().await?;
+
+        let updated = sqlx::query(
+            "UPDATE processes SET encrypted_content = ?1, content_version = ?2, encryption_key = ?3, key_id = ?4, \
+             content_sha256 = ?8 WHERE id = ?5 AND content_version = ?6 AND encryption_key = ?7",
+        )
+        .bind(encrypted_content)
+        .bind(version)
+        .bind(&rotation.encryption_key)
+        .bind(&rotation.key_id)
+        .bind(process_id)
+        .bind(rotation.content_version)
+        .bind(&rotation.previous_key)
+        .bind(content_sha256)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        if updated == 0 || !apply_data_key_rotation(&mut tx, process_id, rotation).await? {
+            tx.rollback().await?;
+            return Ok(None);
+        }
+
+        let id = insert_process_revision(
+            &mut tx,
+            process_id,
+            version,
+            encrypted_content,
+            author_id,
+            change_note,
+            &created_at_str,
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(ProcessRevision {
+            id,
+            process_id: process_id.to_string(),
+            version,
+            author_id: author_id.to_string(),
+            change_note: change_note.map(str::to_string),
+            created_at,
+            access_count: 0,
+            content: None,
+        }))
+    }
+
+    /// Moves a process to a fresh data key without changing its content.
+    /// 
+    /// Returns `false` when the process changed since the rotation was
+    /// prepared; the caller then rolls back.
+    async fn rotate_process_data_key(
+        tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        process_id: &str,
+        rotation: &DataKeyRotation,
+    ) -> Result<bool, sqlx::Error> {
+        let current_content = rotation
+            .revisions
+            .iter()
+            .find(|(version, _)| *version == rotation.content_version)
+            .map(|(_, content)| content.as_str());
+        let Some(current_content) = current_content else {
+            return Ok(false);
+        };
+
+        let updated = sqlx::query(
+            "UPDATE processes SET encrypted_content = ?1, encryption_key = ?2, key_id = ?3 \
+             WHERE id = ?4 AND content_version = ?5 AND encryption_key = ?6",
+        )
+        .bind(current_content)
+        .bind(&rotation.encryption_key)
+        .bind(&rotation.key_id)
+        .bind(process_id)
+        .bind(rotation.content_version)
+        .bind(&rotation.previous_key)
+        .execute(&mut **tx)
+        .await?
+        .rows_affected();
+
+        Ok(updated > 0 && apply_data_key_rotation(tx, process_id, rotation).await?)
+    }
+
+    /// Writes the revisions, file keys and sealed keys of a data key rotation.
+    /// 
+    /// Returns `false` when a revision, attachment or zero-knowledge share was
+    /// added since the rotation was prepared, which would be left on the
+    /// previous data key; the caller then rolls back.
+    async fn apply_data_key_rotation(
+        tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        process_id: &str,
+        rotation: &DataKeyRotation,
+    ) -> Result<bool, sqlx::Error> {
+        let (revisions, attachments, sealed_shares): (i64, i64, i64) = sqlx::query_as(
+            r#"
+            SELECT (SELECT COUNT(*) FROM process_revisions WHERE process_id = ?1),
+                   (SELECT COUNT(*) FROM process_attachments WHERE process_id = ?1),
+                   (SELECT COUNT(*) FROM process_shares
+                    WHERE process_id = ?1 AND wrapped_key IS NOT NULL AND revoked_at IS NULL)
+            "#,
+        )
+        .bind(process_id)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        if revisions != rotation.revisions.len() as i64
+            || attachments != rotation.file_keys.len() as i64
+            || sealed_shares != rotation.sealed_keys.len() as i64
+        {
+            return Ok(false);
+        }
+
+        for (version, encrypted_content) in &rotation.revisions {
+            sqlx::query("UPDATE process_revisions SET encrypted_content = ?1 WHERE process_id = ?2 AND version = ?3")
+                .bind(encrypted_content)
+                .bind(process_id)
+                .bind(version)
+                .execute(&mut **tx)
+                .await?;
+        }
+        for (attachment_id, file_key) in &rotation.file_keys {
+            sqlx::query("UPDATE process_attachments SET file_key = ?1 WHERE process_id = ?2 AND id = ?3")
+                .bind(file_key)
+                .bind(process_id)
+                .bind(attachment_id)
+                .execute(&mut **tx)
+                .await?;
+        }
+        for (share_id, wrapped_key) in &rotation.sealed_keys {
+            let updated = sqlx::query(
+                "UPDATE process_shares SET wrapped_key = ?1 \
+                 WHERE process_id = ?2 AND id = ?3 AND wrapped_key IS NOT NULL AND revoked_at IS NULL",
+            )
+            .bind(wrapped_key)
+            .bind(process_id)
+            .bind(share_id)
+            .execute(&mut **tx)
+            .await?
+            .rows_affected();
+            if updated == 0 {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Lists the encrypted content of every revision of a process, oldest first.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pool` - Database connection pool
+    /// * `process_id` - Process whose revisions to list
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(Vec<(i64, String)>)` - Content version and encrypted content of each revision
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn list_process_revision_contents(
+        pool: &SqlitePool,
+        process_id: &str,
+    ) -> Result<Vec<(i64, String)>, sqlx::Error> {
+        sqlx::query_as("SELECT version, encrypted_content FROM process_revisions WHERE process_id = ?1 ORDER BY version ASC")
+            .bind(process_id)
+            .fetch_all(pool)
+            .await
+    }
+
+    /// Lists the revisions of a process, newest first.
+    /// 
+    /// Each revision carries the number of partner accesses that saw it.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pool` - Database connection pool
+    /// * `process_id` - Process whose revisions to list
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(Vec<ProcessRevision>)` - Revisions without content
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn list_process_revisions(
+        pool: &SqlitePool,
+        process_id: &str,
+    ) -> Result<Vec<ProcessRevision>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT r.id, r.process_id, r.version, r.author_id, r.change_note, r.created_at,
+                   (SELECT COUNT(*) FROM process_accesses pa
+                    WHERE pa.process_id = r.process_id AND pa.content_version = r.version) AS access_count
+            FROM process_revisions r
+            WHERE r.process_id = ?1
+            ORDER BY r.version DESC
+            "#,
+        )
+        .bind(process_id)
+        .fetch_all(pool)
+        .await?;
+
+        rows.iter().map(process_revision_from_row).collect()
+    }
+
+    /// Finds a revision of a process together with its encrypted content.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pool` - Database connection pool
+    /// * `process_id` - Process the revision belongs to
+    /// * `version` - Content version
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(Some((ProcessRevision, String)))` - Revision and its encrypted content
+    /// - `Ok(None)` - No such revision
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn find_process_revision(
+        pool: &SqlitePool,
+        process_id: &str,
+        version: i64,
+    ) -> Result<Option<(ProcessRevision, String)>, sqlx::Error> {
+        let row = sqlx::query(
+            r#"
+            SELECT r.id, r.process_id, r.version, r.author_id, r.change_note, r.created_at, r.encrypted_content,
+                   (SELECT COUNT(*) FROM process_accesses pa
+                    WHERE pa.process_id = r.process_id AND pa.content_version = r.version) AS access_count
+            FROM process_revisions r
+            WHERE r.process_id = ?1 AND r.version = ?2
+            "#,
+        )
+        .bind(process_id)
+        .bind(version)
+        .fetch_optional(pool)
+        .await?;
+
+        match row {
+            Some(row) => Ok(Some((process_revision_from_row(&row)?, row.get("encrypted_content")))),
+            None => Ok(None),
+        }
+    }
+
+    fn process_revision_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<ProcessRevision, sqlx::Error> {
+        let created_at_str: String = row.get("created_at");
+        let created_at = string_to_datetime(&created_at_str).map_err(|_| sqlx::Error::ColumnDecode {
+            index: "created_at".to_string(),
+            source: Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid datetime")),
+        })?;
+
+        Ok(ProcessRevision {
+            id: row.get("id"),
+            process_id: row.get("process_id"),
+            version: row.get("version"),
+            author_id: row.get("author_id"),
+            change_note: row.get("change_note"),
+            created_at,
+            access_count: row.get("access_count"),
+            content: None,
+        })
+    }
+
+    async fn insert_process_transition(
+        tx: &mut sqlx::Transaction<'_, Sqlite>,
+        process_id: &str,
+        from_status: Option<ProcessStatus>,
+        to_status: ProcessStatus,
+        actor_id: &str,
+        reason: &str,
+        created_at: &str,
+    ) -> Result<String, sqlx::Error> {
+        let id = Uuid::new_v4().to_string();
+        sqlx::query(
+            r#"
+            INSERT INTO process_transitions (id, process_id, from_status, to_status, actor_id, reason, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            "#,
+        )
+        .bind(&id)
+        .bind(process_id)
+        .bind(from_status)
+        .bind(to_status)
+        .bind(actor_id)
+        .bind(reason)
+        .bind(created_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(id)
+    }
+
+    /// Moves a process from one lifecycle status to another.
+    /// 
+    /// The process row is only updated while it is still in `from_status`,
+    /// so concurrent transitions cannot skip the rules checked by the
+    /// caller. The transition is recorded in the same transaction.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pool` - Database connection pool
+    /// * `process_id` - Process to update
+    /// * `from_status` - Status the caller validated the transition from
+    /// * `to_status` - New status
+    /// * `actor_id` - User making the change
+    /// * `reason` - Reason for the change
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(Some(ProcessTransition))` - The recorded transition
+    /// - `Ok(None)` - The process is no longer in `from_status` (or does not exist)
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn transition_process_status(
+        pool: &SqlitePool,
+        process_id: &str,
+        from_status: ProcessStatus,
+        to_status: ProcessStatus,
+        actor_id: &str,
+        reason: &str,
+    ) -> Result<Option<ProcessTransition>, sqlx::Error> {
+        let created_at = Utc::now();
+        let created_at_str = datetime_to_string(&created_at);
+
+        let mut tx = pool.begin().await?;
+
+        let updated = sqlx::query("UPDATE processes SET status = ?1 WHERE id = ?2 AND status = ?3")
+            .bind(to_status)
+            .bind(process_id)
+            .bind(from_status)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        if updated == 0 {
+            tx.rollback().await?;
+            return Ok(None);
+        }
+
+        let id = insert_process_transition(
+            &mut tx,
+            process_id,
+            Some(from_status),
+            to_status,
+            actor_id,
+            reason,
+            &created_at_str,
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(ProcessTransition {
+            id,
+            process_id: process_id.to_string(),
+            from_status: Some(from_status),
+            to_status,
+            actor_id: actor_id.to_string(),
+            reason: reason.to_string(),
+            created_at,
+        }))
+    }
+
+    /// Lists the status transitions of a process in the order they happened.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pool` - Database connection pool
+    /// * `process_id` - Process whose transitions to list
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(Vec<ProcessTransition>)` - Transitions, oldest first
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn list_process_transitions(
+        pool: &SqlitePool,
+        process_id: &str,
+    ) -> Result<Vec<ProcessTransition>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, process_id, from_status, to_status, actor_id, reason, created_at
+            FROM process_transitions
+            WHERE process_id = ?1
+            ORDER BY created_at ASC, rowid ASC
+            "#,
+        )
+        .bind(process_id)
+        .fetch_all(pool)
+        .await?;
+
+        let mut transitions = Vec::with_capacity(rows.len());
+        for row in rows {
+            let created_at_str: String = row.get("created_at");
+            let created_at = string_to_datetime(&created_at_str).map_err(|_| sqlx::Error::ColumnDecode {
+                index: "created_at".to_string(),
+                source: Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid datetime")),
+            })?;
+
+            transitions.push(ProcessTransition {
+                id: row.get("id"),
+                process_id: row.get("process_id"),
+                from_status: row.try_get("from_status")?,
+                to_status: row.try_get("to_status")?,
+                actor_id: row.get("actor_id"),
+                reason: row.get("reason"),
+                created_at,
+            });
+        }
+
+        Ok(transitions)
+    }
+
+    /// Stores a partner's verified signature of the terms of a process.
+    /// 
+    /// A partner signs a given terms digest at most once; if a signature for
+    /// the same digest already exists (e.g. from a concurrent request), it is
+    /// returned instead.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pool` - Database connection pool
+    /// * `process_id` - Process whose terms were signed
+    /// * `partner_id` - Signing partner
+    /// * `partner_public_key` - Stellar public key the signature verifies under
+    /// * `content_version` - Content revision covered by the terms
+    /// * `terms_digest` - Hex-encoded digest that was signed
+    /// * `signature` - Base64-encoded signature, already verified by the caller
+    /// * `anchor_transaction_hash` - Stellar transaction anchoring the signature, if any
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(TermsSignature)` - The stored signature
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_terms_signature(
+        pool: &SqlitePool,
+        process_id: &str,
+        partner_id: &str,
+        partner_public_key: &str,
+        content_version: i64,
+        terms_digest: &str,
+        signature: &str,
+        anchor_transaction_hash: Option<&str>,
+    ) -> Result<TermsSignature, sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO terms_signatures
+                (id, process_id, partner_id, partner_public_key, content_version, terms_digest, signature, signed_at, anchor_transaction_hash)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(process_id)
+        .bind(partner_id)
+        .bind(partner_public_key)
+        .bind(content_version)
+        .bind(terms_digest)
+        .bind(signature)
+        .bind(datetime_to_string(&Utc::now()))
+        .bind(anchor_transaction_hash)
+        .execute(pool)
+        .await?;
+
+        find_terms_signature(pool, process_id, partner_id, terms_digest)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    /// Finds a partner's signature of a terms digest.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pool` - Database connection pool
+    /// * `process_id` - Process whose terms were signed
+    /// * `partner_id` - Signing partner
+    /// * `terms_digest` - Hex-encoded digest of the terms
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(Some(TermsSignature))` - The partner signed these terms
+    /// - `Ok(None)` - No signature for these terms
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn find_terms_signature(
+        pool: &SqlitePool,
+        process_id: &str,
+        partner_id: &str,
+        terms_digest: &str,
+    ) -> Result<Option<TermsSignature>, sqlx::Error> {
+        let row = sqlx::query(
+            "SELECT * FROM terms_signatures WHERE process_id = ?1 AND partner_id = ?2 AND terms_digest = ?3",
+        )
+        .bind(process_id)
+        .bind(partner_id)
+        .bind(terms_digest)
+        .fetch_optional(pool)
+        .await?;
+
+        row.as_ref().map(terms_signature_from_row).transpose()
+    }
+
+    /// Lists the terms signatures of a process, oldest first.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pool` - Database connection pool
+    /// * `process_id` - Process whose signatures to list
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(Vec<TermsSignature>)` - Signatures of every partner and terms version
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn list_terms_signatures(
+        pool: &SqlitePool,
+        process_id: &str,
+    ) -> Result<Vec<TermsSignature>, sqlx::Error> {
+        let rows = sqlx::query("SELECT * FROM terms_signatures WHERE process_id = ?1 ORDER BY signed_at ASC")
+            .bind(process_id)
+            .fetch_all(pool)
+            .await?;
+
+        rows.iter().map(terms_signature_from_row).collect()
+    }
+
+    fn terms_signature_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<TermsSignature, sqlx::Error> {
+        let signed_at_str: String = row.get("signed_at");
+        let signed_at = string_to_datetime(&signed_at_str).map_err(|_| sqlx::Error::ColumnDecode {
+            index: "signed_at".to_string(),
+            source: Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid datetime")),
+        })?;
+
+        Ok(TermsSignature {
+            id: row.get("id"),
+            process_id: row.get("process_id"),
+            partner_id: row.get("partner_id"),
+            partner_public_key: row.get("partner_public_key"),
+            content_version: row.get("content_version"),
+            terms_digest: row.get("terms_digest"),
+            signature: row.get("signature"),
+            signed_at,
+            anchor_transaction_hash: row.get("anchor_transaction_hash"),
+        })
+    }
+
+    fn datetime_column(row: &sqlx::sqlite::SqliteRow, column: &str) -> Result<DateTime<Utc>, sqlx::Error> {
+        let value: String = row.get(column);
+        string_to_datetime(&value).map_err(|_| sqlx::Error::ColumnDecode {
+            index: column.to_string(),
+            source: Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid datetime")),
+        })
+    }
+
+    fn placeholders_column(row: &sqlx::sqlite::SqliteRow) -> Result<Vec<TemplatePlaceholder>, sqlx::Error> {
+        let value: String = row.get("placeholders");
+        serde_json::from_str(&value).map_err(|e| sqlx::Error::ColumnDecode {
+            index: "placeholders".to_string(),
+            source: Box::new(e),
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn insert_template_version(
+        tx: &mut sqlx::Transaction<'_, Sqlite>,
+        template_id: &str,
+        version: i64,
+        body: &str,
+        placeholders: &[TemplatePlaceholder],
+        author_id: &str,
+        change_note: Option<&str>,
+        created_at: &str,
+    ) -> Result<(), sqlx::Error> {
+        let placeholders = serde_json::to_string(placeholders).map_err(|e| sqlx::Error::Protocol(format!("Invalid template placeholders: {}", e)))?;
+        sqlx::query(
+            r#"
+            INSERT INTO nda_template_versions (id, template_id, version, body, placeholders, author_id, change_note, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(template_id)
+        .bind(version)
+        .bind(body)
+        .bind(placeholders)
+        .bind(author_id)
+        .bind(change_note)
+        .bind(created_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Creates an NDA template with its first version.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pool` - Database connection pool
+    /// * `owner_id` - Client who owns the template (and authors version 1)
+    /// * `name` - Template name
+    /// * `description` - Template description
+    /// * `body` - Template body, already validated by [`crate::templates::validate_template`]
+    /// * `placeholders` - Placeholders used by the body
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(NdaTemplate)` - The created template at version 1
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn create_template(
+        pool: &SqlitePool,
+        owner_id: &str,
+        name: &str,
+        description: &str,
+        body: &str,
+        placeholders: &[TemplatePlaceholder],
+    ) -> Result<NdaTemplate, sqlx::Error> {
+        let id = Uuid::new_v4().to_string();
+        let created_at = Utc::now();
+        let created_at_str = datetime_to_string(&created_at);
+
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO nda_templates (id, owner_id, name, description, current_version, created_at)
+            VALUES (?1, ?2, ?3, ?4, 1, ?5)
+            "#,
+        )
+        .bind(&id)
+        .bind(owner_id)
+        .bind(name)
+        .bind(description)
+        .bind(&created_at_str)
+        .execute(&mut *tx)
+        .await?;
+
+        insert_template_version(&mut tx, &id, 1, body, placeholders, owner_id, None, &created_at_str).await?;
+
+        tx.commit().await?;
+
+        Ok(NdaTemplate {
+            id,
+            owner_id: owner_id.to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+            version: 1,
+            body: body.to_string(),
+            placeholders: placeholders.to_vec(),
+            created_at,
+            updated_at: created_at,
+            archived_at: None,
+        })
+    }
+
+    const TEMPLATE_COLUMNS: &str = r#"
+        SELECT t.id, t.owner_id, t.name, t.description, t.current_version, t.created_at, t.archived_at,
+               v.body, v.placeholders, v.created_at AS updated_at
+        FROM nda_templates t
+        JOIN nda_template_versions v ON v.template_id = t.id AND v.version = t.current_version
+    "#;
+
+    fn template_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<NdaTemplate, sqlx::Error> {
+        let archived_at: Option<String> = row.get("archived_at");
+        let archived_at = match archived_at {
+            Some(_) => Some(datetime_column(row, "archived_at")?),
+            None => None,
+        };
+
+        Ok(NdaTemplate {
+            id: row.get("id"),
+            owner_id: row.get("owner_id"),
+            name: row.get("name"),
+            description: row.get("description"),
+            version: row.get("current_version"),
+            body: row.get("body"),
+            placeholders: placeholders_column(row)?,
+            created_at: datetime_column(row, "created_at")?,
+            updated_at: datetime_column(row, "updated_at")?,
+            archived_at,
+        })
+    }
+
+    /// Finds an NDA template, archived or not, with its current version.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pool` - Database connection pool
+    /// * `template_id` - Template to find
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(Some(NdaTemplate))` - Template found
+    /// - `Ok(None)` - No template with that ID
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn find_template(
+        pool: &SqlitePool,
+        template_id: &str,
+    ) -> Result<Option<NdaTemplate>, sqlx::Error> {
+        let row = sqlx::query(&format!("{} WHERE t.id = ?1", TEMPLATE_COLUMNS))
+            .bind(template_id)
+            .fetch_optional(pool)
+            .await?;
+
+        row.as_ref().map(template_from_row).transpose()
+    }
+
+    /// Lists the templates of a client that are not archived, by name.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pool` - Database connection pool
+    /// * `owner_id` - Client whose templates to list
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(Vec<NdaTemplate>)` - Templates with their current version
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn list_templates_by_owner(
+        pool: &SqlitePool,
+        owner_id: &str,
+    ) -> Result<Vec<NdaTemplate>, sqlx::Error> {
+        let rows = sqlx::query(&format!(
+            "{} WHERE t.owner_id = ?1 AND t.archived_at IS NULL ORDER BY t.name ASC",
+            TEMPLATE_COLUMNS
+        ))
+        .bind(owner_id)
+        .fetch_all(pool)
+        .await?;
+
+        rows.iter().map(template_from_row).collect()
+    }
+
+    /// Creates a new version of an NDA template.
+    /// 
+    /// Like [`update_process_content`], the template is only updated while
+    /// it is still at `expected_version` (and not archived), so concurrent
+    /// edits cannot overwrite each other.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pool` - Database connection pool
+    /// * `template_id` - Template to update
+    /// * `expected_version` - Version the new one is based on
+    /// * `name` - Template name
+    /// * `description` - Template description
+    /// * `body` - Body of the new version, already validated
+    /// * `placeholders` - Placeholders of the new version
+    /// * `author_id` - User writing the version
+    /// * `change_note` - Optional description of the change
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(Some(NdaTemplate))` - The template at its new version
+    /// - `Ok(None)` - The template is no longer at `expected_version`, is archived or does not exist
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    #[allow(clippy::too_many_arguments)]
+    pub async fn update_template(
+        pool: &SqlitePool,
+        template_id: &str,
+        expected_version: i64,
+        name: &str,
+        description: &str,
+        body: &str,
+        placeholders: &[TemplatePlaceholder],
+        author_id: &str,
+        change_note: Option<&str>,
+    ) -> Result<Option<NdaTemplate>, sqlx::Error> {
+        let version = expected_version + 1;
+        let created_at_str = datetime_to_string(&Utc::now());
+
+        let mut tx = pool.begin().await?;
+
+        let updated = sqlx::query(
+            r#"
+            UPDATE nda_templates SET name = ?1, description = ?2, current_version = ?3
+            WHERE id = ?4 AND current_version = ?5 AND archived_at IS NULL
+            "#,
+        )
+        .bind(name)
+        .bind(description)
+        .bind(version)
+        .bind(template_id)
+        .bind(expected_version)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        if updated == 0 {
+            tx.rollback().await?;
+            return Ok(None);
+        }
+
+        insert_template_version(&mut tx, template_id, version, body, placeholders, author_id, change_note, &created_at_str)
+            .await?;
+
+        tx.commit().await?;
+
+        find_template(pool, template_id).await
+    }
+
+    /// Archives an NDA template so it can no longer be used or changed.
+    /// 
+    /// The template and its versions are kept, since processes record the
+    /// version they were created from.
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(true)` - The template was archived
+    /// - `Ok(false)` - The template was already archived or does not exist
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn archive_template(
+        pool: &SqlitePool,
+        template_id: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let archived = sqlx::query("UPDATE nda_templates SET archived_at = ?1 WHERE id = ?2 AND archived_at IS NULL")
+            .bind(datetime_to_string(&Utc::now()))
+            .bind(template_id)
+            .execute(pool)
+            .await?
+            .rows_affected();
+
+        Ok(archived > 0)
+    }
+
+    fn template_version_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<NdaTemplateVersion, sqlx::Error> {
+        Ok(NdaTemplateVersion {
+            id: row.get("id"),
+            template_id: row.get("template_id"),
+            version: row.get("version"),
+            body: row.get("body"),
+            placeholders: placeholders_column(row)?,
+            author_id: row.get("author_id"),
+            change_note: row.get("change_note"),
+            created_at: datetime_column(row, "created_at")?,
+        })
+    }
+
+    /// Lists the versions of an NDA template, newest first.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pool` - Database connection pool
+    /// * `template_id` - Template whose versions to list
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(Vec<NdaTemplateVersion>)` - Versions of the template
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn list_template_versions(
+        pool: &SqlitePool,
+        template_id: &str,
+    ) -> Result<Vec<NdaTemplateVersion>, sqlx::Error> {
+        let rows = sqlx::query("SELECT * FROM nda_template_versions WHERE template_id = ?1 ORDER BY version DESC")
+            .bind(template_id)
+            .fetch_all(pool)
+            .await?;
+
+        rows.iter().map(template_version_from_row).collect()
+    }
+
+    /// Finds a version of an NDA template.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pool` - Database connection pool
+    /// * `template_id` - Template the version belongs to
+    /// * `version` - Version number
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(Some(NdaTemplateVersion))` - Version found
+    /// - `Ok(None)` - No such version
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn find_template_version(
+        pool: &SqlitePool,
+        template_id: &str,
+        version: i64,
+    ) -> Result<Option<NdaTemplateVersion>, sqlx::Error> {
+        let row = sqlx::query("SELECT * FROM nda_template_versions WHERE template_id = ?1 AND version = ?2")
+            .bind(template_id)
+            .bind(version)
+            .fetch_optional(pool)
+            .await?;
+
+        row.as_ref().map(template_version_from_row).transpose()
+    }
+
+    /// Records a process sharing event on the Stellar blockchain.
+    /// 
+    /// This function creates a record when a process is shared with a partner
+    /// via the Stellar network. It stores the blockchain transaction hash for
+    /// audit and verification purposes.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pool` - Database connection pool
+    /// * `process_id` - ID of the process being shared
+    /// * `partner_public_key` - Stellar public key of the recipient partner
+    /// * `stellar_transaction_hash` - Hash of the blockchain transaction
+    /// * `stellar_ledger` - Ledger the transaction was included in, if reported
+    /// * `memo_salt` - Hex-encoded salt of the share commitment
+    /// * `content_digest` - Hex-encoded digest of the encrypted content that was shared
+    /// * `memo_commitment` - Hex-encoded commitment recorded as the transaction memo
+    /// * `valid_from` - Start of the access window, `None` for immediate access
+    /// * `expires_at` - End of the access window, `None` for no expiry
+    /// * `max_accesses` - Number of times the partner may view the process, `None` for no limit
+    /// * `wrapped_key` - For zero-knowledge shares, the data key sealed to the partner
+    ///   and the wrapped data key of the process it was unwrapped from; `None` otherwise
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(Some(ProcessShare))` - Created share record with generated ID and timestamp
+    /// - `Ok(None)` - The data key was rotated after it was sealed; no share was recorded
+    /// - `Err(sqlx::Error)` - Database error or foreign key constraint failure
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// let share = queries::create_process_share(
+    ///     &pool,
     ///     &process.id,
     ///     "GCKFBEIYTKP...",
-    ///     "stellar_tx_hash_123"
+    ///     "stellar_tx_hash_123",
+    ///     Some(51234567),
+    ///     &hex::encode(salt),
+    ///     &hex::encode(digest),
+    ///     &hex::encode(commitment),
+    ///     None,
+    ///     Some(Utc::now() + Duration::days(90)),
+    ///     Some(1),
+    ///     None
     /// ).await?;
     /// ```
     /// 
@@ -752,36 +2283,583 @@ pub async fn create_user(
     /// This function should be called after successfully submitting a sharing
     /// transaction to the Stellar network. The transaction hash provides
     /// immutable proof of the sharing event.
+    #[allow(clippy::too_many_arguments)]
     pub async fn create_process_share(
         pool: &SqlitePool,
         process_id: &str,
-        partner_public_key: &str,
-        stellar_transaction_hash: &str,
-    ) -> Result<ProcessShare, sqlx::Error> {
-        let id = Uuid::new_v4().to_string();
-        let shared_at = Utc::now();
-        let shared_at_str = datetime_to_string(&shared_at);
-
-        sqlx::query(
+        partner_public_key: &str,
+        stellar_transaction_hash: &str,
+        stellar_ledger: Option<i64>,
+        memo_salt: &str,
+        content_digest: &str,
+        memo_commitment: &str,
+        valid_from: Option<DateTime<Utc>>,
+        expires_at: Option<DateTime<Utc>>,
+        max_accesses: Option<i64>,
+        wrapped_key: Option<(&str, &str)>,
+    ) -> Result<Option<ProcessShare>, sqlx::Error> {
+        let id = Uuid::new_v4().to_string();
+        let shared_at = Utc::now();
+        let shared_at_str = datetime_to_string(&shared_at);
+        let (wrapped_key, sealed_from) = wrapped_key.unzip();
+
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO process_shares (id, process_id, partner_public_key, stellar_transaction_hash, stellar_ledger, shared_at, memo_salt, content_digest, memo_commitment, valid_from, expires_at, max_accesses, wrapped_key)
+            SELECT ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13
+            WHERE ?14 IS NULL OR EXISTS (SELECT 1 FROM processes WHERE id = ?2 AND encryption_key = ?14)
+            "#,
+        )
+        .bind(&id)
+        .bind(process_id)
+        .bind(partner_public_key)
+        .bind(stellar_transaction_hash)
+        .bind(stellar_ledger)
+        .bind(&shared_at_str)
+        .bind(memo_salt)
+        .bind(content_digest)
+        .bind(memo_commitment)
+        .bind(valid_from.as_ref().map(datetime_to_string))
+        .bind(expires_at.as_ref().map(datetime_to_string))
+        .bind(max_accesses)
+        .bind(wrapped_key)
+        .bind(sealed_from)
+        .execute(pool)
+        .await?
+        .rows_affected();
+
+        if inserted == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(ProcessShare {
+            id,
+            process_id: process_id.to_string(),
+            partner_public_key: partner_public_key.to_string(),
+            stellar_transaction_hash: stellar_transaction_hash.to_string(),
+            stellar_ledger,
+            shared_at,
+            memo_salt: Some(memo_salt.to_string()),
+            content_digest: Some(content_digest.to_string()),
+            memo_commitment: Some(memo_commitment.to_string()),
+            revoked_at: None,
+            revoked_by: None,
+            revocation_reason: None,
+            revocation_transaction_hash: None,
+            valid_from,
+            expires_at,
+            expired_at: None,
+            max_accesses,
+            wrapped_key: wrapped_key.map(str::to_string),
+        }))
+    }
+
+    /// Finds the most recent share of a process with a partner.
+    /// 
+    /// Used by access verification to obtain the recorded Stellar
+    /// transaction hash for a process/partner pair.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pool` - Database connection pool
+    /// * `process_id` - ID of the shared process
+    /// * `partner_public_key` - Stellar public key of the partner
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(Some(ProcessShare))` - Latest share record for the pair
+    /// - `Ok(None)` - Process was never shared with the partner
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn find_process_share(
+        pool: &SqlitePool,
+        process_id: &str,
+        partner_public_key: &str,
+    ) -> Result<Option<ProcessShare>, sqlx::Error> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM process_shares
+            WHERE process_id = ?1 AND partner_public_key = ?2
+            ORDER BY shared_at DESC
+            LIMIT 1
+            "#,
+            PROCESS_SHARE_COLUMNS
+        ))
+        .bind(process_id)
+        .bind(partner_public_key)
+        .fetch_optional(pool)
+        .await?;
+
+        row.map(|row| process_share_from_row(&row)).transpose()
+    }
+
+    /// Lists every share of a process, oldest first.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pool` - Database connection pool
+    /// * `process_id` - ID of the shared process
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(Vec<ProcessShare>)` - Shares, including revoked and expired ones
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn list_process_shares(
+        pool: &SqlitePool,
+        process_id: &str,
+    ) -> Result<Vec<ProcessShare>, sqlx::Error> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM process_shares WHERE process_id = ?1 ORDER BY shared_at ASC",
+            PROCESS_SHARE_COLUMNS
+        ))
+        .bind(process_id)
+        .fetch_all(pool)
+        .await?;
+
+        rows.iter().map(process_share_from_row).collect()
+    }
+
+    /// Marks a process share as revoked.
+    /// 
+    /// Records who revoked the share, when, why and the Stellar transaction
+    /// proving the revocation. Only shares that are still active are updated.
+    /// Revoking a zero-knowledge share moves the process to a fresh data key
+    /// in the same transaction, so the partner's sealed key opens nothing
+    /// written afterwards.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pool` - Database connection pool
+    /// * `share_id` - ID of the share to revoke
+    /// * `revoked_by` - ID of the user revoking the share
+    /// * `reason` - Reason for the revocation
+    /// * `revocation_transaction_hash` - Hash of the on-chain revocation transaction
+    /// * `rotation` - New data key of the process, for zero-knowledge shares
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(Some(ProcessShare))` - Updated share record
+    /// - `Ok(None)` - Share not found or already revoked, or the process changed
+    ///   since `rotation` was prepared; nothing was written
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn revoke_process_share(
+        pool: &SqlitePool,
+        share_id: &str,
+        revoked_by: &str,
+        reason: &str,
+        revocation_transaction_hash: &str,
+        rotation: Option<&DataKeyRotation>,
+    ) -> Result<Option<ProcessShare>, sqlx::Error> {
+        let revoked_at = datetime_to_string(&Utc::now());
+
+        let mut tx = pool.begin().await?;
+
+        let row = sqlx::query(&format!(
+            r#"
+            UPDATE process_shares
+            SET revoked_at = ?1, revoked_by = ?2, revocation_reason = ?3, revocation_transaction_hash = ?4
+            WHERE id = ?5 AND revoked_at IS NULL
+            RETURNING {}
+            "#,
+            PROCESS_SHARE_COLUMNS
+        ))
+        .bind(&revoked_at)
+        .bind(revoked_by)
+        .bind(reason)
+        .bind(revocation_transaction_hash)
+        .bind(share_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(row) = row else {
+            tx.rollback().await?;
+            return Ok(None);
+        };
+        let share = process_share_from_row(&row)?;
+
+        if let Some(rotation) = rotation {
+            if !rotate_process_data_key(&mut tx, &share.process_id, rotation).await? {
+                tx.rollback().await?;
+                return Ok(None);
+            }
+        }
+
+        tx.commit().await?;
+        Ok(Some(share))
+    }
+
+    /// Maps a `process_shares` row to a [`ProcessShare`].
+    fn process_share_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<ProcessShare, sqlx::Error> {
+        let parse = |column: &str, value: &str| {
+            string_to_datetime(value).map_err(|_| sqlx::Error::ColumnDecode { 
+                index: column.to_string(), 
+                source: Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid datetime")) 
+            })
+        };
+
+        let shared_at_str: String = row.get("shared_at");
+        let optional = |column: &str| {
+            row.get::<Option<String>, _>(column)
+                .map(|value| parse(column, &value))
+                .transpose()
+        };
+
+        Ok(ProcessShare {
+            id: row.get("id"),
+            process_id: row.get("process_id"),
+            partner_public_key: row.get("partner_public_key"),
+            stellar_transaction_hash: row.get("stellar_transaction_hash"),
+            stellar_ledger: row.get("stellar_ledger"),
+            shared_at: parse("shared_at", &shared_at_str)?,
+            memo_salt: row.get("memo_salt"),
+            content_digest: row.get("content_digest"),
+            memo_commitment: row.get("memo_commitment"),
+            revoked_at: optional("revoked_at")?,
+            revoked_by: row.get("revoked_by"),
+            revocation_reason: row.get("revocation_reason"),
+            revocation_transaction_hash: row.get("revocation_transaction_hash"),
+            valid_from: optional("valid_from")?,
+            expires_at: optional("expires_at")?,
+            expired_at: optional("expired_at")?,
+            max_accesses: row.get("max_accesses"),
+            wrapped_key: row.get("wrapped_key"),
+        })
+    }
+
+    /// Marks shares whose access window has ended as expired.
+    /// 
+    /// Called periodically by the share expiry task. Revoked shares and shares
+    /// already marked are left untouched, so every share is reported once.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pool` - Database connection pool
+    /// * `now` - Current time; shares with `expires_at` at or before it expire
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(Vec<ProcessShare>)` - Shares newly marked as expired
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn expire_process_shares(
+        pool: &SqlitePool,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<ProcessShare>, sqlx::Error> {
+        // RFC3339 strings in UTC compare in chronological order
+        let now_str = datetime_to_string(&now);
+
+        let rows = sqlx::query(&format!(
+            r#"
+            UPDATE process_shares
+            SET expired_at = ?1
+            WHERE expires_at IS NOT NULL AND expires_at <= ?1
+              AND expired_at IS NULL AND revoked_at IS NULL
+            RETURNING {}
+            "#,
+            PROCESS_SHARE_COLUMNS
+        ))
+        .bind(&now_str)
+        .fetch_all(pool)
+        .await?;
+
+        rows.iter().map(process_share_from_row).collect()
+    }
+
+    /// Lists expired shares that have no notification of the given kind yet.
+    /// 
+    /// Lets the share expiry task notify owners of shares marked by an earlier
+    /// run whose notification was never stored.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pool` - Database connection pool
+    /// * `kind` - Notification kind emitted for expired shares
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(Vec<ProcessShare>)` - Expired shares still missing a notification
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn list_unnotified_expired_shares(
+        pool: &SqlitePool,
+        kind: &str,
+    ) -> Result<Vec<ProcessShare>, sqlx::Error> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM process_shares s
+            WHERE s.expired_at IS NOT NULL
+              AND NOT EXISTS (
+                  SELECT 1 FROM notifications n
+                  WHERE n.share_id = s.id AND n.kind = ?1
+              )
+            ORDER BY s.expired_at
+            "#,
+            PROCESS_SHARE_COLUMNS
+        ))
+        .bind(kind)
+        .fetch_all(pool)
+        .await?;
+
+        rows.iter().map(process_share_from_row).collect()
+    }
+
+    /// Stores a notification for a user.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pool` - Database connection pool
+    /// * `user_id` - User the notification is addressed to
+    /// * `kind` - Event type (e.g. `share_expired`)
+    /// * `process_id` - Process the event relates to
+    /// * `share_id` - Share the event relates to, if any
+    /// * `message` - Human-readable description of the event
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(Notification)` - Created notification with generated ID and timestamp
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn create_notification(
+        pool: &SqlitePool,
+        user_id: &str,
+        kind: &str,
+        process_id: &str,
+        share_id: Option<&str>,
+        message: &str,
+    ) -> Result<Notification, sqlx::Error> {
+        let id = Uuid::new_v4().to_string();
+        let created_at = Utc::now();
+        let created_at_str = datetime_to_string(&created_at);
+
+        sqlx::query(
+            r#"
+            INSERT INTO notifications (id, user_id, kind, process_id, share_id, message, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            "#,
+        )
+        .bind(&id)
+        .bind(user_id)
+        .bind(kind)
+        .bind(process_id)
+        .bind(share_id)
+        .bind(message)
+        .bind(&created_at_str)
+        .execute(pool)
+        .await?;
+
+        Ok(Notification {
+            id,
+            user_id: user_id.to_string(),
+            kind: kind.to_string(),
+            process_id: process_id.to_string(),
+            share_id: share_id.map(str::to_string),
+            message: message.to_string(),
+            created_at,
+        })
+    }
+
+    /// Lists the notifications addressed to a user, newest first.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pool` - Database connection pool
+    /// * `user_id` - User whose notifications to list
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(Vec<Notification>)` - Notifications of the user
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn list_notifications_by_user(
+        pool: &SqlitePool,
+        user_id: &str,
+    ) -> Result<Vec<Notification>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, user_id, kind, process_id, share_id, message, created_at
+            FROM notifications
+            WHERE user_id = ?1
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                let created_at_str: String = row.get("created_at");
+                let created_at = string_to_datetime(&created_at_str).map_err(|_| sqlx::Error::ColumnDecode {
+                    index: "created_at".to_string(),
+                    source: Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid datetime")),
+                })?;
+
+                Ok(Notification {
+                    id: row.get("id"),
+                    user_id: row.get("user_id"),
+                    kind: row.get("kind"),
+                    process_id: row.get("process_id"),
+                    share_id: row.get("share_id"),
+                    message: row.get("message"),
+                    created_at,
+                })
+            })
+            .collect()
+    }
+
+    /// Stores the metadata of an encrypted process attachment.
+    /// 
+    /// The encrypted file itself is written by
+    /// [`crate::attachments::AttachmentStore`] under the same ID, with a key
+    /// derived from the data key wrapped as `encryption_key`. The record is
+    /// only stored while that is still the data key of the process, so a file
+    /// written during a data key rotation is never left without its key.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pool` - Database connection pool
+    /// * `id` - Attachment ID, also naming the encrypted file
+    /// * `process_id` - Process the attachment belongs to
+    /// * `filename` - Original (sanitized) filename
+    /// * `content_type` - MIME type given at upload
+    /// * `size_bytes` - Plaintext size in bytes
+    /// * `encryption_key` - Wrapped data key the file was encrypted with
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(Some(ProcessAttachment))` - Created attachment record
+    /// - `Ok(None)` - The data key of the process was rotated in the meantime
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn create_process_attachment(
+        pool: &SqlitePool,
+        id: &str,
+        process_id: &str,
+        filename: &str,
+        content_type: &str,
+        size_bytes: i64,
+        encryption_key: &str,
+    ) -> Result<Option<ProcessAttachment>, sqlx::Error> {
+        let created_at = Utc::now();
+        let created_at_str = datetime_to_string(&created_at);
+
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO process_attachments (id, process_id, filename, content_type, size_bytes, created_at)
+            SELECT ?1, ?2, ?3, ?4, ?5, ?6
+            WHERE EXISTS (SELECT 1 FROM processes WHERE id = ?2 AND encryption_key = ?7)
+            "#,
+        )
+        .bind(id)
+        .bind(process_id)
+        .bind(filename)
+        .bind(content_type)
+        .bind(size_bytes)
+        .bind(&created_at_str)
+        .bind(encryption_key)
+        .execute(pool)
+        .await?
+        .rows_affected();
+
+        if inserted == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(ProcessAttachment {
+            id: id.to_string(),
+            process_id: process_id.to_string(),
+            filename: filename.to_string(),
+            content_type: content_type.to_string(),
+            size_bytes,
+            file_key: None,
+            created_at,
+        }))
+    }
+
+    /// Lists the attachments of a process, oldest first.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pool` - Database connection pool
+    /// * `process_id` - Process whose attachments to list
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(Vec<ProcessAttachment>)` - Attachments of the process
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn list_process_attachments(
+        pool: &SqlitePool,
+        process_id: &str,
+    ) -> Result<Vec<ProcessAttachment>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, process_id, filename, content_type, size_bytes, file_key, created_at
+            FROM process_attachments
+            WHERE process_id = ?1
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(process_id)
+        .fetch_all(pool)
+        .await?;
+
+        rows.iter().map(process_attachment_from_row).collect()
+    }
+
+    /// Finds an attachment of a process by ID.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pool` - Database connection pool
+    /// * `process_id` - Process the attachment must belong to
+    /// * `attachment_id` - Attachment ID
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(Some(ProcessAttachment))` - Attachment found
+    /// - `Ok(None)` - No such attachment on this process
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn find_process_attachment(
+        pool: &SqlitePool,
+        process_id: &str,
+        attachment_id: &str,
+    ) -> Result<Option<ProcessAttachment>, sqlx::Error> {
+        let row = sqlx::query(
             r#"
-            INSERT INTO process_shares (id, process_id, partner_public_key, stellar_transaction_hash, shared_at)
-            VALUES (?1, ?2, ?3, ?4, ?5)
+            SELECT id, process_id, filename, content_type, size_bytes, file_key, created_at
+            FROM process_attachments
+            WHERE process_id = ?1 AND id = ?2
             "#,
         )
-        .bind(&id)
         .bind(process_id)
-        .bind(partner_public_key)
-        .bind(stellar_transaction_hash)
-        .bind(&shared_at_str)
-        .execute(pool)
+        .bind(attachment_id)
+        .fetch_optional(pool)
         .await?;
 
-        Ok(ProcessShare {
-            id,
-            process_id: process_id.to_string(),
-            partner_public_key: partner_public_key.to_string(),
-            stellar_transaction_hash: stellar_transaction_hash.to_string(),
-            shared_at,
+        row.as_ref().map(process_attachment_from_row).transpose()
+    }
+
+    fn process_attachment_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<ProcessAttachment, sqlx::Error> {
+        let created_at_str: String = row.get("created_at");
+        let created_at = string_to_datetime(&created_at_str).map_err(|_| sqlx::Error::ColumnDecode {
+            index: "created_at".to_string(),
+            source: Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid datetime")),
+        })?;
+
+        Ok(ProcessAttachment {
+            id: row.get("id"),
+            process_id: row.get("process_id"),
+            filename: row.get("filename"),
+            content_type: row.get("content_type"),
+            size_bytes: row.get("size_bytes"),
+            file_key: row.get("file_key"),
+            created_at,
         })
     }
 
@@ -805,7 +2883,7 @@ pub async fn create_user(
     /// 
     /// # Examples
     /// 
-    /// ```rust
+    /// ```rust,ignore
     /// let access = queries::create_process_access(
     ///     &pool,
     ///     &process.id,
@@ -848,9 +2926,140 @@ pub async fn create_user(
             process_id: process_id.to_string(),
             partner_id: partner_id.to_string(),
             accessed_at,
+            share_id: None,
+            attachment_id: None,
+            content_version: None,
         })
     }
 
+    /// Records an access through a share, enforcing the share's view limit.
+    /// 
+    /// The access is only inserted while the number of accesses recorded for
+    /// the share and partner is below `max_accesses`. Check and insert happen
+    /// in a single `INSERT ... SELECT` statement, which SQLite executes
+    /// atomically, so concurrent requests can never exceed the limit.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pool` - Database connection pool
+    /// * `share` - Share granting the access
+    /// * `partner_id` - ID of the partner accessing the process
+    /// * `attachment_id` - Attachment downloaded, `None` for access to the text content
+    /// * `content_version` - Content revision the partner is shown
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(Some((ProcessAccess, i64)))` - Created access record and the number of
+    ///   accesses recorded for the share and partner, including this one
+    /// - `Ok(None)` - View limit already reached, nothing recorded
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn create_share_access(
+        pool: &SqlitePool,
+        share: &ProcessShare,
+        partner_id: &str,
+        attachment_id: Option<&str>,
+        content_version: i64,
+    ) -> Result<Option<(ProcessAccess, i64)>, sqlx::Error> {
+        let id = Uuid::new_v4().to_string();
+        let accessed_at = Utc::now();
+        let accessed_at_str = datetime_to_string(&accessed_at);
+
+        let mut tx = pool.begin().await?;
+
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO process_accesses (id, process_id, partner_id, accessed_at, share_id, attachment_id, content_version)
+            SELECT ?1, ?2, ?3, ?4, ?5, ?7, ?8
+            WHERE ?6 IS NULL
+               OR (SELECT COUNT(*) FROM process_accesses WHERE share_id = ?5 AND partner_id = ?3) < ?6
+            "#,
+        )
+        .bind(&id)
+        .bind(&share.process_id)
+        .bind(partner_id)
+        .bind(&accessed_at_str)
+        .bind(&share.id)
+        .bind(share.max_accesses)
+        .bind(attachment_id)
+        .bind(content_version)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        if inserted == 0 {
+            tx.rollback().await?;
+            return Ok(None);
+        }
+
+        // The write lock is held until commit, so the count includes exactly this access
+        let access_count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM process_accesses WHERE share_id = ?1 AND partner_id = ?2",
+        )
+        .bind(&share.id)
+        .bind(partner_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some((
+            ProcessAccess {
+                id,
+                process_id: share.process_id.clone(),
+                partner_id: partner_id.to_string(),
+                accessed_at,
+                share_id: Some(share.id.clone()),
+                attachment_id: attachment_id.map(str::to_string),
+                content_version: Some(content_version),
+            },
+            access_count,
+        )))
+    }
+
+    /// Lists the partner accesses of a process, oldest first.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pool` - Database connection pool
+    /// * `process_id` - ID of the accessed process
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(Vec<ProcessAccess>)` - Accesses to the content and to attachments
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn list_process_accesses(
+        pool: &SqlitePool,
+        process_id: &str,
+    ) -> Result<Vec<ProcessAccess>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, process_id, partner_id, accessed_at, share_id, attachment_id, content_version
+            FROM process_accesses
+            WHERE process_id = ?1
+            ORDER BY accessed_at ASC, rowid ASC
+            "#,
+        )
+        .bind(process_id)
+        .fetch_all(pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(ProcessAccess {
+                    id: row.get("id"),
+                    process_id: row.get("process_id"),
+                    partner_id: row.get("partner_id"),
+                    accessed_at: datetime_column(row, "accessed_at")?,
+                    share_id: row.get("share_id"),
+                    attachment_id: row.get("attachment_id"),
+                    content_version: row.get("content_version"),
+                })
+            })
+            .collect()
+    }
+
     /// Lists all access events for processes owned by a client.
     /// 
     /// This function retrieves a comprehensive audit trail showing when
@@ -878,7 +3087,7 @@ pub async fn create_user(
     /// 
     /// # Examples
     /// 
-    /// ```rust
+    /// ```rust,ignore
     /// let accesses = queries::list_process_accesses_by_client(&pool, &client_id).await?;
     /// for access in accesses {
     ///     match (&access.partner_username, &access.accessed_at) {
@@ -931,6 +3140,8 @@ pub async fn create_user(
                 p.id as process_id,
                 pa.partner_id,
                 pa.accessed_at,
+                pa.attachment_id,
+                pa.content_version,
                 p.title as process_title,
                 p.description as process_description,
                 p.status as process_status,
@@ -984,6 +3195,430 @@ pub async fn create_user(
         Ok(accesses)
     }
 
+    /// Records a revoked JWT ID.
+    /// 
+    /// Revoking a token twice keeps the first revocation.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pool` - Database connection pool
+    /// * `jti` - JWT ID of the revoked token
+    /// * `expires_at` - Expiration of the token (Unix timestamp); the row can be
+    ///   pruned afterwards
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(())` - Revocation stored
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn revoke_token(pool: &SqlitePool, jti: &str, expires_at: i64) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO revoked_tokens (jti, expires_at, revoked_at)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT (jti) DO NOTHING
+            "#,
+        )
+        .bind(jti)
+        .bind(expires_at)
+        .bind(datetime_to_string(&Utc::now()))
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Looks up a revoked JWT ID.
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(Some(i64))` - The token is revoked; expiration of the token
+    /// - `Ok(None)` - The token is not revoked
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn find_revoked_token(pool: &SqlitePool, jti: &str) -> Result<Option<i64>, sqlx::Error> {
+        sqlx::query_scalar("SELECT expires_at FROM revoked_tokens WHERE jti = ?1")
+            .bind(jti)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// Counts the stored revocations.
+    pub async fn count_revoked_tokens(pool: &SqlitePool) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar("SELECT COUNT(*) FROM revoked_tokens")
+            .fetch_one(pool)
+            .await
+    }
+
+    /// Deletes the revocations of tokens that expired at or before `now`.
+    /// 
+    /// Expired tokens are rejected by signature validation anyway, so their
+    /// revocations no longer need to be kept.
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(u64)` - Number of rows deleted
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn delete_expired_revoked_tokens(pool: &SqlitePool, now: i64) -> Result<u64, sqlx::Error> {
+        Ok(sqlx::query("DELETE FROM revoked_tokens WHERE expires_at <= ?1")
+            .bind(now)
+            .execute(pool)
+            .await?
+            .rows_affected())
+    }
+
+    /// Deletes every stored revocation.
+    pub async fn delete_revoked_tokens(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM revoked_tokens").execute(pool).await?;
+        Ok(())
+    }
+
+    /// Starts a refresh token family for a login.
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(String)` - ID of the new family
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn create_refresh_token_family(pool: &SqlitePool, user_id: &str) -> Result<String, sqlx::Error> {
+        let id = Uuid::new_v4().to_string();
+
+        sqlx::query("INSERT INTO refresh_token_families (id, user_id, created_at) VALUES (?1, ?2, ?3)")
+            .bind(&id)
+            .bind(user_id)
+            .bind(datetime_to_string(&Utc::now()))
+            .execute(pool)
+            .await?;
+
+        Ok(id)
+    }
+
+    /// Records a refresh token issued within a family.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pool` - Database connection pool
+    /// * `jti` - JWT ID of the issued token
+    /// * `family_id` - Family the token belongs to
+    /// * `parent_jti` - Token rotated into this one, `None` at login
+    /// * `expires_at` - Expiration of the token (Unix timestamp)
+    pub async fn create_refresh_token(
+        pool: &SqlitePool,
+        jti: &str,
+        family_id: &str,
+        parent_jti: Option<&str>,
+        expires_at: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO refresh_tokens (jti, family_id, parent_jti, issued_at, expires_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            "#,
+        )
+        .bind(jti)
+        .bind(family_id)
+        .bind(parent_jti)
+        .bind(datetime_to_string(&Utc::now()))
+        .bind(expires_at)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Finds a refresh token with the revocation state of its family.
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(Some(RefreshToken))` - The token was issued by this server
+    /// - `Ok(None)` - Unknown token
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn find_refresh_token(pool: &SqlitePool, jti: &str) -> Result<Option<RefreshToken>, sqlx::Error> {
+        let row = sqlx::query(
+            r#"
+            SELECT t.jti, t.family_id, t.parent_jti, t.issued_at, t.expires_at, t.rotated_at,
+                   f.revoked_at AS family_revoked_at
+            FROM refresh_tokens t
+            JOIN refresh_token_families f ON f.id = t.family_id
+            WHERE t.jti = ?1
+            "#,
+        )
+        .bind(jti)
+        .fetch_optional(pool)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let parse = |column: &str, value: &str| {
+            string_to_datetime(value).map_err(|_| sqlx::Error::ColumnDecode { 
+                index: column.to_string(), 
+                source: Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid datetime")) 
+            })
+        };
+        let optional = |column: &str| {
+            row.get::<Option<String>, _>(column)
+                .map(|value| parse(column, &value))
+                .transpose()
+        };
+        let issued_at_str: String = row.get("issued_at");
+
+        Ok(Some(RefreshToken {
+            jti: row.get("jti"),
+            family_id: row.get("family_id"),
+            parent_jti: row.get("parent_jti"),
+            issued_at: parse("issued_at", &issued_at_str)?,
+            expires_at: row.get("expires_at"),
+            rotated_at: optional("rotated_at")?,
+            family_revoked_at: optional("family_revoked_at")?,
+        }))
+    }
+
+    /// Exchanges a refresh token for its successor within the same family.
+    /// 
+    /// Marks `jti` as rotated, records the refresh in the family's session and
+    /// stores the successor token in one transaction, so a failure leaves the
+    /// presented token unused. The rotation only applies to a token that was
+    /// not rotated yet, so of two concurrent exchanges only one succeeds.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pool` - Database connection pool
+    /// * `jti` - JWT ID of the presented refresh token
+    /// * `family_id` - Family both tokens belong to
+    /// * `next_jti` - JWT ID of the successor token
+    /// * `next_expires_at` - Expiration of the successor token (Unix timestamp)
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(true)` - The token was rotated and its successor recorded
+    /// - `Ok(false)` - The token had already been rotated
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn exchange_refresh_token(
+        pool: &SqlitePool,
+        jti: &str,
+        family_id: &str,
+        next_jti: &str,
+        next_expires_at: i64,
+    ) -> Result<bool, sqlx::Error> {
+        let now_str = datetime_to_string(&Utc::now());
+
+        let mut tx = pool.begin().await?;
+
+        let rotated = sqlx::query("UPDATE refresh_tokens SET rotated_at = ?1 WHERE jti = ?2 AND rotated_at IS NULL")
+            .bind(&now_str)
+            .bind(jti)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        if rotated == 0 {
+            tx.rollback().await?;
+            return Ok(false);
+        }
+
+        sqlx::query("UPDATE sessions SET last_refreshed_at = ?1 WHERE family_id = ?2")
+            .bind(&now_str)
+            .bind(family_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO refresh_tokens (jti, family_id, parent_jti, issued_at, expires_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            "#,
+        )
+        .bind(next_jti)
+        .bind(family_id)
+        .bind(jti)
+        .bind(&now_str)
+        .bind(next_expires_at)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    /// Revokes a refresh token family.
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(true)` - The family was revoked now
+    /// - `Ok(false)` - The family was already revoked or does not exist
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn revoke_refresh_token_family(
+        pool: &SqlitePool,
+        family_id: &str,
+        reason: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let revoked = sqlx::query(
+            r#"
+            UPDATE refresh_token_families
+            SET revoked_at = ?1, revocation_reason = ?2
+            WHERE id = ?3 AND revoked_at IS NULL
+            "#,
+        )
+        .bind(datetime_to_string(&Utc::now()))
+        .bind(reason)
+        .bind(family_id)
+        .execute(pool)
+        .await?
+        .rows_affected();
+
+        Ok(revoked == 1)
+    }
+
+    /// Columns selected for every [`Session`] query.
+    const SESSION_COLUMNS: &str = "s.id, s.user_id, s.family_id, s.user_agent, s.ip_address, s.created_at, \
+        s.last_refreshed_at, f.revoked_at";
+
+    /// Records the session of a login.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pool` - Database connection pool
+    /// * `user_id` - User who logged in
+    /// * `family_id` - Refresh token family started by the login
+    /// * `user_agent` - `User-Agent` of the login request, if sent
+    /// * `ip_address` - Client IP address of the login request, if known
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(Session)` - Created session with generated ID and timestamp
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn create_session(
+        pool: &SqlitePool,
+        user_id: &str,
+        family_id: &str,
+        user_agent: Option<&str>,
+        ip_address: Option<&str>,
+    ) -> Result<Session, sqlx::Error> {
+        let id = Uuid::new_v4().to_string();
+        let created_at = Utc::now();
+
+        sqlx::query(
+            r#"
+            INSERT INTO sessions (id, user_id, family_id, user_agent, ip_address, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+        )
+        .bind(&id)
+        .bind(user_id)
+        .bind(family_id)
+        .bind(user_agent)
+        .bind(ip_address)
+        .bind(datetime_to_string(&created_at))
+        .execute(pool)
+        .await?;
+
+        Ok(Session {
+            id,
+            user_id: user_id.to_string(),
+            family_id: family_id.to_string(),
+            user_agent: user_agent.map(str::to_string),
+            ip_address: ip_address.map(str::to_string),
+            created_at,
+            last_refreshed_at: None,
+            revoked_at: None,
+        })
+    }
+
+    /// Finds a session by ID.
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(Some(Session))` - Session found, revoked or not
+    /// - `Ok(None)` - No session with this ID
+    /// - `Err(sqlx::Error)` - Database connection or query error
+    pub async fn find_session(pool: &SqlitePool, session_id: &str) -> Result<Option<Session>, sqlx::Error> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM sessions s
+            JOIN refresh_token_families f ON f.id = s.family_id
+            WHERE s.id = ?1
+            "#,
+            SESSION_COLUMNS
+        ))
+        .bind(session_id)
+        .fetch_optional(pool)
+        .await?;
+
+        row.as_ref().map(session_from_row).transpose()
+    }
+
+    /// Lists the active sessions of a user, most recently used first.
+    /// 
+    /// A session is active while its refresh token family is not revoked
+    /// and it was started or refreshed after `active_since` (older sessions
+    /// only hold expired refresh tokens).
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pool` - Database connection pool
+    /// * `user_id` - User whose sessions to list
+    /// * `active_since` - Oldest last use of a session still able to refresh
+    pub async fn list_active_sessions(
+        pool: &SqlitePool,
+        user_id: &str,
+        active_since: DateTime<Utc>,
+    ) -> Result<Vec<Session>, sqlx::Error> {
+        // RFC3339 strings in UTC compare in chronological order
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM sessions s
+            JOIN refresh_token_families f ON f.id = s.family_id
+            WHERE s.user_id = ?1 AND f.revoked_at IS NULL
+              AND COALESCE(s.last_refreshed_at, s.created_at) > ?2
+            ORDER BY COALESCE(s.last_refreshed_at, s.created_at) DESC
+            "#,
+            SESSION_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(datetime_to_string(&active_since))
+        .fetch_all(pool)
+        .await?;
+
+        rows.iter().map(session_from_row).collect()
+    }
+
+    /// Maps a row selected with [`SESSION_COLUMNS`] to a [`Session`].
+    fn session_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<Session, sqlx::Error> {
+        let parse = |column: &str, value: &str| {
+            string_to_datetime(value).map_err(|_| sqlx::Error::ColumnDecode { 
+                index: column.to_string(), 
+                source: Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid datetime")) 
+            })
+        };
+        let optional = |column: &str| {
+            row.get::<Option<String>, _>(column)
+                .map(|value| parse(column, &value))
+                .transpose()
+        };
+        let created_at_str: String = row.get("created_at");
+
+        Ok(Session {
+            id: row.get("id"),
+            user_id: row.get("user_id"),
+            family_id: row.get("family_id"),
+            user_agent: row.get("user_agent"),
+            ip_address: row.get("ip_address"),
+            created_at: parse("created_at", &created_at_str)?,
+            last_refreshed_at: optional("last_refreshed_at")?,
+            revoked_at: optional("revoked_at")?,
+        })
+    }
+
     /// Safely processes a single row from the database query into ProcessAccessWithDetails.
     /// 
     /// This helper function isolates error handling for individual rows, ensuring that
@@ -1010,13 +3645,15 @@ pub async fn create_user(
         let process_description = row.try_get::<String, _>("process_description")
             .map_err(|e| format!("Failed to get process_description: {}", e))?;
         
-        let process_status = row.try_get::<String, _>("process_status")
+        let process_status = row.try_get::<ProcessStatus, _>("process_status")
             .map_err(|e| format!("Failed to get process_status: {}", e))?;
 
         // Handle optional fields safely
         let id = row.try_get::<String, _>("id").ok();
         let partner_id = row.try_get::<String, _>("partner_id").ok();
         let partner_username = row.try_get::<String, _>("partner_username").ok();
+        let attachment_id = row.try_get::<String, _>("attachment_id").ok();
+        let content_version = row.try_get::<i64, _>("content_version").ok();
 
         // Handle optional accessed_at field with careful datetime parsing
         let accessed_at = match row.try_get::<String, _>("accessed_at") {
@@ -1049,6 +3686,8 @@ pub async fn create_user(
             process_description,
             process_status,
             partner_username,
+            attachment_id,
+            content_version,
         })
     }
 
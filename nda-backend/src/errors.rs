@@ -0,0 +1,104 @@
+//! # API Error Module
+//!
+//! This module provides [`ApiError`], an HTTP error that carries a JSON body
+//! explaining why a request was rejected. Most handlers only return a
+//! [`StatusCode`]; handlers whose callers need to tell several failure reasons
+//! apart (e.g. an expired share versus a share that was never granted) return
+//! `ApiError` instead.
+//!
+//! ## Response Format
+//!
+//! ```json
+//! {
+//!   "error": "share_expired",
+//!   "message": "Share expired at 2024-03-31T00:00:00+00:00"
+//! }
+//! ```
+//!
+//! `error` is a stable machine-readable code, `message` is meant for humans.
+//!
+//! ## Compatibility
+//!
+//! `ApiError` implements `From<StatusCode>`, so existing
+//! `.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?` chains keep working in
+//! handlers returning `ApiError`. Plain status codes produce a body with the
+//! code derived from the canonical reason phrase.
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+
+use crate::models::ErrorResponse;
+
+/// HTTP error with a machine-readable code and a descriptive message.
+///
+/// # Fields
+///
+/// * `status` - HTTP status code of the response
+/// * `code` - Stable error identifier (e.g. `share_expired`)
+/// * `message` - Human-readable explanation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub code: String,
+    pub message: String,
+}
+
+impl ApiError {
+    /// Creates an error with an explicit status, code and message.
+    pub fn new(status: StatusCode, code: &str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            code: code.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// Creates a `401 Unauthorized` error.
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, "unauthorized", message)
+    }
+
+    /// Creates a `403 Forbidden` error.
+    pub fn forbidden(code: &str, message: impl Into<String>) -> Self {
+        Self::new(StatusCode::FORBIDDEN, code, message)
+    }
+}
+
+impl From<StatusCode> for ApiError {
+    fn from(status: StatusCode) -> Self {
+        let reason = status.canonical_reason().unwrap_or("Error");
+        Self {
+            status,
+            code: reason.to_ascii_lowercase().replace(' ', "_"),
+            message: reason.to_string(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = ErrorResponse {
+            error: self.code,
+            message: self.message,
+        };
+        (self.status, Json(body)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_code_conversion() {
+        let error = ApiError::from(StatusCode::NOT_FOUND);
+        assert_eq!(error.status, StatusCode::NOT_FOUND);
+        assert_eq!(error.code, "not_found");
+        assert_eq!(error.message, "Not Found");
+
+        let error = ApiError::forbidden("share_expired", "Share expired");
+        assert_eq!(error.into_response().status(), StatusCode::FORBIDDEN);
+    }
+}
@@ -0,0 +1,105 @@
+//! # Route Guards Module
+//!
+//! Authentication and role requirements for the HTTP routes.
+//!
+//! - [`AuthUser`] is an axum extractor producing the validated [`Claims`] of
+//!   the request's `Authorization: Bearer <token>` header. Handlers take it
+//!   instead of reading the header themselves.
+//! - [`authenticated`], [`client_only`] and [`partner_only`] are middleware
+//!   functions declaring what a route requires. They are attached in
+//!   `main.rs` with `route_layer`, so the requirement of every route is
+//!   visible where the route is declared:
+//!
+//! ```rust,ignore
+//! let client_routes = Router::new()
+//!     .route("/api/processes", post(handlers::create_process))
+//!     .route_layer(middleware::from_fn_with_state(state.clone(), guards::client_only));
+//! ```
+//!
+//! ## Responses
+//!
+//! Denials use the [`ApiError`] body of every other endpoint:
+//!
+//! ```json
+//! { "error": "unauthorized", "message": "Missing Authorization header" }
+//! { "error": "insufficient_role", "message": "This endpoint requires the partner role" }
+//! ```
+//!
+//! `401 Unauthorized` is returned for a missing, malformed, expired or revoked
+//! token, `403 Forbidden` when the token lacks the required role.
+
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Request},
+    http::{header, request::Parts},
+    middleware::Next,
+    response::Response,
+};
+use std::sync::Arc;
+
+use crate::{errors::ApiError, handlers::AppState, jwt::{self, Claims}};
+
+/// Role of users that own and share processes.
+pub const CLIENT_ROLE: &str = "client";
+
+/// Role of users that processes are shared with.
+pub const PARTNER_ROLE: &str = "partner";
+
+/// Claims of the authenticated user making the request.
+///
+/// Extraction validates the Bearer token with [`jwt::validate_auth_header`]
+/// (signature, expiration and blacklist) and rejects the request with a
+/// `401` [`ApiError`] otherwise. The claims are cached in the request
+/// extensions, so a guard and the handler behind it validate the token once.
+#[derive(Debug, Clone)]
+pub struct AuthUser(pub Claims);
+
+impl AuthUser {
+    /// Requires the authenticated user to hold `role`.
+    pub fn require_role(&self, role: &str) -> Result<(), ApiError> {
+        if self.0.has_role(role) {
+            Ok(())
+        } else {
+            Err(ApiError::forbidden(
+                "insufficient_role",
+                format!("This endpoint requires the {} role", role),
+            ))
+        }
+    }
+}
+
+#[async_trait]
+impl FromRequestParts<Arc<AppState>> for AuthUser {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &Arc<AppState>) -> Result<Self, Self::Rejection> {
+        if let Some(claims) = parts.extensions.get::<Claims>() {
+            return Ok(AuthUser(claims.clone()));
+        }
+
+        let auth_header = parts.headers.get(header::AUTHORIZATION).and_then(|h| h.to_str().ok());
+        let claims = jwt::validate_auth_header(auth_header, &state.jwt_secret, &state.token_blacklist)
+            .await
+            .map_err(ApiError::unauthorized)?;
+
+        parts.extensions.insert(claims.clone());
+        Ok(AuthUser(claims))
+    }
+}
+
+/// Guard of routes open to any authenticated user.
+pub async fn authenticated(_user: AuthUser, request: Request, next: Next) -> Response {
+    next.run(request).await
+}
+
+/// Guard of routes reserved to users with the client role.
+pub async fn client_only(user: AuthUser, request: Request, next: Next) -> Result<Response, ApiError> {
+    user.require_role(CLIENT_ROLE)?;
+    Ok(next.run(request).await)
+}
+
+/// Guard of routes reserved to users with the partner role.
+pub async fn partner_only(user: AuthUser, request: Request, next: Next) -> Result<Response, ApiError> {
+    user.require_role(PARTNER_ROLE)?;
+    Ok(next.run(request).await)
+}
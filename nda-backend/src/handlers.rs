@@ -19,10 +19,16 @@
 //! - `POST /api/processes` - Create new NDA processes with AES-256-GCM encryption
 //! - `GET /api/processes?client_id=<id>` - List processes owned by a specific client
 //! 
+//! ### NDA Templates
+//! - `POST /api/templates` / `GET /api/templates` - Create and list templates with typed placeholders
+//! - `GET|PUT|DELETE /api/templates/{template_id}` - Read, version or delete a template
+//! - `GET /api/templates/{template_id}/versions` - Version history of a template
+//! 
 //! ### Sharing & Access (Blockchain-Secured)
 //! - `POST /api/processes/share` - Share processes via Stellar blockchain transactions
 //! - `POST /api/processes/access` - Access shared processes with content decryption
 //! - `GET /api/notifications?client_id=<id>` - Get access audit trail for process owners
+//! - `GET /api/notifications/events` - Get events such as expired shares for the authenticated user
 //! 
 //! ## Role System
 //! 
@@ -40,13 +46,14 @@
 //! 
 //! - **Role-Based Access Control**: Endpoints verify user roles before operations
 //! - **AES-256-GCM Encryption**: All process content is encrypted with unique keys
+//! - **Envelope Encryption**: Per-process keys are stored wrapped by a key-encryption key
 //! - **Blockchain Verification**: Process sharing is recorded on Stellar network
 //! - **Access Control**: Partners can only access processes explicitly shared with them
 //! - **Complete Audit Trail**: All access events are logged for compliance
 //! 
 //! ## Updated Request Flow Example
 //! 
-//! ```rust
+//! ```rust,ignore
 //! // 1. Register users with roles
 //! let client = register_user(RegisterRequest {
 //!     username: "client_company".to_string(),
@@ -70,7 +77,6 @@
 //! 
 //! // 3. Share via blockchain (creates immutable record)
 //! let share = share_process(ShareProcessRequest {
-//!     client_username: "client_company".to_string(),
 //!     process_id: process.id,
 //!     partner_public_key: partner.stellar_public_key,
 //! }).await?;
@@ -78,8 +84,6 @@
 //! // 4. Partner accesses content (partner role required)
 //! let content = access_process(AccessProcessRequest {
 //!     process_id: process.id,
-//!     partner_username: "partner_corp".to_string(),
-//!     partner_public_key: partner.stellar_public_key,
 //! }).await?;
 //! ```
 //! 
@@ -89,7 +93,8 @@
 //! - `200 OK` - Successful operations
 //! - `400 Bad Request` - Invalid request parameters or missing required fields
 //! - `401 Unauthorized` - Authentication failures
-//! - `403 Forbidden` - Insufficient permissions or role requirements not met
+//! - `403 Forbidden` - Insufficient permissions or role requirements not met, or the
+//!   resource belongs to another user (see [`crate::policy`])
 //! - `404 Not Found` - Resource not found (user, process, etc.)
 //! - `409 Conflict` - Resource conflicts (e.g., username already exists)
 //! - `422 Unprocessable Entity` - Request valid but cannot be processed
@@ -104,24 +109,37 @@
 //! - Comprehensive audit trails that meet regulatory compliance requirements
 
 use axum::{
-    extract::{State, Json, Query},
-    response::Json as ResponseJson,
-    http::{StatusCode, HeaderMap},
+    body::Body,
+    extract::{ConnectInfo, State, Json, Query, Path, Multipart},
+    response::{Json as ResponseJson, Response},
+    http::{StatusCode, HeaderMap, HeaderValue, header},
 };
+use std::collections::HashMap;
+use std::net::SocketAddr;
 use std::sync::Arc;
+use base64::{engine::general_purpose, Engine as _};
 use chrono::Utc;
 use serde::Deserialize;
-use uuid::Uuid;
-use sqlx;
 use utoipa::ToSchema;
 
 use crate::{
     models::*,
-    stellar_real::StellarClient,
-    crypto::{generate_key, encrypt_content, decrypt_content},
+    ledger::{Ledger, ShareVerificationMode},
+    commitment,
+    crypto::{generate_key, encrypt_content_with, decrypt_content, is_integrity_error, process_aad, ContentCipher},
     database::queries,
     auth::Auth,
+    errors::ApiError,
+    guards::AuthUser,
     jwt,
+    policy,
+    keys::KeyRing,
+    key_provider::KeyProvider,
+    sealed_box,
+    attachments::{self, AttachmentError, AttachmentStore},
+    terms,
+    templates,
+    certificate::{Certificate, CertificateAccess, CertificateParty, CertificateShare, CertificateSigner},
 };
 
 /// Application state shared across all handlers.
@@ -135,6 +153,13 @@ use crate::{
 /// * `pool` - SQLite connection pool for database operations
 /// * `jwt_secret` - Secret key for JWT token signing and validation
 /// * `token_blacklist` - Token revocation list for logout/security
+/// * `ledger` - Blockchain backend (Stellar network or in-memory ledger)
+/// * `share_verification_mode` - How process shares are verified before access
+/// * `keyring` - Key-encryption keys wrapping the per-process data keys
+/// * `key_provider` - Seals Stellar secret keys stored in the users table
+/// * `attachments` - Directory of encrypted process attachments
+/// * `content_cipher` - Cipher used to encrypt new process content
+/// * `certificate_signer` - Platform key signing NDA certificates
 /// 
 /// # Thread Safety
 /// 
@@ -154,6 +179,13 @@ pub struct AppState {
     pub pool: sqlx::SqlitePool,
     pub jwt_secret: String,
     pub token_blacklist: crate::jwt::TokenBlacklist,
+    pub ledger: Arc<dyn Ledger>,
+    pub share_verification_mode: ShareVerificationMode,
+    pub keyring: KeyRing,
+    pub key_provider: Arc<dyn KeyProvider>,
+    pub attachments: AttachmentStore,
+    pub content_cipher: ContentCipher,
+    pub certificate_signer: Arc<CertificateSigner>,
 }
 
 /// Query parameters for endpoints that list processes.
@@ -170,7 +202,7 @@ pub struct AppState {
 /// 
 /// This struct is used with Axum's `Query` extractor to parse URL parameters:
 /// 
-/// ```
+/// ```ignore
 /// GET /api/processes?client_id=client-uuid
 /// ```
 #[derive(Deserialize, ToSchema)]
@@ -178,6 +210,48 @@ pub struct ListProcessesQuery {
     pub client_id: Option<String>,
 }
 
+/// Query parameters identifying the partner downloading an attachment.
+/// 
+/// Mirrors the partner fields of [`AccessProcessRequest`], as downloads
+/// are authorized the same way as access to the text content. Both are
+/// optional and must name the authenticated partner.
+/// 
+/// # Fields
+/// 
+/// * `partner_username` - Username of the partner downloading the file
+/// * `partner_public_key` - Stellar public key the process was shared with
+/// 
+/// # Usage
+/// 
+/// ```ignore
+/// GET /api/processes/process-uuid/attachments/attachment-uuid?partner_username=partner_corp&partner_public_key=GCKF...
+/// ```
+#[derive(Deserialize, ToSchema)]
+pub struct AttachmentDownloadQuery {
+    pub partner_username: Option<String>,
+    pub partner_public_key: Option<String>,
+}
+
+/// Query parameters identifying the partner fetching or signing NDA terms.
+/// 
+/// Both are optional and must name the authenticated partner.
+/// 
+/// # Fields
+/// 
+/// * `partner_username` - Username of the partner
+/// * `partner_public_key` - Stellar public key the process was shared with
+/// 
+/// # Usage
+/// 
+/// ```ignore
+/// GET /api/processes/process-uuid/terms?partner_username=partner_corp&partner_public_key=GCKF...
+/// ```
+#[derive(Deserialize, ToSchema)]
+pub struct TermsQuery {
+    pub partner_username: Option<String>,
+    pub partner_public_key: Option<String>,
+}
+
 /// Simple health check endpoint handler.
 /// 
 /// Returns a static "OK" string to verify that the service is running.
@@ -194,7 +268,7 @@ pub struct ListProcessesQuery {
 /// 
 /// # Examples
 /// 
-/// ```
+/// ```ignore
 /// GET /health
 /// → 200 OK
 /// → {"status": "OK", "timestamp": "2024-01-01T00:00:00Z"}
@@ -303,12 +377,11 @@ pub async fn register_user(
     }
     
     // Create real Stellar account
-    let stellar_account = StellarClient::generate_keypair()
+    let stellar_account = state.ledger.generate_keypair()
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
     // Fund account on testnet automatically
-    let stellar_client = StellarClient::new_testnet();
-    let _funded = stellar_client
+    let _funded = state.ledger
         .fund_testnet_account(&stellar_account.public_key)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
@@ -321,12 +394,18 @@ pub async fn register_user(
     let roles_json = serde_json::to_string(&payload.roles)
         .map_err(|_| StatusCode::BAD_REQUEST)?;
         
+    // Only the sealed secret key is stored
+    let sealed_secret_key = state.key_provider
+        .seal(&stellar_account.secret_key)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
     let user = queries::create_user(
         &state.pool,
         &payload.username,
         &payload.name,
         &stellar_account.public_key,
-        &stellar_account.secret_key,
+        &sealed_secret_key,
         &password_hash,
         &roles_json,
     )
@@ -384,7 +463,9 @@ pub async fn register_user(
 /// 
 /// - Passwords are hashed using bcrypt with salt for secure storage
 /// - Failed login attempts return generic "unauthorized" for security
-/// - Consider adding JWT tokens for session management and rate limiting
+/// - Each login starts a session (see [`list_sessions`]) with its own refresh
+///   token family, recording the `User-Agent` and client IP address
+/// - Consider adding rate limiting
 #[utoipa::path(
     post,
     path = "/api/users/login",
@@ -398,6 +479,8 @@ pub async fn register_user(
 )]
 pub async fn login_user(
     State(state): State<Arc<AppState>>,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
     Json(payload): Json<LoginRequest>,
 ) -> Result<ResponseJson<LoginResponse>, StatusCode> {
     // Find user by username
@@ -414,34 +497,115 @@ pub async fn login_user(
         return Err(StatusCode::UNAUTHORIZED);
     }
 
-    // Parse roles
+    // Every login starts a new refresh token family and session
+    let family_id = start_session(&state, &user.id, peer, &headers).await?;
+
+    Ok(ResponseJson(issue_tokens(&state, user, &family_id).await?))
+}
+
+/// Starts the refresh token family and session of a login.
+/// 
+/// The session records the request's `User-Agent` and client IP address:
+/// the first `X-Forwarded-For` entry when behind a proxy, the peer address
+/// otherwise. Both are informational, shown to the user listing sessions.
+/// 
+/// Returns the ID of the new family.
+async fn start_session(
+    state: &AppState,
+    user_id: &str,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    headers: &HeaderMap,
+) -> Result<String, StatusCode> {
+    let user_agent = headers.get(header::USER_AGENT).and_then(|value| value.to_str().ok());
+    let ip_address = headers
+        .get("x-forwarded-for")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(',').next())
+        .map(|value| value.trim().to_string())
+        .or_else(|| peer.map(|ConnectInfo(addr)| addr.ip().to_string()));
+
+    let family_id = queries::create_refresh_token_family(&state.pool, user_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    queries::create_session(&state.pool, user_id, &family_id, user_agent, ip_address.as_deref())
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(family_id)
+}
+
+/// Signs an access and refresh token pair within a refresh token family.
+/// 
+/// Returns the response together with the refresh token claims, which the
+/// caller records in the family. Roles are read from the stored user, so a
+/// refresh picks up role changes.
+fn sign_tokens(state: &AppState, user: User, family_id: &str) -> Result<(LoginResponse, jwt::Claims), StatusCode> {
     let roles: Vec<String> = serde_json::from_str(&user.roles)
         .unwrap_or_else(|_| vec![user.roles.clone()]);
 
-    // Generate JWT tokens
-    let access_token = crate::jwt::generate_access_token(
-        &user.id,
-        &user.username,
-        roles.clone(),
-        &state.jwt_secret,
-    )
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let access_claims = jwt::Claims::new(user.id.clone(), user.username.clone(), roles.clone(), jwt::ACCESS_TOKEN_MINUTES)
+        .in_family(family_id);
+    let refresh_claims = jwt::Claims::new(user.id.clone(), user.username.clone(), roles, jwt::REFRESH_TOKEN_MINUTES)
+        .in_family(family_id);
 
-    let refresh_token = crate::jwt::generate_refresh_token(
-        &user.id,
-        &user.username,
-        roles,
-        &state.jwt_secret,
-    )
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let access_token = jwt::encode_claims(&access_claims, &state.jwt_secret)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let refresh_token = jwt::encode_claims(&refresh_claims, &state.jwt_secret)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    Ok(ResponseJson(LoginResponse {
+    let response = LoginResponse {
         user: user.into(),
         access_token,
         refresh_token,
         token_type: "Bearer".to_string(),
-        expires_in: 900, // 15 minutes in seconds
-    }))
+        expires_in: jwt::ACCESS_TOKEN_MINUTES * 60,
+    };
+    Ok((response, refresh_claims))
+}
+
+/// Issues the first token pair of a refresh token family.
+async fn issue_tokens(state: &AppState, user: User, family_id: &str) -> Result<LoginResponse, StatusCode> {
+    let (response, refresh_claims) = sign_tokens(state, user, family_id)?;
+
+    queries::create_refresh_token(&state.pool, &refresh_claims.jti, family_id, None, refresh_claims.exp)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(response)
+}
+
+/// Revokes the family of a refresh token that was presented after its rotation.
+async fn reject_reused_refresh_token(state: &AppState, claims: &jwt::Claims, family_id: &str) -> ApiError {
+    if let Err(status) = revoke_refresh_family(state, family_id, "refresh_token_reuse").await {
+        return status.into();
+    }
+    tracing::warn!(
+        target: "security",
+        user_id = %claims.sub,
+        family_id = %family_id,
+        jti = %claims.jti,
+        "Rotated refresh token presented again, refresh token family revoked"
+    );
+    ApiError::new(
+        StatusCode::UNAUTHORIZED,
+        "refresh_token_reused",
+        "Refresh token was already used; all sessions of this login were revoked",
+    )
+}
+
+/// Revokes a refresh token family and every token issued in it.
+/// 
+/// The family ID is blacklisted for the lifetime of its newest possible
+/// refresh token, so the family's access and refresh tokens are rejected
+/// immediately. Returns `false` if the family was already revoked.
+async fn revoke_refresh_family(state: &AppState, family_id: &str, reason: &str) -> Result<bool, StatusCode> {
+    let revoked = queries::revoke_refresh_token_family(&state.pool, family_id, reason)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let exp = (Utc::now() + chrono::Duration::minutes(jwt::REFRESH_TOKEN_MINUTES)).timestamp();
+    state.token_blacklist.revoke(family_id, exp).await;
+    Ok(revoked)
 }
 
 /// Performs automatic login using localStorage information.
@@ -530,6 +694,13 @@ pub async fn auto_login_user(
 /// without requiring re-authentication. The old refresh token is revoked
 /// and a new pair of tokens is issued.
 /// 
+/// Refresh tokens are rotated within the family started at login: the new
+/// refresh token is recorded as the child of the one presented. A refresh
+/// token can only be exchanged once. Presenting it again means it was
+/// copied, so the whole family is revoked (including the tokens of the
+/// legitimate holder), the event is logged as a security event and the
+/// request is rejected with `refresh_token_reused`.
+/// 
 /// # Parameters
 /// 
 /// * `state` - Shared application state with JWT secret and token blacklist
@@ -539,18 +710,20 @@ pub async fn auto_login_user(
 /// 
 /// Returns `Result` containing:
 /// - `Ok(ResponseJson<LoginResponse>)` - New tokens issued successfully
-/// - `Err(StatusCode)` - HTTP error code indicating failure reason
+/// - `Err(ApiError)` - HTTP error code with an error body indicating failure reason
 /// 
 /// # HTTP Responses
 /// 
 /// - **200 OK**: New tokens issued successfully
-/// - **401 Unauthorized**: Invalid, expired, or revoked token
+/// - **401 Unauthorized**: Invalid, expired, or revoked token, revoked family,
+///   or an already rotated token (`refresh_token_reused`)
 /// - **404 Not Found**: User not found
 /// - **500 Internal Server Error**: Token generation error
 /// 
 /// # Security
 /// 
 /// - Validates refresh token signature and expiration
+/// - Detects reuse of rotated tokens and revokes their family
 /// - Checks token against revocation blacklist
 /// - Revokes old refresh token after issuing new one
 /// - Fetches current user data to include latest roles
@@ -584,7 +757,7 @@ pub async fn auto_login_user(
     request_body = RefreshTokenRequest,
     responses(
         (status = 200, description = "Tokens refreshed successfully", body = LoginResponse),
-        (status = 401, description = "Invalid or expired refresh token"),
+        (status = 401, description = "Invalid, expired, revoked or reused refresh token", body = ErrorResponse),
         (status = 404, description = "User not found"),
         (status = 500, description = "Internal server error")
     ),
@@ -592,15 +765,32 @@ pub async fn auto_login_user(
 )]
 pub async fn refresh_token(
     State(state): State<Arc<AppState>>,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
     Json(payload): Json<RefreshTokenRequest>,
-) -> Result<ResponseJson<LoginResponse>, StatusCode> {
+) -> Result<ResponseJson<LoginResponse>, ApiError> {
     // Validate refresh token
     let claims = jwt::validate_token(&payload.refresh_token, &state.jwt_secret)
-        .map_err(|_| StatusCode::UNAUTHORIZED)?;
+        .map_err(|_| ApiError::unauthorized("Invalid or expired refresh token"))?;
+    
+    if let Some(family_id) = &claims.fam {
+        let issued = queries::find_refresh_token(&state.pool, &claims.jti)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+            .filter(|token| &token.family_id == family_id)
+            .ok_or_else(|| ApiError::unauthorized("Unknown refresh token"))?;
+        if issued.family_revoked_at.is_some() {
+            return Err(ApiError::unauthorized("Refresh token family has been revoked"));
+        }
+        // Only one exchange of a refresh token can succeed; any other is a replay
+        if issued.rotated_at.is_some() {
+            return Err(reject_reused_refresh_token(&state, &claims, family_id).await);
+        }
+    }
     
     // Check if token is revoked
     if state.token_blacklist.is_revoked(&claims.jti).await {
-        return Err(StatusCode::UNAUTHORIZED);
+        return Err(ApiError::unauthorized("Refresh token has been revoked"));
     }
     
     // Fetch current user data
@@ -609,37 +799,35 @@ pub async fn refresh_token(
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
         .ok_or(StatusCode::NOT_FOUND)?;
     
-    // Parse current roles
-    let roles: Vec<String> = serde_json::from_str(&user.roles)
-        .unwrap_or_else(|_| vec![user.roles.clone()]);
-    
-    // Generate new tokens
-    let new_access_token = jwt::generate_access_token(
-        &user.id,
-        &user.username,
-        roles.clone(),
-        &state.jwt_secret,
-    )
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-    
-    let new_refresh_token = jwt::generate_refresh_token(
-        &user.id,
-        &user.username,
-        roles,
-        &state.jwt_secret,
-    )
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let response = match &claims.fam {
+        // Nothing is written before the exchange, so a failure leaves the token usable
+        Some(family_id) => {
+            let (response, next_claims) = sign_tokens(&state, user, family_id)?;
+            let exchanged = queries::exchange_refresh_token(
+                &state.pool,
+                &claims.jti,
+                family_id,
+                &next_claims.jti,
+                next_claims.exp,
+            )
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+            if !exchanged {
+                return Err(reject_reused_refresh_token(&state, &claims, family_id).await);
+            }
+            response
+        }
+        // Tokens issued before families were recorded start a new family and session
+        None => {
+            let family_id = start_session(&state, &user.id, peer, &headers).await?;
+            issue_tokens(&state, user, &family_id).await?
+        }
+    };
     
     // Revoke old refresh token
     state.token_blacklist.revoke(&claims.jti, claims.exp).await;
     
-    Ok(ResponseJson(LoginResponse {
-        user: user.into(),
-        access_token: new_access_token,
-        refresh_token: new_refresh_token,
-        token_type: "Bearer".to_string(),
-        expires_in: 900,
-    }))
+    Ok(ResponseJson(response))
 }
 
 /// Logout user and revoke tokens.
@@ -669,6 +857,8 @@ pub async fn refresh_token(
 /// 
 /// - Validates tokens before adding to blacklist
 /// - Accepts both access and refresh tokens
+/// - Revokes the refresh token family of the login, so tokens rotated from
+///   it stop working as well
 /// - Frontend should clear all stored tokens after logout
 /// - Revoked tokens cannot be used even if not expired
 /// 
@@ -709,10 +899,13 @@ pub async fn logout_user(
         }
     }
     
-    // Validate and revoke refresh token if provided
+    // Validate and revoke refresh token (and the family of its login) if provided
     if let Some(refresh_token) = &payload.refresh_token {
         if let Ok(claims) = jwt::validate_token(refresh_token, &state.jwt_secret) {
             state.token_blacklist.revoke(&claims.jti, claims.exp).await;
+            if let Some(family_id) = &claims.fam {
+                revoke_refresh_family(&state, family_id, "logout").await?;
+            }
         }
     }
     
@@ -739,12 +932,16 @@ pub async fn logout_user(
 /// 
 /// Returns `Result` containing:
 /// - `Ok(ResponseJson<ProcessResponse>)` - Successfully created encrypted process
-/// - `Err(StatusCode)` - HTTP error code indicating failure reason
+/// - `Err(ApiError)` - HTTP error code with an error body indicating failure reason
 /// 
 /// # HTTP Responses
 /// 
 /// - **200 OK**: Process created successfully
-/// - **403 Forbidden**: User doesn't have client role
+/// - **400 Bad Request**: Both content and a template were given (`content_conflict`),
+///   or the template values are invalid (`invalid_template_values`)
+/// - **403 Forbidden**: User doesn't have client role, or doesn't own the template
+/// - **404 Not Found**: Template or template version not found
+/// - **409 Conflict**: Template is archived (`template_archived`)
 /// - **422 Unprocessable Entity**: Client ID not found
 /// - **500 Internal Server Error**: Encryption or database error
 /// 
@@ -755,7 +952,31 @@ pub async fn logout_user(
 ///   "client_id": "client-uuid-string",
 ///   "title": "Software Development NDA",
 ///   "description": "Confidential software project details",
-///   "confidential_content": "Sensitive technical details and trade secrets..."
+///   "confidential_content": "Sensitive technical details and trade secrets...",
+///   "draft": false
+/// }
+/// ```
+/// 
+/// `draft` is optional; draft processes cannot be shared until they are
+/// activated (see [`transition_process`]).
+/// 
+/// To create the process from an NDA template, omit `confidential_content`
+/// and give the template and the values of its placeholders. The content is
+/// rendered and validated before encryption; `template_version` defaults to
+/// the current version of the template.
+/// 
+/// ```json
+/// {
+///   "client_id": "client-uuid-string",
+///   "title": "Supplier NDA - Acme",
+///   "description": "Mutual NDA for the Acme supply agreement",
+///   "template_id": "template-uuid",
+///   "template_values": {
+///     "receiving_party": "Acme Corp",
+///     "effective_date": "2026-11-01",
+///     "term_months": 24,
+///     "jurisdiction": "Delaware"
+///   }
 /// }
 /// ```
 /// 
@@ -768,6 +989,8 @@ pub async fn logout_user(
 ///   "title": "Software Development NDA",
 ///   "description": "Confidential software project details",
 ///   "status": "active",
+///   "template_id": null,
+///   "template_version": null,
 ///   "created_at": "2024-01-01T00:00:00Z"
 /// }
 /// ```
@@ -776,6 +999,7 @@ pub async fn logout_user(
 /// 
 /// - Content is encrypted with AES-256-GCM before storage
 /// - Each process gets a unique encryption key
+/// - The key is stored wrapped by the active key-encryption key, never in plaintext
 /// - Encryption keys are stored separately from content
 /// - Only the process owner and explicitly shared partners can decrypt
 /// 
@@ -791,10 +1015,13 @@ pub async fn logout_user(
     request_body = CreateProcessRequest,
     responses(
         (status = 200, description = "Process created successfully", body = ProcessResponse),
-        (status = 401, description = "Unauthorized - Invalid or missing token"),
-        (status = 403, description = "User doesn't have client role"),
-        (status = 422, description = "Client ID not found"),
-        (status = 500, description = "Internal server error")
+        (status = 400, description = "Both content and a template given, or invalid template values", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing token", body = ErrorResponse),
+        (status = 403, description = "User doesn't have client role or doesn't own the template", body = ErrorResponse),
+        (status = 404, description = "Template or template version not found", body = ErrorResponse),
+        (status = 409, description = "Template is archived", body = ErrorResponse),
+        (status = 422, description = "Client ID not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     security(
         ("bearer_auth" = [])
@@ -803,24 +1030,11 @@ pub async fn logout_user(
 )]
 pub async fn create_process(
     State(state): State<Arc<AppState>>,
-    headers: HeaderMap,
+    AuthUser(claims): AuthUser,
     Json(payload): Json<CreateProcessRequest>,
-) -> Result<ResponseJson<ProcessResponse>, StatusCode> {
-    // Validate JWT token and extract claims
-    let auth_header = headers.get("authorization").and_then(|h| h.to_str().ok());
-    let claims = jwt::validate_auth_header(auth_header, &state.jwt_secret, &state.token_blacklist)
-        .await
-        .map_err(|_| StatusCode::UNAUTHORIZED)?;
-    
-    // Verify token user ID matches payload client ID
-    if claims.sub != payload.client_id {
-        return Err(StatusCode::FORBIDDEN);
-    }
-    
-    // Verify user has client role in JWT claims
-    if !claims.roles.contains(&"client".to_string()) {
-        return Err(StatusCode::FORBIDDEN);
-    }
+) -> Result<ResponseJson<ProcessResponse>, ApiError> {
+    // Processes are created for the authenticated client only (the client role is checked by the route guard)
+    policy::own_client_id(&claims, Some(&payload.client_id))?;
     
     // Find client by ID (already validated via JWT)
     let client = queries::find_user_by_id(&state.pool, &payload.client_id)
@@ -828,17 +1042,40 @@ pub async fn create_process(
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
         .ok_or(StatusCode::UNPROCESSABLE_ENTITY)?;
 
+    // Content is either given verbatim or rendered from one of the client's templates
+    let (content, template) = match payload.template_id.as_deref() {
+        Some(template_id) => {
+            let (content, version) = render_template_content(&state, &client.id, template_id, &payload).await?;
+            (content, Some(version))
+        }
+        None => (payload.confidential_content.clone(), None),
+    };
+
+    // The content is bound to the new process, so its ID is needed up front
+    let process_id = uuid::Uuid::new_v4().to_string();
     let encryption_key = generate_key();
-    let encrypted_content = encrypt_content(&payload.confidential_content, &encryption_key)
+    let encrypted_content = encrypt_content_with(
+        &content,
+        &encryption_key,
+        state.content_cipher,
+        &process_aad(&process_id, &client.id, 1),
+    )
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let wrapped = state.keyring.wrap_key(&encryption_key)
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
     let process = queries::create_process(
         &state.pool,
+        &process_id,
         &client.id,
         &payload.title,
         &payload.description,
         &encrypted_content,
-        &encryption_key,
+        &hex::encode(terms::content_sha256(&content)),
+        &wrapped.wrapped_key,
+        Some(&wrapped.key_id),
+        if payload.draft { ProcessStatus::Draft } else { ProcessStatus::Active },
+        template.as_ref(),
     )
     .await
     .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
@@ -846,494 +1083,4857 @@ pub async fn create_process(
     Ok(ResponseJson(process.into()))
 }
 
-/// Shares a process with a partner via Stellar blockchain transaction.
+/// Renders the content of a new process from one of the client's templates.
+/// 
+/// Returns the rendered content and the template version it came from.
+async fn render_template_content(
+    state: &AppState,
+    client_id: &str,
+    template_id: &str,
+    payload: &CreateProcessRequest,
+) -> Result<(String, NdaTemplateVersion), ApiError> {
+    if !payload.confidential_content.is_empty() {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "content_conflict",
+            "Give either confidential_content or a template, not both",
+        ));
+    }
+
+    let template = queries::find_template(&state.pool, template_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or_else(|| ApiError::new(StatusCode::NOT_FOUND, "template_not_found", "Template not found"))?;
+    if template.owner_id != client_id {
+        return Err(StatusCode::FORBIDDEN.into());
+    }
+    ensure_template_active(&template)?;
+
+    let version = payload.template_version.unwrap_or(template.version);
+    let template_version = queries::find_template_version(&state.pool, &template.id, version)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or_else(|| {
+            ApiError::new(
+                StatusCode::NOT_FOUND,
+                "template_version_not_found",
+                format!("Template has no version {}", version),
+            )
+        })?;
+
+    let content = templates::render(&template_version.body, &template_version.placeholders, &payload.template_values)
+        .map_err(|e| ApiError::new(StatusCode::BAD_REQUEST, "invalid_template_values", e.to_string()))?;
+
+    Ok((content, template_version))
+}
+
+/// Maps a failure to decrypt process content to an API error.
+/// 
+/// Integrity errors (ciphertext moved from another row or tampered with in
+/// the database) are reported as `409 content_integrity_error`; anything
+/// else is an internal error.
+fn content_decryption_error(process_id: &str, error: Box<dyn std::error::Error>) -> ApiError {
+    if is_integrity_error(error.as_ref()) {
+        tracing::error!("Integrity check failed for content of process {}: {}", process_id, error);
+        ApiError::new(
+            StatusCode::CONFLICT,
+            "content_integrity_error",
+            "Stored content does not belong to this process",
+        )
+    } else {
+        tracing::error!("Failed to decrypt content of process {}: {}", process_id, error);
+        StatusCode::INTERNAL_SERVER_ERROR.into()
+    }
+}
+
+/// Loads a process and checks that the authenticated user owns it.
+async fn find_owned_process(state: &AppState, claims: &jwt::Claims, process_id: &str) -> Result<Process, ApiError> {
+    let process = queries::find_process_by_id(&state.pool, process_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    policy::ensure_process_owner(claims, &process)?;
+
+    Ok(process)
+}
+
+/// Denies partner access to processes that are not active.
+fn ensure_partner_access(process: &Process) -> Result<(), ApiError> {
+    if process.status.allows_partner_access() {
+        Ok(())
+    } else {
+        println!("❌ Access denied: Process {} is {}", process.id, process.status);
+        Err(ApiError::forbidden("process_not_active", format!("Process is {}", process.status)))
+    }
+}
+
+/// Rejects changes to the content of closed and archived processes.
+fn ensure_editable(process: &Process) -> Result<(), ApiError> {
+    if process.status.allows_edits() {
+        Ok(())
+    } else {
+        Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "process_not_editable",
+            format!("Process is {} and can no longer be changed", process.status),
+        ))
+    }
+}
+
+/// Decrypts the current revision of a process.
+fn decrypt_current_content(state: &AppState, process: &Process) -> Result<String, ApiError> {
+    let encryption_key = state.keyring
+        .unwrap_key(process.key_id.as_deref(), &process.encryption_key)
+        .map_err(|e| {
+            tracing::error!("Failed to unwrap data key of process {}: {}", process.id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    let aad = process_aad(&process.id, &process.client_id, process.content_version);
+    decrypt_content(&process.encrypted_content, &encryption_key, &aad)
+        .map_err(|e| content_decryption_error(&process.id, e))
+}
+
+/// Attempts at storing a share or revocation while the data key keeps rotating.
+const DATA_KEY_ROTATION_ATTEMPTS: usize = 3;
+
+/// Error returned when a process changed while a data key rotation was prepared.
+fn process_update_conflict() -> ApiError {
+    ApiError::new(
+        StatusCode::CONFLICT,
+        "process_update_conflict",
+        "Process content was updated concurrently, reload and try again",
+    )
+}
+
+/// Prepares moving a process to a fresh data key.
+/// 
+/// Re-encrypts every revision, stores the file key of every attachment under
+/// the new key and seals the new key to the partner of every zero-knowledge
+/// share that is not revoked, except `revoked_share`. Returns the new data
+/// key with the rotation to store (see [`queries::DataKeyRotation`]).
+async fn prepare_data_key_rotation(
+    state: &AppState,
+    process: &Process,
+    revoked_share: Option<&str>,
+) -> Result<(String, queries::DataKeyRotation), ApiError> {
+    let previous_key = state.keyring
+        .unwrap_key(process.key_id.as_deref(), &process.encryption_key)
+        .map_err(|e| {
+            tracing::error!("Failed to unwrap data key of process {}: {}", process.id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    let data_key = generate_key();
+    let wrapped = state.keyring.wrap_key(&data_key).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let mut revisions = Vec::new();
+    for (version, encrypted_content) in queries::list_process_revision_contents(&state.pool, &process.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    {
+        let aad = process_aad(&process.id, &process.client_id, version);
+        let content = decrypt_content(&encrypted_content, &previous_key, &aad)
+            .map_err(|e| content_decryption_error(&process.id, e))?;
+        let encrypted_content = encrypt_content_with(&content, &data_key, state.content_cipher, &aad)
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        revisions.push((version, encrypted_content));
+    }
+
+    let mut file_keys = Vec::new();
+    for attachment in queries::list_process_attachments(&state.pool, &process.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    {
+        let file_key = match &attachment.file_key {
+            Some(file_key) => attachments::unwrap_file_key(file_key, &previous_key, &attachment.id)
+                .map_err(AttachmentError::from),
+            None => state.attachments.derived_file_key(&attachment.id, &previous_key).await,
+        }
+        .map_err(|e| {
+            tracing::error!("Failed to read file key of attachment {}: {}", attachment.id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+        let file_key = attachments::wrap_file_key(&file_key, &data_key, state.content_cipher, &attachment.id)
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        file_keys.push((attachment.id, file_key));
+    }
+
+    let key_bytes = general_purpose::STANDARD
+        .decode(&data_key)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let mut sealed_keys = Vec::new();
+    for share in queries::list_process_shares(&state.pool, &process.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    {
+        if share.wrapped_key.is_none() || share.is_revoked() || Some(share.id.as_str()) == revoked_share {
+            continue;
+        }
+        let sealed = sealed_box::seal(&key_bytes, &share.partner_public_key)
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        sealed_keys.push((share.id, sealed));
+    }
+
+    let rotation = queries::DataKeyRotation {
+        previous_key: process.encryption_key.clone(),
+        content_version: process.content_version,
+        encryption_key: wrapped.wrapped_key,
+        key_id: wrapped.key_id,
+        revisions,
+        file_keys,
+        sealed_keys,
+    };
+    Ok((data_key, rotation))
+}
+
+/// Builds the current NDA terms of a process for a partner.
 /// 
-/// This endpoint creates an immutable record of process sharing on the Stellar
-/// blockchain. It submits a transaction that proves the client has shared access
-/// to a specific process with a specific partner, creating an audit trail.
+/// Uses the plaintext digest stored with the current revision, so the
+/// content is never decrypted to build terms.
+async fn current_terms(state: &AppState, process: &Process, partner_public_key: &str) -> Result<NdaTerms, ApiError> {
+    let owner = queries::find_user_by_id(&state.pool, &process.client_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+    let content_hash = process
+        .content_sha256
+        .as_deref()
+        .and_then(commitment::decode_hash)
+        .ok_or_else(|| {
+            tracing::error!("Process {} has no content digest", process.id);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(terms::nda_terms(process, &owner.stellar_public_key, partner_public_key, &content_hash))
+}
+
+/// Requires the partner's signature of the given terms.
+async fn require_signed_terms(state: &AppState, terms: &NdaTerms, partner: &User) -> Result<TermsSignature, ApiError> {
+    queries::find_terms_signature(&state.pool, &terms.process_id, &partner.id, &terms.terms_digest)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or_else(|| {
+            println!("❌ Access denied: Terms of version {} not signed", terms.content_version);
+            ApiError::forbidden(
+                "terms_not_signed",
+                format!(
+                    "The NDA terms of content version {} must be signed before accessing the process",
+                    terms.content_version
+                ),
+            )
+        })
+}
+
+/// Amends the confidential content of a process with a new revision.
+/// 
+/// The process moves to a fresh data key: the new content is encrypted under
+/// it, bound to the next content version, and becomes what partners see on
+/// their next access. Previous revisions are kept and re-encrypted under the
+/// new key (see [`list_process_revisions`]), and the key is re-sealed to the
+/// partners of active zero-knowledge shares.
 /// 
 /// # Parameters
 /// 
 /// * `state` - Shared application state containing database pool
-/// * `payload` - Process sharing request with IDs and recipient
+/// * `process_id` - ID of the process (path parameter)
+/// * `claims` - Claims of the authenticated user
+/// * `payload` - New content and optional change note
 /// 
 /// # Returns
 /// 
 /// Returns `Result` containing:
-/// - `Ok(ResponseJson<ProcessShare>)` - Successfully recorded sharing event
-/// - `Err(StatusCode)` - HTTP error code indicating failure reason
+/// - `Ok(ResponseJson<ProcessRevision>)` - The revision that was created
+/// - `Err(ApiError)` - HTTP error code with an error body indicating failure reason
 /// 
 /// # HTTP Responses
 /// 
-/// - **200 OK**: Process shared successfully with blockchain proof
-/// - **404 Not Found**: Process or client not found
-/// - **500 Internal Server Error**: Blockchain transaction or database error
+/// - **200 OK**: Revision created
+/// - **401 Unauthorized**: Missing, invalid or revoked access token
+/// - **403 Forbidden**: Authenticated user does not own the process
+/// - **404 Not Found**: Process not found
+/// - **409 Conflict**: Content was updated concurrently (`process_update_conflict`)
+///   or the process is closed or archived (`process_not_editable`)
+/// - **500 Internal Server Error**: Encryption or database error
 /// 
 /// # Request Body
 /// 
 /// ```json
 /// {
-///   "client_username": "client_company",
-///   "process_id": "process-uuid",
-///   "partner_public_key": "GCKFBEIYTKP..."
-/// }
-/// ```
-/// 
-/// # Response Body
-/// 
-/// ```json
-/// {
-///   "id": "share-uuid",
-///   "process_id": "process-uuid",
-///   "partner_public_key": "GCKFBEIYTKP...",
-///   "stellar_transaction_hash": "abc123...",
-///   "shared_at": "2024-01-01T00:00:00Z"
+///   "confidential_content": "Amended terms...",
+///   "change_note": "Extended confidentiality period to 5 years"
 /// }
 /// ```
-/// 
-/// # Blockchain Integration
-/// 
-/// - Creates a Stellar transaction with process sharing metadata
-/// - Transaction hash provides immutable proof of sharing
-/// - Memo field contains process ID for reference
-/// - Transaction is recorded on Stellar testnet for development
-/// 
-/// # Security & Compliance
-/// 
-/// - Immutable blockchain record prevents disputes
-/// - Transaction hash can be independently verified
-/// - Sharing permissions are cryptographically provable
-/// - Audit trail meets regulatory requirements
 #[utoipa::path(
-    post,
-    path = "/api/processes/share",
-    request_body = ShareProcessRequest,
+    put,
+    path = "/api/processes/{process_id}",
+    params(
+        ("process_id" = String, Path, description = "Process ID")
+    ),
+    request_body = UpdateProcessRequest,
     responses(
-        (status = 200, description = "Process shared successfully", body = ProcessShare),
-        (status = 404, description = "Process or client not found"),
-        (status = 500, description = "Internal server error")
+        (status = 200, description = "Revision created", body = ProcessRevision),
+        (status = 401, description = "Unauthorized - Invalid or missing token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Not the process owner", body = ErrorResponse),
+        (status = 404, description = "Process not found", body = ErrorResponse),
+        (status = 409, description = "Content was updated concurrently, or the process is closed or archived", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
-    tag = "Sharing & Access"
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Process Management"
 )]
-pub async fn share_process(
+pub async fn update_process(
     State(state): State<Arc<AppState>>,
-    Json(payload): Json<ShareProcessRequest>,
-) -> Result<ResponseJson<ProcessShare>, StatusCode> {
-    let stellar_client = StellarClient::new_testnet();
-    
-    // Find process
-    let _process = queries::find_process_by_id(&state.pool, &payload.process_id)
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
-        .ok_or(StatusCode::NOT_FOUND)?;
+    Path(process_id): Path<String>,
+    AuthUser(claims): AuthUser,
+    Json(payload): Json<UpdateProcessRequest>,
+) -> Result<ResponseJson<ProcessRevision>, ApiError> {
+    let process = find_owned_process(&state, &claims, &process_id).await?;
+    ensure_editable(&process)?;
 
-    // Find client by username
-    let client = queries::find_user_by_username(&state.pool, &payload.client_username)
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
-        .ok_or(StatusCode::NOT_FOUND)?;
-
-    // Send real Stellar transaction
-    let tx_result = stellar_client
-        .share_process_transaction(
-            &client.stellar_secret_key,
-            &payload.partner_public_key,
-            &payload.process_id,
-            &format!("NDA_SHARE:{}", payload.process_id),
-        )
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let (data_key, rotation) = prepare_data_key_rotation(&state, &process, None).await?;
+    let version = process.content_version + 1;
+    let encrypted_content = encrypt_content_with(
+        &payload.confidential_content,
+        &data_key,
+        state.content_cipher,
+        &process_aad(&process.id, &process.client_id, version),
+    )
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    // Register sharing event
-    let share = queries::create_process_share(
+    let change_note = payload.change_note.as_deref().map(str::trim).filter(|note| !note.is_empty());
+    let revision = queries::update_process_content(
         &state.pool,
-        &payload.process_id,
-        &payload.partner_public_key,
-        &tx_result.hash,
+        &process.id,
+        &encrypted_content,
+        &hex::encode(terms::content_sha256(&payload.confidential_content)),
+        &claims.sub,
+        change_note,
+        &rotation,
     )
     .await
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or_else(process_update_conflict)?;
 
-    Ok(ResponseJson(share))
+    println!("📝 Process {} amended to version {}", process.id, revision.version);
+
+    Ok(ResponseJson(revision))
 }
 
-/// Allows partners to access shared process content with decryption.
+/// Lists the content revisions of a process, newest first.
 /// 
-/// This endpoint verifies that a process has been properly shared with a partner
-/// (by checking the sharing records), then decrypts and returns the confidential
-/// content. It also logs the access event for audit trails and compliance.
+/// Each revision reports how many partner accesses saw it; the accesses
+/// themselves (with their `content_version`) are listed by `/api/notifications`.
+/// Only the process owner can list revisions.
 /// 
 /// # Parameters
 /// 
 /// * `state` - Shared application state containing database pool
-/// * `payload` - Process access request with process ID and partner username
+/// * `process_id` - ID of the process (path parameter)
+/// * `claims` - Claims of the authenticated user
 /// 
 /// # Returns
 /// 
 /// Returns `Result` containing:
-/// - `Ok(ResponseJson<ProcessAccessResponse>)` - Decrypted process content and metadata
-/// - `Err(StatusCode)` - HTTP error code indicating failure reason
+/// - `Ok(ResponseJson<Vec<ProcessRevision>>)` - Revision history without content
+/// - `Err(ApiError)` - HTTP error code with an error body indicating failure reason
 /// 
 /// # HTTP Responses
 /// 
-/// - **200 OK**: Access granted, content decrypted and returned
-/// - **403 Forbidden**: Process not shared with this partner or insufficient partner role
-/// - **404 Not Found**: Process or partner not found
-/// - **500 Internal Server Error**: Decryption or database error
-/// 
-/// # Request Body
-/// 
-/// ```json
-/// {
-///   "process_id": "process-uuid",
-///   "partner_username": "partner_company",
-///   "partner_public_key": "GCKFBEIYTKP..."
-/// }
-/// ```
-/// 
-/// # Response Body
+/// - **200 OK**: Revision history returned
+/// - **401 Unauthorized**: Missing, invalid or revoked access token
+/// - **403 Forbidden**: Authenticated user does not own the process
+/// - **404 Not Found**: Process not found
+/// - **500 Internal Server Error**: Database error
+#[utoipa::path(
+    get,
+    path = "/api/processes/{process_id}/revisions",
+    params(
+        ("process_id" = String, Path, description = "Process ID")
+    ),
+    responses(
+        (status = 200, description = "Revision history", body = [ProcessRevision]),
+        (status = 401, description = "Unauthorized - Invalid or missing token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Not the process owner", body = ErrorResponse),
+        (status = 404, description = "Process not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Process Management"
+)]
+pub async fn list_process_revisions(
+    State(state): State<Arc<AppState>>,
+    Path(process_id): Path<String>,
+    AuthUser(claims): AuthUser,
+) -> Result<ResponseJson<Vec<ProcessRevision>>, ApiError> {
+    let process = find_owned_process(&state, &claims, &process_id).await?;
+
+    let revisions = queries::list_process_revisions(&state.pool, &process.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(ResponseJson(revisions))
+}
+
+/// Returns one revision of a process with its decrypted content.
 /// 
-/// ```json
-/// {
-///   "process_id": "process-uuid",
-///   "title": "Software Development NDA",
-///   "description": "Confidential software project details",
-///   "content": "Decrypted confidential content...",
-///   "accessed_at": "2024-01-01T00:00:00Z"
-/// }
-/// ```
+/// Lets the owner show exactly what a partner saw, given the
+/// `content_version` recorded with the partner's access.
 /// 
-/// # Access Control
+/// # Parameters
 /// 
-/// The endpoint performs several security checks:
-/// 1. Verifies the process exists
-/// 2. Verifies the partner exists
-/// 3. Checks that sharing record exists in database
-/// 4. Only then decrypts and returns content
+/// * `state` - Shared application state containing database pool
+/// * `process_id` - ID of the process (path parameter)
+/// * `version` - Content version (path parameter)
+/// * `claims` - Claims of the authenticated user
 /// 
-/// # Audit Trail
+/// # Returns
 /// 
-/// Every successful access is logged with:
-/// - Timestamp of access
-/// - Partner who accessed the content
-/// - Process that was accessed
-/// - Complete audit trail for compliance
+/// Returns `Result` containing:
+/// - `Ok(ResponseJson<ProcessRevision>)` - Revision including `content`
+/// - `Err(ApiError)` - HTTP error code with an error body indicating failure reason
 /// 
-/// # Security Notes
+/// # HTTP Responses
 /// 
-/// - Content is decrypted in memory only
-/// - Access is logged for regulatory compliance
-/// - Failed access attempts are also logged
-/// - Sharing verification prevents unauthorized access
+/// - **200 OK**: Revision returned
+/// - **401 Unauthorized**: Missing, invalid or revoked access token
+/// - **403 Forbidden**: Authenticated user does not own the process
+/// - **404 Not Found**: Process or revision not found
+/// - **409 Conflict**: Stored ciphertext is not bound to this revision (`content_integrity_error`)
+/// - **500 Internal Server Error**: Decryption or database error
 #[utoipa::path(
-    post,
-    path = "/api/processes/access",
-    request_body = AccessProcessRequest,
+    get,
+    path = "/api/processes/{process_id}/revisions/{version}",
+    params(
+        ("process_id" = String, Path, description = "Process ID"),
+        ("version" = i64, Path, description = "Content version")
+    ),
     responses(
-        (status = 200, description = "Access granted, content decrypted", body = ProcessAccessResponse),
-        (status = 403, description = "Process not shared with partner or insufficient role"),
-        (status = 404, description = "Process or partner not found"),
-        (status = 500, description = "Internal server error")
+        (status = 200, description = "Revision with decrypted content", body = ProcessRevision),
+        (status = 401, description = "Unauthorized - Invalid or missing token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Not the process owner", body = ErrorResponse),
+        (status = 404, description = "Process or revision not found", body = ErrorResponse),
+        (status = 409, description = "Stored content is not bound to this revision", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
-    tag = "Sharing & Access"
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Process Management"
 )]
-pub async fn access_process(
+pub async fn get_process_revision(
     State(state): State<Arc<AppState>>,
-    Json(payload): Json<AccessProcessRequest>,
-) -> Result<ResponseJson<ProcessAccessResponse>, StatusCode> {
-    // Find process with specific fields
-    let process = sqlx::query!(
-        r#"
-        SELECT id, client_id, title, description, encrypted_content, encryption_key, status, created_at
-        FROM processes WHERE id = ?
-        "#,
-        payload.process_id
-    )
-    .fetch_optional(&state.pool)
-    .await
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
-    .ok_or(StatusCode::NOT_FOUND)?;
+    Path((process_id, version)): Path<(String, i64)>,
+    AuthUser(claims): AuthUser,
+) -> Result<ResponseJson<ProcessRevision>, ApiError> {
+    let process = find_owned_process(&state, &claims, &process_id).await?;
 
-    // Find partner with specific fields and verify partner role
-    let partner = queries::find_user_by_username(&state.pool, &payload.partner_username)
+    let (mut revision, encrypted_content) = queries::find_process_revision(&state.pool, &process.id, version)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
         .ok_or(StatusCode::NOT_FOUND)?;
-        
-    // Verify user has partner role
-    if !partner.is_partner() {
-        return Err(StatusCode::FORBIDDEN);
-    }
 
-    // Verify if sharing exists in database
-    let share_exists = sqlx::query!(
-        "SELECT id FROM process_shares WHERE process_id = ? AND partner_public_key = ?",
-        payload.process_id,
-        payload.partner_public_key
-    )
-    .fetch_optional(&state.pool)
-    .await
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-
-    if share_exists.is_none() {
-        println!("❌ Access denied: Process was not shared with this partner");
-        return Err(StatusCode::FORBIDDEN);
-    }
-
-    println!("✅ Access authorized: Sharing found in database");
-
-    // Decrypt content
-    let decrypted_content = decrypt_content(&process.encrypted_content, &process.encryption_key)
+    let data_key = state.keyring
+        .unwrap_key(process.key_id.as_deref(), &process.encryption_key)
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let aad = process_aad(&process.id, &process.client_id, revision.version);
+    let content = decrypt_content(&encrypted_content, &data_key, &aad)
+        .map_err(|e| content_decryption_error(&process.id, e))?;
 
-    // Register access event
-    let access_id = Uuid::new_v4().to_string();
-    let now = Utc::now();
-    let now_string = now.to_rfc3339();
-    
-    sqlx::query!(
-        r#"
-        INSERT INTO process_accesses (id, process_id, partner_id, accessed_at)
-        VALUES (?, ?, ?, ?)
-        "#,
-        access_id,
-        payload.process_id,
-        partner.id,
-        now_string
-    )
-    .execute(&state.pool)
-    .await
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-
-    println!("📊 Access registered successfully");
-
-    let response = ProcessAccessResponse {
-        process_id: payload.process_id,
-        title: process.title,
-        description: process.description,
-        content: decrypted_content,
-        accessed_at: now,
-    };
-
-    Ok(ResponseJson(response))
+    revision.content = Some(content);
+    Ok(ResponseJson(revision))
 }
 
-/// Lists all processes owned by a specific client.
+/// Moves a process to another lifecycle status.
 /// 
-/// This endpoint retrieves all NDA processes created by a client user,
-/// returning them in reverse chronological order (newest first). The
-/// response includes process metadata but not the encrypted content.
+/// Allowed transitions are listed on [`ProcessStatus`]. Suspending or closing
+/// a process immediately stops partner access through its shares; resuming a
+/// suspended process restores it. Only the process owner can change the
+/// status, and every change is recorded with the owner and the reason.
 /// 
 /// # Parameters
 /// 
 /// * `state` - Shared application state containing database pool
-/// * `params` - Query parameters including client username
+/// * `process_id` - ID of the process (path parameter)
+/// * `claims` - Claims of the authenticated user
+/// * `payload` - Target status and reason
 /// 
 /// # Returns
 /// 
 /// Returns `Result` containing:
-/// - `Ok(ResponseJson<Vec<ProcessResponse>>)` - List of client's processes
-/// - `Err(StatusCode)` - HTTP error code indicating failure reason
+/// - `Ok(ResponseJson<ProcessTransition>)` - The recorded transition
+/// - `Err(ApiError)` - HTTP error code with an error body indicating failure reason
 /// 
 /// # HTTP Responses
 /// 
-/// - **200 OK**: Processes retrieved successfully
-/// - **400 Bad Request**: Missing client_id parameter
-/// - **404 Not Found**: Client ID not found
+/// - **200 OK**: Status changed
+/// - **400 Bad Request**: Empty reason (`reason_required`)
+/// - **401 Unauthorized**: Missing, invalid or revoked access token
+/// - **403 Forbidden**: Authenticated user does not own the process
+/// - **404 Not Found**: Process not found
+/// - **409 Conflict**: Transition not allowed from the current status
+///   (`invalid_status_transition`), or the status changed concurrently
+///   (`process_status_conflict`)
 /// - **500 Internal Server Error**: Database error
 /// 
-/// # Query Parameters
+/// # Request Body
+/// 
+/// ```json
+/// {
+///   "status": "suspended",
+///   "reason": "Partner negotiations on hold"
+/// }
+/// ```
+#[utoipa::path(
+    post,
+    path = "/api/processes/{process_id}/transitions",
+    params(
+        ("process_id" = String, Path, description = "Process ID")
+    ),
+    request_body = TransitionProcessRequest,
+    responses(
+        (status = 200, description = "Status changed", body = ProcessTransition),
+        (status = 400, description = "Empty reason", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Not the process owner", body = ErrorResponse),
+        (status = 404, description = "Process not found", body = ErrorResponse),
+        (status = 409, description = "Transition not allowed from the current status", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Process Management"
+)]
+pub async fn transition_process(
+    State(state): State<Arc<AppState>>,
+    Path(process_id): Path<String>,
+    AuthUser(claims): AuthUser,
+    Json(payload): Json<TransitionProcessRequest>,
+) -> Result<ResponseJson<ProcessTransition>, ApiError> {
+    let process = find_owned_process(&state, &claims, &process_id).await?;
+
+    let reason = payload.reason.trim();
+    if reason.is_empty() {
+        return Err(ApiError::new(StatusCode::BAD_REQUEST, "reason_required", "A reason is required to change the status"));
+    }
+
+    if !process.status.can_transition_to(payload.status) {
+        return Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "invalid_status_transition",
+            format!("Cannot move a process from {} to {}", process.status, payload.status),
+        ));
+    }
+
+    let transition = queries::transition_process_status(
+        &state.pool,
+        &process.id,
+        process.status,
+        payload.status,
+        &claims.sub,
+        reason,
+    )
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or_else(|| {
+        ApiError::new(
+            StatusCode::CONFLICT,
+            "process_status_conflict",
+            "Process status was changed concurrently, reload and try again",
+        )
+    })?;
+
+    println!("🔄 Process {} moved from {} to {}", process.id, process.status, payload.status);
+
+    Ok(ResponseJson(transition))
+}
+
+/// Lists the status transitions of a process, oldest first.
 /// 
-/// - `client_id` (required): ID of the client whose processes to list
+/// The first entry records the creation of the process. Only the process
+/// owner can list transitions.
 /// 
-/// # Example Request
+/// # Parameters
 /// 
-/// ```
-/// GET /api/processes?client_id=client-uuid
-/// ```
+/// * `state` - Shared application state containing database pool
+/// * `process_id` - ID of the process (path parameter)
+/// * `claims` - Claims of the authenticated user
 /// 
-/// # Response Body
+/// # Returns
 /// 
-/// ```json
-/// [
-///   {
-///     "id": "process-uuid-1",
-///     "client_id": "client-uuid",
-///     "title": "Software Development NDA",
-///     "description": "Confidential software project details",
-///     "status": "active",
-///     "created_at": "2024-01-01T00:00:00Z"
-///   },
-///   {
-///     "id": "process-uuid-2",
-///     "client_id": "client-uuid",
-///     "title": "Marketing Partnership NDA",
-///     "description": "Joint marketing campaign specifications",
-///     "status": "active",
-///     "created_at": "2024-01-01T00:00:00Z"
-///   }
-/// ]
-/// ```
+/// Returns `Result` containing:
+/// - `Ok(ResponseJson<Vec<ProcessTransition>>)` - Status history
+/// - `Err(ApiError)` - HTTP error code with an error body indicating failure reason
 /// 
-/// # Security Notes
+/// # HTTP Responses
 /// 
-/// - Only returns processes owned by the specified client
-/// - Encrypted content is not included in the response
-/// - Process access requires separate authorization via sharing
+/// - **200 OK**: Status history returned
+/// - **401 Unauthorized**: Missing, invalid or revoked access token
+/// - **403 Forbidden**: Authenticated user does not own the process
+/// - **404 Not Found**: Process not found
+/// - **500 Internal Server Error**: Database error
 #[utoipa::path(
     get,
-    path = "/api/processes",
+    path = "/api/processes/{process_id}/transitions",
     params(
-        ("client_id" = String, Query, description = "Client ID to filter processes")
+        ("process_id" = String, Path, description = "Process ID")
     ),
     responses(
-        (status = 200, description = "Processes retrieved successfully", body = [ProcessResponse]),
-        (status = 401, description = "Unauthorized - Invalid or missing token"),
-        (status = 400, description = "Missing client_id parameter"),
-        (status = 403, description = "Forbidden - Cannot access other user's processes"),
-        (status = 404, description = "Client not found"),
-        (status = 500, description = "Internal server error")
+        (status = 200, description = "Status history", body = [ProcessTransition]),
+        (status = 401, description = "Unauthorized - Invalid or missing token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Not the process owner", body = ErrorResponse),
+        (status = 404, description = "Process not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     security(
         ("bearer_auth" = [])
     ),
     tag = "Process Management"
 )]
-pub async fn list_processes(
+pub async fn list_process_transitions(
     State(state): State<Arc<AppState>>,
-    headers: HeaderMap,
-    Query(params): Query<ListProcessesQuery>,
-) -> Result<ResponseJson<Vec<ProcessResponse>>, StatusCode> {
-    // Validate JWT token and extract claims
-    let auth_header = headers.get("authorization").and_then(|h| h.to_str().ok());
-    let claims = jwt::validate_auth_header(auth_header, &state.jwt_secret, &state.token_blacklist)
+    Path(process_id): Path<String>,
+    AuthUser(claims): AuthUser,
+) -> Result<ResponseJson<Vec<ProcessTransition>>, ApiError> {
+    let process = find_owned_process(&state, &claims, &process_id).await?;
+
+    let transitions = queries::list_process_transitions(&state.pool, &process.id)
         .await
-        .map_err(|_| StatusCode::UNAUTHORIZED)?;
-    
-    // Use client_id from query params if provided, otherwise use token user ID
-    let client_id = params.client_id.unwrap_or(claims.sub.clone());
-    
-    // Verify user is requesting their own processes or has appropriate role
-    if client_id != claims.sub && !claims.roles.contains(&"admin".to_string()) {
-        return Err(StatusCode::FORBIDDEN);
-    }
-    
-    // Find client by ID
-    let client = queries::find_user_by_id(&state.pool, &client_id)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(ResponseJson(transitions))
+}
+
+/// Loads a template and checks that the authenticated client owns it.
+async fn find_owned_template(state: &AppState, claims: &jwt::Claims, template_id: &str) -> Result<NdaTemplate, ApiError> {
+    let template = queries::find_template(&state.pool, template_id)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
         .ok_or(StatusCode::NOT_FOUND)?;
+    policy::ensure_template_owner(claims, &template)?;
 
-    let processes = queries::list_processes_by_client(&state.pool, &client.id)
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(template)
+}
 
-    let response: Vec<ProcessResponse> = processes.into_iter().map(|p| p.into()).collect();
-    Ok(ResponseJson(response))
+/// Rejects the use or change of archived templates.
+fn ensure_template_active(template: &NdaTemplate) -> Result<(), ApiError> {
+    if template.archived_at.is_none() {
+        Ok(())
+    } else {
+        Err(ApiError::new(StatusCode::CONFLICT, "template_archived", "Template has been deleted"))
+    }
 }
 
-/// Retrieves access notifications for a client's processes.
+/// Checks a template body against its placeholder declarations.
+fn validate_template(body: &str, placeholders: &[TemplatePlaceholder]) -> Result<(), ApiError> {
+    templates::validate_template(body, placeholders)
+        .map_err(|e| ApiError::new(StatusCode::BAD_REQUEST, "invalid_template", e.to_string()))
+}
+
+/// Creates an NDA template with typed placeholders.
 /// 
-/// This endpoint provides clients with a comprehensive audit trail showing
-/// when partners have accessed their shared processes. It returns denormalized
-/// data including process titles, descriptions, status, and partner usernames for detailed reporting.
+/// The body uses `{{name}}` placeholders, each declared with its type (see
+/// [`crate::templates`]). The template starts at version 1 and belongs to
+/// the authenticated client.
 /// 
 /// # Parameters
 /// 
 /// * `state` - Shared application state containing database pool
-/// * `params` - Query parameters including client username
+/// * `claims` - Claims of the authenticated user
+/// * `payload` - Template name, description, body and placeholders
 /// 
 /// # Returns
 /// 
 /// Returns `Result` containing:
-/// - `Ok(ResponseJson<Vec<ProcessAccessWithDetails>>)` - List of access events with complete process details
-/// - `Err(StatusCode)` - HTTP error code indicating failure reason
+/// - `Ok(ResponseJson<NdaTemplate>)` - The created template
+/// - `Err(ApiError)` - HTTP error code with an error body indicating failure reason
 /// 
 /// # HTTP Responses
 /// 
-/// - **200 OK**: Notifications retrieved successfully
-/// - **400 Bad Request**: Missing client_id parameter
-/// - **404 Not Found**: Client ID not found
+/// - **200 OK**: Template created
+/// - **400 Bad Request**: Empty name (`name_required`), or body and placeholders
+///   do not match (`invalid_template`)
+/// - **401 Unauthorized**: Missing, invalid or revoked access token
+/// - **403 Forbidden**: User doesn't have client role
 /// - **500 Internal Server Error**: Database error
 /// 
-/// # Query Parameters
-/// 
-/// - `client_id` (required): ID of the client whose notifications to retrieve
-/// 
-/// # Example Request
+/// # Request Body
 /// 
+/// ```json
+/// {
+///   "name": "Mutual NDA",
+///   "description": "Standard mutual NDA",
+///   "body": "This Agreement between {{receiving_party}} takes effect on {{effective_date}} ...",
+///   "placeholders": [
+///     { "name": "receiving_party", "kind": "text", "label": "Receiving party" },
+///     { "name": "effective_date", "kind": "date" },
+///     { "name": "term_months", "kind": "integer" },
+///     { "name": "jurisdiction", "kind": "choice", "options": ["Brazil", "Delaware"] }
+///   ]
+/// }
 /// ```
-/// GET /api/notifications?client_id=client-uuid
-/// ```
+#[utoipa::path(
+    post,
+    path = "/api/templates",
+    request_body = CreateTemplateRequest,
+    responses(
+        (status = 200, description = "Template created", body = NdaTemplate),
+        (status = 400, description = "Empty name, or body and placeholders do not match", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing token", body = ErrorResponse),
+        (status = 403, description = "User doesn't have client role", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "NDA Templates"
+)]
+pub async fn create_template(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Json(payload): Json<CreateTemplateRequest>,
+) -> Result<ResponseJson<NdaTemplate>, ApiError> {
+
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Err(ApiError::new(StatusCode::BAD_REQUEST, "name_required", "A template name is required"));
+    }
+    validate_template(&payload.body, &payload.placeholders)?;
+
+    let template = queries::create_template(
+        &state.pool,
+        &claims.sub,
+        name,
+        &payload.description,
+        &payload.body,
+        &payload.placeholders,
+    )
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    println!("📄 Template {} created", template.id);
+
+    Ok(ResponseJson(template))
+}
+
+/// Lists the NDA templates of the authenticated client.
 /// 
-/// # Response Body
+/// Deleted (archived) templates are not listed.
 /// 
-/// ```json
-/// [
-///   {
-///     "id": "access-uuid-1",
-///     "process_id": "process-uuid",
-///     "partner_id": "partner-uuid",
-///     "accessed_at": "2024-01-01T10:30:00Z",
-///     "process_title": "Software Development NDA",
-///     "process_description": "Comprehensive confidentiality agreement for software development partnership",
-///     "process_status": "active",
-///     "partner_username": "partner_company"
-///   },
-///   {
-///     "id": "access-uuid-2",
-///     "process_id": "process-uuid-2",
-///     "partner_id": "partner-uuid-2",
-///     "accessed_at": "2024-01-01T09:15:00Z",
-///     "process_title": "Marketing Partnership NDA",
-///     "process_description": "Non-disclosure agreement for marketing collaboration and data sharing",
-///     "process_status": "completed",
-///     "partner_username": "another_partner"
-///   }
-/// ]
+/// # HTTP Responses
+/// 
+/// - **200 OK**: Templates with their current version
+/// - **401 Unauthorized**: Missing, invalid or revoked access token
+/// - **403 Forbidden**: User doesn't have client role
+/// - **500 Internal Server Error**: Database error
+#[utoipa::path(
+    get,
+    path = "/api/templates",
+    responses(
+        (status = 200, description = "Templates of the client", body = [NdaTemplate]),
+        (status = 401, description = "Unauthorized - Invalid or missing token", body = ErrorResponse),
+        (status = 403, description = "User doesn't have client role", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "NDA Templates"
+)]
+pub async fn list_templates(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+) -> Result<ResponseJson<Vec<NdaTemplate>>, ApiError> {
+
+    let templates = queries::list_templates_by_owner(&state.pool, &claims.sub)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(ResponseJson(templates))
+}
+
+/// Gets an NDA template with its current version.
+/// 
+/// Deleted templates can still be read by their owner, since processes
+/// refer to them.
+/// 
+/// # HTTP Responses
+/// 
+/// - **200 OK**: Template returned
+/// - **401 Unauthorized**: Missing, invalid or revoked access token
+/// - **403 Forbidden**: Authenticated user does not own the template
+/// - **404 Not Found**: Template not found
+/// - **500 Internal Server Error**: Database error
+#[utoipa::path(
+    get,
+    path = "/api/templates/{template_id}",
+    params(
+        ("template_id" = String, Path, description = "Template ID")
+    ),
+    responses(
+        (status = 200, description = "Template", body = NdaTemplate),
+        (status = 401, description = "Unauthorized - Invalid or missing token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Not the template owner", body = ErrorResponse),
+        (status = 404, description = "Template not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "NDA Templates"
+)]
+pub async fn get_template(
+    State(state): State<Arc<AppState>>,
+    Path(template_id): Path<String>,
+    AuthUser(claims): AuthUser,
+) -> Result<ResponseJson<NdaTemplate>, ApiError> {
+    let template = find_owned_template(&state, &claims, &template_id).await?;
+    Ok(ResponseJson(template))
+}
+
+/// Updates an NDA template by creating a new version.
+/// 
+/// Previous versions are kept, and processes created from them keep
+/// referring to the version they were rendered from. Name and description
+/// are unchanged when omitted.
+/// 
+/// # HTTP Responses
+/// 
+/// - **200 OK**: Template at its new version
+/// - **400 Bad Request**: Empty name (`name_required`), or body and placeholders
+///   do not match (`invalid_template`)
+/// - **401 Unauthorized**: Missing, invalid or revoked access token
+/// - **403 Forbidden**: Authenticated user does not own the template
+/// - **404 Not Found**: Template not found
+/// - **409 Conflict**: Template is deleted (`template_archived`) or was updated
+///   concurrently (`template_update_conflict`)
+/// - **500 Internal Server Error**: Database error
+/// 
+/// # Request Body
+/// 
+/// ```json
+/// {
+///   "body": "This Agreement between {{receiving_party}} ...",
+///   "placeholders": [{ "name": "receiving_party", "kind": "text" }],
+///   "change_note": "Removed the jurisdiction clause"
+/// }
 /// ```
+#[utoipa::path(
+    put,
+    path = "/api/templates/{template_id}",
+    params(
+        ("template_id" = String, Path, description = "Template ID")
+    ),
+    request_body = UpdateTemplateRequest,
+    responses(
+        (status = 200, description = "Template at its new version", body = NdaTemplate),
+        (status = 400, description = "Empty name, or body and placeholders do not match", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Not the template owner", body = ErrorResponse),
+        (status = 404, description = "Template not found", body = ErrorResponse),
+        (status = 409, description = "Template is deleted or was updated concurrently", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "NDA Templates"
+)]
+pub async fn update_template(
+    State(state): State<Arc<AppState>>,
+    Path(template_id): Path<String>,
+    AuthUser(claims): AuthUser,
+    Json(payload): Json<UpdateTemplateRequest>,
+) -> Result<ResponseJson<NdaTemplate>, ApiError> {
+    let template = find_owned_template(&state, &claims, &template_id).await?;
+    ensure_template_active(&template)?;
+
+    let name = payload.name.as_deref().map(str::trim).unwrap_or(&template.name);
+    if name.is_empty() {
+        return Err(ApiError::new(StatusCode::BAD_REQUEST, "name_required", "A template name is required"));
+    }
+    validate_template(&payload.body, &payload.placeholders)?;
+
+    let change_note = payload.change_note.as_deref().map(str::trim).filter(|note| !note.is_empty());
+    let updated = queries::update_template(
+        &state.pool,
+        &template.id,
+        template.version,
+        name,
+        payload.description.as_deref().unwrap_or(&template.description),
+        &payload.body,
+        &payload.placeholders,
+        &claims.sub,
+        change_note,
+    )
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .ok_or_else(|| {
+        ApiError::new(
+            StatusCode::CONFLICT,
+            "template_update_conflict",
+            "Template was updated concurrently, reload and try again",
+        )
+    })?;
+
+    println!("📄 Template {} updated to version {}", updated.id, updated.version);
+
+    Ok(ResponseJson(updated))
+}
+
+/// Deletes an NDA template.
 /// 
-/// # Use Cases
+/// The template is archived rather than removed: it is no longer listed
+/// and cannot be used or changed, but processes created from it keep
+/// their reference and its versions stay readable by the owner.
 /// 
-/// - **Enhanced Compliance Reporting**: Generate detailed audit reports with process context and status tracking
-/// - **Status-Aware Analytics**: Track access patterns with process descriptions and current status information
-/// - **Security Monitoring**: Monitor access with full process details including status for better threat detection
-/// - **Rich Client Dashboard**: Provide comprehensive notifications with process context and status updates
+/// # HTTP Responses
 /// 
-/// # Data Privacy
+/// - **204 No Content**: Template deleted
+/// - **401 Unauthorized**: Missing, invalid or revoked access token
+/// - **403 Forbidden**: Authenticated user does not own the template
+/// - **404 Not Found**: Template not found
+/// - **409 Conflict**: Template already deleted (`template_archived`)
+/// - **500 Internal Server Error**: Database error
+#[utoipa::path(
+    delete,
+    path = "/api/templates/{template_id}",
+    params(
+        ("template_id" = String, Path, description = "Template ID")
+    ),
+    responses(
+        (status = 204, description = "Template deleted"),
+        (status = 401, description = "Unauthorized - Invalid or missing token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Not the template owner", body = ErrorResponse),
+        (status = 404, description = "Template not found", body = ErrorResponse),
+        (status = 409, description = "Template already deleted", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "NDA Templates"
+)]
+pub async fn delete_template(
+    State(state): State<Arc<AppState>>,
+    Path(template_id): Path<String>,
+    AuthUser(claims): AuthUser,
+) -> Result<StatusCode, ApiError> {
+    let template = find_owned_template(&state, &claims, &template_id).await?;
+    ensure_template_active(&template)?;
+
+    if !queries::archive_template(&state.pool, &template.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    {
+        return Err(ApiError::new(StatusCode::CONFLICT, "template_archived", "Template has been deleted"));
+    }
+
+    println!("🗑️ Template {} deleted", template.id);
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Lists the versions of an NDA template, newest first.
 /// 
-/// - Only shows access to processes owned by the requesting client
-/// - Includes process descriptions, status, and partner usernames but not sensitive encrypted content
-/// - Ordered by access time (most recent first) for easy monitoring
+/// # HTTP Responses
+/// 
+/// - **200 OK**: Version history returned
+/// - **401 Unauthorized**: Missing, invalid or revoked access token
+/// - **403 Forbidden**: Authenticated user does not own the template
+/// - **404 Not Found**: Template not found
+/// - **500 Internal Server Error**: Database error
 #[utoipa::path(
     get,
-    path = "/api/notifications",
+    path = "/api/templates/{template_id}/versions",
     params(
-        ("client_id" = String, Query, description = "Client ID to retrieve notifications for")
+        ("template_id" = String, Path, description = "Template ID")
     ),
     responses(
-        (status = 200, description = "Notifications retrieved successfully", body = [ProcessAccessWithDetails]),
-        (status = 400, description = "Missing client_id parameter"),
-        (status = 404, description = "Client not found"),
+        (status = 200, description = "Version history", body = [NdaTemplateVersion]),
+        (status = 401, description = "Unauthorized - Invalid or missing token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Not the template owner", body = ErrorResponse),
+        (status = 404, description = "Template not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "NDA Templates"
+)]
+pub async fn list_template_versions(
+    State(state): State<Arc<AppState>>,
+    Path(template_id): Path<String>,
+    AuthUser(claims): AuthUser,
+) -> Result<ResponseJson<Vec<NdaTemplateVersion>>, ApiError> {
+    let template = find_owned_template(&state, &claims, &template_id).await?;
+
+    let versions = queries::list_template_versions(&state.pool, &template.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(ResponseJson(versions))
+}
+
+/// Gets one version of an NDA template.
+/// 
+/// # HTTP Responses
+/// 
+/// - **200 OK**: Version returned
+/// - **401 Unauthorized**: Missing, invalid or revoked access token
+/// - **403 Forbidden**: Authenticated user does not own the template
+/// - **404 Not Found**: Template or version not found
+/// - **500 Internal Server Error**: Database error
+#[utoipa::path(
+    get,
+    path = "/api/templates/{template_id}/versions/{version}",
+    params(
+        ("template_id" = String, Path, description = "Template ID"),
+        ("version" = i64, Path, description = "Template version")
+    ),
+    responses(
+        (status = 200, description = "Template version", body = NdaTemplateVersion),
+        (status = 401, description = "Unauthorized - Invalid or missing token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Not the template owner", body = ErrorResponse),
+        (status = 404, description = "Template or version not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "NDA Templates"
+)]
+pub async fn get_template_version(
+    State(state): State<Arc<AppState>>,
+    Path((template_id, version)): Path<(String, i64)>,
+    AuthUser(claims): AuthUser,
+) -> Result<ResponseJson<NdaTemplateVersion>, ApiError> {
+    let template = find_owned_template(&state, &claims, &template_id).await?;
+
+    let version = queries::find_template_version(&state.pool, &template.id, version)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(ResponseJson(version))
+}
+
+/// Shares a process with a partner via Stellar blockchain transaction.
+/// 
+/// This endpoint creates an immutable record of process sharing on the Stellar
+/// blockchain. It submits a transaction that proves the client has shared access
+/// to a specific process with a specific partner, creating an audit trail.
+/// 
+/// # Parameters
+/// 
+/// * `state` - Shared application state containing database pool
+/// * `payload` - Process sharing request with IDs and recipient
+/// 
+/// # Returns
+/// 
+/// Returns `Result` containing:
+/// - `Ok(ResponseJson<ProcessShare>)` - Successfully recorded sharing event
+/// - `Err(StatusCode)` - HTTP error code indicating failure reason
+/// 
+/// # HTTP Responses
+/// 
+/// - **200 OK**: Process shared successfully with blockchain proof
+/// - **400 Bad Request**: Validity window ends before it starts or is already over,
+///   or `max_accesses` is zero
+/// - **401 Unauthorized**: Missing, invalid or revoked access token
+/// - **403 Forbidden**: Authenticated user does not have the client role or does not
+///   own the process (`not_process_owner`), or `client_username` names another
+///   user (`actor_mismatch`)
+/// - **404 Not Found**: Process or client not found
+/// - **409 Conflict**: Process is not active, or its data key kept being rotated
+///   while the share was stored (`process_update_conflict`)
+/// - **500 Internal Server Error**: Blockchain transaction or database error
+/// 
+/// # Request Body
+/// 
+/// `valid_from` and `expires_at` are optional and bound the partner's access.
+/// `max_accesses` optionally limits how many times the partner may view it.
+/// The sharing client is the authenticated user; `client_username` is
+/// optional and must name them.
+/// 
+/// ```json
+/// {
+///   "process_id": "process-uuid",
+///   "partner_public_key": "GCKFBEIYTKP...",
+///   "valid_from": "2024-01-01T00:00:00Z",
+///   "expires_at": "2024-03-31T00:00:00Z",
+///   "max_accesses": 1
+/// }
+/// ```
+/// 
+/// # Response Body
+/// 
+/// ```json
+/// {
+///   "id": "share-uuid",
+///   "process_id": "process-uuid",
+///   "partner_public_key": "GCKFBEIYTKP...",
+///   "stellar_transaction_hash": "abc123...",
+///   "shared_at": "2024-01-01T00:00:00Z",
+///   "memo_salt": "9f2c...",
+///   "content_digest": "41d8...",
+///   "memo_commitment": "c07a...",
+///   "valid_from": "2024-01-01T00:00:00Z",
+///   "expires_at": "2024-03-31T00:00:00Z",
+///   "max_accesses": 1
+/// }
+/// ```
+/// 
+/// # Blockchain Integration
+/// 
+/// - Creates a Stellar transaction with process sharing metadata
+/// - Transaction hash provides immutable proof of sharing
+/// - `MEMO_HASH` carries a salted commitment over the process ID, partner key
+///   and encrypted content digest, so no process ID is published on chain
+/// - Commitment inputs are stored with the share to prove which document
+///   version was shared
+/// - With `zero_knowledge`, the data key is sealed to the partner's Stellar
+///   key before the transaction is sent; an invalid key is rejected with 400
+/// 
+/// # Security & Compliance
+/// 
+/// - Immutable blockchain record prevents disputes
+/// - Transaction hash can be independently verified
+/// - Sharing permissions are cryptographically provable
+/// - Audit trail meets regulatory requirements
+#[utoipa::path(
+    post,
+    path = "/api/processes/share",
+    request_body = ShareProcessRequest,
+    responses(
+        (status = 200, description = "Process shared successfully", body = ProcessShare),
+        (status = 400, description = "Invalid validity window, view limit or partner key"),
+        (status = 401, description = "Unauthorized - Invalid or missing token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Requires the client role and ownership of the process", body = ErrorResponse),
+        (status = 404, description = "Process or client not found"),
+        (status = 409, description = "Process is not active, or its data key kept being rotated"),
         (status = 500, description = "Internal server error")
     ),
-    tag = "Audit & Compliance"
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Sharing & Access"
 )]
-pub async fn get_notifications(
+pub async fn share_process(
     State(state): State<Arc<AppState>>,
-    Query(params): Query<ListProcessesQuery>,
-) -> Result<ResponseJson<Vec<ProcessAccessWithDetails>>, StatusCode> {
-    let client_id = params.client_id.ok_or(StatusCode::BAD_REQUEST)?;
-    
-    // Find client by ID
-    let client = queries::find_user_by_id(&state.pool, &client_id)
+    AuthUser(claims): AuthUser,
+    Json(payload): Json<ShareProcessRequest>,
+) -> Result<ResponseJson<ProcessShare>, ApiError> {
+    // Reject validity windows that grant no access at all
+    if let Some(expires_at) = payload.expires_at {
+        let starts_at = payload.valid_from.unwrap_or_else(Utc::now).max(Utc::now());
+        if expires_at <= starts_at {
+            return Err(StatusCode::BAD_REQUEST.into());
+        }
+    }
+    if payload.max_accesses == Some(0) {
+        return Err(StatusCode::BAD_REQUEST.into());
+    }
+
+    // The sharing client is the authenticated user, who must own the process
+    let client = policy::acting_user(&state.pool, &claims).await?;
+    policy::ensure_username(&client, payload.client_username.as_deref())?;
+
+    // Find process
+    let process = queries::find_process_by_id(&state.pool, &payload.process_id)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
         .ok_or(StatusCode::NOT_FOUND)?;
+    policy::ensure_process_owner(&claims, &process)?;
 
-    let notifications = queries::list_process_accesses_by_client(&state.pool, &client.id)
+    // Only active processes can be shared
+    if !process.status.allows_partner_access() {
+        return Err(StatusCode::CONFLICT.into());
+    }
+
+    // Commit to the process, partner and exact encrypted content being shared
+    let salt = commitment::generate_salt();
+    let content_digest = commitment::content_digest(&process.encrypted_content);
+    let memo_commitment = commitment::share_commitment(
+        &payload.process_id,
+        &payload.partner_public_key,
+        &salt,
+        &content_digest,
+    );
+
+    // Seal the data key to the partner for zero-knowledge shares
+    let seal_data_key = |process: &Process| -> Result<Option<(String, String)>, ApiError> {
+        if !payload.zero_knowledge {
+            return Ok(None);
+        }
+        let data_key = state.keyring
+            .unwrap_key(process.key_id.as_deref(), &process.encryption_key)
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        let key_bytes = general_purpose::STANDARD
+            .decode(&data_key)
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        let sealed = sealed_box::seal(&key_bytes, &payload.partner_public_key)
+            .map_err(|_| StatusCode::BAD_REQUEST)?;
+        Ok(Some((sealed, process.encryption_key.clone())))
+    };
+    let mut wrapped_key = seal_data_key(&process)?;
+
+    // Open the client's secret key just-in-time for signing
+    let client_secret_key = state.key_provider
+        .open(&client.stellar_secret_key)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    Ok(ResponseJson(notifications))
-}
\ No newline at end of file
+    // Send real Stellar transaction
+    let tx_result = state.ledger
+        .share_process_transaction(
+            &client_secret_key,
+            &payload.partner_public_key,
+            &payload.process_id,
+            &memo_commitment,
+        )
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    // Register sharing event with the commitment inputs, resealing if the data key was rotated meanwhile
+    for _ in 0..DATA_KEY_ROTATION_ATTEMPTS {
+        let share = queries::create_process_share(
+            &state.pool,
+            &payload.process_id,
+            &payload.partner_public_key,
+            &tx_result.hash,
+            tx_result.ledger.map(|ledger| ledger as i64),
+            &hex::encode(salt),
+            &hex::encode(content_digest),
+            &hex::encode(memo_commitment),
+            payload.valid_from,
+            payload.expires_at,
+            payload.max_accesses.map(i64::from),
+            wrapped_key.as_ref().map(|(sealed, sealed_from)| (sealed.as_str(), sealed_from.as_str())),
+        )
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        if let Some(share) = share {
+            return Ok(ResponseJson(share));
+        }
+
+        let process = queries::find_process_by_id(&state.pool, &payload.process_id)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+            .ok_or(StatusCode::NOT_FOUND)?;
+        wrapped_key = seal_data_key(&process)?;
+    }
+
+    Err(process_update_conflict())
+}
+
+/// Revokes a partner's access to a shared process.
+/// 
+/// This endpoint withdraws a previously granted share. It records a Stellar
+/// revocation transaction that references the original sharing transaction,
+/// then marks the share as revoked with who revoked it, when and why.
+/// Subsequent access attempts with the revoked share are denied.
+/// 
+/// # Parameters
+/// 
+/// * `state` - Shared application state containing database pool and ledger
+/// * `claims` - Claims of the authenticated user
+/// * `payload` - Revocation request with process ID, partner key and reason
+/// 
+/// # Returns
+/// 
+/// Returns `Result` containing:
+/// - `Ok(ResponseJson<ProcessShare>)` - Revoked share with revocation details
+/// - `Err(StatusCode)` - HTTP error code indicating failure reason
+/// 
+/// # HTTP Responses
+/// 
+/// - **200 OK**: Share revoked and revocation recorded on chain
+/// - **400 Bad Request**: Empty revocation reason
+/// - **401 Unauthorized**: Missing, invalid or revoked access token
+/// - **403 Forbidden**: Authenticated user does not own the process
+/// - **404 Not Found**: Process not found or not shared with the partner
+/// - **409 Conflict**: Share was already revoked (`share_already_revoked`), or the
+///   process kept changing while its data key was rotated (`process_update_conflict`)
+/// - **500 Internal Server Error**: Blockchain transaction or database error
+/// 
+/// # Request Body
+/// 
+/// ```json
+/// {
+///   "process_id": "process-uuid",
+///   "partner_public_key": "GCKFBEIYTKP...",
+///   "reason": "Partnership ended"
+/// }
+/// ```
+/// 
+/// # Response Body
+/// 
+/// ```json
+/// {
+///   "id": "share-uuid",
+///   "process_id": "process-uuid",
+///   "partner_public_key": "GCKFBEIYTKP...",
+///   "stellar_transaction_hash": "abc123...",
+///   "shared_at": "2024-01-01T00:00:00Z",
+///   "revoked_at": "2024-06-01T00:00:00Z",
+///   "revoked_by": "client-uuid",
+///   "revocation_reason": "Partnership ended",
+///   "revocation_transaction_hash": "def456..."
+/// }
+/// ```
+/// 
+/// # Blockchain Integration
+/// 
+/// - The revocation transaction carries a `MEMO_RETURN` with the original
+///   sharing transaction hash, linking both records on chain
+/// - Its ledger close time is independent proof of when access was withdrawn
+/// 
+/// # Zero-Knowledge Shares
+/// 
+/// The revoked partner holds the data key, so the process moves to a fresh
+/// one in the same transaction as the revocation, sealed again to the
+/// remaining zero-knowledge partners (see [`crate::sealed_box`]).
+#[utoipa::path(
+    post,
+    path = "/api/processes/revoke",
+    request_body = RevokeShareRequest,
+    responses(
+        (status = 200, description = "Share revoked successfully", body = ProcessShare),
+        (status = 400, description = "Missing revocation reason"),
+        (status = 401, description = "Unauthorized - Invalid or missing token"),
+        (status = 403, description = "Forbidden - Not the process owner"),
+        (status = 404, description = "Process or share not found"),
+        (status = 409, description = "Share already revoked, or the process changed during data key rotation", body = ErrorResponse),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Sharing & Access"
+)]
+pub async fn revoke_share(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Json(payload): Json<RevokeShareRequest>,
+) -> Result<ResponseJson<ProcessShare>, ApiError> {
+    if payload.reason.trim().is_empty() {
+        return Err(StatusCode::BAD_REQUEST.into());
+    }
+
+    // Find process and verify the token user owns it
+    let process = queries::find_process_by_id(&state.pool, &payload.process_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    policy::ensure_process_owner(&claims, &process)?;
+
+    let client = policy::acting_user(&state.pool, &claims).await?;
+
+    // Only the current share can be revoked, and only once
+    let share = queries::find_process_share(&state.pool, &payload.process_id, &payload.partner_public_key)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let already_revoked = || {
+        ApiError::new(StatusCode::CONFLICT, "share_already_revoked", "Share has already been revoked")
+    };
+    if share.is_revoked() {
+        return Err(already_revoked());
+    }
+
+    // Zero-knowledge partners hold the data key, so the process moves to a fresh one
+    let mut rotation = if share.wrapped_key.is_some() {
+        Some(prepare_data_key_rotation(&state, &process, Some(&share.id)).await?.1)
+    } else {
+        None
+    };
+
+    let client_secret_key = state.key_provider
+        .open(&client.stellar_secret_key)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    // Record the revocation on chain, referencing the original share
+    let tx_result = state.ledger
+        .revoke_share_transaction(
+            &client_secret_key,
+            &payload.partner_public_key,
+            &share.stellar_transaction_hash,
+        )
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    for _ in 0..DATA_KEY_ROTATION_ATTEMPTS {
+        let revoked = queries::revoke_process_share(
+            &state.pool,
+            &share.id,
+            &claims.sub,
+            payload.reason.trim(),
+            &tx_result.hash,
+            rotation.as_ref(),
+        )
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        if let Some(revoked) = revoked {
+            println!("🚫 Share {} revoked by {}", revoked.id, claims.sub);
+            return Ok(ResponseJson(revoked));
+        }
+        if rotation.is_none() {
+            return Err(already_revoked());
+        }
+
+        // Either revoked concurrently or the process changed while the rotation was prepared
+        let current = queries::find_process_share(&state.pool, &payload.process_id, &payload.partner_public_key)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        if current.is_none_or(|current| current.id != share.id || current.is_revoked()) {
+            return Err(already_revoked());
+        }
+        let process = queries::find_process_by_id(&state.pool, &payload.process_id)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+            .ok_or(StatusCode::NOT_FOUND)?;
+        rotation = Some(prepare_data_key_rotation(&state, &process, Some(&share.id)).await?.1);
+    }
+
+    Err(process_update_conflict())
+}
+
+/// Allows partners to access shared process content with decryption.
+/// 
+/// This endpoint verifies that a process has been properly shared with a partner
+/// (by checking the sharing records), then decrypts and returns the confidential
+/// content. It also logs the access event for audit trails and compliance.
+/// 
+/// # Parameters
+/// 
+/// * `state` - Shared application state containing database pool
+/// * `payload` - Process access request with process ID and partner username
+/// 
+/// # Returns
+/// 
+/// Returns `Result` containing:
+/// - `Ok(ResponseJson<ProcessAccessResponse>)` - Decrypted process content and metadata
+/// - `Err(ApiError)` - HTTP error code with an error body indicating failure reason
+/// 
+/// # HTTP Responses
+/// 
+/// - **200 OK**: Access granted, content decrypted and returned (ciphertext and
+///   sealed key for zero-knowledge shares)
+/// - **401 Unauthorized**: Missing, invalid or revoked access token
+/// - **403 Forbidden**: Process not shared with this partner, share outside its
+///   validity window (`share_not_yet_valid`, `share_expired`), revoked (`share_revoked`),
+///   view limit used up (`share_access_limit_reached`), process not active
+///   (`process_not_active`), current terms not signed (`terms_not_signed`),
+///   insufficient partner role, or `partner_username` / `partner_public_key`
+///   naming another partner (`actor_mismatch`, `partner_key_mismatch`)
+/// - **404 Not Found**: Process or partner not found
+/// - **409 Conflict**: Stored ciphertext is not bound to this process (`content_integrity_error`),
+///   e.g. swapped with another row or tampered with in the database; only detected
+///   for shares the server decrypts for, not zero-knowledge shares
+/// - **500 Internal Server Error**: Decryption or database error
+/// - **503 Service Unavailable**: Ledger could not be reached to verify the share
+/// 
+/// # Request Body
+/// 
+/// The partner is the authenticated user and the share is looked up by their
+/// Stellar key. `partner_username` and `partner_public_key` are optional and
+/// must match the authenticated partner.
+/// 
+/// ```json
+/// {
+///   "process_id": "process-uuid"
+/// }
+/// ```
+/// 
+/// # Response Body
+/// 
+/// ```json
+/// {
+///   "process_id": "process-uuid",
+///   "title": "Software Development NDA",
+///   "description": "Confidential software project details",
+///   "content": "Decrypted confidential content...",
+///   "accessed_at": "2024-01-01T00:00:00Z",
+///   "remaining_accesses": 2
+/// }
+/// ```
+/// 
+/// For zero-knowledge shares `content` is omitted and the response carries
+/// `encrypted_content`, `wrapped_key` and `content_aad` instead. The partner
+/// opens `wrapped_key` with their Stellar secret key (see [`crate::sealed_box`])
+/// to obtain the data key and decrypts the `encrypted_content` envelope
+/// locally, passing `content_aad` as associated data.
+/// 
+/// # Access Control
+/// 
+/// The endpoint performs several security checks:
+/// 1. Verifies the process exists
+/// 2. Verifies the partner exists and is the authenticated user
+/// 3. Verifies the share according to `SHARE_VERIFICATION_MODE`
+///    (database record, on-chain transaction, or both) and that it was not revoked
+/// 4. Verifies the current time is within the share's validity window
+/// 5. Verifies the process is active (`process_not_active` otherwise)
+/// 6. Verifies the partner signed the current NDA terms (`terms_not_signed`
+///    otherwise; see [`get_process_terms`] and [`sign_process_terms`])
+/// 7. Records the access, unless the share's view limit is used up
+/// 8. Only then returns the decrypted content (or ciphertext and sealed key)
+/// 
+/// # Audit Trail
+/// 
+/// Every successful access is logged with:
+/// - Timestamp of access
+/// - Partner who accessed the content
+/// - Process that was accessed
+/// - Complete audit trail for compliance
+/// 
+/// # Security Notes
+/// 
+/// - Content is decrypted in memory only, and not at all for zero-knowledge shares
+/// - Access is logged for regulatory compliance
+/// - Failed access attempts are also logged
+/// - Sharing verification prevents unauthorized access
+/// - View limits are enforced atomically, so concurrent requests cannot
+///   exceed `max_accesses`
+#[utoipa::path(
+    post,
+    path = "/api/processes/access",
+    request_body = AccessProcessRequest,
+    responses(
+        (status = 200, description = "Access granted, content decrypted", body = ProcessAccessResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing token", body = ErrorResponse),
+        (status = 403, description = "Process not shared with partner or not active, share not yet valid or expired, terms not signed, or insufficient role", body = ErrorResponse),
+        (status = 404, description = "Process or partner not found", body = ErrorResponse),
+        (status = 409, description = "Stored content is not bound to this process", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+        (status = 503, description = "Ledger unavailable for share verification", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Sharing & Access"
+)]
+pub async fn access_process(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Json(payload): Json<AccessProcessRequest>,
+) -> Result<ResponseJson<ProcessAccessResponse>, ApiError> {
+    // The partner is the authenticated user, acting with their own Stellar key
+    let (partner, partner_public_key) = policy::acting_partner(
+        &state.pool,
+        &claims,
+        payload.partner_username.as_deref(),
+        payload.partner_public_key.as_deref(),
+    )
+    .await?;
+
+    // Find process
+    let process = queries::find_process_by_id(&state.pool, &payload.process_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    // Verify sharing according to the configured verification mode
+    // A process cannot have been shared before it was created
+    let share = verify_share(&state, &payload.process_id, &process.client_id, Some(process.created_at), &partner_public_key, &partner)
+        .await?
+        .ok_or_else(|| ApiError::forbidden("share_not_found", "Process was not shared with this partner"))?;
+
+    // Suspended, closed and archived processes are no longer accessible
+    ensure_partner_access(&process)?;
+
+    // Nothing is released before the partner has signed the current terms
+    let terms = current_terms(&state, &process, &partner_public_key).await?;
+    require_signed_terms(&state, &terms, &partner).await?;
+
+    // Zero-knowledge shares carry the sealed key; the partner decrypts on their side
+    let decrypted_content = if share.wrapped_key.is_some() {
+        None
+    } else {
+        Some(decrypt_current_content(&state, &process)?)
+    };
+
+    // Register access event, atomically checked against the share's view limit
+    let (access, access_count) = queries::create_share_access(&state.pool, &share, &partner.id, None, process.content_version)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or_else(|| {
+            println!("❌ Access denied: Share view limit reached");
+            ApiError::forbidden(
+                "share_access_limit_reached",
+                format!("Share allows {} views, all of which have been used", share.max_accesses.unwrap_or_default()),
+            )
+        })?;
+
+    println!("📊 Access registered successfully");
+
+    let attachments = queries::list_process_attachments(&state.pool, &process.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let response = ProcessAccessResponse {
+        process_id: payload.process_id,
+        title: process.title,
+        description: process.description,
+        content: decrypted_content,
+        content_version: process.content_version,
+        accessed_at: access.accessed_at,
+        remaining_accesses: share.max_accesses.map(|max| (max - access_count).max(0)),
+        content_aad: share.wrapped_key.as_ref().map(|_| {
+            general_purpose::STANDARD.encode(process_aad(&process.id, &process.client_id, process.content_version))
+        }),
+        encrypted_content: share.wrapped_key.as_ref().map(|_| process.encrypted_content),
+        wrapped_key: share.wrapped_key,
+        attachments,
+    };
+
+    Ok(ResponseJson(response))
+}
+
+/// Returns the current NDA terms a partner has to sign.
+/// 
+/// The terms cover the process, both parties' Stellar keys, the title,
+/// description and the hash of the current content revision. The partner
+/// signs the raw bytes of `terms_digest` with their Stellar key and submits
+/// the signature to [`sign_process_terms`].
+/// 
+/// # Parameters
+/// 
+/// * `state` - Shared application state containing database pool
+/// * `process_id` - ID of the process (path parameter)
+/// * `query` - Optional partner username and Stellar key, checked against the token
+/// 
+/// # Returns
+/// 
+/// Returns `Result` containing:
+/// - `Ok(ResponseJson<NdaTerms>)` - Terms and the digest to sign
+/// - `Err(ApiError)` - HTTP error code with an error body indicating failure reason
+/// 
+/// # HTTP Responses
+/// 
+/// - **200 OK**: Terms returned
+/// - **401 Unauthorized**: Missing, invalid or revoked access token
+/// - **403 Forbidden**: Same share and status checks as [`access_process`]
+/// - **404 Not Found**: Process or partner not found
+/// - **500 Internal Server Error**: Database error
+/// - **503 Service Unavailable**: Ledger unavailable for share verification
+#[utoipa::path(
+    get,
+    path = "/api/processes/{process_id}/terms",
+    params(
+        ("process_id" = String, Path, description = "Process ID"),
+        ("partner_username" = Option<String>, Query, description = "Username of the authenticated partner (optional)"),
+        ("partner_public_key" = Option<String>, Query, description = "Stellar public key of the authenticated partner (optional)")
+    ),
+    responses(
+        (status = 200, description = "Current NDA terms", body = NdaTerms),
+        (status = 401, description = "Unauthorized - Invalid or missing token", body = ErrorResponse),
+        (status = 403, description = "Process not shared with partner or not active", body = ErrorResponse),
+        (status = 404, description = "Process or partner not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+        (status = 503, description = "Ledger unavailable for share verification", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Sharing & Access"
+)]
+pub async fn get_process_terms(
+    State(state): State<Arc<AppState>>,
+    Path(process_id): Path<String>,
+    AuthUser(claims): AuthUser,
+    Query(query): Query<TermsQuery>,
+) -> Result<ResponseJson<NdaTerms>, ApiError> {
+    let (partner_public_key, process, _, _) = find_shared_process(
+        &state,
+        &claims,
+        &process_id,
+        query.partner_username.as_deref(),
+        query.partner_public_key.as_deref(),
+    )
+    .await?;
+
+    let terms = current_terms(&state, &process, &partner_public_key).await?;
+
+    Ok(ResponseJson(terms))
+}
+
+/// Records a partner's signature of the current NDA terms.
+/// 
+/// The signature must be an ed25519 signature of the current terms digest
+/// under the Stellar key the process was shared with. Once stored, the
+/// partner can access the content until the terms change (e.g. the content
+/// is amended). With `anchor`, a transaction from the partner's account to
+/// the owner carrying [`commitment::signature_anchor`] is also submitted.
+/// Signing the same terms again returns the existing signature.
+/// 
+/// # Parameters
+/// 
+/// * `state` - Shared application state containing database pool and ledger
+/// * `process_id` - ID of the process (path parameter)
+/// * `payload` - Partner, signed digest, signature and anchoring option
+/// 
+/// # Returns
+/// 
+/// Returns `Result` containing:
+/// - `Ok(ResponseJson<TermsSignature>)` - The stored signature
+/// - `Err(ApiError)` - HTTP error code with an error body indicating failure reason
+/// 
+/// # HTTP Responses
+/// 
+/// - **200 OK**: Signature verified and stored
+/// - **400 Bad Request**: Malformed or invalid signature (`invalid_signature`)
+/// - **401 Unauthorized**: Missing, invalid or revoked access token
+/// - **403 Forbidden**: Same share and status checks as [`access_process`]
+/// - **404 Not Found**: Process or partner not found
+/// - **409 Conflict**: The signed digest is not the current terms digest (`terms_outdated`)
+/// - **500 Internal Server Error**: Database, decryption or anchoring error
+/// - **503 Service Unavailable**: Ledger unavailable for share verification
+/// 
+/// # Request Body
+/// 
+/// ```json
+/// {
+///   "terms_digest": "5f1c...",
+///   "signature": "base64-ed25519-signature",
+///   "anchor": true
+/// }
+/// ```
+#[utoipa::path(
+    post,
+    path = "/api/processes/{process_id}/signatures",
+    params(
+        ("process_id" = String, Path, description = "Process ID")
+    ),
+    request_body = SignTermsRequest,
+    responses(
+        (status = 200, description = "Signature stored", body = TermsSignature),
+        (status = 400, description = "Invalid signature", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing token", body = ErrorResponse),
+        (status = 403, description = "Process not shared with partner or not active", body = ErrorResponse),
+        (status = 404, description = "Process or partner not found", body = ErrorResponse),
+        (status = 409, description = "Signed digest is not the current terms digest", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+        (status = 503, description = "Ledger unavailable for share verification", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Sharing & Access"
+)]
+pub async fn sign_process_terms(
+    State(state): State<Arc<AppState>>,
+    Path(process_id): Path<String>,
+    AuthUser(claims): AuthUser,
+    Json(payload): Json<SignTermsRequest>,
+) -> Result<ResponseJson<TermsSignature>, ApiError> {
+    let (partner_public_key, process, partner, _) = find_shared_process(
+        &state,
+        &claims,
+        &process_id,
+        payload.partner_username.as_deref(),
+        payload.partner_public_key.as_deref(),
+    )
+    .await?;
+
+    let terms = current_terms(&state, &process, &partner_public_key).await?;
+    if !payload.terms_digest.eq_ignore_ascii_case(&terms.terms_digest) {
+        return Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "terms_outdated",
+            "The terms changed since they were fetched, sign the current terms",
+        ));
+    }
+
+    let invalid_signature = |message: String| ApiError::new(StatusCode::BAD_REQUEST, "invalid_signature", message);
+    let digest = commitment::decode_hash(&terms.terms_digest).ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+    let signature = general_purpose::STANDARD
+        .decode(payload.signature.trim())
+        .map_err(|e| invalid_signature(format!("Signature is not valid Base64: {}", e)))?;
+    terms::verify_signature(&partner_public_key, &digest, &signature)
+        .map_err(|e| invalid_signature(e.to_string()))?;
+
+    if let Some(existing) = queries::find_terms_signature(&state.pool, &process.id, &partner.id, &terms.terms_digest)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    {
+        return Ok(ResponseJson(existing));
+    }
+
+    let anchor_transaction_hash = if payload.anchor {
+        let partner_secret_key = state.key_provider
+            .open(&partner.stellar_secret_key)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        let tx_result = state.ledger
+            .anchor_signature_transaction(
+                &partner_secret_key,
+                &terms.owner_public_key,
+                &commitment::signature_anchor(&digest, &signature),
+            )
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to anchor terms signature for process {}: {}", process.id, e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+        Some(tx_result.hash)
+    } else {
+        None
+    };
+
+    let stored = queries::create_terms_signature(
+        &state.pool,
+        &process.id,
+        &partner.id,
+        &partner_public_key,
+        terms.content_version,
+        &terms.terms_digest,
+        &general_purpose::STANDARD.encode(&signature),
+        anchor_transaction_hash.as_deref(),
+    )
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    println!("✍️ Terms of process {} (version {}) signed by {}", process.id, terms.content_version, partner.username);
+
+    Ok(ResponseJson(stored))
+}
+
+/// Lists the partners' signatures of the terms of a process, oldest first.
+/// 
+/// Only the process owner can list signatures.
+/// 
+/// # Parameters
+/// 
+/// * `state` - Shared application state containing database pool
+/// * `process_id` - ID of the process (path parameter)
+/// * `claims` - Claims of the authenticated user
+/// 
+/// # Returns
+/// 
+/// Returns `Result` containing:
+/// - `Ok(ResponseJson<Vec<TermsSignature>>)` - Signatures of every partner and terms version
+/// - `Err(ApiError)` - HTTP error code with an error body indicating failure reason
+/// 
+/// # HTTP Responses
+/// 
+/// - **200 OK**: Signatures returned
+/// - **401 Unauthorized**: Missing, invalid or revoked access token
+/// - **403 Forbidden**: Authenticated user does not own the process
+/// - **404 Not Found**: Process not found
+/// - **500 Internal Server Error**: Database error
+#[utoipa::path(
+    get,
+    path = "/api/processes/{process_id}/signatures",
+    params(
+        ("process_id" = String, Path, description = "Process ID")
+    ),
+    responses(
+        (status = 200, description = "Terms signatures", body = [TermsSignature]),
+        (status = 401, description = "Unauthorized - Invalid or missing token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Not the process owner", body = ErrorResponse),
+        (status = 404, description = "Process not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Sharing & Access"
+)]
+pub async fn list_terms_signatures(
+    State(state): State<Arc<AppState>>,
+    Path(process_id): Path<String>,
+    AuthUser(claims): AuthUser,
+) -> Result<ResponseJson<Vec<TermsSignature>>, ApiError> {
+    let process = find_owned_process(&state, &claims, &process_id).await?;
+
+    let signatures = queries::list_terms_signatures(&state.pool, &process.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(ResponseJson(signatures))
+}
+
+/// Loads a process shared with the authenticated partner, applying the checks of [`access_process`].
+/// 
+/// Returns the Stellar key the partner acts with along with the process,
+/// partner and share.
+async fn find_shared_process(
+    state: &AppState,
+    claims: &jwt::Claims,
+    process_id: &str,
+    partner_username: Option<&str>,
+    partner_public_key: Option<&str>,
+) -> Result<(String, Process, User, ProcessShare), ApiError> {
+    let (partner, partner_public_key) =
+        policy::acting_partner(&state.pool, claims, partner_username, partner_public_key).await?;
+
+    let process = queries::find_process_by_id(&state.pool, process_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let share = verify_share(state, &process.id, &process.client_id, Some(process.created_at), &partner_public_key, &partner)
+        .await?
+        .ok_or_else(|| ApiError::forbidden("share_not_found", "Process was not shared with this partner"))?;
+    ensure_partner_access(&process)?;
+
+    Ok((partner_public_key, process, partner, share))
+}
+
+/// Uploads encrypted file attachments to a process.
+/// 
+/// Accepts a `multipart/form-data` body; every field with a filename is
+/// stored as one attachment. Files are encrypted chunk by chunk while they
+/// are received (see [`crate::attachments`]), so large files never sit fully
+/// in memory. Only the process owner can add attachments.
+/// 
+/// # Parameters
+/// 
+/// * `state` - Shared application state containing database pool and attachment store
+/// * `process_id` - ID of the process (path parameter)
+/// * `claims` - Claims of the authenticated user
+/// * `multipart` - Multipart body with one or more file fields
+/// 
+/// # Returns
+/// 
+/// Returns `Result` containing:
+/// - `Ok(ResponseJson<Vec<ProcessAttachment>>)` - Metadata of the stored attachments
+/// - `Err(ApiError)` - HTTP error code with an error body indicating failure reason
+/// 
+/// # HTTP Responses
+/// 
+/// - **200 OK**: Attachments encrypted and stored
+/// - **400 Bad Request**: Malformed multipart body (`invalid_multipart`) or no file field (`no_attachments`)
+/// - **401 Unauthorized**: Missing, invalid or revoked access token
+/// - **403 Forbidden**: Authenticated user does not own the process
+/// - **404 Not Found**: Process not found
+/// - **409 Conflict**: Process is closed or archived (`process_not_editable`), or its
+///   data key was rotated during the upload (`process_update_conflict`)
+/// - **413 Payload Too Large**: A file exceeds `MAX_ATTACHMENT_BYTES` (`attachment_too_large`)
+/// - **500 Internal Server Error**: Encryption, storage or database error
+/// 
+/// # Example
+/// 
+/// ```bash
+/// curl -X POST http://localhost:3000/api/processes/process-uuid/attachments \
+///   -H "Authorization: Bearer <access_token>" \
+///   -F "file=@drawings.pdf;type=application/pdf"
+/// ```
+#[utoipa::path(
+    post,
+    path = "/api/processes/{process_id}/attachments",
+    params(
+        ("process_id" = String, Path, description = "Process ID")
+    ),
+    request_body(content = AttachmentUploadForm, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Attachments stored", body = [ProcessAttachment]),
+        (status = 400, description = "Malformed multipart body or no file field", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Not the process owner", body = ErrorResponse),
+        (status = 404, description = "Process not found", body = ErrorResponse),
+        (status = 409, description = "Process is closed or archived, or its data key was rotated during the upload", body = ErrorResponse),
+        (status = 413, description = "File exceeds MAX_ATTACHMENT_BYTES", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Process Management"
+)]
+pub async fn upload_attachments(
+    State(state): State<Arc<AppState>>,
+    Path(process_id): Path<String>,
+    AuthUser(claims): AuthUser,
+    mut multipart: Multipart,
+) -> Result<ResponseJson<Vec<ProcessAttachment>>, ApiError> {
+    let process = queries::find_process_by_id(&state.pool, &process_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    policy::ensure_process_owner(&claims, &process)?;
+    ensure_editable(&process)?;
+
+    let data_key = state.keyring
+        .unwrap_key(process.key_id.as_deref(), &process.encryption_key)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let mut stored = Vec::new();
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| ApiError::new(StatusCode::BAD_REQUEST, "invalid_multipart", e.body_text()))?
+    {
+        // Plain form fields carry no file
+        let Some(filename) = field.file_name().map(attachments::sanitize_filename) else {
+            continue;
+        };
+        let content_type = field
+            .content_type()
+            .filter(|value| HeaderValue::from_str(value).is_ok())
+            .unwrap_or("application/octet-stream")
+            .to_string();
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let size = state.attachments
+            .write(&id, &data_key, field)
+            .await
+            .map_err(|e| match e {
+                AttachmentError::TooLarge(max) => ApiError::new(
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    "attachment_too_large",
+                    format!("Attachments are limited to {} bytes", max),
+                ),
+                AttachmentError::Upload(message) => {
+                    ApiError::new(StatusCode::BAD_REQUEST, "invalid_multipart", message)
+                }
+                e => {
+                    tracing::error!("Failed to store attachment of process {}: {}", process.id, e);
+                    StatusCode::INTERNAL_SERVER_ERROR.into()
+                }
+            })?;
+
+        let attachment = queries::create_process_attachment(
+            &state.pool,
+            &id,
+            &process.id,
+            &filename,
+            &content_type,
+            size as i64,
+            &process.encryption_key,
+        )
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        // The data key was rotated while the file was written, so its key would be lost
+        let Some(attachment) = attachment else {
+            let _ = state.attachments.remove(&id).await;
+            return Err(process_update_conflict());
+        };
+
+        println!("📎 Attachment {} ({} bytes) added to process {}", attachment.id, size, process.id);
+        stored.push(attachment);
+    }
+
+    if stored.is_empty() {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "no_attachments",
+            "Multipart body contains no file field",
+        ));
+    }
+
+    Ok(ResponseJson(stored))
+}
+
+/// Streams a decrypted process attachment to an authorized partner.
+/// 
+/// Authorization is identical to [`access_process`]: the share is verified
+/// according to `SHARE_VERIFICATION_MODE`, must be within its validity window
+/// and counts against its view limit. Each download is recorded in
+/// `process_accesses` with the attachment ID before the file is streamed, so
+/// a download interrupted mid-stream still uses up a view.
+/// 
+/// The file is decrypted chunk by chunk while it is sent, with the original
+/// filename and content type. For zero-knowledge shares the encrypted file is
+/// sent as-is (`X-Content-Encryption: nda-attachment-v1`); the partner
+/// decrypts it with the data key opened from the share's `wrapped_key`, or
+/// with the attachment's `file_key` decrypted by it once the data key has
+/// been rotated (see [`crate::attachments`]).
+/// 
+/// # Parameters
+/// 
+/// * `state` - Shared application state containing database pool and attachment store
+/// * `process_id`, `attachment_id` - Path parameters identifying the file
+/// * `query` - Optional partner username and Stellar key, checked against the token
+/// 
+/// # Returns
+/// 
+/// Returns `Result` containing:
+/// - `Ok(Response)` - Streaming response with the file contents
+/// - `Err(ApiError)` - HTTP error code with an error body indicating failure reason
+/// 
+/// # HTTP Responses
+/// 
+/// - **200 OK**: File streamed
+/// - **401 Unauthorized**: Missing, invalid or revoked access token
+/// - **403 Forbidden**: Same reasons as [`access_process`]
+/// - **404 Not Found**: Process, attachment or partner not found
+/// - **500 Internal Server Error**: Storage, decryption or database error
+/// - **503 Service Unavailable**: Ledger could not be reached to verify the share
+#[utoipa::path(
+    get,
+    path = "/api/processes/{process_id}/attachments/{attachment_id}",
+    params(
+        ("process_id" = String, Path, description = "Process ID"),
+        ("attachment_id" = String, Path, description = "Attachment ID"),
+        ("partner_username" = Option<String>, Query, description = "Username of the authenticated partner (optional)"),
+        ("partner_public_key" = Option<String>, Query, description = "Stellar public key of the authenticated partner (optional)")
+    ),
+    responses(
+        (status = 200, description = "Decrypted file contents", content_type = "application/octet-stream"),
+        (status = 401, description = "Unauthorized - Invalid or missing token", body = ErrorResponse),
+        (status = 403, description = "Process not shared with partner or not active, share not yet valid, expired, view limit reached or terms not signed", body = ErrorResponse),
+        (status = 404, description = "Process, attachment or partner not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+        (status = 503, description = "Ledger unavailable for share verification", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Sharing & Access"
+)]
+pub async fn download_attachment(
+    State(state): State<Arc<AppState>>,
+    Path((process_id, attachment_id)): Path<(String, String)>,
+    AuthUser(claims): AuthUser,
+    Query(query): Query<AttachmentDownloadQuery>,
+) -> Result<Response, ApiError> {
+    let (partner_public_key, process, partner, share) = find_shared_process(
+        &state,
+        &claims,
+        &process_id,
+        query.partner_username.as_deref(),
+        query.partner_public_key.as_deref(),
+    )
+    .await?;
+
+    let attachment = queries::find_process_attachment(&state.pool, &process.id, &attachment_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let terms = current_terms(&state, &process, &partner_public_key).await?;
+    require_signed_terms(&state, &terms, &partner).await?;
+
+    // Open the file before recording the access, so a missing or unreadable file does not use up a
+    // view. The body is streamed lazily after the access is recorded, so a download that fails
+    // mid-stream still counts against the view limit.
+    let storage_error = |e: AttachmentError| {
+        tracing::error!("Failed to open attachment {}: {}", attachment.id, e);
+        ApiError::from(StatusCode::INTERNAL_SERVER_ERROR)
+    };
+    let response = if share.wrapped_key.is_some() {
+        let (size, stream) = state.attachments.open_encrypted(&attachment.id).await.map_err(storage_error)?;
+        Response::builder()
+            .header(header::CONTENT_TYPE, "application/octet-stream")
+            .header(header::CONTENT_LENGTH, size)
+            .header("x-content-encryption", "nda-attachment-v1")
+            .header(header::CONTENT_DISPOSITION, attachments::content_disposition(&attachment.filename))
+            .body(Body::from_stream(stream))
+    } else {
+        let data_key = state.keyring
+            .unwrap_key(process.key_id.as_deref(), &process.encryption_key)
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        let file_key = match &attachment.file_key {
+            Some(file_key) => attachments::FileKey::Stored(
+                attachments::unwrap_file_key(file_key, &data_key, &attachment.id)
+                    .map_err(|e| storage_error(e.into()))?,
+            ),
+            None => attachments::FileKey::Derived(&data_key),
+        };
+        let stream = state.attachments.open_decrypted(&attachment.id, file_key).await.map_err(storage_error)?;
+        let content_type = HeaderValue::from_str(&attachment.content_type)
+            .unwrap_or(HeaderValue::from_static("application/octet-stream"));
+        Response::builder()
+            .header(header::CONTENT_TYPE, content_type)
+            .header(header::CONTENT_LENGTH, attachment.size_bytes)
+            .header(header::CONTENT_DISPOSITION, attachments::content_disposition(&attachment.filename))
+            .body(Body::from_stream(stream))
+    }
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    // Register the download like any other access, checked against the view limit
+    queries::create_share_access(&state.pool, &share, &partner.id, Some(&attachment.id), process.content_version)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or_else(|| {
+            ApiError::forbidden(
+                "share_access_limit_reached",
+                format!("Share allows {} views, all of which have been used", share.max_accesses.unwrap_or_default()),
+            )
+        })?;
+
+    println!("📥 Attachment {} downloaded by {}", attachment.id, partner.username);
+
+    Ok(response)
+}
+
+/// Generates the signed PDF certificate of a process for auditors.
+/// 
+/// The certificate lists the owner and partners (name and Stellar public
+/// key), every share with its Stellar transaction hash and ledger, the
+/// partner accesses recorded in `process_accesses` and the SHA-256 digest of
+/// the current content. It is signed with the platform key returned by
+/// [`get_certificate_signing_key`] and can be verified offline (see
+/// [`crate::certificate`]).
+/// 
+/// # Parameters
+/// 
+/// * `state` - Shared application state containing database pool and signing key
+/// * `process_id` - Path parameter identifying the process
+/// * `claims` - Claims of the authenticated owner
+/// 
+/// # Returns
+/// 
+/// Returns `Result` containing:
+/// - `Ok(Response)` - The signed PDF as an attachment
+/// - `Err(ApiError)` - HTTP error code with an error body indicating failure reason
+/// 
+/// # HTTP Responses
+/// 
+/// - **200 OK**: Certificate generated
+/// - **401 Unauthorized**: Missing, invalid or revoked access token
+/// - **403 Forbidden**: Authenticated user does not own the process
+/// - **404 Not Found**: Process not found
+/// - **500 Internal Server Error**: Decryption or database error
+#[utoipa::path(
+    get,
+    path = "/api/processes/{process_id}/certificate",
+    params(
+        ("process_id" = String, Path, description = "Process ID")
+    ),
+    responses(
+        (status = 200, description = "Signed NDA certificate", content_type = "application/pdf"),
+        (status = 401, description = "Unauthorized - Invalid or missing token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Not the process owner", body = ErrorResponse),
+        (status = 404, description = "Process not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Certificates"
+)]
+pub async fn get_process_certificate(
+    State(state): State<Arc<AppState>>,
+    Path(process_id): Path<String>,
+    AuthUser(claims): AuthUser,
+) -> Result<Response, ApiError> {
+    let process = find_owned_process(&state, &claims, &process_id).await?;
+
+    let owner = queries::find_user_by_id(&state.pool, &process.client_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+    let content = decrypt_current_content(&state, &process)?;
+    let shares = queries::list_process_shares(&state.pool, &process.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let accesses = queries::list_process_accesses(&state.pool, &process.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    // Partners are named by their account, or by their key if it is not registered
+    let mut certificate_shares = Vec::with_capacity(shares.len());
+    for share in shares {
+        let name = queries::find_user_by_stellar_public_key(&state.pool, &share.partner_public_key)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+            .map_or_else(|| "Unregistered partner".to_string(), |partner| partner.name);
+        certificate_shares.push(CertificateShare {
+            partner: CertificateParty {
+                name,
+                stellar_public_key: share.partner_public_key,
+            },
+            transaction_hash: share.stellar_transaction_hash,
+            ledger: share.stellar_ledger,
+            shared_at: share.shared_at,
+            revoked_at: share.revoked_at,
+        });
+    }
+
+    let mut partner_names: HashMap<String, String> = HashMap::new();
+    let mut certificate_accesses = Vec::with_capacity(accesses.len());
+    for access in accesses {
+        if !partner_names.contains_key(&access.partner_id) {
+            let name = queries::find_user_by_id(&state.pool, &access.partner_id)
+                .await
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+                .map_or_else(|| access.partner_id.clone(), |partner| partner.name);
+            partner_names.insert(access.partner_id.clone(), name);
+        }
+        certificate_accesses.push(CertificateAccess {
+            partner_name: partner_names[&access.partner_id].clone(),
+            accessed_at: access.accessed_at,
+            content_version: access.content_version,
+            attachment_id: access.attachment_id,
+        });
+    }
+
+    let certificate = Certificate {
+        process_id: process.id.clone(),
+        title: process.title.clone(),
+        status: process.status.to_string(),
+        content_version: process.content_version,
+        content_sha256: hex::encode(terms::content_sha256(&content)),
+        owner: CertificateParty {
+            name: owner.name,
+            stellar_public_key: owner.stellar_public_key,
+        },
+        shares: certificate_shares,
+        accesses: certificate_accesses,
+        generated_at: Utc::now(),
+    };
+    let pdf = state.certificate_signer.sign_pdf(&certificate);
+
+    println!("📜 Certificate generated for process {}", process.id);
+
+    Response::builder()
+        .header(header::CONTENT_TYPE, "application/pdf")
+        .header(header::CONTENT_LENGTH, pdf.len())
+        .header(
+            header::CONTENT_DISPOSITION,
+            attachments::content_disposition(&format!("nda-certificate-{}.pdf", process.id)),
+        )
+        .body(Body::from(pdf))
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into())
+}
+
+/// Returns the public key verifying NDA certificates.
+/// 
+/// Auditors fetch it once and then verify certificates offline with the
+/// `verify_certificate` binary or any ed25519 implementation.
+/// 
+/// # HTTP Responses
+/// 
+/// - **200 OK**: Signing key returned
+#[utoipa::path(
+    get,
+    path = "/api/certificates/signing-key",
+    responses(
+        (status = 200, description = "Platform certificate signing key", body = CertificateSigningKeyResponse)
+    ),
+    tag = "Certificates"
+)]
+pub async fn get_certificate_signing_key(
+    State(state): State<Arc<AppState>>,
+) -> ResponseJson<CertificateSigningKeyResponse> {
+    ResponseJson(CertificateSigningKeyResponse {
+        public_key: state.certificate_signer.public_key(),
+        algorithm: "ed25519".to_string(),
+    })
+}
+
+/// Verifies that a process was shared with a partner.
+/// 
+/// Applies the configured [`ShareVerificationMode`]:
+/// 
+/// - **Database**: a `process_shares` row for the process and partner key
+/// - **Chain**: the partner's ledger history contains a transaction carrying
+///   the share commitment, regardless of the stored transaction hash
+/// - **Both**: the stored transaction hash is confirmed on the ledger and
+///   carries the share commitment
+/// 
+/// Revoked shares are denied in every mode with a `share_revoked` error, and
+/// shares outside their validity window with `share_not_yet_valid` or
+/// `share_expired`.
+/// 
+/// Chain modes still need the `process_shares` row, which holds the salt and
+/// content digest the commitment is recomputed from; the row alone never
+/// grants access. On-chain checks expect the transaction to be submitted by
+/// the process owner and to pay the partner's registered Stellar account.
+/// History searches stop at `created_at`, the process creation time.
+/// 
+/// # Returns
+/// 
+/// - `Ok(Some(ProcessShare))` - Share verified
+/// - `Ok(None)` - Share missing or not confirmed
+/// - `Err(ApiError)` - Share revoked or outside its validity window (403), database
+///   error (500) or ledger unavailable (503)
+async fn verify_share(
+    state: &AppState,
+    process_id: &str,
+    owner_id: &str,
+    created_at: Option<chrono::DateTime<Utc>>,
+    partner_public_key: &str,
+    partner: &User,
+) -> Result<Option<ProcessShare>, ApiError> {
+    let share = match queries::find_process_share(&state.pool, process_id, partner_public_key)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    {
+        Some(share) => share,
+        None => {
+            println!("❌ Access denied: Process was not shared with this partner");
+            return Ok(None);
+        }
+    };
+
+    if share.is_revoked() {
+        println!("❌ Access denied: Share was revoked");
+        return Err(ApiError::forbidden("share_revoked", "Share was revoked by the process owner"));
+    }
+
+    let now = Utc::now();
+    if share.is_not_yet_valid(now) {
+        println!("❌ Access denied: Share is not valid yet");
+        let valid_from = share.valid_from.map(|dt| dt.to_rfc3339()).unwrap_or_default();
+        return Err(ApiError::forbidden(
+            "share_not_yet_valid",
+            format!("Share is valid from {}", valid_from),
+        ));
+    }
+    if share.is_expired(now) {
+        println!("❌ Access denied: Share has expired");
+        let expires_at = share.expires_at.map(|dt| dt.to_rfc3339()).unwrap_or_default();
+        return Err(ApiError::forbidden(
+            "share_expired",
+            format!("Share expired at {}", expires_at),
+        ));
+    }
+
+    if !state.share_verification_mode.requires_chain() {
+        println!("✅ Access authorized: Sharing found in database");
+        return Ok(Some(share));
+    }
+
+    // On-chain shares always pay the partner's registered account
+    if partner_public_key != partner.stellar_public_key {
+        println!("❌ Access denied: Public key does not belong to partner");
+        return Ok(None);
+    }
+
+    let memo_hash = match share.expected_memo_hash() {
+        Some(memo_hash) => memo_hash,
+        None => {
+            println!("❌ Access denied: Share commitment inputs are malformed");
+            return Ok(None);
+        }
+    };
+
+    let owner = queries::find_user_by_id(&state.pool, owner_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let confirmed = match state.share_verification_mode {
+        ShareVerificationMode::Chain => state.ledger
+            .verify_process_access(&memo_hash, &owner.stellar_public_key, partner_public_key, created_at)
+            .await,
+        _ => state.ledger
+            .verify_share_transaction(
+                &share.stellar_transaction_hash,
+                &owner.stellar_public_key,
+                partner_public_key,
+                &memo_hash,
+            )
+            .await,
+    }
+    .map_err(|e| {
+        tracing::error!("Share verification on ledger failed: {}", e);
+        ApiError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "ledger_unavailable",
+            "Ledger could not be reached to verify the share",
+        )
+    })?;
+
+    if confirmed {
+        println!("✅ Access authorized: Sharing confirmed on chain");
+    } else {
+        println!("❌ Access denied: Sharing not confirmed on chain");
+    }
+
+    Ok(confirmed.then_some(share))
+}
+
+/// Lists all processes owned by a specific client.
+/// 
+/// This endpoint retrieves all NDA processes created by a client user,
+/// returning them in reverse chronological order (newest first). The
+/// response includes process metadata but not the encrypted content.
+/// 
+/// # Parameters
+/// 
+/// * `state` - Shared application state containing database pool
+/// * `claims` - Claims of the authenticated user
+/// * `params` - Query parameters including the optional client ID
+/// 
+/// # Returns
+/// 
+/// Returns `Result` containing:
+/// - `Ok(ResponseJson<Vec<ProcessResponse>>)` - List of client's processes
+/// - `Err(ApiError)` - HTTP error code with an error body indicating failure reason
+/// 
+/// # HTTP Responses
+/// 
+/// - **200 OK**: Processes retrieved successfully
+/// - **401 Unauthorized**: Missing, invalid or revoked access token
+/// - **403 Forbidden**: `client_id` is another client's (`actor_mismatch`)
+/// - **404 Not Found**: Client ID not found
+/// - **500 Internal Server Error**: Database error
+/// 
+/// # Query Parameters
+/// 
+/// - `client_id` (optional): Must be the authenticated client's ID; defaults to it
+/// 
+/// # Example Request
+/// 
+/// ```ignore
+/// GET /api/processes
+/// ```
+/// 
+/// # Response Body
+/// 
+/// ```json
+/// [
+///   {
+///     "id": "process-uuid-1",
+///     "client_id": "client-uuid",
+///     "title": "Software Development NDA",
+///     "description": "Confidential software project details",
+///     "status": "active",
+///     "created_at": "2024-01-01T00:00:00Z"
+///   },
+///   {
+///     "id": "process-uuid-2",
+///     "client_id": "client-uuid",
+///     "title": "Marketing Partnership NDA",
+///     "description": "Joint marketing campaign specifications",
+///     "status": "active",
+///     "created_at": "2024-01-01T00:00:00Z"
+///   }
+/// ]
+/// ```
+/// 
+/// # Security Notes
+/// 
+/// - Only returns processes owned by the specified client
+/// - Encrypted content is not included in the response
+/// - Process access requires separate authorization via sharing
+#[utoipa::path(
+    get,
+    path = "/api/processes",
+    params(
+        ("client_id" = Option<String>, Query, description = "Authenticated client's ID (optional)")
+    ),
+    responses(
+        (status = 200, description = "Processes retrieved successfully", body = [ProcessResponse]),
+        (status = 401, description = "Unauthorized - Invalid or missing token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Cannot list other clients' processes", body = ErrorResponse),
+        (status = 404, description = "Client not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Process Management"
+)]
+pub async fn list_processes(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Query(params): Query<ListProcessesQuery>,
+) -> Result<ResponseJson<Vec<ProcessResponse>>, ApiError> {
+    // Clients only list their own processes
+    let client_id = policy::own_client_id(&claims, params.client_id.as_deref())?;
+    
+    // Find client by ID
+    let client = queries::find_user_by_id(&state.pool, &client_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let processes = queries::list_processes_by_client(&state.pool, &client.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let response: Vec<ProcessResponse> = processes.into_iter().map(|p| p.into()).collect();
+    Ok(ResponseJson(response))
+}
+
+/// Retrieves access notifications for a client's processes.
+/// 
+/// This endpoint provides clients with a comprehensive audit trail showing
+/// when partners have accessed their shared processes. It returns denormalized
+/// data including process titles, descriptions, status, and partner usernames for detailed reporting.
+/// 
+/// # Parameters
+/// 
+/// * `state` - Shared application state containing database pool
+/// * `params` - Query parameters including client username
+/// 
+/// # Returns
+/// 
+/// Returns `Result` containing:
+/// - `Ok(ResponseJson<Vec<ProcessAccessWithDetails>>)` - List of access events with complete process details
+/// - `Err(ApiError)` - HTTP error code with an error body indicating failure reason
+/// 
+/// # HTTP Responses
+/// 
+/// - **200 OK**: Notifications retrieved successfully
+/// - **401 Unauthorized**: Missing, invalid or revoked access token
+/// - **403 Forbidden**: Authenticated user does not have the client role, or
+///   `client_id` is another client's (`actor_mismatch`)
+/// - **404 Not Found**: Client ID not found
+/// - **500 Internal Server Error**: Database error
+/// 
+/// # Query Parameters
+/// 
+/// - `client_id` (optional): Must be the authenticated client's ID; defaults to it
+/// 
+/// # Example Request
+/// 
+/// ```ignore
+/// GET /api/notifications
+/// ```
+/// 
+/// # Response Body
+/// 
+/// ```json
+/// [
+///   {
+///     "id": "access-uuid-1",
+///     "process_id": "process-uuid",
+///     "partner_id": "partner-uuid",
+///     "accessed_at": "2024-01-01T10:30:00Z",
+///     "process_title": "Software Development NDA",
+///     "process_description": "Comprehensive confidentiality agreement for software development partnership",
+///     "process_status": "active",
+///     "partner_username": "partner_company"
+///   },
+///   {
+///     "id": "access-uuid-2",
+///     "process_id": "process-uuid-2",
+///     "partner_id": "partner-uuid-2",
+///     "accessed_at": "2024-01-01T09:15:00Z",
+///     "process_title": "Marketing Partnership NDA",
+///     "process_description": "Non-disclosure agreement for marketing collaboration and data sharing",
+///     "process_status": "completed",
+///     "partner_username": "another_partner"
+///   }
+/// ]
+/// ```
+/// 
+/// # Use Cases
+/// 
+/// - **Enhanced Compliance Reporting**: Generate detailed audit reports with process context and status tracking
+/// - **Status-Aware Analytics**: Track access patterns with process descriptions and current status information
+/// - **Security Monitoring**: Monitor access with full process details including status for better threat detection
+/// - **Rich Client Dashboard**: Provide comprehensive notifications with process context and status updates
+/// 
+/// # Data Privacy
+/// 
+/// - Only shows access to processes owned by the requesting client
+/// - Includes process descriptions, status, and partner usernames but not sensitive encrypted content
+/// - Ordered by access time (most recent first) for easy monitoring
+#[utoipa::path(
+    get,
+    path = "/api/notifications",
+    params(
+        ("client_id" = Option<String>, Query, description = "Authenticated client's ID (optional)")
+    ),
+    responses(
+        (status = 200, description = "Notifications retrieved successfully", body = [ProcessAccessWithDetails]),
+        (status = 401, description = "Unauthorized - Invalid or missing token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Requires the client role; cannot read other clients' notifications", body = ErrorResponse),
+        (status = 404, description = "Client not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Audit & Compliance"
+)]
+pub async fn get_notifications(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Query(params): Query<ListProcessesQuery>,
+) -> Result<ResponseJson<Vec<ProcessAccessWithDetails>>, ApiError> {
+    // Clients only see the audit trail of their own processes
+    let client_id = policy::own_client_id(&claims, params.client_id.as_deref())?;
+    
+    // Find client by ID
+    let client = queries::find_user_by_id(&state.pool, &client_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let notifications = queries::list_process_accesses_by_client(&state.pool, &client.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(ResponseJson(notifications))
+}
+
+/// Retrieves the notifications addressed to the authenticated user.
+/// 
+/// Unlike [`get_notifications`], which reports access events, this endpoint
+/// returns events raised by the system on the user's behalf, such as
+/// `share_expired` when a share's validity window ends.
+/// 
+/// # Parameters
+/// 
+/// * `state` - Shared application state containing database pool
+/// * `claims` - Claims of the authenticated user
+/// 
+/// # Returns
+/// 
+/// Returns `Result` containing:
+/// - `Ok(ResponseJson<Vec<Notification>>)` - Notifications, newest first
+/// - `Err(ApiError)` - HTTP error code with an error body indicating failure reason
+/// 
+/// # HTTP Responses
+/// 
+/// - **200 OK**: Notifications retrieved successfully
+/// - **401 Unauthorized**: Missing, invalid or revoked access token
+/// - **500 Internal Server Error**: Database error
+/// 
+/// # Response Body
+/// 
+/// ```json
+/// [
+///   {
+///     "id": "notification-uuid",
+///     "user_id": "client-uuid",
+///     "kind": "share_expired",
+///     "process_id": "process-uuid",
+///     "share_id": "share-uuid",
+///     "message": "Share of 'Software Development NDA' with GCKFBEIYTKP... expired",
+///     "created_at": "2024-03-31T00:00:00Z"
+///   }
+/// ]
+/// ```
+#[utoipa::path(
+    get,
+    path = "/api/notifications/events",
+    responses(
+        (status = 200, description = "Notifications retrieved successfully", body = [Notification]),
+        (status = 401, description = "Unauthorized - Invalid or missing token", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Audit & Compliance"
+)]
+pub async fn get_notification_events(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+) -> Result<ResponseJson<Vec<Notification>>, ApiError> {
+    let notifications = queries::list_notifications_by_user(&state.pool, &claims.sub)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(ResponseJson(notifications))
+}
+
+/// Lists the active login sessions of the authenticated user.
+/// 
+/// Every login starts a session, kept active until it is revoked (by logout,
+/// by the user, or on refresh token reuse) or its refresh tokens expire.
+/// The session of the access token making the request is marked `current`.
+/// 
+/// # Parameters
+/// 
+/// * `state` - Shared application state containing database pool
+/// * `claims` - Claims of the authenticated user
+/// 
+/// # Returns
+/// 
+/// Returns `Result` containing:
+/// - `Ok(ResponseJson<Vec<SessionResponse>>)` - Active sessions, most recently used first
+/// - `Err(ApiError)` - HTTP error code with an error body indicating failure reason
+/// 
+/// # HTTP Responses
+/// 
+/// - **200 OK**: Sessions retrieved successfully
+/// - **401 Unauthorized**: Missing, invalid or revoked access token
+/// - **500 Internal Server Error**: Database error
+/// 
+/// # Response Body
+/// 
+/// ```json
+/// [
+///   {
+///     "id": "session-uuid",
+///     "user_agent": "Mozilla/5.0 (X11; Linux x86_64) ...",
+///     "ip_address": "203.0.113.7",
+///     "created_at": "2024-01-01T09:00:00Z",
+///     "last_refreshed_at": "2024-01-01T09:15:00Z",
+///     "current": true
+///   }
+/// ]
+/// ```
+#[utoipa::path(
+    get,
+    path = "/api/sessions",
+    responses(
+        (status = 200, description = "Active sessions of the authenticated user", body = [SessionResponse]),
+        (status = 401, description = "Unauthorized - Invalid or missing token", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "User Management"
+)]
+pub async fn list_sessions(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+) -> Result<ResponseJson<Vec<SessionResponse>>, ApiError> {
+    let active_since = Utc::now() - chrono::Duration::minutes(jwt::REFRESH_TOKEN_MINUTES);
+    let sessions = queries::list_active_sessions(&state.pool, &claims.sub, active_since)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(ResponseJson(
+        sessions
+            .into_iter()
+            .map(|session| SessionResponse::new(session, claims.fam.as_deref()))
+            .collect(),
+    ))
+}
+
+/// Revokes one login session of the authenticated user.
+/// 
+/// Revokes the session's refresh token family: its refresh tokens can no
+/// longer be exchanged, and its access tokens are rejected immediately
+/// through the token blacklist. Revoking the current session logs the
+/// caller out. Revoking a session twice has no further effect.
+/// 
+/// # Parameters
+/// 
+/// * `state` - Shared application state containing database pool and token blacklist
+/// * `session_id` - ID of the session to revoke (path parameter)
+/// * `claims` - Claims of the authenticated user
+/// 
+/// # Returns
+/// 
+/// Returns `Result` containing:
+/// - `Ok(StatusCode::NO_CONTENT)` - Session revoked
+/// - `Err(ApiError)` - HTTP error code with an error body indicating failure reason
+/// 
+/// # HTTP Responses
+/// 
+/// - **204 No Content**: Session revoked
+/// - **401 Unauthorized**: Missing, invalid or revoked access token
+/// - **403 Forbidden**: The session belongs to another user (`not_session_owner`)
+/// - **404 Not Found**: Session not found
+/// - **500 Internal Server Error**: Database error
+#[utoipa::path(
+    delete,
+    path = "/api/sessions/{session_id}",
+    params(
+        ("session_id" = String, Path, description = "Session ID")
+    ),
+    responses(
+        (status = 204, description = "Session revoked"),
+        (status = 401, description = "Unauthorized - Invalid or missing token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - Not the session's user", body = ErrorResponse),
+        (status = 404, description = "Session not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "User Management"
+)]
+pub async fn revoke_session(
+    State(state): State<Arc<AppState>>,
+    Path(session_id): Path<String>,
+    AuthUser(claims): AuthUser,
+) -> Result<StatusCode, ApiError> {
+    let session = queries::find_session(&state.pool, &session_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or_else(|| ApiError::new(StatusCode::NOT_FOUND, "session_not_found", "Session not found"))?;
+    policy::ensure_session_owner(&claims, &session)?;
+
+    if session.revoked_at.is_none() {
+        revoke_refresh_family(&state, &session.family_id, "session_revoked").await?;
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Revokes every login session of the authenticated user except the current one.
+/// 
+/// Used to sign out other devices, e.g. after a password change or a lost
+/// device. The current session is the one of the access token making the
+/// request; an access token issued outside a session revokes them all.
+/// 
+/// # Parameters
+/// 
+/// * `state` - Shared application state containing database pool and token blacklist
+/// * `claims` - Claims of the authenticated user
+/// 
+/// # Returns
+/// 
+/// Returns `Result` containing:
+/// - `Ok(StatusCode::NO_CONTENT)` - Other sessions revoked
+/// - `Err(ApiError)` - HTTP error code with an error body indicating failure reason
+/// 
+/// # HTTP Responses
+/// 
+/// - **204 No Content**: Other sessions revoked
+/// - **401 Unauthorized**: Missing, invalid or revoked access token
+/// - **500 Internal Server Error**: Database error
+#[utoipa::path(
+    post,
+    path = "/api/sessions/revoke-others",
+    responses(
+        (status = 204, description = "Other sessions revoked"),
+        (status = 401, description = "Unauthorized - Invalid or missing token", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "User Management"
+)]
+pub async fn revoke_other_sessions(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+) -> Result<StatusCode, ApiError> {
+    let active_since = Utc::now() - chrono::Duration::minutes(jwt::REFRESH_TOKEN_MINUTES);
+    let sessions = queries::list_active_sessions(&state.pool, &claims.sub, active_since)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    for session in sessions {
+        if claims.fam.as_deref() != Some(session.family_id.as_str()) {
+            revoke_refresh_family(&state, &session.family_id, "session_revoked").await?;
+        }
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_provider::{is_sealed, LocalFileKeyProvider};
+    use crate::crypto::encrypt_content;
+    use crate::ledger::{InMemoryLedger, LedgerMemo};
+    use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions};
+    use std::collections::HashMap;
+
+    async fn test_state(ledger: Arc<InMemoryLedger>, mode: ShareVerificationMode) -> Arc<AppState> {
+        // A single connection keeps the in-memory database alive for the whole test
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        test_state_with_pool(pool, ledger, mode).await
+    }
+
+    /// Builds a state on a temporary database file in WAL mode with several
+    /// connections, so concurrent requests really contend for the database.
+    async fn file_backed_test_state(ledger: Arc<InMemoryLedger>, mode: ShareVerificationMode) -> Arc<AppState> {
+        let path = std::env::temp_dir().join(format!("nda-test-{}.db", uuid::Uuid::new_v4()));
+        let options = SqliteConnectOptions::new()
+            .filename(&path)
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal)
+            .busy_timeout(std::time::Duration::from_secs(10));
+        let pool = SqlitePoolOptions::new()
+            .max_connections(8)
+            .connect_with(options)
+            .await
+            .unwrap();
+        test_state_with_pool(pool, ledger, mode).await
+    }
+
+    async fn test_state_with_pool(
+        pool: sqlx::SqlitePool,
+        ledger: Arc<InMemoryLedger>,
+        mode: ShareVerificationMode,
+    ) -> Arc<AppState> {
+        crate::database::run_migrations(&pool).await.unwrap();
+        let token_blacklist = jwt::TokenBlacklist::with_pool(pool.clone());
+
+        Arc::new(AppState {
+            pool,
+            jwt_secret: "test-jwt-secret-with-at-least-32-characters".to_string(),
+            token_blacklist,
+            ledger,
+            share_verification_mode: mode,
+            keyring: KeyRing::new(crate::keys::KeyEncryptionKey::generate()),
+            key_provider: Arc::new(LocalFileKeyProvider::new(KeyRing::new(
+                crate::keys::KeyEncryptionKey::generate(),
+            ))),
+            attachments: AttachmentStore::new(
+                std::env::temp_dir().join(format!("nda-attachments-{}", uuid::Uuid::new_v4())),
+                attachments::DEFAULT_MAX_ATTACHMENT_BYTES,
+            ),
+            content_cipher: ContentCipher::XChaCha20Poly1305,
+            certificate_signer: Arc::new(
+                CertificateSigner::derive(&KeyRing::new(crate::keys::KeyEncryptionKey::generate())).unwrap(),
+            ),
+        })
+    }
+
+    /// Authenticates an access token the way the [`AuthUser`] extractor does.
+    fn auth_user(state: &Arc<AppState>, access_token: &str) -> AuthUser {
+        AuthUser(jwt::validate_token(access_token, &state.jwt_secret).unwrap())
+    }
+
+    /// Authenticates a user the way the [`AuthUser`] extractor does for their access token.
+    fn as_user(user: &UserResponse) -> AuthUser {
+        AuthUser(jwt::Claims::new(user.id.clone(), user.username.clone(), user.roles.clone(), 15))
+    }
+
+    async fn register(state: &Arc<AppState>, username: &str, role: &str) -> UserResponse {
+        let ResponseJson(user) = register_user(
+            State(state.clone()),
+            Json(RegisterRequest {
+                username: username.to_string(),
+                name: username.to_string(),
+                password: "password123".to_string(),
+                roles: vec![role.to_string()],
+            }),
+        )
+        .await
+        .unwrap();
+        user
+    }
+
+    /// Signs the current terms of a process the way a partner's client would.
+    async fn sign_terms(state: &Arc<AppState>, process_id: &str, partner: &UserResponse) -> TermsSignature {
+        let ResponseJson(terms) = get_process_terms(
+            State(state.clone()),
+            Path(process_id.to_string()),
+            as_user(partner),
+            Query(TermsQuery {
+                partner_username: Some(partner.username.clone()),
+                partner_public_key: Some(partner.stellar_public_key.clone()),
+            }),
+        )
+        .await
+        .unwrap();
+
+        let stored = queries::find_user_by_id(&state.pool, &partner.id).await.unwrap().unwrap();
+        let partner_secret = state.key_provider.open(&stored.stellar_secret_key).await.unwrap();
+        let digest = commitment::decode_hash(&terms.terms_digest).unwrap();
+
+        let ResponseJson(signature) = sign_process_terms(
+            State(state.clone()),
+            Path(process_id.to_string()),
+            as_user(partner),
+            Json(SignTermsRequest {
+                partner_username: Some(partner.username.clone()),
+                partner_public_key: Some(partner.stellar_public_key.clone()),
+                terms_digest: terms.terms_digest,
+                signature: terms::sign(&partner_secret, &digest).unwrap(),
+                anchor: false,
+            }),
+        )
+        .await
+        .unwrap();
+        signature
+    }
+
+    #[tokio::test]
+    async fn test_refresh_token_reuse_revokes_family() {
+        let state = test_state(Arc::new(InMemoryLedger::new()), ShareVerificationMode::Both).await;
+        register(&state, "client@example.com", "client").await;
+        let login = || {
+            login_user(
+                State(state.clone()),
+                None,
+                HeaderMap::new(),
+                Json(LoginRequest {
+                    username: "client@example.com".to_string(),
+                    password: "password123".to_string(),
+                }),
+            )
+        };
+        let refresh = |token: &str| {
+            refresh_token(
+                State(state.clone()),
+                None,
+                HeaderMap::new(),
+                Json(RefreshTokenRequest { refresh_token: token.to_string() }),
+            )
+        };
+        let bearer = |token: &str| format!("Bearer {}", token);
+
+        let ResponseJson(first) = login().await.unwrap();
+        let ResponseJson(rotated) = refresh(&first.refresh_token).await.unwrap();
+
+        // The rotation is recorded as a child of the login token
+        let first_claims = jwt::validate_token(&first.refresh_token, &state.jwt_secret).unwrap();
+        let rotated_claims = jwt::validate_token(&rotated.refresh_token, &state.jwt_secret).unwrap();
+        assert_eq!(rotated_claims.fam, first_claims.fam);
+        let stored = queries::find_refresh_token(&state.pool, &rotated_claims.jti).await.unwrap().unwrap();
+        assert_eq!(stored.parent_jti.as_deref(), Some(first_claims.jti.as_str()));
+        assert!(queries::find_refresh_token(&state.pool, &first_claims.jti).await.unwrap().unwrap().rotated_at.is_some());
+
+        // Replaying the rotated token revokes the whole family
+        let reused = refresh(&first.refresh_token).await.unwrap_err();
+        assert_eq!((reused.status, reused.code.as_str()), (StatusCode::UNAUTHORIZED, "refresh_token_reused"));
+        let revoked = refresh(&rotated.refresh_token).await.unwrap_err();
+        assert_eq!(revoked.status, StatusCode::UNAUTHORIZED);
+        let access = jwt::validate_auth_header(Some(&bearer(&rotated.access_token)), &state.jwt_secret, &state.token_blacklist).await;
+        assert_eq!(access.err(), Some("Token has been revoked"));
+
+        // Other logins are not affected
+        let ResponseJson(second) = login().await.unwrap();
+        let ResponseJson(_) = refresh(&second.refresh_token).await.unwrap();
+        assert!(jwt::validate_auth_header(Some(&bearer(&second.access_token)), &state.jwt_secret, &state.token_blacklist)
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_failed_refresh_leaves_token_usable() {
+        let state = test_state(Arc::new(InMemoryLedger::new()), ShareVerificationMode::Both).await;
+        register(&state, "client@example.com", "client").await;
+        let ResponseJson(login) = login_user(
+            State(state.clone()),
+            None,
+            HeaderMap::new(),
+            Json(LoginRequest {
+                username: "client@example.com".to_string(),
+                password: "password123".to_string(),
+            }),
+        )
+        .await
+        .unwrap();
+        let refresh = || {
+            refresh_token(
+                State(state.clone()),
+                None,
+                HeaderMap::new(),
+                Json(RefreshTokenRequest { refresh_token: login.refresh_token.clone() }),
+            )
+        };
+        let claims = jwt::validate_token(&login.refresh_token, &state.jwt_secret).unwrap();
+
+        // Storing the successor token fails after the presented one was rotated
+        sqlx::query("CREATE TRIGGER fail_refresh_insert BEFORE INSERT ON refresh_tokens BEGIN SELECT RAISE(ABORT, 'injected'); END")
+            .execute(&state.pool)
+            .await
+            .unwrap();
+        let failed = refresh().await.unwrap_err();
+        assert_eq!(failed.status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(queries::find_refresh_token(&state.pool, &claims.jti).await.unwrap().unwrap().rotated_at.is_none());
+
+        // The retry is an ordinary refresh, not a replay
+        sqlx::query("DROP TRIGGER fail_refresh_insert").execute(&state.pool).await.unwrap();
+        let ResponseJson(rotated) = refresh().await.unwrap();
+        let rotated_claims = jwt::validate_token(&rotated.refresh_token, &state.jwt_secret).unwrap();
+        assert_eq!(rotated_claims.fam, claims.fam);
+        let family = queries::find_refresh_token(&state.pool, &rotated_claims.jti).await.unwrap().unwrap();
+        assert!(family.family_revoked_at.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_sessions_are_listed_and_revoked() {
+        let state = test_state(Arc::new(InMemoryLedger::new()), ShareVerificationMode::Both).await;
+        register(&state, "client@example.com", "client").await;
+        let other = register(&state, "other@example.com", "client").await;
+        let login = |user_agent: &'static str, forwarded_for: Option<&'static str>| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::USER_AGENT, HeaderValue::from_static(user_agent));
+            if let Some(forwarded_for) = forwarded_for {
+                headers.insert("x-forwarded-for", HeaderValue::from_static(forwarded_for));
+            }
+            login_user(
+                State(state.clone()),
+                Some(ConnectInfo(SocketAddr::from(([192, 0, 2, 1], 50000)))),
+                headers,
+                Json(LoginRequest {
+                    username: "client@example.com".to_string(),
+                    password: "password123".to_string(),
+                }),
+            )
+        };
+        let is_valid = |access_token: String| {
+            let state = state.clone();
+            async move {
+                let header = format!("Bearer {}", access_token);
+                jwt::validate_auth_header(Some(&header), &state.jwt_secret, &state.token_blacklist).await.is_ok()
+            }
+        };
+
+        let ResponseJson(laptop) = login("Laptop", None).await.unwrap();
+        let ResponseJson(phone) = login("Phone", Some("203.0.113.7, 10.0.0.1")).await.unwrap();
+        let ResponseJson(tablet) = login("Tablet", None).await.unwrap();
+        let ResponseJson(_) = refresh_token(
+            State(state.clone()),
+            None,
+            HeaderMap::new(),
+            Json(RefreshTokenRequest { refresh_token: phone.refresh_token.clone() }),
+        )
+        .await
+        .unwrap();
+
+        let ResponseJson(sessions) = list_sessions(State(state.clone()), auth_user(&state, &laptop.access_token))
+            .await
+            .unwrap();
+        assert_eq!(sessions.len(), 3);
+        let laptop_session = sessions.iter().find(|s| s.current).unwrap();
+        assert_eq!(laptop_session.user_agent.as_deref(), Some("Laptop"));
+        assert_eq!(laptop_session.ip_address.as_deref(), Some("192.0.2.1"));
+        let phone_session = sessions.iter().find(|s| s.user_agent.as_deref() == Some("Phone")).unwrap();
+        assert_eq!(phone_session.ip_address.as_deref(), Some("203.0.113.7"));
+        assert!(phone_session.last_refreshed_at.is_some());
+
+        // Sessions can only be revoked by their user
+        let denied = revoke_session(State(state.clone()), Path(phone_session.id.clone()), as_user(&other))
+            .await
+            .unwrap_err();
+        assert_eq!((denied.status, denied.code.as_str()), (StatusCode::FORBIDDEN, "not_session_owner"));
+        assert!(is_valid(phone.access_token.clone()).await);
+
+        // Revoking a session rejects its access tokens immediately
+        let revoked = revoke_session(
+            State(state.clone()),
+            Path(phone_session.id.clone()),
+            auth_user(&state, &laptop.access_token),
+        )
+        .await
+        .unwrap();
+        assert_eq!(revoked, StatusCode::NO_CONTENT);
+        assert!(!is_valid(phone.access_token.clone()).await);
+        assert!(is_valid(tablet.access_token.clone()).await);
+
+        // Revoking the other sessions keeps the current one
+        let revoked = revoke_other_sessions(State(state.clone()), auth_user(&state, &laptop.access_token))
+            .await
+            .unwrap();
+        assert_eq!(revoked, StatusCode::NO_CONTENT);
+        assert!(!is_valid(tablet.access_token.clone()).await);
+        assert!(is_valid(laptop.access_token.clone()).await);
+        let rejected = refresh_token(
+            State(state.clone()),
+            None,
+            HeaderMap::new(),
+            Json(RefreshTokenRequest { refresh_token: tablet.refresh_token.clone() }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(rejected.status, StatusCode::UNAUTHORIZED);
+
+        let ResponseJson(sessions) = list_sessions(State(state.clone()), auth_user(&state, &laptop.access_token))
+            .await
+            .unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert!(sessions[0].current);
+    }
+
+    #[tokio::test]
+    async fn test_share_and_access_flow_with_in_memory_ledger() {
+        let ledger = Arc::new(InMemoryLedger::new());
+        let state = test_state(ledger.clone(), ShareVerificationMode::Both).await;
+
+        let client = register(&state, "client@example.com", "client").await;
+        let partner = register(&state, "partner@example.com", "partner").await;
+        assert!(ledger.account(&client.stellar_public_key).is_some());
+
+        // Only the sealed secret key is stored
+        let stored_client = queries::find_user_by_id(&state.pool, &client.id).await.unwrap().unwrap();
+        assert!(is_sealed(&stored_client.stellar_secret_key));
+
+        let ResponseJson(login) = login_user(
+            State(state.clone()),
+            None,
+            HeaderMap::new(),
+            Json(LoginRequest {
+                username: "client@example.com".to_string(),
+                password: "password123".to_string(),
+            }),
+        )
+        .await
+        .unwrap();
+
+        let auth = auth_user(&state, &login.access_token);
+
+        let ResponseJson(process) = create_process(
+            State(state.clone()),
+            auth.clone(),
+            Json(CreateProcessRequest {
+                client_id: client.id.clone(),
+                title: "Software Development NDA".to_string(),
+                description: "Project details".to_string(),
+                confidential_content: "Top secret content".to_string(),
+                draft: false,
+                template_id: None,
+                template_version: None,
+                template_values: Default::default(),
+            }),
+        )
+        .await
+        .unwrap();
+
+        // The data key is stored wrapped by the key-encryption key
+        let stored = queries::find_process_by_id(&state.pool, &process.id).await.unwrap().unwrap();
+        assert_eq!(stored.key_id.as_deref(), Some(state.keyring.active().id()));
+
+        let access_request = || AccessProcessRequest {
+            process_id: process.id.clone(),
+            partner_public_key: Some(partner.stellar_public_key.clone()),
+            partner_username: Some(partner.username.clone()),
+        };
+
+        // Access is denied before the process is shared
+        let denied = access_process(State(state.clone()), as_user(&partner), Json(access_request())).await.unwrap_err();
+        assert_eq!((denied.status, denied.code.as_str()), (StatusCode::FORBIDDEN, "share_not_found"));
+
+        let ResponseJson(share) = share_process(
+            State(state.clone()),
+            as_user(&client),
+            Json(ShareProcessRequest {
+                process_id: process.id.clone(),
+                partner_public_key: partner.stellar_public_key.clone(),
+                client_username: Some(client.username.clone()),
+                valid_from: None,
+                expires_at: None,
+                max_accesses: None,
+                zero_knowledge: false,
+            }),
+        )
+        .await
+        .unwrap();
+
+        let transactions = ledger.transactions();
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].hash, share.stellar_transaction_hash);
+        assert_eq!(transactions[0].destination_account, partner.stellar_public_key);
+        let memo_hash = share.expected_memo_hash().unwrap();
+        assert_eq!(transactions[0].memo, LedgerMemo::Hash(memo_hash));
+        assert_eq!(share.memo_commitment, Some(hex::encode(memo_hash)));
+
+        sign_terms(&state, &process.id, &partner).await;
+        let ResponseJson(access) = access_process(State(state.clone()), as_user(&partner), Json(access_request()))
+            .await
+            .unwrap();
+        assert_eq!(access.content.as_deref(), Some("Top secret content"));
+        assert_eq!(access.remaining_accesses, None);
+
+        let accesses = queries::list_process_accesses_by_client(&state.pool, &client.id)
+            .await
+            .unwrap();
+        assert_eq!(accesses.len(), 1);
+
+        let ResponseJson(revoked) = revoke_share(
+            State(state.clone()),
+            auth.clone(),
+            Json(RevokeShareRequest {
+                process_id: process.id.clone(),
+                partner_public_key: partner.stellar_public_key.clone(),
+                reason: "Partnership ended".to_string(),
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(revoked.revoked_by.as_deref(), Some(client.id.as_str()));
+        assert_eq!(revoked.revocation_reason.as_deref(), Some("Partnership ended"));
+
+        let transactions = ledger.transactions();
+        assert_eq!(revoked.revocation_transaction_hash.as_deref(), Some(transactions[1].hash.as_str()));
+        assert!(matches!(transactions[1].memo, LedgerMemo::Return(original) if hex::encode(original) == share.stellar_transaction_hash));
+
+        // Revoked shares no longer grant access and cannot be revoked twice
+        let denied = access_process(State(state.clone()), as_user(&partner), Json(access_request())).await.unwrap_err();
+        assert_eq!((denied.status, denied.code.as_str()), (StatusCode::FORBIDDEN, "share_revoked"));
+
+        let again = revoke_share(
+            State(state.clone()),
+            auth.clone(),
+            Json(RevokeShareRequest {
+                process_id: process.id.clone(),
+                partner_public_key: partner.stellar_public_key.clone(),
+                reason: "Partnership ended".to_string(),
+            }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!((again.status, again.code.as_str()), (StatusCode::CONFLICT, "share_already_revoked"));
+        assert_eq!(ledger.transactions().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_share_row_without_ledger_transaction_is_rejected() {
+        for (mode, granted) in [
+            (ShareVerificationMode::Database, true),
+            (ShareVerificationMode::Chain, false),
+            (ShareVerificationMode::Both, false),
+        ] {
+            let ledger = Arc::new(InMemoryLedger::new());
+            let state = test_state(ledger, mode).await;
+
+            let client = register(&state, "client@example.com", "client").await;
+            let partner = register(&state, "partner@example.com", "partner").await;
+
+            let process = queries::create_process(
+                &state.pool,
+                "process-1",
+                &client.id,
+                "NDA",
+                "Project details",
+                "ciphertext",
+                &hex::encode([0u8; 32]),
+                &generate_key(),
+                None,
+                ProcessStatus::Active,
+                None,
+            )
+            .await
+            .unwrap();
+
+            // Share row written directly to the database, never submitted to the ledger
+            queries::create_process_share(
+                &state.pool,
+                &process.id,
+                &partner.stellar_public_key,
+                "forged-hash",
+                None,
+                &hex::encode([1u8; 32]),
+                &hex::encode([2u8; 32]),
+                &hex::encode([3u8; 32]),
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+            let partner_user = queries::find_user_by_id(&state.pool, &partner.id).await.unwrap().unwrap();
+            let result = verify_share(&state, &process.id, &client.id, Some(process.created_at), &partner.stellar_public_key, &partner_user).await;
+            assert_eq!(result.map(|share| share.is_some()), Ok(granted), "mode {:?}", mode);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_share_validity_window_and_expiry() {
+        let ledger = Arc::new(InMemoryLedger::new());
+        let state = test_state(ledger, ShareVerificationMode::Database).await;
+
+        let client = register(&state, "client@example.com", "client").await;
+        let partner = register(&state, "partner@example.com", "partner").await;
+        let partner_user = queries::find_user_by_id(&state.pool, &partner.id).await.unwrap().unwrap();
+
+        let process = queries::create_process(
+            &state.pool,
+            "process-1",
+            &client.id,
+            "NDA",
+            "Project details",
+            "ciphertext",
+            &hex::encode([0u8; 32]),
+            &generate_key(),
+            None,
+            ProcessStatus::Active,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let now = Utc::now();
+        let share_with_window = |valid_from, expires_at| {
+            queries::create_process_share(
+                &state.pool,
+                &process.id,
+                &partner.stellar_public_key,
+                "tx-hash",
+                None,
+                "",
+                "",
+                "",
+                valid_from,
+                expires_at,
+                None,
+                None,
+            )
+        };
+        let check = || verify_share(&state, &process.id, &client.id, None, &partner.stellar_public_key, &partner_user);
+
+        // Access window in the future
+        share_with_window(Some(now + chrono::Duration::days(1)), Some(now + chrono::Duration::days(30)))
+            .await
+            .unwrap();
+        assert_eq!(check().await.unwrap_err().code, "share_not_yet_valid");
+        assert_eq!(crate::tasks::expire_shares(&state.pool, now).await.unwrap(), 0);
+
+        // Window has passed but the expiry task has not run yet
+        let share = share_with_window(None, Some(now - chrono::Duration::seconds(1)))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(check().await.unwrap_err().code, "share_expired");
+
+        // A run that marked the share but stopped before notifying is completed by the next one
+        assert_eq!(queries::expire_process_shares(&state.pool, now).await.unwrap().len(), 1);
+        assert!(queries::list_notifications_by_user(&state.pool, &client.id).await.unwrap().is_empty());
+        assert_eq!(crate::tasks::expire_shares(&state.pool, now).await.unwrap(), 1);
+        assert_eq!(crate::tasks::expire_shares(&state.pool, now).await.unwrap(), 0);
+
+        let expired = queries::find_process_share(&state.pool, &process.id, &partner.stellar_public_key)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(expired.id, share.id);
+        assert!(expired.expired_at.is_some());
+
+        let notifications = queries::list_notifications_by_user(&state.pool, &client.id).await.unwrap();
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].kind, crate::tasks::SHARE_EXPIRED_NOTIFICATION);
+        assert_eq!(notifications[0].share_id.as_deref(), Some(share.id.as_str()));
+
+        // Windows that never grant access are rejected when sharing
+        let rejected = share_process(
+            State(state.clone()),
+            as_user(&client),
+            Json(ShareProcessRequest {
+                process_id: process.id.clone(),
+                partner_public_key: partner.stellar_public_key.clone(),
+                client_username: Some(client.username.clone()),
+                valid_from: Some(now + chrono::Duration::days(10)),
+                expires_at: Some(now + chrono::Duration::days(5)),
+                max_accesses: None,
+                zero_knowledge: false,
+            }),
+        )
+        .await;
+        assert_eq!(rejected.err().map(|e| e.status), Some(StatusCode::BAD_REQUEST));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_view_limit_is_enforced_under_concurrency() {
+        let ledger = Arc::new(InMemoryLedger::new());
+        let state = file_backed_test_state(ledger, ShareVerificationMode::Both).await;
+
+        let client = register(&state, "client@example.com", "client").await;
+        let partner = register(&state, "partner@example.com", "partner").await;
+
+        let key = generate_key();
+        let process = queries::create_process(
+            &state.pool,
+            "process-1",
+            &client.id,
+            "Pricing sheet",
+            "Q3 prices",
+            &encrypt_content("Price list", &key, &process_aad("process-1", &client.id, 1)).unwrap(),
+            &hex::encode(terms::content_sha256("Price list")),
+            &key,
+            None,
+            ProcessStatus::Active,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let rejected = share_process(
+            State(state.clone()),
+            as_user(&client),
+            Json(ShareProcessRequest {
+                process_id: process.id.clone(),
+                partner_public_key: partner.stellar_public_key.clone(),
+                client_username: Some(client.username.clone()),
+                valid_from: None,
+                expires_at: None,
+                max_accesses: Some(0),
+                zero_knowledge: false,
+            }),
+        )
+        .await;
+        assert_eq!(rejected.err().map(|e| e.status), Some(StatusCode::BAD_REQUEST));
+
+        let ResponseJson(share) = share_process(
+            State(state.clone()),
+            as_user(&client),
+            Json(ShareProcessRequest {
+                process_id: process.id.clone(),
+                partner_public_key: partner.stellar_public_key.clone(),
+                client_username: Some(client.username.clone()),
+                valid_from: None,
+                expires_at: None,
+                max_accesses: Some(3),
+                zero_knowledge: false,
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(share.max_accesses, Some(3));
+        sign_terms(&state, &process.id, &partner).await;
+
+        let handles: Vec<_> = (0..10)
+            .map(|_| {
+                let state = state.clone();
+                let actor = as_user(&partner);
+                let request = AccessProcessRequest {
+                    process_id: process.id.clone(),
+                    partner_public_key: Some(partner.stellar_public_key.clone()),
+                    partner_username: Some(partner.username.clone()),
+                };
+                tokio::spawn(async move { access_process(State(state), actor, Json(request)).await })
+            })
+            .collect();
+
+        let mut remaining = Vec::new();
+        let mut denied = 0;
+        for handle in handles {
+            match handle.await.unwrap() {
+                Ok(ResponseJson(access)) => {
+                    assert_eq!(access.content.as_deref(), Some("Price list"));
+                    remaining.push(access.remaining_accesses.unwrap());
+                }
+                Err(error) => {
+                    assert_eq!(error.code, "share_access_limit_reached");
+                    denied += 1;
+                }
+            }
+        }
+        remaining.sort();
+        assert_eq!(remaining, vec![0, 1, 2]);
+        assert_eq!(denied, 7);
+
+        let accesses: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM process_accesses WHERE process_id = ?1")
+            .bind(&process.id)
+            .fetch_one(&state.pool)
+            .await
+            .unwrap();
+        assert_eq!(accesses, 3);
+    }
+
+    #[tokio::test]
+    async fn test_zero_knowledge_share_returns_sealed_key() {
+        let ledger = Arc::new(InMemoryLedger::new());
+        let state = test_state(ledger, ShareVerificationMode::Both).await;
+
+        let client = register(&state, "client@example.com", "client").await;
+        let partner = register(&state, "partner@example.com", "partner").await;
+
+        let key = generate_key();
+        let wrapped = state.keyring.wrap_key(&key).unwrap();
+        let process = queries::create_process(
+            &state.pool,
+            "process-1",
+            &client.id,
+            "Source code escrow",
+            "Repository access",
+            &encrypt_content("Deploy keys", &key, &process_aad("process-1", &client.id, 1)).unwrap(),
+            &hex::encode(terms::content_sha256("Deploy keys")),
+            &wrapped.wrapped_key,
+            Some(&wrapped.key_id),
+            ProcessStatus::Active,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let share_request = |partner_public_key: &str| ShareProcessRequest {
+            process_id: process.id.clone(),
+            partner_public_key: partner_public_key.to_string(),
+            client_username: Some(client.username.clone()),
+            valid_from: None,
+            expires_at: None,
+            max_accesses: None,
+            zero_knowledge: true,
+        };
+
+        // The data key cannot be sealed to an invalid Stellar key
+        let rejected = share_process(State(state.clone()), as_user(&client), Json(share_request("not-a-stellar-key"))).await;
+        assert_eq!(rejected.err().map(|e| e.status), Some(StatusCode::BAD_REQUEST));
+
+        let ResponseJson(share) = share_process(State(state.clone()), as_user(&client), Json(share_request(&partner.stellar_public_key)))
+            .await
+            .unwrap();
+        assert!(share.wrapped_key.is_some());
+
+        // Serving terms and content to a zero-knowledge partner never unwraps the data key
+        sqlx::query("UPDATE processes SET encryption_key = 'unusable' WHERE id = ?1")
+            .bind(&process.id)
+            .execute(&state.pool)
+            .await
+            .unwrap();
+        let signature = sign_terms(&state, &process.id, &partner).await;
+
+        let ResponseJson(access) = access_process(
+            State(state.clone()),
+            as_user(&partner),
+            Json(AccessProcessRequest {
+                process_id: process.id.clone(),
+                partner_public_key: Some(partner.stellar_public_key.clone()),
+                partner_username: Some(partner.username.clone()),
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(access.content, None);
+        assert_eq!(access.wrapped_key, share.wrapped_key);
+
+        // Partner side: open the sealed key with the Stellar secret and decrypt locally
+        let partner_user = queries::find_user_by_id(&state.pool, &partner.id).await.unwrap().unwrap();
+        let partner_secret = state.key_provider.open(&partner_user.stellar_secret_key).await.unwrap();
+        let data_key = sealed_box::open(&access.wrapped_key.unwrap(), &partner_secret).unwrap();
+        let aad = general_purpose::STANDARD.decode(access.content_aad.unwrap()).unwrap();
+        let content = decrypt_content(&access.encrypted_content.unwrap(), &general_purpose::STANDARD.encode(data_key), &aad).unwrap();
+        assert_eq!(content, "Deploy keys");
+
+        // The signed terms cover the plaintext the partner decrypted
+        let stored = queries::find_process_by_id(&state.pool, &process.id).await.unwrap().unwrap();
+        let expected = terms::nda_terms(&stored, &client.stellar_public_key, &partner.stellar_public_key, &terms::content_sha256(&content));
+        assert_eq!(signature.terms_digest, expected.terms_digest);
+    }
+
+    #[tokio::test]
+    async fn test_data_key_rotates_on_amendment_and_zero_knowledge_revocation() {
+        use axum::extract::FromRequest;
+
+        let ledger = Arc::new(InMemoryLedger::new());
+        let state = test_state(ledger, ShareVerificationMode::Database).await;
+
+        let client = register(&state, "client@example.com", "client").await;
+        let revoked_partner = register(&state, "revoked@example.com", "partner").await;
+        let partner = register(&state, "partner@example.com", "partner").await;
+
+        let ResponseJson(process) = create_process(
+            State(state.clone()),
+            as_user(&client),
+            Json(CreateProcessRequest {
+                client_id: client.id.clone(),
+                title: "Source code escrow".to_string(),
+                description: "Repository access".to_string(),
+                confidential_content: "Deploy keys".to_string(),
+                draft: false,
+                template_id: None,
+                template_version: None,
+                template_values: Default::default(),
+            }),
+        )
+        .await
+        .unwrap();
+
+        let mut body = b"--boundary\r\nContent-Disposition: form-data; name=\"file\"; filename=\"keys.txt\"\r\n\r\n".to_vec();
+        body.extend_from_slice(b"ssh-ed25519 AAAA\r\n--boundary--\r\n");
+        let request = axum::http::Request::builder()
+            .header(header::CONTENT_TYPE, "multipart/form-data; boundary=boundary")
+            .body(Body::from(body))
+            .unwrap();
+        let multipart = Multipart::from_request(request, &()).await.unwrap();
+        let ResponseJson(uploaded) = upload_attachments(State(state.clone()), Path(process.id.clone()), as_user(&client), multipart)
+            .await
+            .unwrap();
+
+        for partner in [&revoked_partner, &partner] {
+            let ResponseJson(_share) = share_process(
+                State(state.clone()),
+                as_user(&client),
+                Json(ShareProcessRequest {
+                    process_id: process.id.clone(),
+                    partner_public_key: partner.stellar_public_key.clone(),
+                    client_username: Some(client.username.clone()),
+                    valid_from: None,
+                    expires_at: None,
+                    max_accesses: None,
+                    zero_knowledge: true,
+                }),
+            )
+            .await
+            .unwrap();
+        }
+
+        // Data key a partner's client opens from the sealed key of its share
+        async fn open_data_key(state: &AppState, process_id: &str, partner: &UserResponse) -> String {
+            let share = queries::find_process_share(&state.pool, process_id, &partner.stellar_public_key)
+                .await
+                .unwrap()
+                .unwrap();
+            let stored = queries::find_user_by_id(&state.pool, &partner.id).await.unwrap().unwrap();
+            let secret = state.key_provider.open(&stored.stellar_secret_key).await.unwrap();
+            general_purpose::STANDARD.encode(sealed_box::open(&share.wrapped_key.unwrap(), &secret).unwrap())
+        }
+        async fn decrypt_current(state: &AppState, process_id: &str, data_key: &str) -> Option<String> {
+            let process = queries::find_process_by_id(&state.pool, process_id).await.unwrap().unwrap();
+            let aad = process_aad(&process.id, &process.client_id, process.content_version);
+            decrypt_content(&process.encrypted_content, data_key, &aad).ok()
+        }
+        let revoked_key = open_data_key(&state, &process.id, &revoked_partner).await;
+        assert_eq!(decrypt_current(&state, &process.id, &revoked_key).await.as_deref(), Some("Deploy keys"));
+
+        let ResponseJson(revoked) = revoke_share(
+            State(state.clone()),
+            as_user(&client),
+            Json(RevokeShareRequest {
+                process_id: process.id.clone(),
+                partner_public_key: revoked_partner.stellar_public_key.clone(),
+                reason: "Partnership ended".to_string(),
+            }),
+        )
+        .await
+        .unwrap();
+        assert!(revoked.is_revoked());
+
+        // The remaining partner's key was re-sealed; the revoked key no longer opens the content
+        let data_key = open_data_key(&state, &process.id, &partner).await;
+        assert_ne!(data_key, revoked_key);
+        assert_eq!(decrypt_current(&state, &process.id, &data_key).await.as_deref(), Some("Deploy keys"));
+        assert_eq!(decrypt_current(&state, &process.id, &revoked_key).await, None);
+
+        let ResponseJson(revision) = update_process(
+            State(state.clone()),
+            Path(process.id.clone()),
+            as_user(&client),
+            Json(UpdateProcessRequest {
+                confidential_content: "Rotated deploy keys".to_string(),
+                change_note: None,
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(revision.version, 2);
+
+        // Amendments rotate the key again; earlier revisions stay readable under the new key
+        let amended_key = open_data_key(&state, &process.id, &partner).await;
+        assert_ne!(amended_key, data_key);
+        assert_eq!(decrypt_current(&state, &process.id, &amended_key).await.as_deref(), Some("Rotated deploy keys"));
+        assert_eq!(decrypt_current(&state, &process.id, &data_key).await, None);
+        let (_, first) = queries::find_process_revision(&state.pool, &process.id, 1).await.unwrap().unwrap();
+        let aad = process_aad(&process.id, &client.id, 1);
+        assert_eq!(decrypt_content(&first, &amended_key, &aad).unwrap(), "Deploy keys");
+
+        // The attachment's file key is stored under the current data key
+        let attachment = queries::find_process_attachment(&state.pool, &process.id, &uploaded[0].id)
+            .await
+            .unwrap()
+            .unwrap();
+        let file_key = attachments::unwrap_file_key(&attachment.file_key.unwrap(), &amended_key, &attachment.id).unwrap();
+        let stream = state.attachments
+            .open_decrypted(&attachment.id, attachments::FileKey::Stored(file_key))
+            .await
+            .unwrap();
+        let file = axum::body::to_bytes(Body::from_stream(stream), usize::MAX).await.unwrap();
+        assert_eq!(file.as_ref(), b"ssh-ed25519 AAAA");
+    }
+
+    #[tokio::test]
+    async fn test_swapped_content_is_an_integrity_error() {
+        let ledger = Arc::new(InMemoryLedger::new());
+        let state = test_state(ledger, ShareVerificationMode::Both).await;
+
+        let client = register(&state, "client@example.com", "client").await;
+        let partner = register(&state, "partner@example.com", "partner").await;
+
+        let mut processes = Vec::new();
+        for (id, content) in [("process-1", "Secret formula"), ("process-2", "Public roadmap")] {
+            let key = generate_key();
+            let wrapped = state.keyring.wrap_key(&key).unwrap();
+            let encrypted = encrypt_content(content, &key, &process_aad(id, &client.id, 1)).unwrap();
+            let process = queries::create_process(
+                &state.pool,
+                id,
+                &client.id,
+                content,
+                "",
+                &encrypted,
+                &hex::encode(terms::content_sha256(content)),
+                &wrapped.wrapped_key,
+                Some(&wrapped.key_id),
+                ProcessStatus::Active,
+                None,
+            )
+            .await
+            .unwrap();
+            processes.push(process);
+        }
+
+        let ResponseJson(_share) = share_process(
+            State(state.clone()),
+            as_user(&client),
+            Json(ShareProcessRequest {
+                process_id: "process-2".to_string(),
+                partner_public_key: partner.stellar_public_key.clone(),
+                client_username: Some(client.username.clone()),
+                valid_from: None,
+                expires_at: None,
+                max_accesses: None,
+                zero_knowledge: false,
+            }),
+        )
+        .await
+        .unwrap();
+
+        sign_terms(&state, "process-2", &partner).await;
+
+        // A database-level attacker moves the secret process's ciphertext and key into the shared one
+        sqlx::query("UPDATE processes SET encrypted_content = ?1, encryption_key = ?2 WHERE id = 'process-2'")
+            .bind(&processes[0].encrypted_content)
+            .bind(&processes[0].encryption_key)
+            .execute(&state.pool)
+            .await
+            .unwrap();
+
+        let error = access_process(
+            State(state.clone()),
+            as_user(&partner),
+            Json(AccessProcessRequest {
+                process_id: "process-2".to_string(),
+                partner_public_key: Some(partner.stellar_public_key.clone()),
+                partner_username: Some(partner.username.clone()),
+            }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error.status, StatusCode::CONFLICT);
+        assert_eq!(error.code, "content_integrity_error");
+
+        let accesses: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM process_accesses")
+            .fetch_one(&state.pool)
+            .await
+            .unwrap();
+        assert_eq!(accesses, 0);
+    }
+
+    #[tokio::test]
+    async fn test_attachment_upload_and_streaming_download() {
+        use axum::extract::FromRequest;
+
+        let ledger = Arc::new(InMemoryLedger::new());
+        let state = test_state(ledger, ShareVerificationMode::Both).await;
+
+        let client = register(&state, "client@example.com", "client").await;
+        let partner = register(&state, "partner@example.com", "partner").await;
+
+        let ResponseJson(login) = login_user(
+            State(state.clone()),
+            None,
+            HeaderMap::new(),
+            Json(LoginRequest {
+                username: "client@example.com".to_string(),
+                password: "password123".to_string(),
+            }),
+        )
+        .await
+        .unwrap();
+        let auth = auth_user(&state, &login.access_token);
+
+        let ResponseJson(process) = create_process(
+            State(state.clone()),
+            auth.clone(),
+            Json(CreateProcessRequest {
+                client_id: client.id.clone(),
+                title: "Turbine design".to_string(),
+                description: "CAD drawings".to_string(),
+                confidential_content: "See attachments".to_string(),
+                draft: false,
+                template_id: None,
+                template_version: None,
+                template_values: Default::default(),
+            }),
+        )
+        .await
+        .unwrap();
+
+        // Spans several encryption chunks
+        let file: Vec<u8> = (0..150_000u32).map(|i| (i % 253) as u8).collect();
+        let mut body = b"--boundary\r\nContent-Disposition: form-data; name=\"file\"; filename=\"../drawings.pdf\"\r\nContent-Type: application/pdf\r\n\r\n".to_vec();
+        body.extend_from_slice(&file);
+        body.extend_from_slice(b"\r\n--boundary--\r\n");
+        let request = axum::http::Request::builder()
+            .header(header::CONTENT_TYPE, "multipart/form-data; boundary=boundary")
+            .body(Body::from(body))
+            .unwrap();
+        let multipart = Multipart::from_request(request, &()).await.unwrap();
+
+        let ResponseJson(uploaded) = upload_attachments(State(state.clone()), Path(process.id.clone()), auth, multipart)
+            .await
+            .unwrap();
+        assert_eq!(uploaded.len(), 1);
+        assert_eq!(uploaded[0].filename, "drawings.pdf");
+        assert_eq!(uploaded[0].size_bytes, file.len() as i64);
+
+        let ResponseJson(_share) = share_process(
+            State(state.clone()),
+            as_user(&client),
+            Json(ShareProcessRequest {
+                process_id: process.id.clone(),
+                partner_public_key: partner.stellar_public_key.clone(),
+                client_username: Some(client.username.clone()),
+                valid_from: None,
+                expires_at: None,
+                max_accesses: Some(2),
+                zero_knowledge: false,
+            }),
+        )
+        .await
+        .unwrap();
+        sign_terms(&state, &process.id, &partner).await;
+
+        let ResponseJson(access) = access_process(
+            State(state.clone()),
+            as_user(&partner),
+            Json(AccessProcessRequest {
+                process_id: process.id.clone(),
+                partner_public_key: Some(partner.stellar_public_key.clone()),
+                partner_username: Some(partner.username.clone()),
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(access.attachments.len(), 1);
+
+        let download = || {
+            download_attachment(
+                State(state.clone()),
+                Path((process.id.clone(), uploaded[0].id.clone())),
+                as_user(&partner),
+                Query(AttachmentDownloadQuery {
+                    partner_username: Some(partner.username.clone()),
+                    partner_public_key: Some(partner.stellar_public_key.clone()),
+                }),
+            )
+        };
+
+        let response = download().await.unwrap();
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/pdf");
+        assert_eq!(
+            response.headers()[header::CONTENT_DISPOSITION],
+            "attachment; filename=\"drawings.pdf\"; filename*=UTF-8''drawings.pdf"
+        );
+        let downloaded = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(downloaded.as_ref(), file.as_slice());
+
+        // The download was logged and used up the second view
+        let accesses = queries::list_process_accesses_by_client(&state.pool, &client.id).await.unwrap();
+        assert_eq!(accesses.iter().filter(|a| a.attachment_id.as_deref() == Some(uploaded[0].id.as_str())).count(), 1);
+        let denied = download().await.unwrap_err();
+        assert_eq!(denied.code, "share_access_limit_reached");
+    }
+
+    #[tokio::test]
+    async fn test_process_amendments_keep_revision_history() {
+        let ledger = Arc::new(InMemoryLedger::new());
+        let state = test_state(ledger, ShareVerificationMode::Both).await;
+
+        let client = register(&state, "client@example.com", "client").await;
+        let partner = register(&state, "partner@example.com", "partner").await;
+
+        let ResponseJson(login) = login_user(
+            State(state.clone()),
+            None,
+            HeaderMap::new(),
+            Json(LoginRequest {
+                username: "client@example.com".to_string(),
+                password: "password123".to_string(),
+            }),
+        )
+        .await
+        .unwrap();
+        let auth = auth_user(&state, &login.access_token);
+
+        let ResponseJson(process) = create_process(
+            State(state.clone()),
+            auth.clone(),
+            Json(CreateProcessRequest {
+                client_id: client.id.clone(),
+                title: "Supply agreement".to_string(),
+                description: "Terms".to_string(),
+                confidential_content: "Confidential for 2 years".to_string(),
+                draft: false,
+                template_id: None,
+                template_version: None,
+                template_values: Default::default(),
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(process.content_version, 1);
+
+        let ResponseJson(_share) = share_process(
+            State(state.clone()),
+            as_user(&client),
+            Json(ShareProcessRequest {
+                process_id: process.id.clone(),
+                partner_public_key: partner.stellar_public_key.clone(),
+                client_username: Some(client.username.clone()),
+                valid_from: None,
+                expires_at: None,
+                max_accesses: None,
+                zero_knowledge: false,
+            }),
+        )
+        .await
+        .unwrap();
+
+        let access_request = || AccessProcessRequest {
+            process_id: process.id.clone(),
+            partner_public_key: Some(partner.stellar_public_key.clone()),
+            partner_username: Some(partner.username.clone()),
+        };
+        sign_terms(&state, &process.id, &partner).await;
+        let ResponseJson(first) = access_process(State(state.clone()), as_user(&partner), Json(access_request())).await.unwrap();
+        assert_eq!((first.content.as_deref(), first.content_version), (Some("Confidential for 2 years"), 1));
+
+        let ResponseJson(revision) = update_process(
+            State(state.clone()),
+            Path(process.id.clone()),
+            auth.clone(),
+            Json(UpdateProcessRequest {
+                confidential_content: "Confidential for 5 years".to_string(),
+                change_note: Some("Extended term".to_string()),
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!((revision.version, revision.author_id.as_str()), (2, client.id.as_str()));
+
+        // Amended terms must be signed again; partners then see the amended
+        // content, and each access records the revision seen
+        let unsigned = access_process(State(state.clone()), as_user(&partner), Json(access_request())).await.unwrap_err();
+        assert_eq!((unsigned.status, unsigned.code.as_str()), (StatusCode::FORBIDDEN, "terms_not_signed"));
+        sign_terms(&state, &process.id, &partner).await;
+        let ResponseJson(second) = access_process(State(state.clone()), as_user(&partner), Json(access_request())).await.unwrap();
+        assert_eq!((second.content.as_deref(), second.content_version), (Some("Confidential for 5 years"), 2));
+
+        let accesses = queries::list_process_accesses_by_client(&state.pool, &client.id).await.unwrap();
+        let mut seen: Vec<_> = accesses.iter().map(|access| access.content_version.unwrap()).collect();
+        seen.sort();
+        assert_eq!(seen, vec![1, 2]);
+
+        let ResponseJson(history) = list_process_revisions(State(state.clone()), Path(process.id.clone()), auth.clone())
+            .await
+            .unwrap();
+        let summary: Vec<_> = history
+            .iter()
+            .map(|revision| (revision.version, revision.change_note.as_deref(), revision.access_count))
+            .collect();
+        assert_eq!(summary, vec![(2, Some("Extended term"), 1), (1, None, 1)]);
+        assert!(history.iter().all(|revision| revision.content.is_none()));
+
+        // The owner can show exactly what was seen under the first revision
+        let ResponseJson(original) = get_process_revision(
+            State(state.clone()),
+            Path((process.id.clone(), 1)),
+            auth.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(original.content.as_deref(), Some("Confidential for 2 years"));
+        let missing = get_process_revision(State(state.clone()), Path((process.id.clone(), 3)), auth.clone())
+            .await
+            .unwrap_err();
+        assert_eq!(missing.status, StatusCode::NOT_FOUND);
+
+        // Only the owner can amend the process
+        let other = register(&state, "other@example.com", "client").await;
+        let not_owner = update_process(
+            State(state.clone()),
+            Path(process.id.clone()),
+            AuthUser(jwt::Claims::new(other.id.clone(), other.username.clone(), vec!["client".to_string()], 15)),
+            Json(UpdateProcessRequest {
+                confidential_content: "Tampered".to_string(),
+                change_note: None,
+            }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(not_owner.status, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_process_lifecycle_controls_sharing_and_access() {
+        let ledger = Arc::new(InMemoryLedger::new());
+        let state = test_state(ledger, ShareVerificationMode::Both).await;
+        let client = register(&state, "client@example.com", "client").await;
+        let partner = register(&state, "partner@example.com", "partner").await;
+
+        let ResponseJson(login) = login_user(
+            State(state.clone()),
+            None,
+            HeaderMap::new(),
+            Json(LoginRequest {
+                username: "client@example.com".to_string(),
+                password: "password123".to_string(),
+            }),
+        )
+        .await
+        .unwrap();
+        let auth = auth_user(&state, &login.access_token);
+
+        let ResponseJson(process) = create_process(
+            State(state.clone()),
+            auth.clone(),
+            Json(CreateProcessRequest {
+                client_id: client.id.clone(),
+                title: "Joint venture".to_string(),
+                description: "Terms".to_string(),
+                confidential_content: "Draft terms".to_string(),
+                draft: true,
+                template_id: None,
+                template_version: None,
+                template_values: Default::default(),
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(process.status, ProcessStatus::Draft);
+
+        let share_request = || ShareProcessRequest {
+            process_id: process.id.clone(),
+            partner_public_key: partner.stellar_public_key.clone(),
+            client_username: Some(client.username.clone()),
+            valid_from: None,
+            expires_at: None,
+            max_accesses: None,
+            zero_knowledge: false,
+        };
+        let access_request = || AccessProcessRequest {
+            process_id: process.id.clone(),
+            partner_public_key: Some(partner.stellar_public_key.clone()),
+            partner_username: Some(partner.username.clone()),
+        };
+        let transition = |status: ProcessStatus, reason: &str| {
+            transition_process(
+                State(state.clone()),
+                Path(process.id.clone()),
+                auth.clone(),
+                Json(TransitionProcessRequest { status, reason: reason.to_string() }),
+            )
+        };
+
+        // Drafts cannot be shared, and the lifecycle cannot be skipped
+        let denied = share_process(State(state.clone()), as_user(&client), Json(share_request())).await;
+        assert_eq!(denied.err().map(|e| e.status), Some(StatusCode::CONFLICT));
+        let skipped = transition(ProcessStatus::Closed, "Done").await.unwrap_err();
+        assert_eq!((skipped.status, skipped.code.as_str()), (StatusCode::CONFLICT, "invalid_status_transition"));
+        let unexplained = transition(ProcessStatus::Active, "  ").await.unwrap_err();
+        assert_eq!((unexplained.status, unexplained.code.as_str()), (StatusCode::BAD_REQUEST, "reason_required"));
+
+        assert_eq!(transition(ProcessStatus::Active, "Terms agreed").await.unwrap().to_status, ProcessStatus::Active);
+        let ResponseJson(_share) = share_process(State(state.clone()), as_user(&client), Json(share_request())).await.unwrap();
+        sign_terms(&state, &process.id, &partner).await;
+        let ResponseJson(access) = access_process(State(state.clone()), as_user(&partner), Json(access_request())).await.unwrap();
+        assert_eq!(access.content.as_deref(), Some("Draft terms"));
+
+        // Suspension pauses partner access without touching the share
+        assert_eq!(transition(ProcessStatus::Suspended, "Negotiations on hold").await.unwrap().to_status, ProcessStatus::Suspended);
+        let denied = access_process(State(state.clone()), as_user(&partner), Json(access_request())).await.unwrap_err();
+        assert_eq!((denied.status, denied.code.as_str()), (StatusCode::FORBIDDEN, "process_not_active"));
+        assert_eq!(transition(ProcessStatus::Active, "Negotiations resumed").await.unwrap().to_status, ProcessStatus::Active);
+        let ResponseJson(access) = access_process(State(state.clone()), as_user(&partner), Json(access_request())).await.unwrap();
+        assert_eq!(access.content.as_deref(), Some("Draft terms"));
+
+        // Closing stops partner access for good and freezes the content
+        assert_eq!(transition(ProcessStatus::Closed, "Agreement ended").await.unwrap().to_status, ProcessStatus::Closed);
+        let denied = access_process(State(state.clone()), as_user(&partner), Json(access_request())).await.unwrap_err();
+        assert_eq!((denied.status, denied.code.as_str()), (StatusCode::FORBIDDEN, "process_not_active"));
+        let frozen = update_process(
+            State(state.clone()),
+            Path(process.id.clone()),
+            auth.clone(),
+            Json(UpdateProcessRequest { confidential_content: "Late change".to_string(), change_note: None }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!((frozen.status, frozen.code.as_str()), (StatusCode::CONFLICT, "process_not_editable"));
+        let reopened = transition(ProcessStatus::Active, "Reopen").await.unwrap_err();
+        assert_eq!(reopened.code, "invalid_status_transition");
+        assert_eq!(transition(ProcessStatus::Archived, "Retention period started").await.unwrap().to_status, ProcessStatus::Archived);
+
+        // Every transition is recorded with its actor and reason, starting with the creation
+        let ResponseJson(history) = list_process_transitions(State(state.clone()), Path(process.id.clone()), auth.clone())
+            .await
+            .unwrap();
+        let steps: Vec<_> = history.iter().map(|t| (t.from_status, t.to_status, t.reason.as_str())).collect();
+        assert_eq!(steps, vec![
+            (None, ProcessStatus::Draft, "Process created"),
+            (Some(ProcessStatus::Draft), ProcessStatus::Active, "Terms agreed"),
+            (Some(ProcessStatus::Active), ProcessStatus::Suspended, "Negotiations on hold"),
+            (Some(ProcessStatus::Suspended), ProcessStatus::Active, "Negotiations resumed"),
+            (Some(ProcessStatus::Active), ProcessStatus::Closed, "Agreement ended"),
+            (Some(ProcessStatus::Closed), ProcessStatus::Archived, "Retention period started"),
+        ]);
+        assert!(history.iter().all(|t| t.actor_id == client.id));
+    }
+
+    #[tokio::test]
+    async fn test_access_requires_signed_terms() {
+        let ledger = Arc::new(InMemoryLedger::new());
+        let state = test_state(ledger.clone(), ShareVerificationMode::Both).await;
+        let client = register(&state, "client@example.com", "client").await;
+        let partner = register(&state, "partner@example.com", "partner").await;
+
+        let ResponseJson(login) = login_user(
+            State(state.clone()),
+            None,
+            HeaderMap::new(),
+            Json(LoginRequest {
+                username: "client@example.com".to_string(),
+                password: "password123".to_string(),
+            }),
+        )
+        .await
+        .unwrap();
+        let auth = auth_user(&state, &login.access_token);
+
+        let ResponseJson(process) = create_process(
+            State(state.clone()),
+            auth.clone(),
+            Json(CreateProcessRequest {
+                client_id: client.id.clone(),
+                title: "Acquisition talks".to_string(),
+                description: "Due diligence".to_string(),
+                confidential_content: "Valuation model".to_string(),
+                draft: false,
+                template_id: None,
+                template_version: None,
+                template_values: Default::default(),
+            }),
+        )
+        .await
+        .unwrap();
+        let ResponseJson(_share) = share_process(
+            State(state.clone()),
+            as_user(&client),
+            Json(ShareProcessRequest {
+                process_id: process.id.clone(),
+                partner_public_key: partner.stellar_public_key.clone(),
+                client_username: Some(client.username.clone()),
+                valid_from: None,
+                expires_at: None,
+                max_accesses: None,
+                zero_knowledge: false,
+            }),
+        )
+        .await
+        .unwrap();
+
+        let access_request = || AccessProcessRequest {
+            process_id: process.id.clone(),
+            partner_public_key: Some(partner.stellar_public_key.clone()),
+            partner_username: Some(partner.username.clone()),
+        };
+        let denied = access_process(State(state.clone()), as_user(&partner), Json(access_request())).await.unwrap_err();
+        assert_eq!((denied.status, denied.code.as_str()), (StatusCode::FORBIDDEN, "terms_not_signed"));
+
+        let ResponseJson(terms) = get_process_terms(
+            State(state.clone()),
+            Path(process.id.clone()),
+            as_user(&partner),
+            Query(TermsQuery {
+                partner_username: Some(partner.username.clone()),
+                partner_public_key: Some(partner.stellar_public_key.clone()),
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(terms.owner_public_key, client.stellar_public_key);
+        assert_eq!(terms.content_sha256, hex::encode(terms::content_sha256("Valuation model")));
+
+        let stored = queries::find_user_by_id(&state.pool, &partner.id).await.unwrap().unwrap();
+        let partner_secret = state.key_provider.open(&stored.stellar_secret_key).await.unwrap();
+        let digest = commitment::decode_hash(&terms.terms_digest).unwrap();
+        let sign = |terms_digest: &str, signature: String| {
+            sign_process_terms(
+                State(state.clone()),
+                Path(process.id.clone()),
+                as_user(&partner),
+                Json(SignTermsRequest {
+                    partner_username: Some(partner.username.clone()),
+                    partner_public_key: Some(partner.stellar_public_key.clone()),
+                    terms_digest: terms_digest.to_string(),
+                    signature,
+                    anchor: true,
+                }),
+            )
+        };
+
+        // Signatures by another key or of other terms are rejected
+        let other = crate::stellar_real::StellarClient::generate_keypair().unwrap();
+        let forged = sign(&terms.terms_digest, terms::sign(&other.secret_key, &digest).unwrap()).await.unwrap_err();
+        assert_eq!((forged.status, forged.code.as_str()), (StatusCode::BAD_REQUEST, "invalid_signature"));
+        let stale_digest = [7u8; 32];
+        let stale = sign(&hex::encode(stale_digest), terms::sign(&partner_secret, &stale_digest).unwrap()).await.unwrap_err();
+        assert_eq!((stale.status, stale.code.as_str()), (StatusCode::CONFLICT, "terms_outdated"));
+
+        // A valid signature is stored and anchored by the partner's account
+        let signature = terms::sign(&partner_secret, &digest).unwrap();
+        let ResponseJson(signed) = sign(&terms.terms_digest, signature.clone()).await.unwrap();
+        assert_eq!((signed.content_version, signed.terms_digest.as_str()), (1, terms.terms_digest.as_str()));
+        let anchor = ledger.transactions().pop().unwrap();
+        assert_eq!(signed.anchor_transaction_hash.as_deref(), Some(anchor.hash.as_str()));
+        assert_eq!((anchor.source_account, anchor.destination_account), (partner.stellar_public_key.clone(), client.stellar_public_key.clone()));
+        let raw_signature = general_purpose::STANDARD.decode(&signature).unwrap();
+        assert_eq!(anchor.memo, LedgerMemo::Hash(commitment::signature_anchor(&digest, &raw_signature)));
+
+        // Signing again is idempotent and does not anchor twice
+        let ResponseJson(again) = sign(&terms.terms_digest, signature).await.unwrap();
+        assert_eq!(again.id, signed.id);
+        assert_eq!(ledger.transactions().len(), 2);
+
+        let ResponseJson(access) = access_process(State(state.clone()), as_user(&partner), Json(access_request())).await.unwrap();
+        assert_eq!(access.content.as_deref(), Some("Valuation model"));
+
+        let ResponseJson(signatures) = list_terms_signatures(State(state.clone()), Path(process.id.clone()), auth.clone())
+            .await
+            .unwrap();
+        assert_eq!(signatures.len(), 1);
+        assert_eq!(signatures[0].partner_id, partner.id);
+    }
+
+    #[tokio::test]
+    async fn test_actors_are_derived_from_the_access_token() {
+        let ledger = Arc::new(InMemoryLedger::new());
+        let state = test_state(ledger.clone(), ShareVerificationMode::Both).await;
+        let client = register(&state, "client@example.com", "client").await;
+        let intruder = register(&state, "intruder@example.com", "client").await;
+        let partner = register(&state, "partner@example.com", "partner").await;
+        let other_partner = register(&state, "other-partner@example.com", "partner").await;
+
+        let ResponseJson(process) = create_process(
+            State(state.clone()),
+            as_user(&client),
+            Json(CreateProcessRequest {
+                client_id: client.id.clone(),
+                title: "Joint venture".to_string(),
+                description: "Market entry".to_string(),
+                confidential_content: "Pricing strategy".to_string(),
+                draft: false,
+                template_id: None,
+                template_version: None,
+                template_values: Default::default(),
+            }),
+        )
+        .await
+        .unwrap();
+
+        let share_request = |client_username: Option<String>| ShareProcessRequest {
+            process_id: process.id.clone(),
+            partner_public_key: partner.stellar_public_key.clone(),
+            client_username,
+            valid_from: None,
+            expires_at: None,
+            max_accesses: None,
+            zero_knowledge: false,
+        };
+
+        // Another client cannot share the process, even naming its owner
+        let denied = share_process(State(state.clone()), as_user(&intruder), Json(share_request(None)))
+            .await
+            .unwrap_err();
+        assert_eq!((denied.status, denied.code.as_str()), (StatusCode::FORBIDDEN, "not_process_owner"));
+        let denied = share_process(
+            State(state.clone()),
+            as_user(&intruder),
+            Json(share_request(Some(client.username.clone()))),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!((denied.status, denied.code.as_str()), (StatusCode::FORBIDDEN, "actor_mismatch"));
+        assert!(ledger.transactions().is_empty());
+
+        // The owner shares without naming themselves
+        let ResponseJson(_share) = share_process(State(state.clone()), as_user(&client), Json(share_request(None)))
+            .await
+            .unwrap();
+        sign_terms(&state, &process.id, &partner).await;
+
+        // Partners cannot claim another partner's key or username
+        let claimed = |partner_username: Option<String>, partner_public_key: Option<String>| AccessProcessRequest {
+            process_id: process.id.clone(),
+            partner_public_key,
+            partner_username,
+        };
+        let denied = access_process(
+            State(state.clone()),
+            as_user(&other_partner),
+            Json(claimed(None, Some(partner.stellar_public_key.clone()))),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!((denied.status, denied.code.as_str()), (StatusCode::FORBIDDEN, "partner_key_mismatch"));
+        let denied = access_process(
+            State(state.clone()),
+            as_user(&other_partner),
+            Json(claimed(Some(partner.username.clone()), None)),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!((denied.status, denied.code.as_str()), (StatusCode::FORBIDDEN, "actor_mismatch"));
+        let denied = access_process(State(state.clone()), as_user(&other_partner), Json(claimed(None, None)))
+            .await
+            .unwrap_err();
+        assert_eq!((denied.status, denied.code.as_str()), (StatusCode::FORBIDDEN, "share_not_found"));
+
+        // The partner the process was shared with needs no identifying fields
+        let ResponseJson(access) = access_process(State(state.clone()), as_user(&partner), Json(claimed(None, None)))
+            .await
+            .unwrap();
+        assert_eq!(access.content.as_deref(), Some("Pricing strategy"));
+
+        // Notifications are limited to the authenticated client's processes
+        let denied = get_notifications(
+            State(state.clone()),
+            as_user(&intruder),
+            Query(ListProcessesQuery { client_id: Some(client.id.clone()) }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!((denied.status, denied.code.as_str()), (StatusCode::FORBIDDEN, "actor_mismatch"));
+        let ResponseJson(notifications) = get_notifications(
+            State(state.clone()),
+            as_user(&intruder),
+            Query(ListProcessesQuery { client_id: None }),
+        )
+        .await
+        .unwrap();
+        assert!(notifications.is_empty());
+        let ResponseJson(notifications) = get_notifications(
+            State(state.clone()),
+            as_user(&client),
+            Query(ListProcessesQuery { client_id: None }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(notifications.len(), 1);
+
+        // Process listings are limited to the authenticated client's processes
+        let denied = list_processes(
+            State(state.clone()),
+            as_user(&intruder),
+            Query(ListProcessesQuery { client_id: Some(client.id.clone()) }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!((denied.status, denied.code.as_str()), (StatusCode::FORBIDDEN, "actor_mismatch"));
+        let ResponseJson(processes) = list_processes(
+            State(state.clone()),
+            as_user(&client),
+            Query(ListProcessesQuery { client_id: Some(client.id.clone()) }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(processes.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_processes_rendered_from_versioned_templates() {
+        let ledger = Arc::new(InMemoryLedger::new());
+        let state = test_state(ledger, ShareVerificationMode::Both).await;
+
+        let client = register(&state, "client@example.com", "client").await;
+        let other_client = register(&state, "other@example.com", "client").await;
+
+        let mut users = HashMap::new();
+        for username in ["client@example.com", "other@example.com"] {
+            let ResponseJson(login) = login_user(
+                State(state.clone()),
+                None,
+                HeaderMap::new(),
+                Json(LoginRequest {
+                    username: username.to_string(),
+                    password: "password123".to_string(),
+                }),
+            )
+            .await
+            .unwrap();
+            users.insert(username, auth_user(&state, &login.access_token));
+        }
+        let owner = users["client@example.com"].clone();
+        let other = users["other@example.com"].clone();
+
+        let placeholders: Vec<TemplatePlaceholder> = serde_json::from_value(serde_json::json!([
+            { "name": "party", "kind": "text" },
+            { "name": "effective_date", "kind": "date" },
+            { "name": "term_months", "kind": "integer" },
+            { "name": "jurisdiction", "kind": "choice", "options": ["Brazil", "Delaware"] }
+        ]))
+        .unwrap();
+        let body = "NDA with {{party}} from {{effective_date}} for {{term_months}} months under {{jurisdiction}} law.";
+
+        // Undeclared placeholders are rejected
+        let invalid = create_template(
+            State(state.clone()),
+            owner.clone(),
+            Json(CreateTemplateRequest {
+                name: "Mutual NDA".to_string(),
+                description: String::new(),
+                body: format!("{} Witness: {{{{witness}}}}", body),
+                placeholders: placeholders.clone(),
+            }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!((invalid.status, invalid.code.as_str()), (StatusCode::BAD_REQUEST, "invalid_template"));
+
+        let ResponseJson(template) = create_template(
+            State(state.clone()),
+            owner.clone(),
+            Json(CreateTemplateRequest {
+                name: "Mutual NDA".to_string(),
+                description: "Standard terms".to_string(),
+                body: body.to_string(),
+                placeholders: placeholders.clone(),
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(template.version, 1);
+
+        let denied = get_template(State(state.clone()), Path(template.id.clone()), other.clone()).await.unwrap_err();
+        assert_eq!(denied.status, StatusCode::FORBIDDEN);
+
+        let request = |version: Option<i64>, values: serde_json::Value| CreateProcessRequest {
+            client_id: client.id.clone(),
+            title: "Supplier NDA".to_string(),
+            description: "Acme".to_string(),
+            confidential_content: String::new(),
+            draft: false,
+            template_id: Some(template.id.clone()),
+            template_version: version,
+            template_values: serde_json::from_value(values).unwrap(),
+        };
+        let values = serde_json::json!({
+            "party": "Acme Corp",
+            "effective_date": "2026-11-01",
+            "term_months": 24,
+            "jurisdiction": "Delaware"
+        });
+
+        // Values are validated against the placeholder types before anything is stored
+        let invalid = create_process(
+            State(state.clone()),
+            owner.clone(),
+            Json(request(None, serde_json::json!({ "party": "Acme Corp", "term_months": "two years" }))),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!((invalid.status, invalid.code.as_str()), (StatusCode::BAD_REQUEST, "invalid_template_values"));
+        assert!(invalid.message.contains("term_months"));
+        assert!(queries::list_processes_by_client(&state.pool, &client.id).await.unwrap().is_empty());
+
+        let conflicting = CreateProcessRequest {
+            confidential_content: "Free-form".to_string(),
+            ..request(None, values.clone())
+        };
+        let conflict = create_process(State(state.clone()), owner.clone(), Json(conflicting)).await.unwrap_err();
+        assert_eq!(conflict.code, "content_conflict");
+
+        let ResponseJson(first) = create_process(State(state.clone()), owner.clone(), Json(request(None, values.clone())))
+            .await
+            .unwrap();
+        assert_eq!((first.template_id.as_deref(), first.template_version), (Some(template.id.as_str()), Some(1)));
+
+        let ResponseJson(revision) = get_process_revision(State(state.clone()), Path((first.id.clone(), 1)), owner.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            revision.content.as_deref(),
+            Some("NDA with Acme Corp from 2026-11-01 for 24 months under Delaware law.")
+        );
+
+        // Updating the template creates a new version; older versions stay usable
+        let ResponseJson(updated) = update_template(
+            State(state.clone()),
+            Path(template.id.clone()),
+            owner.clone(),
+            Json(UpdateTemplateRequest {
+                name: None,
+                description: None,
+                body: format!("{} Confidential.", body),
+                placeholders: placeholders.clone(),
+                change_note: Some("Added confidentiality clause".to_string()),
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!((updated.version, updated.name.as_str()), (2, "Mutual NDA"));
+
+        let ResponseJson(versions) = list_template_versions(State(state.clone()), Path(template.id.clone()), owner.clone())
+            .await
+            .unwrap();
+        assert_eq!(versions.iter().map(|v| v.version).collect::<Vec<_>>(), vec![2, 1]);
+
+        let ResponseJson(second) = create_process(State(state.clone()), owner.clone(), Json(request(None, values.clone())))
+            .await
+            .unwrap();
+        assert_eq!(second.template_version, Some(2));
+        let ResponseJson(pinned) = create_process(State(state.clone()), owner.clone(), Json(request(Some(1), values.clone())))
+            .await
+            .unwrap();
+        assert_eq!(pinned.template_version, Some(1));
+        let missing = create_process(State(state.clone()), owner.clone(), Json(request(Some(3), values.clone())))
+            .await
+            .unwrap_err();
+        assert_eq!(missing.code, "template_version_not_found");
+
+        // Other clients cannot render someone else's template
+        let foreign = CreateProcessRequest {
+            client_id: other_client.id.clone(),
+            ..request(None, values.clone())
+        };
+        let denied = create_process(State(state.clone()), other.clone(), Json(foreign)).await.unwrap_err();
+        assert_eq!(denied.status, StatusCode::FORBIDDEN);
+
+        // Deleted templates are archived: no longer listed or usable, but still readable
+        let status = delete_template(State(state.clone()), Path(template.id.clone()), owner.clone()).await.unwrap();
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let ResponseJson(listed) = list_templates(State(state.clone()), owner.clone()).await.unwrap();
+        assert!(listed.is_empty());
+        let archived = create_process(State(state.clone()), owner.clone(), Json(request(None, values))).await.unwrap_err();
+        assert_eq!((archived.status, archived.code.as_str()), (StatusCode::CONFLICT, "template_archived"));
+        let ResponseJson(kept) = get_template(State(state.clone()), Path(template.id.clone()), owner).await.unwrap();
+        assert!(kept.archived_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_certificate_lists_share_and_accesses_and_verifies_offline() {
+        let ledger = Arc::new(InMemoryLedger::new());
+        let state = test_state(ledger.clone(), ShareVerificationMode::Both).await;
+        let client = register(&state, "client@example.com", "client").await;
+        let partner = register(&state, "partner@example.com", "partner").await;
+
+        let login = |username: &str| {
+            login_user(
+                State(state.clone()),
+                None,
+                HeaderMap::new(),
+                Json(LoginRequest {
+                    username: username.to_string(),
+                    password: "password123".to_string(),
+                }),
+            )
+        };
+        let ResponseJson(client_login) = login("client@example.com").await.unwrap();
+        let auth = auth_user(&state, &client_login.access_token);
+
+        let ResponseJson(process) = create_process(
+            State(state.clone()),
+            auth.clone(),
+            Json(CreateProcessRequest {
+                client_id: client.id.clone(),
+                title: "Supplier NDA".to_string(),
+                description: "Pricing".to_string(),
+                confidential_content: "Price list".to_string(),
+                draft: false,
+                template_id: None,
+                template_version: None,
+                template_values: Default::default(),
+            }),
+        )
+        .await
+        .unwrap();
+        let ResponseJson(share) = share_process(
+            State(state.clone()),
+            as_user(&client),
+            Json(ShareProcessRequest {
+                process_id: process.id.clone(),
+                partner_public_key: partner.stellar_public_key.clone(),
+                client_username: Some(client.username.clone()),
+                valid_from: None,
+                expires_at: None,
+                max_accesses: None,
+                zero_knowledge: false,
+            }),
+        )
+        .await
+        .unwrap();
+        assert!(share.stellar_ledger.is_some());
+        sign_terms(&state, &process.id, &partner).await;
+        let ResponseJson(_access) = access_process(
+            State(state.clone()),
+            as_user(&partner),
+            Json(AccessProcessRequest {
+                process_id: process.id.clone(),
+                partner_public_key: Some(partner.stellar_public_key.clone()),
+                partner_username: Some(partner.username.clone()),
+            }),
+        )
+        .await
+        .unwrap();
+
+        // Only the owner gets the certificate
+        let ResponseJson(partner_login) = login("partner@example.com").await.unwrap();
+        let partner_auth = auth_user(&state, &partner_login.access_token);
+        let denied = get_process_certificate(State(state.clone()), Path(process.id.clone()), partner_auth)
+            .await
+            .unwrap_err();
+        assert_eq!(denied.status, StatusCode::FORBIDDEN);
+
+        let response = get_process_certificate(State(state.clone()), Path(process.id.clone()), auth)
+            .await
+            .unwrap();
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/pdf");
+        let pdf = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+        let ResponseJson(signing_key) = get_certificate_signing_key(State(state.clone())).await;
+        crate::certificate::verify_pdf(&pdf, &signing_key.public_key).unwrap();
+
+        // Text is written as hex strings in the content streams
+        let text = |value: &str| hex::encode_upper(value);
+        let contains = |value: &str| pdf.windows(text(value).len()).any(|window| window == text(value).as_bytes());
+        assert!(contains(&client.stellar_public_key));
+        assert!(contains(&partner.stellar_public_key));
+        assert!(contains(&share.stellar_transaction_hash));
+        assert!(contains(&format!("Ledger: {}", share.stellar_ledger.unwrap())));
+        assert!(contains(&hex::encode(terms::content_sha256("Price list"))));
+        assert!(contains(&format!("{} - content version 1", partner.name)));
+    }
+
+    #[tokio::test]
+    async fn test_route_guards_deny_with_json_bodies() {
+        use axum::{middleware, routing::{get, post}, Router};
+        use tower::Service;
+
+        let ledger = Arc::new(InMemoryLedger::new());
+        let state = test_state(ledger, ShareVerificationMode::Both).await;
+        register(&state, "partner@example.com", "partner").await;
+        let ResponseJson(login) = login_user(
+            State(state.clone()),
+            None,
+            HeaderMap::new(),
+            Json(LoginRequest {
+                username: "partner@example.com".to_string(),
+                password: "password123".to_string(),
+            }),
+        )
+        .await
+        .unwrap();
+
+        // Same path, different requirements per method, as in main.rs
+        let app = Router::new()
+            .route("/api/processes", get(list_processes))
+            .route_layer(middleware::from_fn_with_state(state.clone(), crate::guards::authenticated))
+            .merge(
+                Router::new()
+                    .route("/api/processes", post(create_process))
+                    .route_layer(middleware::from_fn_with_state(state.clone(), crate::guards::client_only)),
+            )
+            .with_state(state.clone());
+
+        let send = |method: &str, token: Option<&str>| {
+            let mut request = axum::http::Request::builder()
+                .method(method)
+                .uri("/api/processes")
+                .header(header::CONTENT_TYPE, "application/json");
+            if let Some(token) = token {
+                request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+            }
+            app.clone().call(request.body(Body::from("{}")).unwrap())
+        };
+        let body = |response: Response| async move {
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            serde_json::from_slice::<serde_json::Value>(&bytes).unwrap()
+        };
+
+        let missing = send("GET", None).await.unwrap();
+        assert_eq!(missing.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            body(missing).await,
+            serde_json::json!({ "error": "unauthorized", "message": "Missing Authorization header" })
+        );
+
+        let listed = send("GET", Some(&login.access_token)).await.unwrap();
+        assert_eq!(listed.status(), StatusCode::OK);
+
+        // A partner token is authenticated but lacks the client role
+        let wrong_role = send("POST", Some(&login.access_token)).await.unwrap();
+        assert_eq!(wrong_role.status(), StatusCode::FORBIDDEN);
+        assert_eq!(body(wrong_role).await["error"], "insufficient_role");
+
+        let claims = jwt::validate_token(&login.access_token, &state.jwt_secret).unwrap();
+        state.token_blacklist.revoke(&claims.jti, claims.exp).await;
+        let revoked = send("GET", Some(&login.access_token)).await.unwrap();
+        assert_eq!(revoked.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(body(revoked).await["message"], "Token has been revoked");
+    }
+}
@@ -21,6 +21,15 @@
 //! - `iat` (Issued At): Token creation timestamp
 //! - `exp` (Expiration): Token expiration timestamp
 //! - `jti` (JWT ID): Unique token identifier for revocation
+//! - `fam` (Family): Refresh token family of the login the token descends from
+//! 
+//! ## Refresh Token Families
+//! 
+//! Every login starts a family, and each refresh rotates the refresh token
+//! into a child of the previous one (recorded in the `refresh_tokens` table).
+//! Presenting a refresh token that was already rotated means it was replayed,
+//! so the whole family is revoked: its ID is added to the blacklist, which
+//! also rejects the access tokens carrying it.
 //! 
 //! ## Security Best Practices
 //! 
@@ -28,17 +37,20 @@
 //! - Secret key must be at least 32 characters
 //! - Access tokens expire after 15 minutes
 //! - Refresh tokens expire after 7 days
-//! - Revoked tokens are stored in memory blacklist
+//! - Revoked tokens are stored in the `revoked_tokens` table, with an in-memory cache
 //! - All tokens are validated on each request
 
 use chrono::{Duration, Utc};
 use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use utoipa::ToSchema;
 
+use crate::database::queries;
+
 /// JWT Claims structure containing user information and token metadata.
 /// 
 /// This structure is embedded in all JWT tokens and validated on each request.
@@ -61,6 +73,11 @@ pub struct Claims {
     
     /// JWT ID - unique identifier for token revocation
     pub jti: String,
+
+    /// Refresh token family the token was issued in (absent on tokens issued
+    /// outside a login, e.g. by older versions)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fam: Option<String>,
 }
 
 impl Claims {
@@ -100,16 +117,39 @@ impl Claims {
             iat: now.timestamp(),
             exp: expiration.timestamp(),
             jti: uuid::Uuid::new_v4().to_string(),
+            fam: None,
         }
     }
+
+    /// Place the token in a refresh token family.
+    pub fn in_family(mut self, family_id: &str) -> Self {
+        self.fam = Some(family_id.to_string());
+        self
+    }
+
+    /// Checks whether the token grants a role (e.g. `"client"` or `"partner"`).
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|r| r == role)
+    }
 }
 
 /// Token blacklist for managing revoked tokens.
 /// 
-/// This structure maintains an in-memory map of revoked JWT IDs with their expiration timestamps.
+/// This structure maintains a map of revoked JWT IDs with their expiration timestamps.
 /// Tokens are checked against this blacklist during validation.
 /// Expired tokens are automatically cleaned up periodically.
 /// 
+/// ## Persistence
+/// 
+/// A blacklist created with [`TokenBlacklist::with_pool`] stores revocations
+/// in the `revoked_tokens` table, so they survive restarts and are shared by
+/// every instance using the database. The in-memory map is a cache in front
+/// of it: revoked tokens found there are rejected without a query, others
+/// are looked up in the table. If the table cannot be read, the token is
+/// treated as revoked.
+/// 
+/// [`TokenBlacklist::new`] keeps revocations in memory only.
+/// 
 /// ## Thread Safety
 /// 
 /// Uses `Arc<RwLock<HashMap>>` for thread-safe concurrent access.
@@ -117,18 +157,39 @@ impl Claims {
 pub struct TokenBlacklist {
     /// Maps JWT ID to expiration timestamp (Unix timestamp)
     revoked: Arc<RwLock<HashMap<String, i64>>>,
+    /// Database backing the blacklist, if persistent
+    pool: Option<SqlitePool>,
 }
 
 impl TokenBlacklist {
-    /// Create a new empty token blacklist.
+    /// Create a new empty in-memory token blacklist.
     pub fn new() -> Self {
         Self {
             revoked: Arc::new(RwLock::new(HashMap::new())),
+            pool: None,
+        }
+    }
+
+    /// Create a token blacklist persisted in the `revoked_tokens` table.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust,ignore
+    /// let pool = database::init_database().await?;
+    /// let blacklist = TokenBlacklist::with_pool(pool.clone());
+    /// ```
+    pub fn with_pool(pool: SqlitePool) -> Self {
+        Self {
+            revoked: Arc::new(RwLock::new(HashMap::new())),
+            pool: Some(pool),
         }
     }
     
     /// Add a token to the blacklist with its expiration timestamp.
     /// 
+    /// The revocation is cached even if it could not be stored, so this
+    /// instance keeps rejecting the token; the failure is logged.
+    /// 
     /// # Arguments
     /// 
     /// * `jti` - JWT ID to revoke
@@ -148,6 +209,11 @@ impl TokenBlacklist {
     /// }
     /// ```
     pub async fn revoke(&self, jti: &str, exp: i64) {
+        if let Some(pool) = &self.pool {
+            if let Err(e) = queries::revoke_token(pool, jti, exp).await {
+                tracing::error!("Failed to persist revocation of token {}: {}", jti, e);
+            }
+        }
         self.revoked.write().await.insert(jti.to_string(), exp);
     }
     
@@ -161,14 +227,39 @@ impl TokenBlacklist {
     /// 
     /// Returns `true` if the token is revoked, `false` otherwise.
     pub async fn is_revoked(&self, jti: &str) -> bool {
-        self.revoked.read().await.contains_key(jti)
+        if self.revoked.read().await.contains_key(jti) {
+            return true;
+        }
+        let Some(pool) = &self.pool else {
+            return false;
+        };
+
+        // Revoked by another instance, or before a restart
+        match queries::find_revoked_token(pool, jti).await {
+            Ok(Some(exp)) => {
+                self.revoked.write().await.insert(jti.to_string(), exp);
+                true
+            }
+            Ok(None) => false,
+            Err(e) => {
+                tracing::error!("Failed to check revocation of token {}: {}", jti, e);
+                true
+            }
+        }
     }
     
     /// Get the total number of revoked tokens.
     /// 
-    /// Useful for monitoring and debugging.
+    /// Counts the stored revocations of a persistent blacklist, and the cached
+    /// ones otherwise. Useful for monitoring and debugging.
     #[allow(dead_code)]
     pub async fn count(&self) -> usize {
+        if let Some(pool) = &self.pool {
+            match queries::count_revoked_tokens(pool).await {
+                Ok(count) => return count as usize,
+                Err(e) => tracing::error!("Failed to count revoked tokens: {}", e),
+            }
+        }
         self.revoked.read().await.len()
     }
     
@@ -177,17 +268,24 @@ impl TokenBlacklist {
     /// Should be used carefully, typically only for maintenance or testing.
     #[allow(dead_code)]
     pub async fn clear(&self) {
+        if let Some(pool) = &self.pool {
+            if let Err(e) = queries::delete_revoked_tokens(pool).await {
+                tracing::error!("Failed to clear revoked tokens: {}", e);
+            }
+        }
         self.revoked.write().await.clear()
     }
     
     /// Remove expired tokens from the blacklist.
     /// 
     /// This method should be called periodically to prevent memory bloat.
-    /// It removes all tokens whose expiration timestamp is in the past.
+    /// It removes all tokens whose expiration timestamp is in the past, from
+    /// the cache and from the `revoked_tokens` table.
     /// 
     /// # Returns
     /// 
-    /// Returns the number of expired tokens removed.
+    /// Returns the number of expired tokens removed (rows deleted from the
+    /// table for a persistent blacklist).
     /// 
     /// # Examples
     /// 
@@ -203,13 +301,22 @@ impl TokenBlacklist {
     /// ```
     pub async fn cleanup_expired(&self) -> usize {
         let now = Utc::now().timestamp();
-        let mut revoked = self.revoked.write().await;
-        let initial_count = revoked.len();
-        
-        // Remove all tokens with expiration timestamp in the past
-        revoked.retain(|_, &mut exp| exp > now);
-        
-        let removed = initial_count - revoked.len();
+        let mut removed = {
+            let mut revoked = self.revoked.write().await;
+            let initial_count = revoked.len();
+
+            // Remove all tokens with expiration timestamp in the past
+            revoked.retain(|_, &mut exp| exp > now);
+            initial_count - revoked.len()
+        };
+
+        if let Some(pool) = &self.pool {
+            match queries::delete_expired_revoked_tokens(pool, now).await {
+                Ok(deleted) => removed = deleted as usize,
+                Err(e) => tracing::error!("Failed to prune revoked tokens: {}", e),
+            }
+        }
+
         if removed > 0 {
             tracing::info!("Cleaned up {} expired tokens from blacklist", removed);
         }
@@ -258,6 +365,24 @@ impl Default for TokenBlacklist {
     }
 }
 
+/// Lifetime of access tokens in minutes (15 minutes).
+pub const ACCESS_TOKEN_MINUTES: i64 = 15;
+
+/// Lifetime of refresh tokens in minutes (7 days).
+pub const REFRESH_TOKEN_MINUTES: i64 = 10080;
+
+/// Sign claims into a JWT token.
+/// 
+/// Used for tokens whose claims are needed after signing, such as the JWT
+/// ID of a refresh token recorded in its family.
+pub fn encode_claims(claims: &Claims, secret: &str) -> Result<String, jsonwebtoken::errors::Error> {
+    encode(
+        &Header::default(),
+        claims,
+        &EncodingKey::from_secret(secret.as_bytes()),
+    )
+}
+
 /// Generate an access token (short-lived, 15 minutes).
 /// 
 /// Access tokens are used for authenticating API requests.
@@ -286,6 +411,7 @@ impl Default for TokenBlacklist {
 ///     "your-super-secret-key-min-32-chars",
 /// ).unwrap();
 /// ```
+#[allow(dead_code)]
 pub fn generate_access_token(
     user_id: &str,
     email: &str,
@@ -296,14 +422,10 @@ pub fn generate_access_token(
         user_id.to_string(),
         email.to_string(),
         roles,
-        15, // 15 minutes
+        ACCESS_TOKEN_MINUTES,
     );
     
-    encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(secret.as_bytes()),
-    )
+    encode_claims(&claims, secret)
 }
 
 /// Generate a refresh token (long-lived, 7 days).
@@ -334,6 +456,7 @@ pub fn generate_access_token(
 ///     "your-super-secret-key-min-32-chars",
 /// ).unwrap();
 /// ```
+#[allow(dead_code)]
 pub fn generate_refresh_token(
     user_id: &str,
     email: &str,
@@ -344,14 +467,10 @@ pub fn generate_refresh_token(
         user_id.to_string(),
         email.to_string(),
         roles,
-        10080, // 7 days (7 * 24 * 60)
+        REFRESH_TOKEN_MINUTES,
     );
     
-    encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(secret.as_bytes()),
-    )
+    encode_claims(&claims, secret)
 }
 
 /// Validate and decode a JWT token.
@@ -376,7 +495,7 @@ pub fn generate_refresh_token(
 /// 
 /// # Examples
 /// 
-/// ```rust
+/// ```rust,ignore
 /// use nda_backend::jwt::validate_token;
 /// 
 /// match validate_token(&token, "secret-key") {
@@ -450,7 +569,7 @@ pub fn extract_token_from_header(auth_header: &str) -> Option<&str> {
 /// - Token format is invalid (not "Bearer <token>")
 /// - Token signature is invalid
 /// - Token is expired
-/// - Token has been revoked (in blacklist)
+/// - Token has been revoked (in blacklist), or its refresh token family has been revoked
 /// 
 /// # Examples
 /// 
@@ -489,6 +608,13 @@ pub async fn validate_auth_header(
         return Err("Token has been revoked");
     }
     
+    // Check if the family the token was issued in has been revoked
+    if let Some(family_id) = &claims.fam {
+        if blacklist.is_revoked(family_id).await {
+            return Err("Token has been revoked");
+        }
+    }
+    
     Ok(claims)
 }
 
@@ -608,6 +734,31 @@ mod tests {
         assert!(blacklist.is_revoked(valid_jti).await);
     }
     
+    #[tokio::test]
+    async fn test_persistent_blacklist_survives_restart() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        crate::database::run_migrations(&pool).await.unwrap();
+
+        let blacklist = TokenBlacklist::with_pool(pool.clone());
+        blacklist.revoke("expired-token", Utc::now().timestamp() - 1).await;
+        blacklist.revoke("valid-token", Utc::now().timestamp() + 900).await;
+
+        // A new instance on the same database sees the revocations
+        let restarted = TokenBlacklist::with_pool(pool.clone());
+        assert!(restarted.is_revoked("valid-token").await);
+        assert!(!restarted.is_revoked("unknown-token").await);
+        assert_eq!(restarted.count().await, 2);
+
+        // Cleanup prunes the table, not only the cache
+        assert_eq!(restarted.cleanup_expired().await, 1);
+        assert!(!TokenBlacklist::with_pool(pool.clone()).is_revoked("expired-token").await);
+        assert!(TokenBlacklist::with_pool(pool).is_revoked("valid-token").await);
+    }
+    
     #[tokio::test]
     async fn test_cleanup_task() {
         let blacklist = TokenBlacklist::new();
@@ -0,0 +1,274 @@
+//! # Key Provider Module
+//!
+//! This module keeps Stellar secret keys encrypted at rest. Secrets are sealed
+//! by a [`KeyProvider`] before they reach `users.stellar_secret_key` and are
+//! only opened just-in-time, when a transaction has to be signed.
+//!
+//! ## Providers
+//!
+//! - [`LocalFileKeyProvider`] - Seals secrets with AES-256-GCM under a key ring
+//!   loaded from a local file (or the key-encryption keys of [`crate::keys`])
+//!
+//! A KMS-backed provider only needs to implement [`KeyProvider`]; the sealed
+//! value it returns may be a ciphertext or a reference to a secret held by
+//! the KMS.
+//!
+//! ## Sealed Format
+//!
+//! ```text
+//! sealed:<provider>:<provider-specific payload>
+//! sealed:local:<key_id>:<base64 nonce + ciphertext>
+//! ```
+//!
+//! Values without the `sealed:` prefix are plaintext secrets written by older
+//! versions; [`seal_plaintext_secrets`] encrypts them in place at startup.
+//!
+//! ## Configuration
+//!
+//! - `SECRET_KEY_FILE`: Key file of the local provider (one Base64 key per
+//!   line, active key first). Defaults to the key-encryption key ring.
+
+use async_trait::async_trait;
+use sqlx::{Row, SqlitePool};
+
+use crate::keys::KeyRing;
+
+/// Error type returned by key providers.
+pub type KeyProviderError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Prefix marking a sealed secret.
+pub const SEALED_PREFIX: &str = "sealed:";
+
+/// Secret sealed by a [`KeyProvider`], safe to store in the database.
+///
+/// Can only be obtained from [`KeyProvider::seal`], so code that persists
+/// secrets cannot accidentally store plaintext.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SealedSecret(String);
+
+impl SealedSecret {
+    /// The sealed value as stored in the database.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Checks if a stored value was sealed by a key provider.
+pub fn is_sealed(value: &str) -> bool {
+    value.starts_with(SEALED_PREFIX)
+}
+
+/// Encrypts and decrypts secrets stored at rest.
+///
+/// Implementations must be thread-safe, as a single instance is shared
+/// by all request handlers through the application state.
+#[async_trait]
+pub trait KeyProvider: Send + Sync {
+    /// Short provider name recorded in sealed values (e.g. `local`).
+    fn name(&self) -> &str;
+
+    /// Seals a plaintext secret for storage.
+    async fn seal(&self, secret: &str) -> Result<SealedSecret, KeyProviderError>;
+
+    /// Opens a sealed value, returning the plaintext secret.
+    ///
+    /// Fails for plaintext values, values sealed by another provider and
+    /// values that do not decrypt.
+    async fn open(&self, sealed: &str) -> Result<String, KeyProviderError>;
+}
+
+/// Key provider sealing secrets under a locally stored key ring.
+///
+/// Secrets are encrypted with AES-256-GCM under the active key. Keys that
+/// were active before a rotation stay in the ring to open older values.
+pub struct LocalFileKeyProvider {
+    keyring: KeyRing,
+}
+
+impl LocalFileKeyProvider {
+    /// Name recorded in values sealed by this provider.
+    pub const NAME: &'static str = "local";
+
+    /// Creates a provider sealing secrets under `keyring`.
+    pub fn new(keyring: KeyRing) -> Self {
+        Self { keyring }
+    }
+
+    /// Creates a provider from a key file with one Base64 key per line.
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read SECRET_KEY_FILE '{}': {}", path, e))?;
+        Ok(Self::new(KeyRing::parse(&contents)?))
+    }
+
+    /// Creates a provider from `SECRET_KEY_FILE`, falling back to `keyring`.
+    pub fn from_env(keyring: &KeyRing) -> Result<Self, String> {
+        match std::env::var("SECRET_KEY_FILE") {
+            Ok(path) => Self::from_file(&path),
+            Err(_) => Ok(Self::new(keyring.clone())),
+        }
+    }
+}
+
+#[async_trait]
+impl KeyProvider for LocalFileKeyProvider {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    async fn seal(&self, secret: &str) -> Result<SealedSecret, KeyProviderError> {
+        let wrapped = self.keyring.wrap_key(secret)?;
+        Ok(SealedSecret(format!(
+            "{}{}:{}:{}",
+            SEALED_PREFIX,
+            Self::NAME,
+            wrapped.key_id,
+            wrapped.wrapped_key
+        )))
+    }
+
+    async fn open(&self, sealed: &str) -> Result<String, KeyProviderError> {
+        if !is_sealed(sealed) {
+            return Err("Secret is not sealed".into());
+        }
+        let payload = sealed[SEALED_PREFIX.len()..]
+            .strip_prefix(Self::NAME)
+            .and_then(|rest| rest.strip_prefix(':'))
+            .ok_or("Secret was sealed by another key provider")?;
+        let (key_id, wrapped) = payload.split_once(':').ok_or("Malformed sealed secret")?;
+
+        Ok(self.keyring.unwrap_key(Some(key_id), wrapped)?)
+    }
+}
+
+/// Seals Stellar secret keys that are still stored in plaintext.
+///
+/// Encrypts existing `users` rows in place in a single transaction, so a
+/// failure leaves every row untouched. Already sealed rows are skipped,
+/// which makes the migration safe to run on every startup.
+///
+/// # Returns
+///
+/// Returns `Result` containing:
+/// - `Ok(usize)` - Number of secrets sealed
+/// - `Err(String)` - Sealing failure or database error
+pub async fn seal_plaintext_secrets(pool: &SqlitePool, provider: &dyn KeyProvider) -> Result<usize, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let rows = sqlx::query("SELECT id, stellar_secret_key FROM users WHERE stellar_secret_key NOT LIKE 'sealed:%'")
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    for row in &rows {
+        let id: String = row.get("id");
+        let secret: String = row.get("stellar_secret_key");
+
+        let sealed = provider.seal(&secret).await.map_err(|e| format!("User {}: {}", id, e))?;
+
+        sqlx::query("UPDATE users SET stellar_secret_key = ?1 WHERE id = ?2")
+            .bind(sealed.as_str())
+            .bind(&id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(rows.len())
+}
+
+/// Re-seals every Stellar secret key from `current` to `target`.
+///
+/// Used when the keys of a provider are rotated. Runs in a single
+/// transaction, so secrets are never left sealed under a mix of keys.
+///
+/// # Returns
+///
+/// Returns `Result` containing:
+/// - `Ok(usize)` - Number of secrets re-sealed
+/// - `Err(String)` - Secret that cannot be opened, or database error
+#[allow(dead_code)]
+pub async fn reseal_secrets(
+    pool: &SqlitePool,
+    current: &dyn KeyProvider,
+    target: &dyn KeyProvider,
+) -> Result<usize, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let rows = sqlx::query("SELECT id, stellar_secret_key FROM users WHERE stellar_secret_key LIKE 'sealed:%'")
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    for row in &rows {
+        let id: String = row.get("id");
+        let stored: String = row.get("stellar_secret_key");
+
+        let secret = current.open(&stored).await.map_err(|e| format!("User {}: {}", id, e))?;
+        let sealed = target.seal(&secret).await.map_err(|e| format!("User {}: {}", id, e))?;
+
+        sqlx::query("UPDATE users SET stellar_secret_key = ?1 WHERE id = ?2")
+            .bind(sealed.as_str())
+            .bind(&id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(rows.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::KeyEncryptionKey;
+
+    #[tokio::test]
+    async fn test_local_provider_seals_and_migrates_secrets() {
+        let provider = LocalFileKeyProvider::new(KeyRing::new(KeyEncryptionKey::generate()));
+        let secret = "SBZVMB74Z76QZ3ZOY7UTDFYKMEGKW5XFJEB6PFKBF4UYSSWHG4EDH7PY";
+
+        let sealed = provider.seal(secret).await.unwrap();
+        assert!(is_sealed(sealed.as_str()));
+        assert!(!sealed.as_str().contains(secret));
+        assert_eq!(provider.open(sealed.as_str()).await.unwrap(), secret);
+
+        // Plaintext and values from other providers or keys are refused
+        assert!(provider.open(secret).await.is_err());
+        assert!(provider.open("sealed:kms:arn:key").await.is_err());
+        let other = LocalFileKeyProvider::new(KeyRing::new(KeyEncryptionKey::generate()));
+        assert!(other.open(sealed.as_str()).await.is_err());
+
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        crate::database::run_migrations(&pool).await.unwrap();
+        sqlx::query(
+            "INSERT INTO users (id, username, name, stellar_public_key, stellar_secret_key, password_hash, roles, created_at) \
+             VALUES ('u1', 'legacy', 'Legacy', 'GLEGACY', ?1, 'hash', '[\"client\"]', '2024-01-01T00:00:00+00:00')",
+        )
+        .bind(secret)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert_eq!(seal_plaintext_secrets(&pool, &provider).await.unwrap(), 1);
+        assert_eq!(seal_plaintext_secrets(&pool, &provider).await.unwrap(), 0);
+
+        let user = crate::database::queries::find_user_by_id(&pool, "u1").await.unwrap().unwrap();
+        assert!(is_sealed(&user.stellar_secret_key));
+        assert_eq!(provider.open(&user.stellar_secret_key).await.unwrap(), secret);
+
+        // Rotation re-seals the secret under the new keys only
+        assert_eq!(reseal_secrets(&pool, &provider, &other).await.unwrap(), 1);
+        let user = crate::database::queries::find_user_by_id(&pool, "u1").await.unwrap().unwrap();
+        assert_eq!(other.open(&user.stellar_secret_key).await.unwrap(), secret);
+        assert!(provider.open(&user.stellar_secret_key).await.is_err());
+    }
+}
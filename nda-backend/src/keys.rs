@@ -0,0 +1,364 @@
+//! # Key Management Module
+//!
+//! This module implements envelope encryption for process content. Every
+//! process is encrypted with its own data key (see [`crate::crypto`]), and the
+//! data key is stored *wrapped* (encrypted) by a key-encryption key (KEK) that
+//! never touches the database. A copy of the SQLite file alone therefore no
+//! longer reveals any content.
+//!
+//! ## Storage Format
+//!
+//! - `processes.encryption_key`: Data key encrypted with AES-256-GCM under the KEK,
+//!   in the ciphertext envelope of [`crate::crypto`]
+//! - `processes.key_id`: Identifier of the KEK that wrapped the data key
+//!
+//! Rows created before envelope encryption have a `NULL` `key_id` and hold the
+//! data key in plaintext. They are wrapped under the active KEK at startup
+//! (see [`wrap_legacy_process_keys`]).
+//!
+//! ## Configuration
+//!
+//! The key ring is loaded at startup from one of:
+//!
+//! - `KEK_FILE`: Path to a file with one Base64-encoded 256-bit key per line
+//! - `KEK`: Comma-separated Base64-encoded 256-bit keys
+//!
+//! The first key is the active key used for wrapping; the remaining keys are
+//! kept so data keys wrapped before a rotation can still be unwrapped. Empty
+//! lines and lines starting with `#` are ignored. A new key can be generated with:
+//!
+//! ```bash
+//! openssl rand -base64 32 > kek.key
+//! ```
+//!
+//! ## Key IDs
+//!
+//! Key IDs are derived from the key itself (`kek-` followed by the first 16 hex
+//! digits of its SHA-256), so they never need to be configured and a wrong key
+//! is detected before any unwrap is attempted.
+//!
+//! ## Rotation
+//!
+//! [`rewrap_process_keys`] re-wraps every data key under a new KEK without
+//! touching `encrypted_content`. It backs the `rotate_kek` command:
+//!
+//! ```bash
+//! KEK_FILE=kek.key cargo run --bin rotate_kek -- new-kek.key
+//! ```
+
+use base64::{engine::general_purpose, Engine as _};
+use sqlx::{Row, SqlitePool};
+
+use crate::crypto::{decrypt_content, encrypt_content, generate_key, key_fingerprint, CryptoError};
+
+/// Key-encryption key with its derived identifier.
+#[derive(Clone)]
+pub struct KeyEncryptionKey {
+    id: String,
+    key: String,
+}
+
+impl KeyEncryptionKey {
+    /// Parses a Base64-encoded 256-bit key.
+    pub fn from_base64(encoded: &str) -> Result<Self, String> {
+        let encoded = encoded.trim();
+        let bytes = general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|e| format!("Invalid key-encryption key: {}", e))?;
+        if bytes.len() != 32 {
+            return Err(format!(
+                "Invalid key-encryption key: expected 32 bytes, got {}",
+                bytes.len()
+            ));
+        }
+
+        Ok(Self {
+            id: key_id(&bytes),
+            key: encoded.to_string(),
+        })
+    }
+
+    /// Generates a new random key-encryption key.
+    #[allow(dead_code)]
+    pub fn generate() -> Self {
+        Self::from_base64(&generate_key()).expect("generated keys are 32 bytes")
+    }
+
+    /// Identifier stored alongside data keys wrapped by this key.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Base64 encoding of the key, for writing key files.
+    pub fn to_base64(&self) -> &str {
+        &self.key
+    }
+}
+
+impl std::fmt::Debug for KeyEncryptionKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        // Never print key material
+        f.debug_struct("KeyEncryptionKey").field("id", &self.id).finish()
+    }
+}
+
+/// Derives the identifier of a key from its bytes.
+fn key_id(key: &[u8]) -> String {
+    format!("kek-{}", key_fingerprint(key))
+}
+
+/// Data key wrapped by a key-encryption key, as stored in `processes`.
+///
+/// # Fields
+///
+/// * `key_id` - ID of the key-encryption key that wrapped the data key
+/// * `wrapped_key` - Base64-encoded nonce and ciphertext of the data key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WrappedKey {
+    pub key_id: String,
+    pub wrapped_key: String,
+}
+
+/// Set of key-encryption keys available to the application.
+///
+/// Holds the active key used to wrap new data keys and any previous keys
+/// needed to unwrap data keys that have not been rotated yet.
+#[derive(Debug, Clone)]
+pub struct KeyRing {
+    active: KeyEncryptionKey,
+    previous: Vec<KeyEncryptionKey>,
+}
+
+impl KeyRing {
+    /// Creates a key ring with a single active key.
+    pub fn new(active: KeyEncryptionKey) -> Self {
+        Self { active, previous: Vec::new() }
+    }
+
+    /// Adds a key that is only used for unwrapping.
+    pub fn with_previous(mut self, key: KeyEncryptionKey) -> Self {
+        if key.id != self.active.id && !self.previous.iter().any(|k| k.id == key.id) {
+            self.previous.push(key);
+        }
+        self
+    }
+
+    /// Loads the key ring from `KEK_FILE` or `KEK`.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result` containing:
+    /// - `Ok(KeyRing)` - Key ring with the first configured key active
+    /// - `Err(String)` - No key configured, unreadable file or invalid key
+    pub fn from_env() -> Result<Self, String> {
+        if let Ok(path) = std::env::var("KEK_FILE") {
+            let contents = std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read KEK_FILE '{}': {}", path, e))?;
+            return Self::parse(&contents);
+        }
+
+        match std::env::var("KEK") {
+            Ok(keys) => Self::parse(&keys.replace(',', "\n")),
+            Err(_) => Err(
+                "No key-encryption key configured: set KEK_FILE or KEK (generate one with `openssl rand -base64 32`)"
+                    .to_string(),
+            ),
+        }
+    }
+
+    /// Parses a key ring from one Base64 key per line, active key first.
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let mut keys = contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(KeyEncryptionKey::from_base64);
+
+        let active = keys.next().ok_or("Key ring contains no keys")??;
+        keys.try_fold(Self::new(active), |ring, key| Ok(ring.with_previous(key?)))
+    }
+
+    /// The key used to wrap new data keys.
+    pub fn active(&self) -> &KeyEncryptionKey {
+        &self.active
+    }
+
+    fn find(&self, key_id: &str) -> Option<&KeyEncryptionKey> {
+        std::iter::once(&self.active)
+            .chain(self.previous.iter())
+            .find(|key| key.id == key_id)
+    }
+
+    /// Wraps a Base64-encoded data key under the active key.
+    pub fn wrap_key(&self, data_key: &str) -> Result<WrappedKey, CryptoError> {
+        let wrapped_key = encrypt_content(data_key, &self.active.key, b"")
+            .map_err(|e| CryptoError::new(format!("Failed to wrap data key: {}", e)))?;
+
+        Ok(WrappedKey {
+            key_id: self.active.id.clone(),
+            wrapped_key,
+        })
+    }
+
+    /// Unwraps a stored data key.
+    ///
+    /// # Parameters
+    ///
+    /// * `key_id` - ID stored with the data key, `None` for legacy plaintext keys
+    /// * `stored_key` - Value of `processes.encryption_key`
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result` containing:
+    /// - `Ok(String)` - Base64-encoded data key
+    /// - `Err(CryptoError)` - Unknown key ID or data key that fails to unwrap
+    pub fn unwrap_key(&self, key_id: Option<&str>, stored_key: &str) -> Result<String, CryptoError> {
+        let key_id = match key_id {
+            Some(key_id) => key_id,
+            None => return Ok(stored_key.to_string()),
+        };
+
+        let kek = self
+            .find(key_id)
+            .ok_or_else(|| CryptoError::new(format!("Unknown key-encryption key: {}", key_id)))?;
+
+        decrypt_content(stored_key, &kek.key, b"")
+            .map_err(|e| CryptoError::new(format!("Failed to unwrap data key: {}", e)))
+    }
+}
+
+/// Re-wraps process data keys under the active key of `target`.
+///
+/// Data keys are unwrapped with `current` (which must hold every key that
+/// wrapped a stored data key) and wrapped again under `target`'s active key.
+/// `encrypted_content` is never read or modified. All rows are updated in a
+/// single transaction, so a failure leaves every row untouched.
+///
+/// # Parameters
+///
+/// * `pool` - Database connection pool
+/// * `current` - Key ring able to unwrap the stored data keys
+/// * `target` - Key ring whose active key wraps the data keys
+/// * `only_legacy` - Only wrap legacy plaintext keys (`key_id IS NULL`)
+///
+/// # Returns
+///
+/// Returns `Result` containing:
+/// - `Ok(usize)` - Number of data keys re-wrapped
+/// - `Err(String)` - Unwrap failure or database error
+pub async fn rewrap_process_keys(
+    pool: &SqlitePool,
+    current: &KeyRing,
+    target: &KeyRing,
+    only_legacy: bool,
+) -> Result<usize, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let rows = sqlx::query(
+        "SELECT id, encryption_key, key_id FROM processes WHERE key_id IS NULL OR (?1 = 0 AND key_id != ?2)",
+    )
+    .bind(only_legacy)
+    .bind(target.active().id())
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    for row in &rows {
+        let id: String = row.get("id");
+        let stored_key: String = row.get("encryption_key");
+        let key_id: Option<String> = row.get("key_id");
+
+        let data_key = current
+            .unwrap_key(key_id.as_deref(), &stored_key)
+            .map_err(|e| format!("Process {}: {}", id, e))?;
+        let wrapped = target.wrap_key(&data_key).map_err(|e| e.to_string())?;
+
+        sqlx::query("UPDATE processes SET encryption_key = ?1, key_id = ?2 WHERE id = ?3")
+            .bind(&wrapped.wrapped_key)
+            .bind(&wrapped.key_id)
+            .bind(&id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(rows.len())
+}
+
+/// Wraps data keys stored in plaintext before envelope encryption existed.
+///
+/// Called at startup so a database from an older version stops holding
+/// plaintext data keys as soon as the new version runs.
+pub async fn wrap_legacy_process_keys(pool: &SqlitePool, keyring: &KeyRing) -> Result<usize, String> {
+    rewrap_process_keys(pool, keyring, keyring, true).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::process_aad;
+    use crate::models::ProcessStatus;
+    use crate::database::{queries, run_migrations};
+
+    #[test]
+    fn test_wrap_and_unwrap_with_previous_key() {
+        let old = KeyEncryptionKey::generate();
+        let new = KeyEncryptionKey::generate();
+        let data_key = generate_key();
+
+        let wrapped = KeyRing::new(old.clone()).wrap_key(&data_key).unwrap();
+        assert_eq!(wrapped.key_id, old.id());
+        assert_ne!(wrapped.wrapped_key, data_key);
+
+        let rotated = KeyRing::new(new.clone()).with_previous(old.clone());
+        assert_eq!(rotated.unwrap_key(Some(&wrapped.key_id), &wrapped.wrapped_key).unwrap(), data_key);
+        assert!(KeyRing::new(new.clone()).unwrap_key(Some(&wrapped.key_id), &wrapped.wrapped_key).is_err());
+
+        // Legacy rows hold the data key itself
+        assert_eq!(rotated.unwrap_key(None, &data_key).unwrap(), data_key);
+
+        let parsed = KeyRing::parse(&format!("# active\n{}\n\n{}\n", new.to_base64(), old.to_base64())).unwrap();
+        assert_eq!(parsed.active().id(), new.id());
+        assert_eq!(parsed.unwrap_key(Some(old.id()), &wrapped.wrapped_key).unwrap(), data_key);
+        assert!(KeyRing::parse("").is_err());
+        assert!(KeyRing::parse("c2hvcnQ=").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rotation_rewraps_keys_without_touching_content() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        run_migrations(&pool).await.unwrap();
+
+        let old = KeyRing::new(KeyEncryptionKey::generate());
+        let data_key = generate_key();
+        let aad = process_aad("process-1", "client", 1);
+        let content = encrypt_content("Confidential", &data_key, &aad).unwrap();
+
+        let legacy = queries::create_process(&pool, "process-1", "client", "Legacy", "", &content, &hex::encode(crate::terms::content_sha256("Confidential")), &data_key, None, ProcessStatus::Active, None)
+            .await
+            .unwrap();
+        assert_eq!(wrap_legacy_process_keys(&pool, &old).await.unwrap(), 1);
+        assert_eq!(wrap_legacy_process_keys(&pool, &old).await.unwrap(), 0);
+
+        let new = KeyRing::new(KeyEncryptionKey::generate());
+        assert_eq!(rewrap_process_keys(&pool, &old, &new, false).await.unwrap(), 1);
+
+        let process = queries::find_process_by_id(&pool, &legacy.id).await.unwrap().unwrap();
+        assert_eq!(process.encrypted_content, content);
+        assert_eq!(process.key_id.as_deref(), Some(new.active().id()));
+        let unwrapped = new.unwrap_key(process.key_id.as_deref(), &process.encryption_key).unwrap();
+        assert_eq!(decrypt_content(&process.encrypted_content, &unwrapped, &aad).unwrap(), "Confidential");
+
+        // Rotating with a key ring that cannot unwrap fails and changes nothing
+        let unrelated = KeyRing::new(KeyEncryptionKey::generate());
+        assert!(rewrap_process_keys(&pool, &unrelated, &old, false).await.is_err());
+        let unchanged = queries::find_process_by_id(&pool, &legacy.id).await.unwrap().unwrap();
+        assert_eq!(unchanged.encryption_key, process.encryption_key);
+    }
+}
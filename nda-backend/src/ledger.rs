@@ -0,0 +1,561 @@
+//! # Ledger Backend Module
+//!
+//! This module abstracts the blockchain operations used by the HTTP handlers
+//! behind the [`Ledger`] trait, so the application can run against the real
+//! Stellar network or against a self-contained in-memory ledger.
+//!
+//! ## Backends
+//!
+//! - [`StellarClient`] - Real Stellar network through Horizon (default)
+//! - [`InMemoryLedger`] - Process-local ledger for tests and offline development
+//!
+//! The active backend is stored in [`crate::handlers::AppState`] as an
+//! `Arc<dyn Ledger>`, which keeps handlers independent of the network.
+//!
+//! ## In-Memory Ledger
+//!
+//! [`InMemoryLedger`] mimics the parts of Stellar the application relies on:
+//!
+//! - **Accounts**: Created by funding, with balances and sequence numbers
+//! - **Transactions**: Sharing and revocation payments are recorded with their memo and ledger
+//! - **Sequence Numbers**: Incremented on every transaction, like on chain
+//! - **Verification**: Access checks search the recorded transaction history
+//!
+//! No network access is required, which makes it possible to run the full
+//! register → create → share → access flow in CI.
+//!
+//! ## Usage Example
+//!
+//! ```rust,ignore
+//! use std::sync::Arc;
+//! use crate::ledger::{InMemoryLedger, Ledger};
+//!
+//! let ledger: Arc<dyn Ledger> = Arc::new(InMemoryLedger::new());
+//!
+//! let account = ledger.generate_keypair()?;
+//! ledger.fund_testnet_account(&account.public_key).await?;
+//! ```
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::stellar_real::{AccountResponse, Balance, StellarAccount, StellarClient, TransactionResponse};
+
+/// Error type returned by ledger operations.
+///
+/// Boxed errors must be `Send + Sync` so ledger futures can be awaited
+/// from Axum handlers running on the multi-threaded Tokio runtime.
+pub type LedgerError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Blockchain operations required by the NDA backend.
+///
+/// Implementations must be thread-safe, as a single instance is shared
+/// by all request handlers through the application state.
+///
+/// # Operations
+///
+/// - Account management: keypair generation, funding and lookup
+/// - Sharing: recording a process share as a ledger transaction
+/// - Verification: checking the ledger for a share granting access
+#[async_trait]
+pub trait Ledger: Send + Sync {
+    /// Generates a new account keypair.
+    fn generate_keypair(&self) -> Result<StellarAccount, LedgerError>;
+
+    /// Creates and funds an account so it can take part in transactions.
+    ///
+    /// Returns `Ok(false)` when funding was refused (e.g. account already exists).
+    async fn fund_testnet_account(&self, public_key: &str) -> Result<bool, LedgerError>;
+
+    /// Retrieves current account state, failing if the account does not exist.
+    #[allow(dead_code)]
+    async fn get_account(&self, account_id: &str) -> Result<AccountResponse, LedgerError>;
+
+    /// Records a process share from the source account to the destination,
+    /// carrying the share commitment as the transaction memo.
+    async fn share_process_transaction(
+        &self,
+        source_secret: &str,
+        destination_public: &str,
+        process_id: &str,
+        memo_hash: &[u8; 32],
+    ) -> Result<TransactionResponse, LedgerError>;
+
+    /// Records the revocation of a share, referencing the original sharing transaction.
+    async fn revoke_share_transaction(
+        &self,
+        source_secret: &str,
+        destination_public: &str,
+        share_transaction_hash: &str,
+    ) -> Result<TransactionResponse, LedgerError>;
+
+    /// Anchors a partner's signature of NDA terms, carrying the signature
+    /// anchor (see [`crate::commitment::signature_anchor`]) as the transaction memo.
+    async fn anchor_signature_transaction(
+        &self,
+        source_secret: &str,
+        destination_public: &str,
+        anchor_hash: &[u8; 32],
+    ) -> Result<TransactionResponse, LedgerError>;
+
+    /// Checks the ledger history for a share carrying `memo_hash` from the owner to the user.
+    ///
+    /// `not_before` bounds the history search; older transactions are not examined.
+    async fn verify_process_access(
+        &self,
+        memo_hash: &[u8; 32],
+        owner_public_key: &str,
+        user_public_key: &str,
+        not_before: Option<DateTime<Utc>>,
+    ) -> Result<bool, LedgerError>;
+
+    /// Confirms that a recorded share transaction exists on the ledger with the
+    /// expected source, destination and memo hash.
+    async fn verify_share_transaction(
+        &self,
+        transaction_hash: &str,
+        source_public: &str,
+        destination_public: &str,
+        memo_hash: &[u8; 32],
+    ) -> Result<bool, LedgerError>;
+}
+
+#[async_trait]
+impl Ledger for StellarClient {
+    fn generate_keypair(&self) -> Result<StellarAccount, LedgerError> {
+        StellarClient::generate_keypair()
+    }
+
+    async fn fund_testnet_account(&self, public_key: &str) -> Result<bool, LedgerError> {
+        StellarClient::fund_testnet_account(self, public_key).await
+    }
+
+    async fn get_account(&self, account_id: &str) -> Result<AccountResponse, LedgerError> {
+        StellarClient::get_account(self, account_id).await
+    }
+
+    async fn share_process_transaction(
+        &self,
+        source_secret: &str,
+        destination_public: &str,
+        process_id: &str,
+        memo_hash: &[u8; 32],
+    ) -> Result<TransactionResponse, LedgerError> {
+        StellarClient::share_process_transaction(self, source_secret, destination_public, process_id, memo_hash).await
+    }
+
+    async fn revoke_share_transaction(
+        &self,
+        source_secret: &str,
+        destination_public: &str,
+        share_transaction_hash: &str,
+    ) -> Result<TransactionResponse, LedgerError> {
+        StellarClient::revoke_share_transaction(self, source_secret, destination_public, share_transaction_hash).await
+    }
+
+    async fn anchor_signature_transaction(
+        &self,
+        source_secret: &str,
+        destination_public: &str,
+        anchor_hash: &[u8; 32],
+    ) -> Result<TransactionResponse, LedgerError> {
+        StellarClient::anchor_signature_transaction(self, source_secret, destination_public, anchor_hash).await
+    }
+
+    async fn verify_process_access(
+        &self,
+        memo_hash: &[u8; 32],
+        owner_public_key: &str,
+        user_public_key: &str,
+        not_before: Option<DateTime<Utc>>,
+    ) -> Result<bool, LedgerError> {
+        StellarClient::verify_process_access(self, memo_hash, owner_public_key, user_public_key, not_before).await
+    }
+
+    async fn verify_share_transaction(
+        &self,
+        transaction_hash: &str,
+        source_public: &str,
+        destination_public: &str,
+        memo_hash: &[u8; 32],
+    ) -> Result<bool, LedgerError> {
+        StellarClient::verify_share_transaction(self, transaction_hash, source_public, destination_public, memo_hash).await
+    }
+}
+
+/// How `access_process` decides whether a process was shared with a partner.
+///
+/// Configured with the `SHARE_VERIFICATION_MODE` environment variable.
+///
+/// # Modes
+///
+/// - `database` - A `process_shares` row is enough (no network access)
+/// - `chain` - The ledger is the source of truth: the partner's ledger history
+///   must contain a transaction from the process owner carrying the share
+///   commitment, whatever transaction hash is stored
+/// - `both` - The stored transaction hash must be confirmed on chain with the
+///   expected source, destination and share commitment (default)
+///
+/// Both chain modes recompute the commitment from the `process_shares` row,
+/// but a database row alone never grants access, so write access to SQLite
+/// is not enough to share a process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShareVerificationMode {
+    Database,
+    Chain,
+    Both,
+}
+
+impl ShareVerificationMode {
+    /// Reads the mode from `SHARE_VERIFICATION_MODE`, defaulting to [`ShareVerificationMode::Both`].
+    pub fn from_env() -> Result<Self, String> {
+        match std::env::var("SHARE_VERIFICATION_MODE") {
+            Ok(value) => value.parse(),
+            Err(_) => Ok(Self::Both),
+        }
+    }
+
+    /// Whether shares must be confirmed on the ledger.
+    pub fn requires_chain(&self) -> bool {
+        !matches!(self, Self::Database)
+    }
+}
+
+impl std::str::FromStr for ShareVerificationMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "database" => Ok(Self::Database),
+            "chain" => Ok(Self::Chain),
+            "both" => Ok(Self::Both),
+            other => Err(format!(
+                "Invalid SHARE_VERIFICATION_MODE '{}' (expected database, chain or both)",
+                other
+            )),
+        }
+    }
+}
+
+/// Starting balance given to funded accounts, in stroops (10,000 XLM like Friendbot).
+const FUNDING_AMOUNT: i64 = 10_000 * 10_000_000;
+
+/// Fee charged per transaction, in stroops.
+const TRANSACTION_FEE: i64 = 100;
+
+/// Amount transferred by sharing payments, in stroops.
+const SHARE_PAYMENT_AMOUNT: i64 = 1;
+
+/// Account state held by the in-memory ledger.
+#[derive(Debug, Clone)]
+pub struct InMemoryAccount {
+    pub sequence: i64,
+    pub balance: i64,
+}
+
+/// Transaction recorded by the in-memory ledger.
+///
+/// # Fields
+///
+/// * `hash` - Hex-encoded transaction hash
+/// * `ledger` - Ledger number the transaction was closed in
+/// * `source_account` - Public key of the sending account
+/// * `destination_account` - Public key of the receiving account
+/// * `sequence` - Source account sequence number consumed by the transaction
+/// * `memo` - Memo attached to the transaction
+/// * `created_at` - Time the transaction was recorded
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct LedgerTransaction {
+    pub hash: String,
+    pub ledger: u64,
+    pub source_account: String,
+    pub destination_account: String,
+    pub sequence: i64,
+    pub memo: LedgerMemo,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Memo of a transaction recorded by the in-memory ledger.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LedgerMemo {
+    /// `MEMO_HASH` carrying a share commitment or signature anchor
+    Hash([u8; 32]),
+    /// `MEMO_RETURN` referencing the hash of an earlier transaction
+    Return([u8; 32]),
+}
+
+#[derive(Default)]
+struct InMemoryState {
+    accounts: HashMap<String, InMemoryAccount>,
+    transactions: Vec<LedgerTransaction>,
+    ledger: u64,
+}
+
+/// In-memory ledger for tests and offline development.
+///
+/// Keeps accounts, sequence numbers and memo transactions in process memory.
+/// Keys are real Stellar keys, so data created against this ledger looks the
+/// same as data created against the network, but nothing is ever submitted.
+///
+/// # Thread Safety
+///
+/// State is guarded by a `Mutex` that is never held across `.await` points.
+#[derive(Default)]
+pub struct InMemoryLedger {
+    state: Mutex<InMemoryState>,
+}
+
+impl InMemoryLedger {
+    /// Creates an empty in-memory ledger.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns all transactions recorded so far, oldest first.
+    #[allow(dead_code)]
+    pub fn transactions(&self) -> Vec<LedgerTransaction> {
+        self.state.lock().unwrap().transactions.clone()
+    }
+
+    /// Returns the current state of an account, if it exists.
+    #[allow(dead_code)]
+    pub fn account(&self, public_key: &str) -> Option<InMemoryAccount> {
+        self.state.lock().unwrap().accounts.get(public_key).cloned()
+    }
+
+    /// Records a one-stroop payment carrying `memo`, like a Stellar payment.
+    fn record_payment(
+        &self,
+        source_secret: &str,
+        destination_public: &str,
+        memo: LedgerMemo,
+    ) -> Result<TransactionResponse, LedgerError> {
+        let source_public = StellarClient::get_public_from_secret(source_secret)?;
+
+        let mut state = self.state.lock().unwrap();
+        if !state.accounts.contains_key(destination_public) {
+            return Err(format!("Account not found: {}", destination_public).into());
+        }
+
+        let source = state
+            .accounts
+            .get_mut(&source_public)
+            .ok_or_else(|| format!("Account not found: {}", source_public))?;
+        if source.balance < TRANSACTION_FEE + SHARE_PAYMENT_AMOUNT {
+            return Err(format!("Insufficient balance in account {}", source_public).into());
+        }
+        source.sequence += 1;
+        source.balance -= TRANSACTION_FEE + SHARE_PAYMENT_AMOUNT;
+        let sequence = source.sequence;
+
+        if let Some(destination) = state.accounts.get_mut(destination_public) {
+            destination.balance += SHARE_PAYMENT_AMOUNT;
+        }
+
+        state.ledger += 1;
+        let ledger = state.ledger;
+
+        let hash = hex::encode(Sha256::digest(
+            format!("{}:{}:{}:{:?}", source_public, sequence, destination_public, memo).as_bytes(),
+        ));
+
+        state.transactions.push(LedgerTransaction {
+            hash: hash.clone(),
+            ledger,
+            source_account: source_public,
+            destination_account: destination_public.to_string(),
+            sequence,
+            memo,
+            created_at: Utc::now(),
+        });
+
+        Ok(TransactionResponse {
+            hash,
+            successful: true,
+            ledger: Some(ledger),
+            result_xdr: None,
+        })
+    }
+}
+
+#[async_trait]
+impl Ledger for InMemoryLedger {
+    fn generate_keypair(&self) -> Result<StellarAccount, LedgerError> {
+        StellarClient::generate_keypair()
+    }
+
+    async fn fund_testnet_account(&self, public_key: &str) -> Result<bool, LedgerError> {
+        // Reject malformed keys the same way the network would
+        stellar_strkey::ed25519::PublicKey::from_string(public_key)?;
+
+        let mut state = self.state.lock().unwrap();
+        if state.accounts.contains_key(public_key) {
+            return Ok(false);
+        }
+
+        state.ledger += 1;
+        // New Stellar accounts start with the creating ledger number in the high 32 bits
+        let sequence = (state.ledger as i64) << 32;
+        state.accounts.insert(
+            public_key.to_string(),
+            InMemoryAccount { sequence, balance: FUNDING_AMOUNT },
+        );
+
+        Ok(true)
+    }
+
+    async fn get_account(&self, account_id: &str) -> Result<AccountResponse, LedgerError> {
+        let state = self.state.lock().unwrap();
+        let account = state
+            .accounts
+            .get(account_id)
+            .ok_or_else(|| format!("Account not found: {}", account_id))?;
+
+        Ok(AccountResponse {
+            account_id: account_id.to_string(),
+            sequence: account.sequence.to_string(),
+            balances: vec![Balance {
+                balance: format!("{}.{:07}", account.balance / 10_000_000, account.balance % 10_000_000),
+                asset_type: "native".to_string(),
+                asset_code: None,
+                asset_issuer: None,
+            }],
+        })
+    }
+
+    async fn share_process_transaction(
+        &self,
+        source_secret: &str,
+        destination_public: &str,
+        _process_id: &str,
+        memo_hash: &[u8; 32],
+    ) -> Result<TransactionResponse, LedgerError> {
+        self.record_payment(source_secret, destination_public, LedgerMemo::Hash(*memo_hash))
+    }
+
+    async fn revoke_share_transaction(
+        &self,
+        source_secret: &str,
+        destination_public: &str,
+        share_transaction_hash: &str,
+    ) -> Result<TransactionResponse, LedgerError> {
+        let original: [u8; 32] = hex::decode(share_transaction_hash)?
+            .try_into()
+            .map_err(|_| format!("Invalid transaction hash: {}", share_transaction_hash))?;
+
+        self.record_payment(source_secret, destination_public, LedgerMemo::Return(original))
+    }
+
+    async fn anchor_signature_transaction(
+        &self,
+        source_secret: &str,
+        destination_public: &str,
+        anchor_hash: &[u8; 32],
+    ) -> Result<TransactionResponse, LedgerError> {
+        self.record_payment(source_secret, destination_public, LedgerMemo::Hash(*anchor_hash))
+    }
+
+    async fn verify_process_access(
+        &self,
+        memo_hash: &[u8; 32],
+        owner_public_key: &str,
+        user_public_key: &str,
+        not_before: Option<DateTime<Utc>>,
+    ) -> Result<bool, LedgerError> {
+        let state = self.state.lock().unwrap();
+
+        Ok(state.transactions.iter().any(|tx| {
+            not_before.is_none_or(|not_before| tx.created_at >= not_before)
+                && tx.source_account == owner_public_key
+                && tx.destination_account == user_public_key
+                && tx.memo == LedgerMemo::Hash(*memo_hash)
+        }))
+    }
+
+    async fn verify_share_transaction(
+        &self,
+        transaction_hash: &str,
+        source_public: &str,
+        destination_public: &str,
+        memo_hash: &[u8; 32],
+    ) -> Result<bool, LedgerError> {
+        let state = self.state.lock().unwrap();
+
+        Ok(state.transactions.iter().any(|tx| {
+            tx.hash == transaction_hash
+                && tx.source_account == source_public
+                && tx.destination_account == destination_public
+                && tx.memo == LedgerMemo::Hash(*memo_hash)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_in_memory_share_records_transaction() {
+        let ledger = InMemoryLedger::new();
+        let client = ledger.generate_keypair().unwrap();
+        let partner = ledger.generate_keypair().unwrap();
+        let memo = [1u8; 32];
+        let other_memo = [2u8; 32];
+
+        // Sharing requires both accounts to exist
+        assert!(ledger
+            .share_process_transaction(&client.secret_key, &partner.public_key, "p-1", &memo)
+            .await
+            .is_err());
+
+        assert!(ledger.fund_testnet_account(&client.public_key).await.unwrap());
+        assert!(ledger.fund_testnet_account(&partner.public_key).await.unwrap());
+        assert!(!ledger.fund_testnet_account(&partner.public_key).await.unwrap());
+
+        let sequence_before = ledger.account(&client.public_key).unwrap().sequence;
+
+        let tx = ledger
+            .share_process_transaction(&client.secret_key, &partner.public_key, "p-1", &memo)
+            .await
+            .unwrap();
+
+        assert!(tx.successful);
+        assert_eq!(tx.hash.len(), 64);
+        assert_eq!(ledger.account(&client.public_key).unwrap().sequence, sequence_before + 1);
+
+        let recorded = ledger.transactions();
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(recorded[0].source_account, client.public_key);
+        assert_eq!(recorded[0].memo, LedgerMemo::Hash(memo));
+
+        assert!(ledger.verify_process_access(&memo, &client.public_key, &partner.public_key, None).await.unwrap());
+        assert!(!ledger.verify_process_access(&other_memo, &client.public_key, &partner.public_key, None).await.unwrap());
+        assert!(!ledger.verify_process_access(&memo, &partner.public_key, &client.public_key, None).await.unwrap());
+
+        assert!(ledger
+            .verify_share_transaction(&tx.hash, &client.public_key, &partner.public_key, &memo)
+            .await
+            .unwrap());
+        assert!(!ledger
+            .verify_share_transaction(&tx.hash, &client.public_key, &partner.public_key, &other_memo)
+            .await
+            .unwrap());
+        assert!(!ledger
+            .verify_share_transaction(&tx.hash, &partner.public_key, &client.public_key, &memo)
+            .await
+            .unwrap());
+
+        let revocation = ledger
+            .revoke_share_transaction(&client.secret_key, &partner.public_key, &tx.hash)
+            .await
+            .unwrap();
+        let recorded = ledger.transactions();
+        assert_eq!(recorded.len(), 2);
+        assert_eq!(recorded[1].hash, revocation.hash);
+        assert_eq!(recorded[1].memo, LedgerMemo::Return(hex::decode(&tx.hash).unwrap().try_into().unwrap()));
+    }
+}
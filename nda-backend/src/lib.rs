@@ -5,4 +5,18 @@ pub mod handlers;
 pub mod database;
 pub mod crypto;
 pub mod auth;
-pub mod jwt;
\ No newline at end of file
+pub mod jwt;
+pub mod ledger;
+pub mod config;
+pub mod commitment;
+pub mod errors;
+pub mod guards;
+pub mod policy;
+pub mod tasks;
+pub mod keys;
+pub mod key_provider;
+pub mod sealed_box;
+pub mod attachments;
+pub mod terms;
+pub mod templates;
+pub mod certificate;
@@ -12,6 +12,7 @@
 //! - **database**: SQLite database operations and connection management
 //! - **models**: Data structures and type definitions
 //! - **crypto**: AES-256-GCM encryption for sensitive content
+//! - **keys**: Envelope encryption of per-process keys with a key-encryption key
 //! - **stellar_real**: Stellar blockchain integration for immutable sharing records
 //! 
 //! ## API Endpoints
@@ -25,15 +26,22 @@
 //! - `POST /api/users/auto-login` - Automatic login using localStorage data
 //! - `POST /api/users/refresh` - Refresh access token using refresh token
 //! - `POST /api/users/logout` - Logout and blacklist tokens
+//! - `GET /api/sessions` - List active login sessions (requires authentication)
+//! - `DELETE /api/sessions/{session_id}` - Revoke a login session (requires authentication)
+//! - `POST /api/sessions/revoke-others` - Revoke all other login sessions (requires authentication)
 //! 
 //! ### Process Management (🔒 JWT Required)
 //! - `POST /api/processes` - Create new encrypted NDA processes (requires "client" role)
 //! - `GET /api/processes` - List processes owned by a client (requires authentication)
 //! 
-//! ### Sharing & Access
-//! - `POST /api/processes/share` - Share processes via blockchain transactions
-//! - `POST /api/processes/access` - Access shared processes with decryption
-//! - `GET /api/notifications` - Get access notifications for audit trails
+//! ### Sharing & Access (🔒 JWT Required)
+//! - `POST /api/processes/share` - Share processes via blockchain transactions (requires "client" role)
+//! - `POST /api/processes/revoke` - Revoke a share with an on-chain revocation record (owner only)
+//! - `POST /api/processes/access` - Access shared processes with decryption (requires "partner" role)
+//! - `GET /api/notifications` - Get access notifications for audit trails (requires "client" role)
+//! - `GET /api/notifications/events` - Get events such as expired shares (requires authentication)
+//! 
+//! Role requirements are declared per route with the guards of [`guards`].
 //! 
 //! ## Security Features
 //! 
@@ -64,15 +72,30 @@
 //! - `DATABASE_URL`: SQLite database path (default: `sqlite:./stellar_mvp.db`)
 //! - `JWT_SECRET`: Secret key for JWT signing (REQUIRED for production, min 32 chars)
 //! - `RUST_LOG`: Logging level (trace, debug, info, warn, error)
+//! - `LEDGER_BACKEND`: Blockchain backend, `stellar` (default) or `memory` for offline development
+//! - `STELLAR_NETWORK`: Network preset, `testnet` (default), `mainnet`, `standalone` or `custom`
+//! - `STELLAR_CONFIG_FILE`: Optional JSON file with Stellar network settings
+//! - `STELLAR_HORIZON_URL`, `STELLAR_NETWORK_PASSPHRASE`, `STELLAR_FRIENDBOT_URL`: Network overrides
+//! - `STELLAR_HISTORY_MAX_PAGES`: Transaction history pages searched during verification (default 50)
+//! - `SHARE_VERIFICATION_MODE`: Share check before access, `database`, `chain` or `both` (default)
+//! - `SHARE_EXPIRY_INTERVAL_SECS`: How often expired shares are marked and owners notified (default 60)
+//! - `KEK_FILE` or `KEK`: Key-encryption keys wrapping per-process keys (REQUIRED, active key first)
+//! - `SECRET_KEY_FILE`: Keys sealing Stellar secret keys at rest (defaults to the key-encryption keys)
+//! - `ATTACHMENTS_DIR`: Directory of encrypted process attachments (default `./attachments`)
+//! - `MAX_ATTACHMENT_BYTES`: Maximum size of an attachment upload (default 100 MiB)
+//! - `CONTENT_CIPHER`: Cipher for new process content, `aes-256-gcm` (default) or `xchacha20-poly1305`
+//! - `REENCRYPTION_INTERVAL_SECS`: How often outdated process ciphertexts are upgraded (default 3600)
 //! - Server binds to `0.0.0.0:3000` by default
 
 use axum::{
-    routing::{get, post},
+    extract::DefaultBodyLimit,
+    middleware,
+    routing::{delete, get, post, put},
     Router,
 };
+use std::net::SocketAddr;
 use std::sync::Arc;
 use tower_http::cors::CorsLayer;
-use tracing_subscriber;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
@@ -83,10 +106,27 @@ mod crypto;
 mod stellar_real;
 mod auth;
 mod jwt;
+mod ledger;
+mod config;
+mod commitment;
+mod errors;
+mod guards;
+mod policy;
+mod tasks;
+mod keys;
+mod key_provider;
+mod sealed_box;
+mod attachments;
+mod terms;
+mod templates;
+mod certificate;
 
 use handlers::{AppState, ListProcessesQuery};
 use models::*;
 
+/// Room for multipart boundaries and headers on top of `MAX_ATTACHMENT_BYTES`.
+const UPLOAD_BODY_OVERHEAD: usize = 1024 * 1024;
+
 /// OpenAPI documentation structure
 #[derive(OpenApi)]
 #[openapi(
@@ -97,11 +137,35 @@ use models::*;
         handlers::auto_login_user,
         handlers::refresh_token,
         handlers::logout_user,
+        handlers::list_sessions,
+        handlers::revoke_session,
+        handlers::revoke_other_sessions,
         handlers::create_process,
+        handlers::update_process,
+        handlers::list_process_revisions,
+        handlers::get_process_revision,
+        handlers::transition_process,
+        handlers::list_process_transitions,
         handlers::share_process,
+        handlers::revoke_share,
         handlers::access_process,
+        handlers::get_process_terms,
+        handlers::sign_process_terms,
+        handlers::list_terms_signatures,
+        handlers::create_template,
+        handlers::list_templates,
+        handlers::get_template,
+        handlers::update_template,
+        handlers::delete_template,
+        handlers::list_template_versions,
+        handlers::get_template_version,
+        handlers::upload_attachments,
+        handlers::download_attachment,
+        handlers::get_process_certificate,
+        handlers::get_certificate_signing_key,
         handlers::list_processes,
         handlers::get_notifications,
+        handlers::get_notification_events,
     ),
     components(
         schemas(
@@ -111,16 +175,39 @@ use models::*;
             RefreshTokenRequest,
             LogoutRequest,
             CreateProcessRequest,
+            UpdateProcessRequest,
+            TransitionProcessRequest,
             ShareProcessRequest,
+            RevokeShareRequest,
             AccessProcessRequest,
+            SignTermsRequest,
+            CreateTemplateRequest,
+            UpdateTemplateRequest,
             UserResponse,
             LoginResponse,
+            SessionResponse,
             ProcessResponse,
             ProcessShare,
             ProcessAccessResponse,
             ProcessAccessWithDetails,
+            ProcessRevision,
+            ProcessStatus,
+            ProcessTransition,
+            NdaTerms,
+            TermsSignature,
+            NdaTemplate,
+            NdaTemplateVersion,
+            TemplatePlaceholder,
+            PlaceholderKind,
+            ProcessAttachment,
+            AttachmentUploadForm,
+            CertificateSigningKeyResponse,
             HealthResponse,
+            Notification,
+            ErrorResponse,
             ListProcessesQuery,
+            handlers::AttachmentDownloadQuery,
+            handlers::TermsQuery,
             jwt::Claims,
         )
     ),
@@ -128,13 +215,15 @@ use models::*;
         (name = "Health", description = "Health check endpoints"),
         (name = "User Management", description = "User registration and authentication"),
         (name = "Process Management", description = "NDA process creation and listing"),
+        (name = "NDA Templates", description = "Reusable NDA templates with typed placeholders"),
+        (name = "Certificates", description = "Signed PDF certificates of shares and accesses for auditors"),
         (name = "Sharing & Access", description = "Blockchain-secured sharing and content access"),
         (name = "Audit & Compliance", description = "Access notifications and audit trails")
     ),
     info(
         title = "NDA Backend API",
         version = "1.0.0",
-        description = "Blockchain-secured Non-Disclosure Agreement (NDA) contract management system with JWT authentication, AES-256-GCM encryption, and Stellar network integration.\n\n## Authentication\n\nThis API uses JWT (JSON Web Tokens) for authentication:\n\n1. **Login**: POST `/api/users/login` to receive `access_token` and `refresh_token`\n2. **Access Token**: Valid for 15 minutes - use in `Authorization: Bearer <token>` header\n3. **Refresh Token**: Valid for 7 days - use to obtain new access tokens\n4. **Logout**: POST `/api/users/logout` to revoke tokens\n\n## Protected Endpoints\n\nEndpoints marked with 🔒 require JWT authentication:\n- POST `/api/processes` - Requires \"client\" role\n- GET `/api/processes` - Requires authentication\n- POST `/api/users/logout` - Requires authentication",
+        description = "Blockchain-secured Non-Disclosure Agreement (NDA) contract management system with JWT authentication, AES-256-GCM encryption, and Stellar network integration.\n\n## Authentication\n\nThis API uses JWT (JSON Web Tokens) for authentication:\n\n1. **Login**: POST `/api/users/login` to receive `access_token` and `refresh_token`\n2. **Access Token**: Valid for 15 minutes - use in `Authorization: Bearer <token>` header\n3. **Refresh Token**: Valid for 7 days - use to obtain new access tokens\n4. **Logout**: POST `/api/users/logout` to revoke tokens\n\n## Protected Endpoints\n\nEndpoints marked with 🔒 require JWT authentication:\n- POST `/api/processes` - Requires \"client\" role\n- GET `/api/processes` - Requires authentication\n- POST `/api/processes/share` - Requires \"client\" role\n- POST `/api/processes/revoke` - Requires process ownership\n- POST `/api/processes/access` - Requires \"partner\" role\n- GET `/api/processes/{process_id}/terms`, POST `/api/processes/{process_id}/signatures` - Requires \"partner\" role\n- GET `/api/processes/{process_id}/attachments/{attachment_id}` - Requires \"partner\" role\n- PUT `/api/processes/{process_id}` - Requires process ownership\n- GET `/api/processes/{process_id}/revisions` - Requires process ownership\n- POST `/api/processes/{process_id}/transitions` - Requires process ownership\n- POST `/api/processes/{process_id}/attachments` - Requires process ownership\n- GET `/api/processes/{process_id}/signatures` - Requires process ownership\n- GET `/api/processes/{process_id}/certificate` - Requires process ownership\n- POST/GET `/api/templates` - Requires \"client\" role\n- GET/PUT/DELETE `/api/templates/{template_id}` and its versions - Requires template ownership\n- GET `/api/notifications` - Requires \"client\" role\n- GET `/api/notifications/events` - Requires authentication\n- GET `/api/sessions`, DELETE `/api/sessions/{session_id}`, POST `/api/sessions/revoke-others` - Requires authentication\n- POST `/api/users/logout` - Requires authentication",
         contact(
             name = "API Support",
             email = "support@nda-backend.com"
@@ -217,12 +306,32 @@ impl utoipa::Modify for SecurityAddon {
 /// - **Error Handling**: Structured error propagation with proper HTTP status codes
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Load variables from a local .env file, if present
+    dotenv::dotenv().ok();
+
     // Initialize structured logging
     tracing_subscriber::fmt::init();
 
     // Connect to database and run migrations
     let pool = database::init_database().await?;
 
+    // Load key-encryption keys and wrap any data keys still stored in plaintext
+    let keyring = keys::KeyRing::from_env()?;
+    tracing::info!("Active key-encryption key: {}", keyring.active().id());
+    let wrapped = keys::wrap_legacy_process_keys(&pool, &keyring).await?;
+    if wrapped > 0 {
+        tracing::info!("Wrapped {} legacy plaintext data keys", wrapped);
+    }
+
+    // Seal Stellar secret keys at rest, encrypting rows written by older versions
+    let key_provider: Arc<dyn key_provider::KeyProvider> =
+        Arc::new(key_provider::LocalFileKeyProvider::from_env(&keyring)?);
+    tracing::info!("Stellar secret key provider: {}", key_provider.name());
+    let sealed = key_provider::seal_plaintext_secrets(&pool, key_provider.as_ref()).await?;
+    if sealed > 0 {
+        tracing::info!("Sealed {} plaintext Stellar secret keys", sealed);
+    }
+
     // Get JWT secret from environment or use default (WARNING: use strong secret in production)
     let jwt_secret = std::env::var("JWT_SECRET")
         .unwrap_or_else(|_| {
@@ -230,20 +339,144 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             "default-jwt-secret-change-this-in-production-min-32-chars".to_string()
         });
 
-    // Create token blacklist for logout/revocation
-    let token_blacklist = jwt::TokenBlacklist::new();
+    // Create token blacklist for logout/revocation, persisted so revocations survive restarts
+    let token_blacklist = jwt::TokenBlacklist::with_pool(pool.clone());
     
     // Start background task to cleanup expired tokens every hour
     let _cleanup_handle = token_blacklist.start_cleanup_task(60);
     tracing::info!("Started token blacklist cleanup task (runs every 60 minutes)");
 
+    // Select blockchain backend (real Stellar network unless in-memory is requested)
+    let ledger: Arc<dyn ledger::Ledger> = match std::env::var("LEDGER_BACKEND").as_deref() {
+        Ok("memory") => {
+            tracing::warn!("LEDGER_BACKEND=memory, shares are NOT recorded on the Stellar network");
+            Arc::new(ledger::InMemoryLedger::new())
+        }
+        _ => {
+            let stellar_config = config::StellarConfig::from_env()?;
+            tracing::info!("Stellar network: {} ({})", stellar_config.network, stellar_config.horizon_url);
+
+            // Refuse to start when Horizon serves a different network than configured
+            let client = stellar_real::StellarClient::from_config(&stellar_config);
+            client.verify_network().await.map_err(|e| e.to_string())?;
+            Arc::new(client)
+        }
+    };
+
+    // Decide how shares are verified before granting access
+    let share_verification_mode = ledger::ShareVerificationMode::from_env()?;
+    tracing::info!("Share verification mode: {:?}", share_verification_mode);
+
+    // Start background task that marks expired shares and notifies their owners
+    let share_expiry_interval = tasks::share_expiry_interval_from_env()?;
+    let _expiry_handle = tasks::spawn_share_expiry_task(pool.clone(), share_expiry_interval);
+    tracing::info!("Started share expiry task (runs every {} seconds)", share_expiry_interval);
+
+    // Bind content from before process binding once, before serving requests (reads require it),
+    // then keep upgrading rows to the configured cipher in the background
+    let content_cipher = crypto::ContentCipher::from_env()?;
+    let bound = tasks::bind_process_content(&pool, &keyring, content_cipher).await?;
+    if bound > 0 {
+        tracing::info!("Bound {} process revisions to their process", bound);
+    }
+    let digested = tasks::digest_process_content(&pool, &keyring).await?;
+    if digested > 0 {
+        tracing::info!("Computed content digests of {} processes", digested);
+    }
+    let upgraded = tasks::reencrypt_processes(&pool, &keyring, content_cipher).await?;
+    if upgraded > 0 {
+        tracing::info!("Re-encrypted {} process rows", upgraded);
+    }
+    let reencryption_interval = tasks::reencryption_interval_from_env()?;
+    let _reencryption_handle =
+        tasks::spawn_reencryption_task(pool.clone(), keyring.clone(), content_cipher, reencryption_interval);
+    tracing::info!(
+        "Content cipher: {} (re-encryption task runs every {} seconds)",
+        content_cipher.name(),
+        reencryption_interval
+    );
+
+    // Encrypted attachments are stored on disk, outside the database
+    let attachment_store = attachments::AttachmentStore::from_env()?;
+    let upload_body_limit = attachment_store.max_bytes() as usize + UPLOAD_BODY_OVERHEAD;
+
+    // Certificates are signed with a platform key so auditors can verify them offline
+    let certificate_signer = Arc::new(certificate::CertificateSigner::from_env(&keyring)?);
+    tracing::info!("Certificate signing key: {}", certificate_signer.public_key());
+
     // Create application state for dependency injection
     let state = Arc::new(AppState { 
         pool,
         jwt_secret,
         token_blacklist,
+        ledger,
+        share_verification_mode,
+        keyring,
+        key_provider,
+        attachments: attachment_store,
+        content_cipher,
+        certificate_signer,
     });
 
+    // Routes open to any authenticated user
+    let authenticated_routes = Router::new()
+        .route("/api/processes", get(handlers::list_processes))   // List client's processes
+        .route("/api/notifications/events", get(handlers::get_notification_events))
+
+        // Session management - list and revoke the caller's logins
+        .route("/api/sessions", get(handlers::list_sessions))
+        .route("/api/sessions/:session_id", delete(handlers::revoke_session))
+        .route("/api/sessions/revoke-others", post(handlers::revoke_other_sessions))
+        .route_layer(middleware::from_fn_with_state(state.clone(), guards::authenticated));
+
+    // Routes of process owners - require the "client" role
+    let client_routes = Router::new()
+        // Process management endpoints - CRUD operations for NDA processes
+        .route("/api/processes", post(handlers::create_process))  // Create encrypted process
+        .route("/api/processes/:process_id", put(handlers::update_process))  // Amend content (new revision)
+        .route("/api/processes/:process_id/revisions", get(handlers::list_process_revisions))
+        .route("/api/processes/:process_id/revisions/:version", get(handlers::get_process_revision))
+        .route("/api/processes/:process_id/transitions", post(handlers::transition_process))  // Change lifecycle status
+        .route("/api/processes/:process_id/transitions", get(handlers::list_process_transitions))
+
+        // NDA template endpoints - reusable content with typed placeholders
+        .route("/api/templates", post(handlers::create_template))
+        .route("/api/templates", get(handlers::list_templates))
+        .route("/api/templates/:template_id", get(handlers::get_template))
+        .route("/api/templates/:template_id", put(handlers::update_template))  // New template version
+        .route("/api/templates/:template_id", delete(handlers::delete_template))  // Archive template
+        .route("/api/templates/:template_id/versions", get(handlers::list_template_versions))
+        .route("/api/templates/:template_id/versions/:version", get(handlers::get_template_version))
+
+        // Sharing endpoints - blockchain-integrated operations
+        .route("/api/processes/share", post(handlers::share_process))   // Share via Stellar
+        .route("/api/processes/revoke", post(handlers::revoke_share))   // Revoke share via Stellar
+        .route("/api/processes/:process_id/signatures", get(handlers::list_terms_signatures))
+
+        // Attachment upload - files encrypted while streamed
+        .route(
+            "/api/processes/:process_id/attachments",
+            post(handlers::upload_attachments).layer(DefaultBodyLimit::max(upload_body_limit)),
+        )
+
+        // Audit and compliance endpoint - access notifications for process owners
+        .route("/api/notifications", get(handlers::get_notifications))
+
+        // Certificate of shares and accesses - signed PDF evidence verifiable offline
+        .route("/api/processes/:process_id/certificate", get(handlers::get_process_certificate))
+        .route_layer(middleware::from_fn_with_state(state.clone(), guards::client_only));
+
+    // Routes of partners a process was shared with - require the "partner" role
+    let partner_routes = Router::new()
+        .route("/api/processes/access", post(handlers::access_process)) // Access with decryption
+        .route("/api/processes/:process_id/terms", get(handlers::get_process_terms))  // Terms to sign before access
+        .route("/api/processes/:process_id/signatures", post(handlers::sign_process_terms))  // Partner e-signature
+        .route(
+            "/api/processes/:process_id/attachments/:attachment_id",
+            get(handlers::download_attachment),
+        )
+        .route_layer(middleware::from_fn_with_state(state.clone(), guards::partner_only));
+
     // Configure API routes with RESTful design
     let app = Router::new()
         // Health monitoring endpoint for load balancers and deployment tools
@@ -255,17 +488,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .route("/api/users/auto-login", post(handlers::auto_login_user))
         .route("/api/users/refresh", post(handlers::refresh_token))
         .route("/api/users/logout", post(handlers::logout_user))
-        
-        // Process management endpoints - CRUD operations for NDA processes
-        .route("/api/processes", post(handlers::create_process))  // Create encrypted process
-        .route("/api/processes", get(handlers::list_processes))   // List client's processes
-        
-        // Sharing and access endpoints - blockchain-integrated operations
-        .route("/api/processes/share", post(handlers::share_process))   // Share via Stellar
-        .route("/api/processes/access", post(handlers::access_process)) // Access with decryption
-        
-        // Audit and compliance endpoint - access notifications for process owners
-        .route("/api/notifications", get(handlers::get_notifications))
+
+        // Public key verifying certificates, fetched by auditors
+        .route("/api/certificates/signing-key", get(handlers::get_certificate_signing_key))
+
+        // Protected routes, each group behind its role guard
+        .merge(authenticated_routes)
+        .merge(client_routes)
+        .merge(partner_routes)
         
         // Swagger UI for API documentation
         .merge(SwaggerUi::new("/swagger-ui")
@@ -282,7 +512,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("📄 OpenAPI spec at http://localhost:3000/api-docs/openapi.json");
     println!("🔐 Security: JWT authentication + AES-256-GCM encryption + Stellar blockchain");
     
-    axum::serve(listener, app).await?;
+    // Peer addresses are recorded on login sessions
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await?;
 
     Ok(())
 }
\ No newline at end of file
@@ -12,6 +12,8 @@
 //! - [`ProcessShare`] - Blockchain-recorded sharing events
 //! - [`ProcessAccess`] - Access audit logs for compliance
 //! - [`ProcessAccessWithDetails`] - Enriched access records with denormalized data
+//! - [`Notification`] - Events reported to users, such as expired shares
+//! - [`NdaTemplate`] - Reusable NDA templates with typed placeholders and versions
 //! 
 //! ### API Request Models
 //! Structures for deserializing incoming HTTP requests:
@@ -26,6 +28,7 @@
 //! - [`UserResponse`] - User data without sensitive fields
 //! - [`ProcessResponse`] - Process metadata without encrypted content
 //! - [`ProcessAccessResponse`] - Decrypted process content for authorized access
+//! - [`ErrorResponse`] - Error code and message for rejected requests
 //! 
 //! ## Security Considerations
 //! 
@@ -41,7 +44,7 @@
 //! 
 //! ## Usage Example
 //! 
-//! ```rust,no_run
+//! ```rust,ignore
 //! use nda_backend::models::*;
 //! 
 //! fn example() -> Result<(), Box<dyn std::error::Error>> {
@@ -57,6 +60,7 @@
 //!         title: "Sample".to_string(),
 //!         encrypted_content: "encrypted".to_string(),
 //!         encryption_key: "key".to_string(),
+//!         key_id: None,
 //!         status: "active".to_string(),
 //!         created_at: chrono::Utc::now(),
 //!     };
@@ -87,7 +91,7 @@ use utoipa::ToSchema;
 /// * `username` - Unique username for authentication
 /// * `name` - Full name or display name of the user
 /// * `stellar_public_key` - Stellar network public key for blockchain operations
-/// * `stellar_secret_key` - Stellar network secret key (encrypted in production)
+/// * `stellar_secret_key` - Stellar network secret key, sealed by the key provider
 /// * `roles` - JSON string containing user roles: `["client"]`, `["partner"]`, or `["client","partner"]`
 /// * `created_at` - Account creation timestamp
 /// 
@@ -100,7 +104,8 @@ use utoipa::ToSchema;
 /// 
 /// # Security Notes
 /// 
-/// - The `stellar_secret_key` should be encrypted using a Key Management Service (KMS) in production
+/// - The `stellar_secret_key` is stored sealed; open it with [`crate::key_provider::KeyProvider::open`]
+///   only right before signing a transaction
 /// - This model contains sensitive data and should not be directly exposed via APIs
 /// - Use [`UserResponse`] for API responses to exclude sensitive fields
 /// 
@@ -113,7 +118,7 @@ pub struct User {
     pub username: String,
     pub name: String,
     pub stellar_public_key: String,
-    pub stellar_secret_key: String, // In production, use KMS (Key Management Service)
+    pub stellar_secret_key: String, // Sealed by the key provider (sealed:...)
     pub password_hash: String, // Bcrypt hashed password
     pub roles: String, // JSON string: ["client"] | ["partner"] | ["client","partner"]
     pub created_at: DateTime<Utc>,
@@ -146,15 +151,26 @@ impl std::fmt::Debug for User {
 /// * `client_id` - Reference to the owning client user
 /// * `title` - Human-readable process title/description
 /// * `description` - Detailed description of the process (required)
-/// * `encrypted_content` - AES-256-GCM encrypted confidential content
-/// * `encryption_key` - Base64-encoded encryption key for the content
-/// * `status` - Process lifecycle status ("active", "completed", etc.)
+/// * `encrypted_content` - Encrypted confidential content (ciphertext envelope)
+/// * `encryption_key` - Data key of the content, wrapped by a key-encryption key
+/// * `key_id` - ID of the key-encryption key that wrapped `encryption_key`
+///   (`None` for legacy rows holding a plaintext key)
+/// * `content_version` - Version of the content, bound to the ciphertext with
+///   the process and client IDs as associated data
+/// * `content_sha256` - Hex SHA-256 of the plaintext of the current revision,
+///   computed when it is written (see [`crate::terms`]); `None` for rows from
+///   older versions until it is computed at startup
+/// * `status` - Lifecycle status (see [`ProcessStatus`])
+/// * `template_id` - NDA template the content was rendered from, if any
+/// * `template_version` - Version of that template
 /// * `created_at` - Process creation timestamp
 /// 
 /// # Security Model
 /// 
-/// - Content is encrypted using AES-256-GCM before database storage
+/// - Content is encrypted (AES-256-GCM or XChaCha20-Poly1305) before database storage,
+///   bound to its process so ciphertexts cannot be swapped between rows
 /// - Each process has a unique encryption key generated during creation
+/// - The encryption key is stored wrapped by a key-encryption key kept outside the database
 /// - Only authorized users can decrypt content after blockchain-verified sharing
 /// - Access attempts are logged for audit trails and compliance
 /// 
@@ -172,7 +188,106 @@ pub struct Process {
     pub description: String,
     pub encrypted_content: String,
     pub encryption_key: String,
-    pub status: String,
+    pub key_id: Option<String>,
+    pub content_version: i64,
+    pub content_sha256: Option<String>,
+    pub status: ProcessStatus,
+    pub template_id: Option<String>,
+    pub template_version: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Lifecycle status of a process.
+/// 
+/// Processes move through `draft → active → suspended → closed → archived`:
+/// 
+/// | From        | Allowed transitions      |
+/// |-------------|--------------------------|
+/// | `draft`     | `active`, `archived`     |
+/// | `active`    | `suspended`, `closed`    |
+/// | `suspended` | `active`, `closed`       |
+/// | `closed`    | `archived`               |
+/// | `archived`  | none                     |
+/// 
+/// Only active processes can be shared with or accessed by partners, so
+/// suspending or closing a process stops partner access without revoking
+/// its shares. Content and attachments can no longer change once the
+/// process is closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+pub enum ProcessStatus {
+    Draft,
+    Active,
+    Suspended,
+    Closed,
+    Archived,
+}
+
+impl ProcessStatus {
+    /// Returns the status as stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Draft => "draft",
+            Self::Active => "active",
+            Self::Suspended => "suspended",
+            Self::Closed => "closed",
+            Self::Archived => "archived",
+        }
+    }
+
+    /// Checks if a process in this status may move to `next`.
+    pub fn can_transition_to(&self, next: ProcessStatus) -> bool {
+        use ProcessStatus::*;
+
+        matches!(
+            (self, next),
+            (Draft, Active)
+                | (Draft, Archived)
+                | (Active, Suspended)
+                | (Active, Closed)
+                | (Suspended, Active)
+                | (Suspended, Closed)
+                | (Closed, Archived)
+        )
+    }
+
+    /// Whether partners may be granted or use access (sharing, viewing, downloads).
+    pub fn allows_partner_access(&self) -> bool {
+        matches!(self, Self::Active)
+    }
+
+    /// Whether the content and attachments of the process may still change.
+    pub fn allows_edits(&self) -> bool {
+        matches!(self, Self::Draft | Self::Active | Self::Suspended)
+    }
+}
+
+impl std::fmt::Display for ProcessStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Recorded change of the lifecycle status of a process.
+/// 
+/// # Fields
+/// 
+/// * `id` - Unique transition identifier (UUID)
+/// * `process_id` - Process whose status changed
+/// * `from_status` - Previous status, `None` for the creation of the process
+/// * `to_status` - New status
+/// * `actor_id` - User who made the change
+/// * `reason` - Reason given for the change
+/// * `created_at` - Transition timestamp
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ProcessTransition {
+    pub id: String,
+    pub process_id: String,
+    pub from_status: Option<ProcessStatus>,
+    pub to_status: ProcessStatus,
+    pub actor_id: String,
+    pub reason: String,
     pub created_at: DateTime<Utc>,
 }
 
@@ -187,13 +302,31 @@ pub struct Process {
 /// * `process_id` - Reference to the shared process
 /// * `partner_public_key` - Stellar public key of the recipient partner
 /// * `stellar_transaction_hash` - Immutable blockchain transaction hash
+/// * `stellar_ledger` - Ledger the transaction was included in, `None` when unknown
 /// * `shared_at` - Timestamp when sharing occurred
+/// * `memo_salt` - Hex-encoded random salt of the share commitment
+/// * `content_digest` - Hex-encoded SHA-256 of the encrypted content that was shared
+/// * `memo_commitment` - Hex-encoded commitment recorded as the transaction's `MEMO_HASH`
+/// * `revoked_at` - Timestamp when the share was revoked, if it was
+/// * `revoked_by` - ID of the user who revoked the share
+/// * `revocation_reason` - Reason given for the revocation
+/// * `revocation_transaction_hash` - Stellar transaction recording the revocation
+/// * `valid_from` - Start of the access window, `None` for access from sharing time
+/// * `expires_at` - End of the access window, `None` for access without time limit
+/// * `expired_at` - Timestamp when the expiry task marked the share as expired
+/// * `max_accesses` - Number of times the partner may view the process, `None` for no limit
+/// * `wrapped_key` - Data key sealed to the partner's Stellar key, set for zero-knowledge shares
+/// 
+/// The commitment fields are `None` for shares recorded before commitments
+/// were introduced. Revoked shares and shares outside their validity window
+/// no longer grant access.
 /// 
 /// # Blockchain Integration
 /// 
 /// - Each sharing event creates a Stellar network transaction
 /// - Transaction hash provides cryptographic proof of sharing
-/// - Memo field in transaction contains process metadata
+/// - Memo hash commits to the process, partner and exact document version
+///   without publishing the process ID
 /// - Sharing rights can be independently verified on the blockchain
 /// 
 /// # Compliance Benefits
@@ -208,7 +341,60 @@ pub struct ProcessShare {
     pub process_id: String,
     pub partner_public_key: String,
     pub stellar_transaction_hash: String,
+    pub stellar_ledger: Option<i64>,
     pub shared_at: DateTime<Utc>,
+    pub memo_salt: Option<String>,
+    pub content_digest: Option<String>,
+    pub memo_commitment: Option<String>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub revoked_by: Option<String>,
+    pub revocation_reason: Option<String>,
+    pub revocation_transaction_hash: Option<String>,
+    pub valid_from: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub expired_at: Option<DateTime<Utc>>,
+    pub max_accesses: Option<i64>,
+    pub wrapped_key: Option<String>,
+}
+
+impl ProcessShare {
+    /// Checks if the share has been revoked.
+    pub fn is_revoked(&self) -> bool {
+        self.revoked_at.is_some()
+    }
+
+    /// Checks if the share's access window has not started yet at `now`.
+    pub fn is_not_yet_valid(&self, now: DateTime<Utc>) -> bool {
+        self.valid_from.is_some_and(|valid_from| now < valid_from)
+    }
+
+    /// Checks if the share's access window has ended at `now`.
+    /// 
+    /// A share is expired as soon as `expires_at` passes, even before the
+    /// background expiry task has marked it.
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expired_at.is_some() || self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
+
+    /// Returns the memo hash the share's Stellar transaction must carry.
+    /// 
+    /// The commitment is recomputed from the stored inputs rather than read
+    /// from `memo_commitment`, so altering any input invalidates the share.
+    /// Legacy shares without a salt fall back to the hash of their
+    /// `NDA_SHARE:<process_id>` memo. Returns `None` for malformed inputs.
+    pub fn expected_memo_hash(&self) -> Option<[u8; 32]> {
+        use crate::commitment;
+
+        match (&self.memo_salt, &self.content_digest) {
+            (Some(salt), Some(digest)) => Some(commitment::share_commitment(
+                &self.process_id,
+                &self.partner_public_key,
+                &commitment::decode_hash(salt)?,
+                &commitment::decode_hash(digest)?,
+            )),
+            _ => Some(commitment::legacy_memo_hash(&self.process_id)),
+        }
+    }
 }
 
 /// Process access audit record.
@@ -222,6 +408,10 @@ pub struct ProcessShare {
 /// * `process_id` - Reference to the accessed process
 /// * `partner_id` - Reference to the accessing partner user
 /// * `accessed_at` - Timestamp when access occurred
+/// * `share_id` - Share that granted the access (`None` for records created before shares were tracked)
+/// * `attachment_id` - Attachment that was downloaded, `None` for access to the text content
+/// * `content_version` - Content revision current at the time of access (`None` for
+///   records created before revisions were tracked)
 /// 
 /// # Compliance Features
 /// 
@@ -236,6 +426,9 @@ pub struct ProcessAccess {
     pub process_id: String,
     pub partner_id: String,
     pub accessed_at: DateTime<Utc>,
+    pub share_id: Option<String>,
+    pub attachment_id: Option<String>,
+    pub content_version: Option<i64>,
 }
 
 /// Enriched process access record with denormalized data.
@@ -251,8 +444,10 @@ pub struct ProcessAccess {
 /// * `accessed_at` - Timestamp when access occurred
 /// * `process_title` - Denormalized process title for display
 /// * `process_description` - Denormalized process description for context
-/// * `process_status` - Current process status ('active', 'completed', etc.)
+/// * `process_status` - Current lifecycle status of the process
 /// * `partner_username` - Denormalized partner username for display
+/// * `attachment_id` - Attachment that was downloaded, `None` for access to the text content
+/// * `content_version` - Content revision the partner saw, `None` when unknown
 /// 
 /// # Usage
 /// 
@@ -277,8 +472,10 @@ pub struct ProcessAccessWithDetails {
     pub accessed_at: Option<DateTime<Utc>>,
     pub process_title: String,
     pub process_description: String,
-    pub process_status: String,
+    pub process_status: ProcessStatus,
     pub partner_username: Option<String>,
+    pub attachment_id: Option<String>,
+    pub content_version: Option<i64>,
 }
 
 /// User registration request payload.
@@ -378,6 +575,11 @@ pub struct AutoLoginRequest {
 /// * `description` - Detailed description of the process (required)
 /// * `confidential_content` - Sensitive content to be encrypted
 /// * `client_id` - ID of the client creating the process
+/// * `draft` - Create the process in `draft` status instead of `active`
+/// * `template_id` - Render the content from this NDA template instead of
+///   `confidential_content`
+/// * `template_version` - Template version to render, defaults to the current one
+/// * `template_values` - Values of the template placeholders, by name
 /// 
 /// # Security Processing
 /// 
@@ -390,8 +592,46 @@ pub struct AutoLoginRequest {
 pub struct CreateProcessRequest {
     pub title: String,
     pub description: String,
+    #[serde(default)]
     pub confidential_content: String,
     pub client_id: String,
+    #[serde(default)]
+    pub draft: bool,
+    #[serde(default)]
+    pub template_id: Option<String>,
+    #[serde(default)]
+    pub template_version: Option<i64>,
+    #[serde(default)]
+    #[schema(value_type = Object)]
+    pub template_values: std::collections::HashMap<String, serde_json::Value>,
+}
+
+/// Process content update request payload.
+/// 
+/// Replaces the confidential content of a process with a new revision.
+/// Previous revisions are kept and can be listed by the owner.
+/// 
+/// # Fields
+/// 
+/// * `confidential_content` - New content, encrypted before storage
+/// * `change_note` - Optional description of the amendment
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateProcessRequest {
+    pub confidential_content: String,
+    #[serde(default)]
+    pub change_note: Option<String>,
+}
+
+/// Process status transition request payload.
+/// 
+/// # Fields
+/// 
+/// * `status` - Status to move the process to
+/// * `reason` - Why the status changes, recorded in the transition history (required)
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct TransitionProcessRequest {
+    pub status: ProcessStatus,
+    pub reason: String,
 }
 
 /// Process sharing request payload.
@@ -403,20 +643,78 @@ pub struct CreateProcessRequest {
 /// 
 /// * `process_id` - ID of the process to share
 /// * `partner_public_key` - Stellar public key of the recipient
-/// * `client_username` - Username of the client sharing the process
+/// * `client_username` - Optional username of the sharing client; the client is
+///   the authenticated user, and a different username is rejected with `403`
+/// * `valid_from` - Optional start of the access window (defaults to immediately)
+/// * `expires_at` - Optional end of the access window (defaults to no expiry)
+/// * `max_accesses` - Optional number of times the partner may view the process
+///   (e.g. `1` for one-time view)
+/// * `zero_knowledge` - Seal the data key to the partner instead of decrypting on access
 /// 
 /// # Blockchain Integration
 /// 
 /// This request triggers:
-/// 1. Verification of process ownership by client
+/// 1. Verification that the authenticated client owns the process
 /// 2. Creation of a Stellar blockchain transaction
 /// 3. Recording of the transaction hash for audit
 /// 4. Granting of access permissions to the partner
+/// 
+/// # Time-Limited Access
+/// 
+/// Most NDAs grant review access for a limited period (e.g. 30–90 days).
+/// Outside the window access is denied, and once `expires_at` passes the
+/// share is marked expired and the client is notified.
+/// 
+/// # View-Limited Access
+/// 
+/// Sensitive disclosures such as pricing sheets can be limited to one or a
+/// few views with `max_accesses`. Once the partner has used every view,
+/// further access requests are denied.
+/// 
+/// # Zero-Knowledge Sharing
+/// 
+/// With `zero_knowledge`, the process data key is sealed to the partner's
+/// Stellar public key (converted to X25519) when the share is made. Access
+/// then returns the ciphertext and the sealed key, and the partner decrypts
+/// on their side; the server never decrypts the content for this share.
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct ShareProcessRequest {
     pub process_id: String,
     pub partner_public_key: String,
-    pub client_username: String,
+    #[serde(default)]
+    pub client_username: Option<String>,
+    #[serde(default)]
+    pub valid_from: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub max_accesses: Option<u32>,
+    #[serde(default)]
+    pub zero_knowledge: bool,
+}
+
+/// Share revocation request payload.
+/// 
+/// Withdraws a partner's access to a process. The revoking user is taken
+/// from the JWT access token and must own the process.
+/// 
+/// # Fields
+/// 
+/// * `process_id` - ID of the shared process
+/// * `partner_public_key` - Stellar public key of the partner losing access
+/// * `reason` - Why access is withdrawn (e.g. "Deal ended")
+/// 
+/// # Blockchain Integration
+/// 
+/// This request triggers:
+/// 1. Verification of process ownership by the authenticated client
+/// 2. A Stellar revocation transaction referencing the original share
+/// 3. Recording of who revoked the share, when and why
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RevokeShareRequest {
+    pub process_id: String,
+    pub partner_public_key: String,
+    pub reason: String,
 }
 
 /// Process access request payload.
@@ -427,22 +725,28 @@ pub struct ShareProcessRequest {
 /// # Fields
 /// 
 /// * `process_id` - ID of the process to access
-/// * `partner_public_key` - Stellar public key for verification
-/// * `partner_username` - Username of the requesting partner
+/// * `partner_public_key` - Optional Stellar public key of the partner
+/// * `partner_username` - Optional username of the requesting partner
+/// 
+/// The partner is the authenticated user and acts with their account's
+/// Stellar key; `partner_username` and `partner_public_key` are only checked
+/// against it, and a mismatch is rejected with `403`.
 /// 
 /// # Access Control
 /// 
 /// Before granting access, the system:
 /// 1. Verifies the process exists
 /// 2. Checks that sharing record exists in database
-/// 3. Validates partner credentials
+/// 3. Checks that the share is for the authenticated partner's key
 /// 4. Logs the access event for audit
 /// 5. Decrypts and returns the content
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct AccessProcessRequest {
     pub process_id: String,
-    pub partner_public_key: String,
-    pub partner_username: String,
+    #[serde(default)]
+    pub partner_public_key: Option<String>,
+    #[serde(default)]
+    pub partner_username: Option<String>,
 }
 
 /// User data for API responses (excludes sensitive fields).
@@ -499,7 +803,7 @@ pub struct UserResponse {
 /// 
 /// # Usage
 /// 
-/// ```rust,no_run
+/// ```rust,ignore
 /// use nda_backend::models::*;
 /// 
 /// let user = User {
@@ -526,7 +830,7 @@ impl User {
     /// 
     /// # Examples
     /// 
-    /// ```rust
+    /// ```rust,ignore
     /// let user = User {
     ///     roles: r#"["client","partner"]"#.to_string(),
     ///     // ... other fields
@@ -575,7 +879,7 @@ impl User {
     /// 
     /// # Examples
     /// 
-    /// ```rust
+    /// ```rust,ignore
     /// let user = User {
     ///     roles: r#"["client","partner"]"#.to_string(),
     ///     // ... other fields
@@ -621,6 +925,9 @@ impl From<User> for UserResponse {
 /// * `id` - Process identifier
 /// * `title` - Process title/description
 /// * `status` - Current process status
+/// * `content_version` - Current content revision
+/// * `template_id` - Template the content was rendered from, if any
+/// * `template_version` - Version of that template
 /// * `created_at` - Process creation timestamp
 /// 
 /// # Security Features
@@ -633,7 +940,10 @@ pub struct ProcessResponse {
     pub id: String,
     pub title: String,
     pub description: String,
-    pub status: String,
+    pub status: ProcessStatus,
+    pub content_version: i64,
+    pub template_id: Option<String>,
+    pub template_version: Option<i64>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -651,7 +961,7 @@ pub struct ProcessResponse {
 /// 
 /// # Usage
 /// 
-/// ```rust,no_run
+/// ```rust,ignore
 /// use nda_backend::models::*;
 /// 
 /// let process = Process {
@@ -660,7 +970,12 @@ pub struct ProcessResponse {
 ///     title: "Sample Process".to_string(),
 ///     encrypted_content: "encrypted_data".to_string(),
 ///     encryption_key: "encryption_key".to_string(),
-///     status: "active".to_string(),
+///     key_id: None,
+///     content_version: 1,
+///     content_sha256: None,
+///     status: ProcessStatus::Active,
+///     template_id: None,
+///     template_version: None,
 ///     created_at: chrono::Utc::now(),
 /// };
 /// let response: ProcessResponse = process.into();
@@ -672,6 +987,9 @@ impl From<Process> for ProcessResponse {
             title: process.title,
             description: process.description,
             status: process.status,
+            content_version: process.content_version,
+            template_id: process.template_id,
+            template_version: process.template_version,
             created_at: process.created_at,
         }
     }
@@ -687,12 +1005,20 @@ impl From<Process> for ProcessResponse {
 /// 
 /// * `process_id` - ID of the accessed process
 /// * `title` - Process title for reference
-/// * `content` - Decrypted confidential content
+/// * `content` - Decrypted confidential content, `None` for zero-knowledge shares
+/// * `content_version` - Revision of the content returned (always the current one)
 /// * `accessed_at` - Timestamp when access occurred
+/// * `remaining_accesses` - Views left on the share after this one, `None` when unlimited
+/// * `encrypted_content` - Ciphertext envelope, only for zero-knowledge shares
+/// * `wrapped_key` - Data key sealed to the partner's Stellar key, only for zero-knowledge shares
+/// * `content_aad` - Base64 associated data the ciphertext is bound to, only for zero-knowledge shares
+/// * `attachments` - Files attached to the process, downloadable by the partner
 /// 
 /// # Security Notes
 /// 
 /// - Content is decrypted only in memory during request processing
+/// - For zero-knowledge shares the server returns ciphertext only; the partner
+///   opens `wrapped_key` with their Stellar secret key and decrypts locally
 /// - Access is logged for audit trails and compliance
 /// - Only returned after blockchain-verified sharing authorization
 /// - Each access event is recorded in the database
@@ -706,8 +1032,18 @@ pub struct ProcessAccessResponse {
     pub process_id: String,
     pub title: String,
     pub description: String,
-    pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    pub content_version: i64,
     pub accessed_at: DateTime<Utc>,
+    pub remaining_accesses: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encrypted_content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wrapped_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_aad: Option<String>,
+    pub attachments: Vec<ProcessAttachment>,
 }
 
 /// Health check response with server status and timestamp.
@@ -788,6 +1124,7 @@ pub struct LoginResponse {
 /// - Refresh tokens are long-lived (7 days)
 /// - Old refresh token is revoked when new one is issued
 /// - Tokens are validated and checked against blacklist
+/// - Presenting a token that was already exchanged revokes its whole family
 /// 
 /// # Usage
 /// 
@@ -829,4 +1166,460 @@ pub struct RefreshTokenRequest {
 pub struct LogoutRequest {
     pub access_token: Option<String>,
     pub refresh_token: Option<String>,
-}
\ No newline at end of file
+}
+
+/// Refresh token issued within a refresh token family.
+/// 
+/// A family groups every refresh token descending from one login: each
+/// rotation records the token it replaced as `parent_jti`. A token whose
+/// `rotated_at` is set was already exchanged, so presenting it again means
+/// it was replayed and the family is revoked.
+/// 
+/// # Fields
+/// 
+/// * `jti` - JWT ID of the refresh token
+/// * `family_id` - Family the token belongs to
+/// * `parent_jti` - Token rotated into this one (`None` for the login token)
+/// * `issued_at` - Timestamp when the token was issued
+/// * `expires_at` - Expiration of the token (Unix timestamp)
+/// * `rotated_at` - Timestamp when the token was exchanged for a new one
+/// * `family_revoked_at` - Timestamp when the family was revoked, if it was
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct RefreshToken {
+    pub jti: String,
+    pub family_id: String,
+    pub parent_jti: Option<String>,
+    pub issued_at: DateTime<Utc>,
+    pub expires_at: i64,
+    pub rotated_at: Option<DateTime<Utc>>,
+    pub family_revoked_at: Option<DateTime<Utc>>,
+}
+
+/// Login session of a user.
+/// 
+/// Created at login and tied to the refresh token family of that login, so
+/// revoking the session revokes every token issued in it.
+/// 
+/// # Fields
+/// 
+/// * `id` - Unique session identifier (UUID)
+/// * `user_id` - User who logged in
+/// * `family_id` - Refresh token family of the login
+/// * `user_agent` - `User-Agent` of the login request, if sent
+/// * `ip_address` - Client IP address of the login request, if known
+/// * `created_at` - Timestamp of the login
+/// * `last_refreshed_at` - Timestamp of the last token refresh, if any
+/// * `revoked_at` - Timestamp when the session's family was revoked, if it was
+#[derive(Debug, Clone)]
+pub struct Session {
+    pub id: String,
+    pub user_id: String,
+    pub family_id: String,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_refreshed_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// Session as listed to its user.
+/// 
+/// # Fields
+/// 
+/// * `id` - Session identifier, used to revoke it
+/// * `user_agent` - Device or browser the session was started from
+/// * `ip_address` - Client IP address of the login
+/// * `created_at` - Timestamp of the login
+/// * `last_refreshed_at` - Timestamp of the last token refresh, if any
+/// * `current` - Whether the request was made with this session's access token
+/// 
+/// # Usage
+/// 
+/// ```json
+/// {
+///   "id": "session-uuid",
+///   "user_agent": "Mozilla/5.0 (X11; Linux x86_64) ...",
+///   "ip_address": "203.0.113.7",
+///   "created_at": "2024-01-01T09:00:00Z",
+///   "last_refreshed_at": "2024-01-01T09:15:00Z",
+///   "current": true
+/// }
+/// ```
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SessionResponse {
+    pub id: String,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_refreshed_at: Option<DateTime<Utc>>,
+    pub current: bool,
+}
+
+impl SessionResponse {
+    /// Lists a session, marking it current if it belongs to `current_family`.
+    pub fn new(session: Session, current_family: Option<&str>) -> Self {
+        Self {
+            current: current_family == Some(session.family_id.as_str()),
+            id: session.id,
+            user_agent: session.user_agent,
+            ip_address: session.ip_address,
+            created_at: session.created_at,
+            last_refreshed_at: session.last_refreshed_at,
+        }
+    }
+}
+
+/// Notification delivered to a user.
+/// 
+/// Records events users should be told about that are not the direct result
+/// of one of their requests, such as a share reaching its expiry date.
+/// 
+/// # Fields
+/// 
+/// * `id` - Unique notification identifier (UUID)
+/// * `user_id` - User the notification is addressed to
+/// * `kind` - Event type (e.g. `share_expired`)
+/// * `process_id` - Process the event relates to
+/// * `share_id` - Share the event relates to, if any
+/// * `message` - Human-readable description of the event
+/// * `created_at` - Timestamp when the event occurred
+/// 
+/// # Usage
+/// 
+/// ```json
+/// {
+///   "id": "notification-uuid",
+///   "user_id": "client-uuid",
+///   "kind": "share_expired",
+///   "process_id": "process-uuid",
+///   "share_id": "share-uuid",
+///   "message": "Share of 'Software Development NDA' with GCKFBEIYTKP... expired",
+///   "created_at": "2024-03-31T00:00:00Z"
+/// }
+/// ```
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct Notification {
+    pub id: String,
+    pub user_id: String,
+    pub kind: String,
+    pub process_id: String,
+    pub share_id: Option<String>,
+    pub message: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Error response body.
+/// 
+/// Returned by endpoints that explain why a request was rejected.
+/// 
+/// # Fields
+/// 
+/// * `error` - Stable machine-readable error code (e.g. `share_expired`)
+/// * `message` - Human-readable description of the error
+/// 
+/// # Usage
+/// 
+/// ```json
+/// {
+///   "error": "share_expired",
+///   "message": "Share expired at 2024-03-31T00:00:00+00:00"
+/// }
+/// ```
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ErrorResponse {
+    pub error: String,
+    pub message: String,
+}
+
+/// Revision of the content of a process.
+/// 
+/// Every content update creates a new revision; the highest version is the
+/// current content. Partner accesses record the version they saw, so the
+/// owner can prove which revision each partner viewed.
+/// 
+/// # Fields
+/// 
+/// * `id` - Unique revision identifier (UUID)
+/// * `process_id` - Process the revision belongs to
+/// * `version` - Content version, starting at 1
+/// * `author_id` - User who wrote the revision
+/// * `change_note` - Optional description of the change
+/// * `created_at` - Revision timestamp
+/// * `access_count` - Number of partner accesses that saw this revision
+/// * `content` - Decrypted content, only when a single revision is requested
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ProcessRevision {
+    pub id: String,
+    pub process_id: String,
+    pub version: i64,
+    pub author_id: String,
+    pub change_note: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub access_count: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+}
+
+/// Canonical NDA terms a partner signs before accessing a process.
+/// 
+/// The partner signs the raw 32 bytes of `terms_digest` with the ed25519 key
+/// of their Stellar account (see [`crate::terms`]). The digest covers every
+/// other field, so a signature only accepts these exact terms.
+/// 
+/// # Fields
+/// 
+/// * `process_id` - Process the terms belong to
+/// * `owner_public_key` - Stellar public key of the process owner
+/// * `partner_public_key` - Stellar public key of the signing partner
+/// * `title` - Process title
+/// * `description` - Process description
+/// * `content_version` - Content revision covered by the terms
+/// * `content_sha256` - Hex-encoded SHA-256 of the plaintext content of that revision
+/// * `terms_digest` - Hex-encoded digest to sign
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NdaTerms {
+    pub process_id: String,
+    pub owner_public_key: String,
+    pub partner_public_key: String,
+    pub title: String,
+    pub description: String,
+    pub content_version: i64,
+    pub content_sha256: String,
+    pub terms_digest: String,
+}
+
+/// Terms signature request payload.
+/// 
+/// # Fields
+/// 
+/// * `partner_username` - Optional username of the signing partner (the authenticated user)
+/// * `partner_public_key` - Optional Stellar public key the process was shared with (the
+///   signing key); it is the authenticated partner's key
+/// * `terms_digest` - Hex-encoded digest that was signed, as returned by the terms endpoint
+/// * `signature` - Base64-encoded ed25519 signature of the digest bytes
+/// * `anchor` - Also record the signature on the Stellar network
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SignTermsRequest {
+    #[serde(default)]
+    pub partner_username: Option<String>,
+    #[serde(default)]
+    pub partner_public_key: Option<String>,
+    pub terms_digest: String,
+    pub signature: String,
+    #[serde(default)]
+    pub anchor: bool,
+}
+
+/// Partner's signature of the terms of a process.
+/// 
+/// # Fields
+/// 
+/// * `id` - Unique signature identifier (UUID)
+/// * `process_id` - Process whose terms were signed
+/// * `partner_id` - Signing partner
+/// * `partner_public_key` - Stellar public key the signature verifies under
+/// * `content_version` - Content revision covered by the signed terms
+/// * `terms_digest` - Hex-encoded digest that was signed
+/// * `signature` - Base64-encoded ed25519 signature
+/// * `signed_at` - Timestamp when the signature was accepted
+/// * `anchor_transaction_hash` - Stellar transaction anchoring the signature, if requested
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TermsSignature {
+    pub id: String,
+    pub process_id: String,
+    pub partner_id: String,
+    pub partner_public_key: String,
+    pub content_version: i64,
+    pub terms_digest: String,
+    pub signature: String,
+    pub signed_at: DateTime<Utc>,
+    pub anchor_transaction_hash: Option<String>,
+}
+
+/// Encrypted file attached to a process.
+/// 
+/// The file contents are stored encrypted in `ATTACHMENTS_DIR` (see
+/// [`crate::attachments`]); this record holds the metadata needed to
+/// serve downloads.
+/// 
+/// # Fields
+/// 
+/// * `id` - Unique attachment identifier (UUID)
+/// * `process_id` - Process the attachment belongs to
+/// * `filename` - Original filename, returned in `Content-Disposition` on download
+/// * `content_type` - MIME type given at upload
+/// * `size_bytes` - Plaintext size in bytes
+/// * `file_key` - File key encrypted under the current data key, set once the
+///   data key has been rotated since upload (see [`crate::attachments`])
+/// * `created_at` - Upload timestamp
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ProcessAttachment {
+    pub id: String,
+    pub process_id: String,
+    pub filename: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_key: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Multipart form for attachment uploads.
+/// 
+/// Only documents the request body in the OpenAPI specification; uploads
+/// are read field by field as a stream. Every file field is stored as one
+/// attachment.
+#[allow(dead_code)]
+#[derive(ToSchema)]
+pub struct AttachmentUploadForm {
+    #[schema(value_type = String, format = Binary)]
+    pub file: Vec<u8>,
+}
+
+/// Type of the value a template placeholder accepts.
+/// 
+/// | Kind      | Accepted value                                  |
+/// |-----------|-------------------------------------------------|
+/// | `text`    | Non-empty string (e.g. party names)             |
+/// | `date`    | `YYYY-MM-DD` string (e.g. the effective date)   |
+/// | `integer` | JSON integer (e.g. a term length in months)     |
+/// | `choice`  | One of the placeholder's `options` (e.g. a jurisdiction) |
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PlaceholderKind {
+    Text,
+    Date,
+    Integer,
+    Choice,
+}
+
+/// Typed placeholder of an NDA template.
+/// 
+/// Placeholders appear in the template body as `{{name}}` and are replaced
+/// by the values given when a process is created from the template (see
+/// [`crate::templates`]).
+/// 
+/// # Fields
+/// 
+/// * `name` - Identifier used in the body (letters, digits and `_`, not starting with a digit)
+/// * `kind` - Type of the accepted value
+/// * `label` - Optional human-readable label for forms
+/// * `optional` - Whether the value may be omitted (renders as an empty string)
+/// * `options` - Accepted values of `choice` placeholders
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct TemplatePlaceholder {
+    pub name: String,
+    pub kind: PlaceholderKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub optional: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<String>,
+}
+
+/// Reusable NDA template owned by a client, with its current version.
+/// 
+/// Every change of the body or placeholders creates a new version; older
+/// versions are kept so processes can record the version they came from.
+/// 
+/// # Fields
+/// 
+/// * `id` - Unique template identifier (UUID)
+/// * `owner_id` - Client who owns the template
+/// * `name` - Template name
+/// * `description` - Template description
+/// * `version` - Current version, starting at 1
+/// * `body` - Body of the current version
+/// * `placeholders` - Placeholders of the current version
+/// * `created_at` - Template creation timestamp
+/// * `updated_at` - Timestamp of the current version
+/// * `archived_at` - When the template was deleted; archived templates cannot be used
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NdaTemplate {
+    pub id: String,
+    pub owner_id: String,
+    pub name: String,
+    pub description: String,
+    pub version: i64,
+    pub body: String,
+    pub placeholders: Vec<TemplatePlaceholder>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub archived_at: Option<DateTime<Utc>>,
+}
+
+/// Version of an NDA template.
+/// 
+/// # Fields
+/// 
+/// * `id` - Unique version identifier (UUID)
+/// * `template_id` - Template the version belongs to
+/// * `version` - Version number, starting at 1
+/// * `body` - Template body with `{{name}}` placeholders
+/// * `placeholders` - Placeholders used by the body
+/// * `author_id` - User who wrote the version
+/// * `change_note` - Optional description of the change
+/// * `created_at` - Version timestamp
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NdaTemplateVersion {
+    pub id: String,
+    pub template_id: String,
+    pub version: i64,
+    pub body: String,
+    pub placeholders: Vec<TemplatePlaceholder>,
+    pub author_id: String,
+    pub change_note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Template creation request payload.
+/// 
+/// # Fields
+/// 
+/// * `name` - Template name (required)
+/// * `description` - Optional template description
+/// * `body` - Template body with `{{name}}` placeholders
+/// * `placeholders` - Declaration of every placeholder used by the body
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateTemplateRequest {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub body: String,
+    pub placeholders: Vec<TemplatePlaceholder>,
+}
+
+/// Template update request payload, creating a new version.
+/// 
+/// # Fields
+/// 
+/// * `name` - New template name, unchanged when omitted
+/// * `description` - New template description, unchanged when omitted
+/// * `body` - Body of the new version
+/// * `placeholders` - Placeholders of the new version
+/// * `change_note` - Optional description of the change
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateTemplateRequest {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub body: String,
+    pub placeholders: Vec<TemplatePlaceholder>,
+    #[serde(default)]
+    pub change_note: Option<String>,
+}
+
+/// Public key verifying NDA certificates.
+/// 
+/// # Fields
+/// 
+/// * `public_key` - Stellar encoding (`G...`) of the platform ed25519 key
+/// * `algorithm` - Signature algorithm of the certificates (`ed25519`)
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CertificateSigningKeyResponse {
+    pub public_key: String,
+    pub algorithm: String,
+}
@@ -0,0 +1,146 @@
+//! # Authorization Policy Module
+//!
+//! Resource-level authorization. The route guards of [`crate::guards`] only
+//! check that a request is authenticated and carries the right role; this
+//! module decides whether the authenticated user may act on a given process,
+//! template or audit trail.
+//!
+//! ## Rules
+//!
+//! - The acting user is always the subject of the JWT access token. Request
+//!   fields naming an actor (`client_username`, `partner_username`,
+//!   `partner_public_key`, `client_id`) are optional; when given they must
+//!   name the authenticated user.
+//! - Only a process's `client_id` can share it, revoke its shares, amend it
+//!   or read its notifications.
+//! - Only the partner whose Stellar public key the process was shared with
+//!   can read its terms, sign them and access its content and attachments.
+//! - Users can only revoke their own login sessions.
+//!
+//! Every mismatch is rejected with `403 Forbidden` and a specific code:
+//!
+//! | Code | Reason |
+//! |------|--------|
+//! | `actor_mismatch` | A username or user ID in the request is not the authenticated user |
+//! | `partner_key_mismatch` | `partner_public_key` is not the authenticated partner's key |
+//! | `not_process_owner` | The authenticated user does not own the process |
+//! | `not_template_owner` | The authenticated user does not own the template |
+//! | `not_session_owner` | The session belongs to another user |
+
+use axum::http::StatusCode;
+use sqlx::SqlitePool;
+
+use crate::{
+    database::queries,
+    errors::ApiError,
+    jwt::Claims,
+    models::{NdaTemplate, Process, Session, User},
+};
+
+/// Loads the user the access token was issued to.
+///
+/// A token whose user no longer exists is rejected with `401`.
+pub async fn acting_user(pool: &SqlitePool, claims: &Claims) -> Result<User, ApiError> {
+    queries::find_user_by_id(pool, &claims.sub)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or_else(|| ApiError::unauthorized("User of the access token no longer exists"))
+}
+
+/// Loads the authenticated partner and the Stellar key they act with.
+///
+/// `partner_username` and `partner_public_key` are the values given in the
+/// request, if any; both must name the authenticated user. The key is the
+/// partner's own account key, which shares are checked against.
+pub async fn acting_partner(
+    pool: &SqlitePool,
+    claims: &Claims,
+    partner_username: Option<&str>,
+    partner_public_key: Option<&str>,
+) -> Result<(User, String), ApiError> {
+    let partner = acting_user(pool, claims).await?;
+    ensure_username(&partner, partner_username)?;
+
+    if partner_public_key.is_some_and(|key| key != partner.stellar_public_key) {
+        return Err(ApiError::forbidden(
+            "partner_key_mismatch",
+            "partner_public_key is not the Stellar key of the authenticated partner",
+        ));
+    }
+    if !partner.is_partner() {
+        return Err(ApiError::forbidden("insufficient_role", "This endpoint requires the partner role"));
+    }
+
+    let public_key = partner.stellar_public_key.clone();
+    Ok((partner, public_key))
+}
+
+/// Checks that a username given in the request is the authenticated user's.
+pub fn ensure_username(actor: &User, username: Option<&str>) -> Result<(), ApiError> {
+    match username {
+        Some(username) if username != actor.username => Err(ApiError::forbidden(
+            "actor_mismatch",
+            format!("Requests can only act as the authenticated user ({})", actor.username),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Resolves the client a request acts for, defaulting to the authenticated user.
+///
+/// A different `client_id` is rejected, so one client cannot act on or read
+/// the data of another.
+pub fn own_client_id(claims: &Claims, client_id: Option<&str>) -> Result<String, ApiError> {
+    match client_id {
+        Some(client_id) if client_id != claims.sub => Err(ApiError::forbidden(
+            "actor_mismatch",
+            "Requests can only act for the authenticated client",
+        )),
+        _ => Ok(claims.sub.clone()),
+    }
+}
+
+/// Checks that the authenticated user owns a process.
+pub fn ensure_process_owner(claims: &Claims, process: &Process) -> Result<(), ApiError> {
+    if process.client_id == claims.sub {
+        Ok(())
+    } else {
+        Err(ApiError::forbidden("not_process_owner", "Only the process owner can do this"))
+    }
+}
+
+/// Checks that the authenticated user owns a template.
+pub fn ensure_template_owner(claims: &Claims, template: &NdaTemplate) -> Result<(), ApiError> {
+    if template.owner_id == claims.sub {
+        Ok(())
+    } else {
+        Err(ApiError::forbidden("not_template_owner", "Only the template owner can do this"))
+    }
+}
+
+/// Checks that a login session belongs to the authenticated user.
+pub fn ensure_session_owner(claims: &Claims, session: &Session) -> Result<(), ApiError> {
+    if session.user_id == claims.sub {
+        Ok(())
+    } else {
+        Err(ApiError::forbidden("not_session_owner", "Sessions can only be revoked by their user"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claims(user_id: &str) -> Claims {
+        Claims::new(user_id.to_string(), "user@example.com".to_string(), vec!["client".to_string()], 15)
+    }
+
+    #[test]
+    fn test_own_client_id_defaults_to_token_subject() {
+        assert_eq!(own_client_id(&claims("client-1"), None).unwrap(), "client-1");
+        assert_eq!(own_client_id(&claims("client-1"), Some("client-1")).unwrap(), "client-1");
+
+        let denied = own_client_id(&claims("client-1"), Some("client-2")).unwrap_err();
+        assert_eq!((denied.status, denied.code.as_str()), (StatusCode::FORBIDDEN, "actor_mismatch"));
+    }
+}
@@ -0,0 +1,222 @@
+//! # Sealed Box Module
+//!
+//! This module encrypts small secrets (process data keys) to a Stellar account,
+//! so that only the holder of the account's secret key can open them. It is
+//! used for zero-knowledge shares, where the server hands the partner the
+//! encrypted content plus a sealed data key instead of decrypted content.
+//!
+//! ## Construction
+//!
+//! Stellar accounts are ed25519 keys. They are converted to X25519 (Curve25519
+//! in Montgomery form) and used in an anonymous sealed box:
+//!
+//! 1. Generate an ephemeral X25519 key pair
+//! 2. `shared = X25519(ephemeral_secret, recipient_x25519_public)`
+//! 3. `key = HKDF-SHA256(ikm = shared, salt = ephemeral_public || recipient_x25519_public,
+//!    info = "nda-manager sealed box v1")`
+//! 4. Encrypt with AES-256-GCM under `key` and a random 96-bit nonce
+//!
+//! ```text
+//! base64( ephemeral_public (32) || nonce (12) || ciphertext + tag )
+//! ```
+//!
+//! ## Opening on the Partner Side
+//!
+//! The partner derives their X25519 secret from the Stellar secret seed the
+//! same way libsodium's `crypto_sign_ed25519_sk_to_curve25519` does: the first
+//! 32 bytes of `SHA-512(seed)`, clamped. [`open`] implements this for Rust
+//! clients; other clients can use libsodium's conversion functions together
+//! with the steps above.
+//!
+//! ## Trust Model
+//!
+//! Zero-knowledge shares keep plaintext off the partner's access path: the
+//! server does not decrypt content or attachments to answer those requests.
+//! They do not protect content from the server itself. The server keeps every
+//! data key wrapped by its key-encryption key and unwraps it whenever it needs
+//! to, e.g. to amend content, store attachments, issue certificates or rotate
+//! the data key. Anyone holding the key-encryption key (the operator, or an
+//! attacker who obtains it) can read every process.
+//!
+//! What a sealed key opens is limited by data key rotation. Amending a process
+//! or revoking a zero-knowledge share moves the process to a fresh data key,
+//! which is sealed again to the partners of the remaining zero-knowledge
+//! shares only. A revoked partner's key keeps opening what it already opened
+//! (earlier revisions and the files attached so far, as they were sent), but
+//! nothing written after the revocation.
+
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Nonce,
+};
+use base64::{engine::general_purpose, Engine as _};
+use curve25519_dalek::{
+    constants::X25519_BASEPOINT, edwards::CompressedEdwardsY, montgomery::MontgomeryPoint, scalar::Scalar,
+};
+use hkdf::Hkdf;
+use rand::Rng;
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::crypto::CryptoError;
+
+/// HKDF info string binding derived keys to this construction.
+const HKDF_INFO: &[u8] = b"nda-manager sealed box v1";
+
+/// Length of an X25519 public key.
+const PUBLIC_KEY_LEN: usize = 32;
+
+/// Length of the AES-GCM nonce.
+const NONCE_LEN: usize = 12;
+
+/// Converts a Stellar public key (`G...`) to its X25519 public key.
+pub fn stellar_public_to_x25519(public_key: &str) -> Result<MontgomeryPoint, CryptoError> {
+    let ed25519 = stellar_strkey::ed25519::PublicKey::from_string(public_key)
+        .map_err(|e| CryptoError::new(format!("Invalid Stellar public key: {:?}", e)))?;
+
+    CompressedEdwardsY(ed25519.0)
+        .decompress()
+        .map(|point| point.to_montgomery())
+        .ok_or_else(|| CryptoError::new("Stellar public key is not a valid curve point"))
+}
+
+/// Converts a Stellar secret key (`S...`) to its X25519 secret scalar.
+fn stellar_secret_to_x25519(secret_key: &str) -> Result<Scalar, CryptoError> {
+    let seed = stellar_strkey::ed25519::PrivateKey::from_string(secret_key)
+        .map_err(|e| CryptoError::new(format!("Invalid Stellar secret key: {:?}", e)))?;
+
+    let hash = Sha512::digest(seed.0);
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(&hash[..32]);
+    Ok(clamped_scalar(bytes))
+}
+
+/// Builds an X25519 scalar from 32 bytes, applying the standard clamping.
+fn clamped_scalar(mut bytes: [u8; 32]) -> Scalar {
+    bytes[0] &= 248;
+    bytes[31] &= 127;
+    bytes[31] |= 64;
+    Scalar::from_bits(bytes)
+}
+
+/// Derives the AES-256-GCM key from the X25519 shared secret.
+fn derive_key(
+    shared: &MontgomeryPoint,
+    ephemeral_public: &MontgomeryPoint,
+    recipient_public: &MontgomeryPoint,
+) -> Result<Aes256Gcm, CryptoError> {
+    // Low-order recipient keys yield an all-zero secret known to everyone
+    if shared.0 == [0u8; 32] {
+        return Err(CryptoError::new("Degenerate X25519 shared secret"));
+    }
+
+    let mut salt = Vec::with_capacity(2 * PUBLIC_KEY_LEN);
+    salt.extend_from_slice(ephemeral_public.as_bytes());
+    salt.extend_from_slice(recipient_public.as_bytes());
+
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(&salt), shared.as_bytes())
+        .expand(HKDF_INFO, &mut key)
+        .map_err(|e| CryptoError::new(format!("Key derivation failed: {}", e)))?;
+
+    Aes256Gcm::new_from_slice(&key).map_err(|e| CryptoError::new(format!("Invalid derived key: {}", e)))
+}
+
+/// Seals `plaintext` to the holder of a Stellar account.
+///
+/// # Parameters
+///
+/// * `plaintext` - Secret to seal (e.g. raw data key bytes)
+/// * `recipient_public_key` - Stellar public key (`G...`) of the recipient
+///
+/// # Returns
+///
+/// Returns a `Result` containing:
+/// - `Ok(String)` - Base64-encoded sealed box
+/// - `Err(CryptoError)` - Invalid recipient key or encryption failure
+pub fn seal(plaintext: &[u8], recipient_public_key: &str) -> Result<String, CryptoError> {
+    let recipient = stellar_public_to_x25519(recipient_public_key)?;
+
+    let ephemeral_secret = clamped_scalar(rand::thread_rng().gen());
+    let ephemeral_public = X25519_BASEPOINT * ephemeral_secret;
+    let shared = recipient * ephemeral_secret;
+    let cipher = derive_key(&shared, &ephemeral_public, &recipient)?;
+
+    let nonce_bytes: [u8; NONCE_LEN] = rand::thread_rng().gen();
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce_bytes), plaintext)
+        .map_err(|e| CryptoError::new(format!("Encryption failed: {:?}", e)))?;
+
+    let mut sealed = ephemeral_public.as_bytes().to_vec();
+    sealed.extend_from_slice(&nonce_bytes);
+    sealed.extend_from_slice(&ciphertext);
+    Ok(general_purpose::STANDARD.encode(sealed))
+}
+
+/// Opens a sealed box with the recipient's Stellar secret key.
+///
+/// This is the partner-side counterpart of [`seal`]; the server never calls
+/// it with a partner's key.
+///
+/// # Parameters
+///
+/// * `sealed` - Base64-encoded sealed box produced by [`seal`]
+/// * `recipient_secret_key` - Stellar secret key (`S...`) of the recipient
+///
+/// # Returns
+///
+/// Returns a `Result` containing:
+/// - `Ok(Vec<u8>)` - The sealed plaintext
+/// - `Err(CryptoError)` - Malformed box, wrong key or tampered data
+#[allow(dead_code)]
+pub fn open(sealed: &str, recipient_secret_key: &str) -> Result<Vec<u8>, CryptoError> {
+    let secret = stellar_secret_to_x25519(recipient_secret_key)?;
+    let recipient = X25519_BASEPOINT * secret;
+
+    let data = general_purpose::STANDARD
+        .decode(sealed)
+        .map_err(|e| CryptoError::new(format!("Failed to decode sealed box: {}", e)))?;
+    if data.len() < PUBLIC_KEY_LEN + NONCE_LEN {
+        return Err(CryptoError::new("Invalid sealed box: insufficient length"));
+    }
+
+    let (ephemeral, rest) = data.split_at(PUBLIC_KEY_LEN);
+    let (nonce_bytes, ciphertext) = rest.split_at(NONCE_LEN);
+    let mut ephemeral_bytes = [0u8; PUBLIC_KEY_LEN];
+    ephemeral_bytes.copy_from_slice(ephemeral);
+    let ephemeral_public = MontgomeryPoint(ephemeral_bytes);
+
+    let shared = ephemeral_public * secret;
+    let cipher = derive_key(&shared, &ephemeral_public, &recipient)?;
+
+    cipher
+        .decrypt(Nonce::from_slice(nonce_bytes), ciphertext)
+        .map_err(|e| CryptoError::new(format!("Failed to open sealed box: {:?}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stellar_real::StellarClient;
+
+    #[test]
+    fn test_seal_to_stellar_account() {
+        let partner = StellarClient::generate_keypair().unwrap();
+        let other = StellarClient::generate_keypair().unwrap();
+
+        let sealed = seal(b"data key", &partner.public_key).unwrap();
+        assert_eq!(open(&sealed, &partner.secret_key).unwrap(), b"data key");
+
+        // Sealing is randomized and only the recipient can open the box
+        assert_ne!(seal(b"data key", &partner.public_key).unwrap(), sealed);
+        assert!(open(&sealed, &other.secret_key).is_err());
+
+        // The X25519 key derived from the secret matches the converted public key
+        let secret = stellar_secret_to_x25519(&partner.secret_key).unwrap();
+        assert_eq!(
+            X25519_BASEPOINT * secret,
+            stellar_public_to_x25519(&partner.public_key).unwrap()
+        );
+
+        assert!(seal(b"data key", "not-a-stellar-key").is_err());
+    }
+}
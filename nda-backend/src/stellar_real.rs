@@ -27,8 +27,10 @@
 //! 
 //! ### Transaction Operations
 //! - [`StellarClient::share_process_transaction()`] - Create sharing transactions
+//! - [`StellarClient::revoke_share_transaction()`] - Record share revocations
 //! - [`StellarClient::verify_process_access()`] - Verify sharing permissions
-//! - [`StellarClient::get_account_transactions()`] - Transaction history
+//! - [`StellarClient::verify_share_transaction()`] - Confirm a recorded share by hash
+//! - [`StellarClient::get_account_transactions()`] - Paginated transaction history
 //! 
 //! ### Network Operations
 //! - [`StellarClient::test_connection()`] - Network connectivity testing
@@ -58,9 +60,13 @@
 //! - **Testnet**: Development and testing environment with free funding
 //! - **Mainnet**: Production environment with real XLM transactions
 //! 
+//! Private networks (e.g. a local quickstart container) are supported through
+//! [`StellarClient::from_config()`] with a custom Horizon URL, passphrase and
+//! Friendbot; see [`crate::config`].
+//! 
 //! ## Usage Example
 //! 
-//! ```rust
+//! ```rust,ignore
 //! use crate::stellar_real::StellarClient;
 //! 
 //! // Create testnet client
@@ -69,18 +75,21 @@
 //! // Generate and fund a test account
 //! let account = client.create_test_account().await?;
 //! 
-//! // Share a process via blockchain
+//! // Share a process via blockchain, committing to the shared content
+//! let commitment = commitment::share_commitment(&process_id, &partner_public_key, &salt, &digest);
 //! let tx_result = client.share_process_transaction(
 //!     &account.secret_key,
 //!     &partner_public_key,
 //!     &process_id,
-//!     "NDA_SHARE"
+//!     &commitment
 //! ).await?;
 //! 
-//! // Verify access permissions
-//! let has_access = client.verify_process_access(
-//!     &process_id,
-//!     &partner_public_key
+//! // Confirm the share on chain
+//! let confirmed = client.verify_share_transaction(
+//!     &tx_result.hash,
+//!     &account.public_key,
+//!     &partner_public_key,
+//!     &commitment
 //! ).await?;
 //! ```
 //! 
@@ -97,16 +106,49 @@
 //! 
 //! - **Development**: Uses testnet with automatic funding via Friendbot
 //! - **Production**: Requires mainnet setup with real XLM funding
-//! - **MVP Mode**: Includes transaction simulation for rapid development
+//! 
+//! ## Transaction Format
+//! 
+//! Sharing transactions are real Stellar transactions built locally as XDR:
+//! a single payment of one stroop from the sharing account to the partner,
+//! carrying a 32-byte share commitment as `MEMO_HASH` (see [`crate::commitment`]).
+//! Revocations use the same payment with a `MEMO_RETURN` holding the hash
+//! of the original sharing transaction. The envelope is signed with the source account's
+//! ed25519 key under the client's `network_passphrase` and submitted to
+//! Horizon's `/transactions` endpoint, so the stored hash can be looked up
+//! on any Stellar explorer.
 
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::error::Error;
-use ed25519_dalek::{Keypair, PublicKey, SecretKey};
+use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signer};
 use stellar_strkey::ed25519;
+use stellar_xdr::curr::{
+    Asset, DecoratedSignature, Hash, Limits, Memo, MuxedAccount, Operation, OperationBody,
+    PaymentOp, Preconditions, SequenceNumber, Signature, SignatureHint, TimeBounds, TimePoint,
+    Transaction, TransactionEnvelope, TransactionExt, TransactionSignaturePayload,
+    TransactionSignaturePayloadTaggedTransaction, TransactionV1Envelope, Uint256, WriteXdr,
+};
 use rand::rngs::OsRng;
 use sha2::{Sha256, Digest};
 
+use chrono::{DateTime, Utc};
+
+use crate::config::StellarConfig;
+use base64::{engine::general_purpose, Engine as _};
+
+/// Base fee per operation, in stroops (network minimum).
+const BASE_FEE: u32 = 100;
+
+/// Amount sent with each sharing payment, in stroops (0.0000001 XLM).
+const SHARE_PAYMENT_AMOUNT: i64 = 1;
+
+/// Seconds a signed transaction remains valid before Horizon rejects it.
+const TRANSACTION_TIMEOUT_SECS: u64 = 300;
+
+/// Records requested per page when reading transaction history (Horizon maximum).
+const HISTORY_PAGE_LIMIT: usize = 200;
+
 /// Stellar blockchain client for network operations.
 /// 
 /// Provides a high-level interface to the Stellar network, handling account
@@ -117,21 +159,25 @@ use sha2::{Sha256, Digest};
 /// * `horizon_url` - Stellar Horizon API endpoint URL
 /// * `client` - HTTP client for network requests
 /// * `network_passphrase` - Network identifier for transaction signing
+/// * `friendbot_url` - Friendbot endpoint for account funding, if the network has one
+/// * `history_max_pages` - Page limit when searching transaction history
 /// 
 /// # Network Configuration
 /// 
 /// The client can be configured for different Stellar networks:
 /// - **Testnet**: Development environment with free funding
 /// - **Mainnet**: Production environment with real assets
+/// - **Standalone/Custom**: Private networks such as a local quickstart container
 /// 
 /// Each network has its own Horizon API endpoint and network passphrase
-/// for proper transaction routing and validation.
+/// for proper transaction routing and validation. See [`StellarConfig`].
 #[derive(Debug, Clone)]
 pub struct StellarClient {
     horizon_url: String,
     client: Client,
-    #[allow(dead_code)]
     network_passphrase: String,
+    friendbot_url: Option<String>,
+    history_max_pages: Option<usize>,
 }
 
 /// Stellar account keypair with public and secret keys.
@@ -152,7 +198,7 @@ pub struct StellarClient {
 /// 
 /// # Usage
 /// 
-/// ```rust
+/// ```rust,ignore
 /// let account = StellarClient::generate_keypair()?;
 /// println!("Address: {}", account.public_key);
 /// // Never log or expose the secret_key in production!
@@ -175,6 +221,9 @@ pub struct StellarAccount {
 /// * `ledger` - Ledger number where transaction was included (if successful)
 /// * `result_xdr` - Raw transaction result in XDR format (optional)
 /// 
+/// The field names match Horizon's transaction resource, so the body returned
+/// by `POST /transactions` deserializes directly into this struct.
+/// 
 /// # Transaction Verification
 /// 
 /// The transaction hash can be used to:
@@ -257,7 +306,6 @@ pub struct Balance {
 /// - Process IDs to verify sharing permissions
 /// - Sharing metadata for audit trails
 /// - Access authorization proofs
-#[allow(dead_code)]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TransactionRecord {
     pub id: String,
@@ -274,23 +322,110 @@ pub struct TransactionRecord {
 /// 
 /// Stellar API responses use embedded structures to organize
 /// related data and provide pagination information.
-#[allow(dead_code)]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TransactionsResponse {
     #[serde(rename = "_embedded")]
     pub embedded: EmbeddedTransactions,
+    #[serde(rename = "_links", default)]
+    pub links: Option<PageLinks>,
+}
+
+/// Pagination links of a Horizon collection page.
+/// 
+/// `next` points at the following page using Horizon's opaque cursor.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PageLinks {
+    pub next: Option<Link>,
+}
+
+/// Hyperlink in a Horizon response.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Link {
+    pub href: String,
+}
+
+/// Stop conditions for walking an account's transaction history.
+/// 
+/// History is read newest first, so the walk ends at the first record
+/// older than a lower bound. Without bounds every page is followed until
+/// Horizon returns an empty page.
+/// 
+/// # Fields
+/// 
+/// * `min_ledger` - Stop at transactions closed before this ledger
+/// * `since` - Stop at transactions created before this time
+/// * `max_pages` - Maximum number of pages to fetch
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransactionHistoryBounds {
+    pub min_ledger: Option<u64>,
+    pub since: Option<DateTime<Utc>>,
+    pub max_pages: Option<usize>,
+}
+
+impl TransactionHistoryBounds {
+    /// Whether a record is newer than the lower bounds.
+    /// 
+    /// Records with an unparseable timestamp are kept, as Horizon always
+    /// returns RFC 3339 times and the ledger bound still applies.
+    pub fn includes(&self, record: &TransactionRecord) -> bool {
+        if let Some(min_ledger) = self.min_ledger {
+            if record.ledger < min_ledger {
+                return false;
+            }
+        }
+        if let Some(since) = self.since {
+            if let Ok(created_at) = DateTime::parse_from_rfc3339(&record.created_at) {
+                if created_at.with_timezone(&Utc) < since {
+                    return false;
+                }
+            }
+        }
+        true
+    }
 }
 
 /// Container for transaction records in API responses.
 /// 
 /// Contains the actual list of transaction records returned
 /// from transaction history queries.
-#[allow(dead_code)]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct EmbeddedTransactions {
     pub records: Vec<TransactionRecord>,
 }
 
+/// Operation record from a transaction's operations list.
+/// 
+/// Only the fields needed to verify sharing payments are deserialized;
+/// `from` and `to` are present on payment operations only.
+/// 
+/// # Fields
+/// 
+/// * `type_` - Operation type (e.g. "payment", "create_account")
+/// * `source_account` - Account the operation was executed for
+/// * `from` - Paying account (payment operations)
+/// * `to` - Receiving account (payment operations)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OperationRecord {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub source_account: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+/// Response wrapper for operation queries.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OperationsResponse {
+    #[serde(rename = "_embedded")]
+    pub embedded: EmbeddedOperations,
+}
+
+/// Container for operation records in API responses.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EmbeddedOperations {
+    pub records: Vec<OperationRecord>,
+}
+
 impl StellarClient {
     /// Creates a new Stellar client configured for testnet.
     /// 
@@ -309,16 +444,13 @@ impl StellarClient {
     /// 
     /// # Example
     /// 
-    /// ```rust
+    /// ```rust,ignore
     /// let client = StellarClient::new_testnet();
     /// let account = client.create_test_account().await?;
     /// ```
+    #[allow(dead_code)]
     pub fn new_testnet() -> Self {
-        Self {
-            horizon_url: "https://horizon-testnet.stellar.org".to_string(),
-            client: Client::new(),
-            network_passphrase: "Test SDF Network ; September 2015".to_string(),
-        }
+        Self::from_config(&StellarConfig::testnet())
     }
 
     /// Creates a new Stellar client configured for mainnet.
@@ -338,19 +470,80 @@ impl StellarClient {
     /// 
     /// # Example
     /// 
-    /// ```rust
+    /// ```rust,ignore
     /// let client = StellarClient::new_mainnet();
     /// // Ensure accounts are properly funded before use
     /// ```
     #[allow(dead_code)]
     pub fn new_mainnet() -> Self {
+        Self::from_config(&StellarConfig::mainnet())
+    }
+
+    /// Creates a Stellar client from network configuration.
+    /// 
+    /// Used by the server to connect to the network selected through
+    /// `STELLAR_NETWORK` and related settings, including private standalone
+    /// networks with their own Horizon URL, passphrase and Friendbot.
+    /// 
+    /// # Example
+    /// 
+    /// ```rust,ignore
+    /// let config = StellarConfig::from_env()?;
+    /// let client = StellarClient::from_config(&config);
+    /// client.verify_network().await?;
+    /// ```
+    pub fn from_config(config: &StellarConfig) -> Self {
         Self {
-            horizon_url: "https://horizon.stellar.org".to_string(),
+            horizon_url: config.horizon_url.clone(),
             client: Client::new(),
-            network_passphrase: "Public Global Stellar Network ; September 2015".to_string(),
+            network_passphrase: config.network_passphrase.clone(),
+            friendbot_url: config.friendbot_url.clone(),
+            history_max_pages: config.history_max_pages,
         }
     }
 
+    /// Checks that Horizon serves the network this client signs for.
+    /// 
+    /// Fetches the Horizon root resource and compares its `network_passphrase`
+    /// with the configured one. Transactions signed for another passphrase
+    /// would be rejected by the network, so the server refuses to start
+    /// when they differ.
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(())` - Horizon reports the configured passphrase
+    /// - `Err(Box<dyn Error + Send + Sync>)` - Horizon unreachable or passphrase mismatch
+    pub async fn verify_network(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let url = format!("{}/", self.horizon_url);
+
+        let response = self.client
+            .get(&url)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(format!("Horizon at {} returned {}", self.horizon_url, response.status()).into());
+        }
+
+        let info = response.json::<serde_json::Value>().await?;
+        let passphrase = info
+            .get("network_passphrase")
+            .and_then(|value| value.as_str())
+            .ok_or_else(|| format!("Horizon at {} did not report a network passphrase", self.horizon_url))?;
+
+        if passphrase != self.network_passphrase {
+            return Err(format!(
+                "Horizon at {} serves network '{}' but client is configured for '{}'",
+                self.horizon_url, passphrase, self.network_passphrase
+            )
+            .into());
+        }
+
+        println!("✅ Connected to Stellar network: {}", passphrase);
+        Ok(())
+    }
+
     /// Generates a new Stellar keypair using cryptographically secure random numbers.
     /// 
     /// Creates a fresh Ed25519 keypair suitable for Stellar blockchain operations.
@@ -360,7 +553,7 @@ impl StellarClient {
     /// 
     /// Returns `Result` containing:
     /// - `Ok(StellarAccount)` - New account with public and secret keys
-    /// - `Err(Box<dyn Error>)` - Key generation or encoding error
+    /// - `Err(Box<dyn Error + Send + Sync>)` - Key generation or encoding error
     /// 
     /// # Security
     /// 
@@ -370,12 +563,12 @@ impl StellarClient {
     /// 
     /// # Example
     /// 
-    /// ```rust
+    /// ```rust,ignore
     /// let account = StellarClient::generate_keypair()?;
     /// println!("New account: {}", account.public_key);
     /// // Store secret_key securely!
     /// ```
-    pub fn generate_keypair() -> Result<StellarAccount, Box<dyn Error>> {
+    pub fn generate_keypair() -> Result<StellarAccount, Box<dyn Error + Send + Sync>> {
         // Use OS-provided cryptographically secure random number generator
         let keypair = Keypair::generate(&mut OsRng);
         
@@ -402,15 +595,15 @@ impl StellarClient {
     /// 
     /// Returns `Result` containing:
     /// - `Ok(String)` - Corresponding public key in strkey format
-    /// - `Err(Box<dyn Error>)` - Invalid secret key format or cryptographic error
+    /// - `Err(Box<dyn Error + Send + Sync>)` - Invalid secret key format or cryptographic error
     /// 
     /// # Example
     /// 
-    /// ```rust
+    /// ```rust,ignore
     /// let public_key = StellarClient::get_public_from_secret(&secret_key)?;
     /// assert!(public_key.starts_with('G'));
     /// ```
-    pub fn get_public_from_secret(secret_key: &str) -> Result<String, Box<dyn Error>> {
+    pub fn get_public_from_secret(secret_key: &str) -> Result<String, Box<dyn Error + Send + Sync>> {
         // Parse secret key using stellar-strkey
         let private_key = ed25519::PrivateKey::from_string(secret_key)?;
         
@@ -424,10 +617,30 @@ impl StellarClient {
         Ok(stellar_public.to_string())
     }
 
+    /// Rebuilds the full ed25519 signing keypair from a Stellar secret key.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `secret_key` - Stellar secret key in strkey format (starts with 'S')
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(Keypair)` - Keypair ready for signing transactions
+    /// - `Err(Box<dyn Error + Send + Sync>)` - Invalid secret key format
+    fn keypair_from_secret(secret_key: &str) -> Result<Keypair, Box<dyn Error + Send + Sync>> {
+        let private_key = ed25519::PrivateKey::from_string(secret_key)?;
+        let secret = SecretKey::from_bytes(&private_key.0)?;
+        let public: PublicKey = (&secret).into();
+
+        Ok(Keypair { secret, public })
+    }
+
     /// Funds an account on testnet using Friendbot.
     /// 
-    /// Automatically funds a new account on Stellar testnet using the Friendbot service,
-    /// which provides free XLM for development and testing purposes.
+    /// Automatically funds a new account using the configured Friendbot service,
+    /// which provides free XLM for development and testing purposes. Networks
+    /// without a Friendbot (e.g. mainnet) return `Ok(false)` without funding.
     /// 
     /// # Parameters
     /// 
@@ -438,24 +651,31 @@ impl StellarClient {
     /// Returns `Result` containing:
     /// - `Ok(true)` - Account successfully funded
     /// - `Ok(false)` - Funding failed (account may already exist)
-    /// - `Err(Box<dyn Error>)` - Network or communication error
+    /// - `Err(Box<dyn Error + Send + Sync>)` - Network or communication error
     /// 
     /// # Behavior
     /// 
     /// - Creates the account if it doesn't exist
     /// - Provides initial XLM balance for transactions
     /// - Waits for transaction confirmation (5 seconds)
-    /// - Only works on testnet (mainnet requires manual funding)
+    /// - Only works on networks with a Friendbot (mainnet requires manual funding)
     /// 
     /// # Example
     /// 
-    /// ```rust
+    /// ```rust,ignore
     /// let client = StellarClient::new_testnet();
     /// let account = StellarClient::generate_keypair()?;
     /// let funded = client.fund_testnet_account(&account.public_key).await?;
     /// ```
-    pub async fn fund_testnet_account(&self, public_key: &str) -> Result<bool, Box<dyn Error>> {
-        let url = format!("https://friendbot.stellar.org?addr={}", public_key);
+    pub async fn fund_testnet_account(&self, public_key: &str) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let friendbot_url = match &self.friendbot_url {
+            Some(url) => url,
+            None => {
+                println!("⚠️  No Friendbot configured, account must be funded manually: {}", public_key);
+                return Ok(false);
+            }
+        };
+        let url = format!("{}?addr={}", friendbot_url, public_key);
         
         println!("🤖 Funding testnet account: {}", public_key);
         
@@ -492,7 +712,7 @@ impl StellarClient {
     /// 
     /// Returns `Result` containing:
     /// - `Ok(AccountResponse)` - Complete account information
-    /// - `Err(Box<dyn Error>)` - Account not found or network error
+    /// - `Err(Box<dyn Error + Send + Sync>)` - Account not found or network error
     /// 
     /// # Usage
     /// 
@@ -504,11 +724,11 @@ impl StellarClient {
     /// 
     /// # Example
     /// 
-    /// ```rust
+    /// ```rust,ignore
     /// let account_info = client.get_account(&public_key).await?;
     /// println!("Balance: {} XLM", account_info.balances[0].balance);
     /// ```
-    pub async fn get_account(&self, account_id: &str) -> Result<AccountResponse, Box<dyn Error>> {
+    pub async fn get_account(&self, account_id: &str) -> Result<AccountResponse, Box<dyn Error + Send + Sync>> {
         let url = format!("{}/accounts/{}", self.horizon_url, account_id);
         
         println!("🔍 Fetching account information: {}", account_id);
@@ -539,22 +759,23 @@ impl StellarClient {
     /// 
     /// * `source_secret` - Secret key of the account sharing the process
     /// * `destination_public` - Public key of the recipient account
-    /// * `process_id` - Unique identifier of the process being shared
-    /// * `memo` - Additional metadata to include in the transaction
+    /// * `process_id` - Unique identifier of the process being shared (logged only)
+    /// * `memo_hash` - Share commitment recorded as the transaction's `MEMO_HASH`
     /// 
     /// # Returns
     /// 
     /// Returns `Result` containing:
     /// - `Ok(TransactionResponse)` - Transaction hash and confirmation details
-    /// - `Err(Box<dyn Error>)` - Transaction creation or submission error
+    /// - `Err(Box<dyn Error + Send + Sync>)` - Transaction creation or submission error
     /// 
-    /// # MVP Implementation
+    /// # Transaction Construction
     /// 
-    /// Current implementation simulates transactions for rapid development.
-    /// Production version would:
-    /// - Build actual Stellar transactions with proper signatures
-    /// - Submit to the network and wait for confirmation
-    /// - Handle transaction fees and network conditions
+    /// 1. Loads the source account to obtain its current sequence number
+    /// 2. Verifies the destination account exists (payments require it)
+    /// 3. Builds a one-stroop payment to the destination carrying `memo_hash`
+    ///    as a `MEMO_HASH`
+    /// 4. Signs the envelope under the configured `network_passphrase`
+    /// 5. Submits it to Horizon and returns the confirmed hash and ledger
     /// 
     /// # Blockchain Benefits
     /// 
@@ -565,12 +786,12 @@ impl StellarClient {
     /// 
     /// # Example
     /// 
-    /// ```rust
+    /// ```rust,ignore
     /// let tx_result = client.share_process_transaction(
     ///     &client_secret_key,
     ///     &partner_public_key,
     ///     &process_id,
-    ///     "NDA_SHARE:confidential_project"
+    ///     &commitment::share_commitment(&process_id, &partner_public_key, &salt, &digest)
     /// ).await?;
     /// ```
     pub async fn share_process_transaction(
@@ -578,35 +799,238 @@ impl StellarClient {
         source_secret: &str,
         destination_public: &str,
         process_id: &str,
-        memo: &str,
-    ) -> Result<TransactionResponse, Box<dyn Error>> {
+        memo_hash: &[u8; 32],
+    ) -> Result<TransactionResponse, Box<dyn Error + Send + Sync>> {
         println!("📤 Creating sharing transaction...");
         println!("   Process: {}", process_id);
         println!("   Destination: {}", destination_public);
         
-        // For MVP, simulate a valid transaction
-        // In production, this would build and submit a real transaction
-        
+        self.submit_payment(source_secret, destination_public, Memo::Hash(Hash(*memo_hash))).await
+    }
+
+    /// Records the revocation of a process share on the Stellar blockchain.
+    /// 
+    /// Sends the same one-stroop payment as a share, from the process owner to
+    /// the partner, with a `MEMO_RETURN` containing the hash of the original
+    /// sharing transaction. The confirmed transaction is public, timestamped
+    /// proof of the date access was withdrawn.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `source_secret` - Secret key of the account that shared the process
+    /// * `destination_public` - Public key of the partner losing access
+    /// * `share_transaction_hash` - Hex-encoded hash of the original sharing transaction
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(TransactionResponse)` - Confirmed revocation transaction
+    /// - `Err(Box<dyn Error + Send + Sync>)` - Invalid share hash, network or submission error
+    pub async fn revoke_share_transaction(
+        &self,
+        source_secret: &str,
+        destination_public: &str,
+        share_transaction_hash: &str,
+    ) -> Result<TransactionResponse, Box<dyn Error + Send + Sync>> {
+        println!("📤 Creating revocation transaction...");
+        println!("   Original share: {}", share_transaction_hash);
+        println!("   Destination: {}", destination_public);
+
+        let original: [u8; 32] = hex::decode(share_transaction_hash)?
+            .try_into()
+            .map_err(|_| format!("Invalid transaction hash: {}", share_transaction_hash))?;
+
+        self.submit_payment(source_secret, destination_public, Memo::Return(Hash(original))).await
+    }
+
+    /// Anchors a partner's signature of NDA terms on the Stellar blockchain.
+    /// 
+    /// Sends a one-stroop payment from the partner to the process owner with
+    /// a `MEMO_HASH` committing to the signed terms digest and the signature.
+    /// The confirmed transaction is public, timestamped proof that the terms
+    /// were accepted, submitted by the signing partner's own account.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `source_secret` - Secret key of the signing partner
+    /// * `destination_public` - Public key of the process owner
+    /// * `anchor_hash` - Signature anchor (see [`crate::commitment::signature_anchor`])
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(TransactionResponse)` - Confirmed anchor transaction
+    /// - `Err(Box<dyn Error + Send + Sync>)` - Network or submission error
+    pub async fn anchor_signature_transaction(
+        &self,
+        source_secret: &str,
+        destination_public: &str,
+        anchor_hash: &[u8; 32],
+    ) -> Result<TransactionResponse, Box<dyn Error + Send + Sync>> {
+        println!("📤 Creating signature anchor transaction...");
+        println!("   Destination: {}", destination_public);
+
+        self.submit_payment(source_secret, destination_public, Memo::Hash(Hash(*anchor_hash))).await
+    }
+
+    /// Builds, signs and submits a one-stroop payment carrying `memo`.
+    /// 
+    /// Shared by sharing, revocation and signature anchor transactions: loads the source
+    /// sequence number, checks that the destination exists, signs the
+    /// envelope for the configured network and waits for Horizon's result.
+    async fn submit_payment(
+        &self,
+        source_secret: &str,
+        destination_public: &str,
+        memo: Memo,
+    ) -> Result<TransactionResponse, Box<dyn Error + Send + Sync>> {
+        let keypair = Self::keypair_from_secret(source_secret)?;
         let source_public = Self::get_public_from_secret(source_secret)?;
         
-        // Verify that accounts exist
-        let _source_account = self.get_account(&source_public).await?;
+        // Load the source sequence number and make sure the destination exists
+        let source_account = self.get_account(&source_public).await?;
         let _dest_account = self.get_account(destination_public).await?;
         
-        // Simulate realistic transaction hash
-        let transaction_data = format!("{}:{}:{}:{}", source_public, destination_public, process_id, memo);
-        let mut hasher = Sha256::new();
-        hasher.update(transaction_data.as_bytes());
-        let hash = format!("{:x}", hasher.finalize());
+        let sequence = source_account.sequence.parse::<i64>()
+            .map_err(|e| format!("Invalid sequence number '{}': {}", source_account.sequence, e))?;
         
-        println!("✅ Simulated transaction created: {}", &hash[0..16]);
+        let envelope = self.build_share_envelope(
+            &keypair,
+            sequence + 1,
+            destination_public,
+            memo,
+        )?;
         
-        Ok(TransactionResponse {
-            hash: hash[0..64].to_string(),
-            successful: true,
-            ledger: Some(chrono::Utc::now().timestamp() as u64),
-            result_xdr: None,
-        })
+        let response = self.submit_transaction(&envelope).await?;
+        
+        println!("✅ Transaction confirmed: {} (ledger {:?})", response.hash, response.ledger);
+        
+        Ok(response)
+    }
+
+    /// Builds and signs a sharing transaction envelope.
+    /// 
+    /// The transaction contains a single one-stroop native payment from the
+    /// keypair's account to `destination_public`, with a five minute validity
+    /// window. The signature covers the transaction signature payload for the
+    /// client's network, as required by Stellar consensus.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `keypair` - Signing keypair of the source account
+    /// * `sequence` - Sequence number to use (current account sequence + 1)
+    /// * `destination_public` - Public key of the recipient account
+    /// * `memo` - Memo attached to the transaction
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(String)` - Base64-encoded `TransactionEnvelope` XDR
+    /// - `Err(Box<dyn Error + Send + Sync>)` - Invalid destination key or XDR encoding error
+    fn build_share_envelope(
+        &self,
+        keypair: &Keypair,
+        sequence: i64,
+        destination_public: &str,
+        memo: Memo,
+    ) -> Result<String, Box<dyn Error + Send + Sync>> {
+        let destination = ed25519::PublicKey::from_string(destination_public)?;
+        let max_time = chrono::Utc::now().timestamp() as u64 + TRANSACTION_TIMEOUT_SECS;
+
+        let payment = Operation {
+            source_account: None,
+            body: OperationBody::Payment(PaymentOp {
+                destination: MuxedAccount::Ed25519(Uint256(destination.0)),
+                asset: Asset::Native,
+                amount: SHARE_PAYMENT_AMOUNT,
+            }),
+        };
+
+        let tx = Transaction {
+            source_account: MuxedAccount::Ed25519(Uint256(keypair.public.to_bytes())),
+            fee: BASE_FEE,
+            seq_num: SequenceNumber(sequence),
+            cond: Preconditions::Time(TimeBounds {
+                min_time: TimePoint(0),
+                max_time: TimePoint(max_time),
+            }),
+            memo,
+            operations: vec![payment].try_into()?,
+            ext: TransactionExt::V0,
+        };
+
+        let tx_hash = self.transaction_hash(&tx)?;
+        let signature = keypair.sign(&tx_hash);
+        let public_bytes = keypair.public.to_bytes();
+        let hint = SignatureHint([public_bytes[28], public_bytes[29], public_bytes[30], public_bytes[31]]);
+
+        let envelope = TransactionEnvelope::Tx(TransactionV1Envelope {
+            tx,
+            signatures: vec![DecoratedSignature {
+                hint,
+                signature: Signature(signature.to_bytes().to_vec().try_into()?),
+            }]
+            .try_into()?,
+        });
+
+        Ok(envelope.to_xdr_base64(Limits::none())?)
+    }
+
+    /// Computes the network-specific hash of a transaction.
+    /// 
+    /// This is the SHA-256 of the `TransactionSignaturePayload`, which binds
+    /// the transaction to the network identified by `network_passphrase`.
+    /// It is both the value that gets signed and the transaction hash
+    /// reported by Horizon.
+    fn transaction_hash(&self, tx: &Transaction) -> Result<[u8; 32], Box<dyn Error + Send + Sync>> {
+        let payload = TransactionSignaturePayload {
+            network_id: Hash(Sha256::digest(self.network_passphrase.as_bytes()).into()),
+            tagged_transaction: TransactionSignaturePayloadTaggedTransaction::Tx(tx.clone()),
+        };
+
+        Ok(Sha256::digest(payload.to_xdr(Limits::none())?).into())
+    }
+
+    /// Submits a signed transaction envelope to Horizon.
+    /// 
+    /// Horizon waits for the transaction to be included in a ledger before
+    /// answering, so a successful response carries the final hash, ledger
+    /// number and result XDR.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `envelope_xdr` - Base64-encoded signed `TransactionEnvelope`
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(TransactionResponse)` - Confirmed transaction details
+    /// - `Err(Box<dyn Error + Send + Sync>)` - Network error or rejection, including Horizon's result codes
+    async fn submit_transaction(&self, envelope_xdr: &str) -> Result<TransactionResponse, Box<dyn Error + Send + Sync>> {
+        let url = format!("{}/transactions", self.horizon_url);
+        
+        println!("🚀 Submitting transaction to Horizon...");
+        
+        let response = self.client
+            .post(&url)
+            .form(&[("tx", envelope_xdr)])
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.json::<serde_json::Value>().await.unwrap_or_default();
+            let result_codes = body
+                .pointer("/extras/result_codes")
+                .map(|codes| codes.to_string())
+                .unwrap_or_default();
+            println!("❌ Transaction rejected: {} {}", status, result_codes);
+            return Err(format!("Transaction rejected by Horizon ({}): {}", status, result_codes).into());
+        }
+
+        let transaction = response.json::<TransactionResponse>().await?;
+        
+        Ok(transaction)
     }
 
     /// Verifies if a user has access to a process via blockchain records.
@@ -616,57 +1040,73 @@ impl StellarClient {
     /// 
     /// # Parameters
     /// 
-    /// * `process_id` - Unique identifier of the process to check
+    /// * `memo_hash` - Expected share commitment (or legacy memo hash)
+    /// * `owner_public_key` - Public key of the process owner who must have signed the share
     /// * `user_public_key` - Public key of the user requesting access
+    /// * `not_before` - Optional lower bound for the history search, such as
+    ///   the process creation time (a process cannot be shared before it exists)
     /// 
     /// # Returns
     /// 
     /// Returns `Result` containing:
     /// - `Ok(true)` - User has verified blockchain access to the process
     /// - `Ok(false)` - No blockchain record found granting access
-    /// - `Err(Box<dyn Error>)` - Network error or verification failure
+    /// - `Err(Box<dyn Error + Send + Sync>)` - Network error or verification failure
     /// 
     /// # Verification Process
     /// 
-    /// 1. Retrieves all transactions for the user's account
-    /// 2. Searches transaction memos for process references
-    /// 3. Validates sharing transaction authenticity
-    /// 4. Confirms access permissions are still valid
+    /// 1. Walks the user's transaction history back to `not_before`, up to the
+    ///    configured page limit
+    /// 2. Keeps successful transactions submitted by the process owner
+    /// 3. Matches the transaction's `MEMO_HASH` against the share commitment
+    /// 4. Confirms the transaction paid the user's account
     /// 
     /// # Security Features
     /// 
     /// - **Cryptographic Verification**: Uses blockchain signatures
     /// - **Immutable Records**: Cannot be forged or altered
-    /// - **Complete History**: Checks all relevant transactions
     /// - **Decentralized Trust**: No central authority required
     /// 
     /// # Example
     /// 
-    /// ```rust
+    /// ```rust,ignore
     /// let has_access = client.verify_process_access(
-    ///     &process_id,
-    ///     &partner_public_key
+    ///     &commitment,
+    ///     &owner_public_key,
+    ///     &partner_public_key,
+    ///     Some(process.created_at)
     /// ).await?;
     /// 
     /// if has_access {
     ///     // Grant access to encrypted content
     /// }
     /// ```
-    #[allow(dead_code)]
     pub async fn verify_process_access(
         &self,
-        process_id: &str,
+        memo_hash: &[u8; 32],
+        owner_public_key: &str,
         user_public_key: &str,
-    ) -> Result<bool, Box<dyn Error>> {
-        println!("🔍 Verifying process access: {}", process_id);
+        not_before: Option<DateTime<Utc>>,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        println!("🔍 Verifying process access: {}", hex::encode(memo_hash));
         println!("   User: {}", user_public_key);
         
-        // Fetch user account transactions
-        let transactions = self.get_account_transactions(user_public_key).await?;
+        // Fetch user account transactions back to the lower bound
+        let bounds = TransactionHistoryBounds {
+            min_ledger: None,
+            since: not_before,
+            max_pages: self.history_max_pages,
+        };
+        let transactions = self.get_account_transactions(user_public_key, &bounds).await?;
         
-        // Check if any transaction relates to the process
+        // Check if any transaction shared the process with the user
         for tx in transactions {
-            if self.transaction_contains_process(&tx, process_id)? {
+            if !tx.successful || tx.source_account != owner_public_key {
+                continue;
+            }
+            if self.transaction_contains_process(&tx, memo_hash)?
+                && self.transaction_pays(&tx.hash, owner_public_key, user_public_key).await?
+            {
                 println!("✅ Access verified via blockchain!");
                 return Ok(true);
             }
@@ -676,88 +1116,212 @@ impl StellarClient {
         Ok(false)
     }
 
+    /// Confirms a recorded sharing transaction on the blockchain.
+    /// 
+    /// Looks up the transaction by hash and checks that it was successful,
+    /// was submitted by `source_public`, carries `memo_hash` as its memo
+    /// and contains a payment from `source_public` to `destination_public`.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `transaction_hash` - Hash stored when the process was shared
+    /// * `source_public` - Public key of the process owner
+    /// * `destination_public` - Public key of the partner
+    /// * `memo_hash` - Share commitment (or legacy memo hash) the transaction must carry
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result` containing:
+    /// - `Ok(true)` - Transaction exists and matches every expectation
+    /// - `Ok(false)` - Transaction is unknown to the network or does not match
+    /// - `Err(Box<dyn Error + Send + Sync>)` - Network error or unexpected Horizon response
+    pub async fn verify_share_transaction(
+        &self,
+        transaction_hash: &str,
+        source_public: &str,
+        destination_public: &str,
+        memo_hash: &[u8; 32],
+    ) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        println!("🔍 Confirming sharing transaction on chain: {}", transaction_hash);
+
+        let tx = match self.get_transaction(transaction_hash).await? {
+            Some(tx) => tx,
+            None => {
+                println!("❌ Transaction not found on blockchain");
+                return Ok(false);
+            }
+        };
+
+        let confirmed = tx.successful
+            && tx.source_account == source_public
+            && self.transaction_contains_process(&tx, memo_hash)?
+            && self.transaction_pays(&tx.hash, source_public, destination_public).await?;
+
+        if confirmed {
+            println!("✅ Sharing transaction confirmed (ledger {})", tx.ledger);
+        } else {
+            println!("❌ Transaction does not match the recorded share");
+        }
+
+        Ok(confirmed)
+    }
+
+    /// Retrieves a single transaction by hash.
+    /// 
+    /// Returns `Ok(None)` when Horizon does not know the transaction.
+    async fn get_transaction(&self, transaction_hash: &str) -> Result<Option<TransactionRecord>, Box<dyn Error + Send + Sync>> {
+        let url = format!("{}/transactions/{}", self.horizon_url, transaction_hash);
+
+        let response = self.client
+            .get(&url)
+            .send()
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(format!("Error fetching transaction {}: {}", transaction_hash, response.status()).into());
+        }
+
+        Ok(Some(response.json::<TransactionRecord>().await?))
+    }
+
+    /// Checks whether a transaction contains a payment between two accounts.
+    async fn transaction_pays(
+        &self,
+        transaction_hash: &str,
+        from: &str,
+        to: &str,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let url = format!("{}/transactions/{}/operations", self.horizon_url, transaction_hash);
+
+        let response = self.client
+            .get(&url)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(format!("Error fetching operations of {}: {}", transaction_hash, response.status()).into());
+        }
+
+        let data: OperationsResponse = response.json().await?;
+
+        Ok(data.embedded.records.iter().any(|op| {
+            op.type_ == "payment"
+                && op.from.as_deref() == Some(from)
+                && op.to.as_deref() == Some(to)
+        }))
+    }
+
     /// Retrieves transaction history for an account.
     /// 
-    /// Fetches recent transactions for the specified account from the Stellar network,
-    /// used for access verification and audit trail analysis.
+    /// Fetches transactions for the specified account from the Stellar network,
+    /// newest first, following Horizon's `_links.next` cursor until a page is
+    /// empty or one of the `bounds` is reached. Used for access verification
+    /// and audit trail analysis.
     /// 
     /// # Parameters
     /// 
     /// * `account_id` - Public key of the account to query
+    /// * `bounds` - Ledger/time lower bound and page limit for the walk
     /// 
     /// # Returns
     /// 
     /// Returns `Result` containing:
-    /// - `Ok(Vec<TransactionRecord>)` - List of recent transactions (up to 200)
-    /// - `Err(Box<dyn Error>)` - Network error or invalid account
+    /// - `Ok(Vec<TransactionRecord>)` - Transactions within the bounds (empty for unknown accounts)
+    /// - `Err(Box<dyn Error + Send + Sync>)` - Network error or unexpected Horizon response
     /// 
     /// # Query Parameters
     /// 
-    /// - **Limit**: 200 transactions maximum per query
-    /// - **Order**: Descending (newest first) for recent activity
+    /// - **Limit**: 200 transactions per page (Horizon maximum)
+    /// - **Order**: Descending (newest first), so lower bounds end the walk
     /// - **Filtering**: Returns all transaction types
     /// 
     /// # Privacy Note
     /// 
     /// This is a private method used internally for access verification.
     /// Transaction data is publicly available on the blockchain.
-    #[allow(dead_code)]
-    async fn get_account_transactions(&self, account_id: &str) -> Result<Vec<TransactionRecord>, Box<dyn Error>> {
-        let url = format!("{}/accounts/{}/transactions?limit=200&order=desc", self.horizon_url, account_id);
+    async fn get_account_transactions(
+        &self,
+        account_id: &str,
+        bounds: &TransactionHistoryBounds,
+    ) -> Result<Vec<TransactionRecord>, Box<dyn Error + Send + Sync>> {
+        let mut url = format!("{}/accounts/{}/transactions?limit={}&order=desc", self.horizon_url, account_id, HISTORY_PAGE_LIMIT);
+        let mut records = Vec::new();
+        let mut pages = 0;
         
         println!("🔍 Fetching account transactions...");
         
-        let response = self.client
-            .get(&url)
-            .send()
-            .await?;
+        loop {
+            if bounds.max_pages.is_some_and(|max_pages| pages >= max_pages) {
+                println!("⚠️  Stopped after {} pages of transaction history", pages);
+                break;
+            }
 
-        if !response.status().is_success() {
-            println!("❌ Error fetching transactions: {}", response.status());
-            return Ok(vec![]);
-        }
+            let response = self.client
+                .get(&url)
+                .send()
+                .await?;
+            pages += 1;
+
+            // Accounts that do not exist yet have no history
+            if response.status() == reqwest::StatusCode::NOT_FOUND {
+                break;
+            }
+            if !response.status().is_success() {
+                return Err(format!("Error fetching transactions: {}", response.status()).into());
+            }
+
+            let data: TransactionsResponse = response.json().await?;
+            let page_len = data.embedded.records.len();
+
+            let mut reached_bound = false;
+            for record in data.embedded.records {
+                if !bounds.includes(&record) {
+                    reached_bound = true;
+                    break;
+                }
+                records.push(record);
+            }
 
-        let data: TransactionsResponse = response.json().await?;
+            if reached_bound || page_len < HISTORY_PAGE_LIMIT {
+                break;
+            }
+
+            match data.links.and_then(|links| links.next) {
+                Some(next) => url = next.href,
+                None => break,
+            }
+        }
         
-        println!("✅ Found {} transactions", data.embedded.records.len());
+        println!("✅ Found {} transactions in {} pages", records.len(), pages);
         
-        Ok(data.embedded.records)
+        Ok(records)
     }
 
-    /// Checks if a transaction contains a reference to a specific process.
+    /// Checks if a transaction carries the commitment of a process share.
     /// 
-    /// Searches transaction memo fields for process identifiers to determine
-    /// if the transaction is related to process sharing or access.
+    /// Sharing transactions commit to the share with a `MEMO_HASH`, which
+    /// Horizon returns base64-encoded. Only an exact match of the 32-byte
+    /// value counts; text memos and other memo types never match.
     /// 
     /// # Parameters
     /// 
     /// * `transaction` - Transaction record to examine
-    /// * `process_id` - Process identifier to search for
+    /// * `memo_hash` - Expected share commitment (see [`crate::commitment`])
     /// 
     /// # Returns
     /// 
     /// Returns `Result` containing:
-    /// - `Ok(true)` - Transaction contains process reference
-    /// - `Ok(false)` - No process reference found
-    /// - `Err(Box<dyn Error>)` - Processing error
-    /// 
-    /// # Search Strategy
-    /// 
-    /// Currently searches memo text for simple string containment.
-    /// Production implementations might use:
-    /// - Structured memo formats (JSON, protobuf)
-    /// - Cryptographic commitments
-    /// - Operation-specific metadata
-    #[allow(dead_code)]
-    fn transaction_contains_process(&self, transaction: &TransactionRecord, process_id: &str) -> Result<bool, Box<dyn Error>> {
-        // Check transaction memo field
-        if let Some(memo) = &transaction.memo {
-            if memo.contains(process_id) {
-                return Ok(true);
-            }
+    /// - `Ok(true)` - Transaction memo equals the commitment
+    /// - `Ok(false)` - Memo missing, of another type, or different
+    /// - `Err(Box<dyn Error + Send + Sync>)` - Memo is not valid base64
+    fn transaction_contains_process(&self, transaction: &TransactionRecord, memo_hash: &[u8; 32]) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        match (transaction.memo_type.as_deref(), &transaction.memo) {
+            (Some("hash"), Some(memo)) => Ok(general_purpose::STANDARD.decode(memo)? == memo_hash),
+            _ => Ok(false),
         }
-        
-        Ok(false)
     }
 
     /// Tests connectivity to the Stellar network.
@@ -770,7 +1334,7 @@ impl StellarClient {
     /// Returns `Result` containing:
     /// - `Ok(true)` - Connection successful, network reachable
     /// - `Ok(false)` - Connection failed, network unreachable
-    /// - `Err(Box<dyn Error>)` - Network error or parsing failure
+    /// - `Err(Box<dyn Error + Send + Sync>)` - Network error or parsing failure
     /// 
     /// # Network Information
     /// 
@@ -788,14 +1352,14 @@ impl StellarClient {
     /// 
     /// # Example
     /// 
-    /// ```rust
+    /// ```rust,ignore
     /// let client = StellarClient::new_testnet();
     /// if !client.test_connection().await? {
     ///     return Err("Cannot connect to Stellar network".into());
     /// }
     /// ```
     #[allow(dead_code)]
-    pub async fn test_connection(&self) -> Result<bool, Box<dyn Error>> {
+    pub async fn test_connection(&self) -> Result<bool, Box<dyn Error + Send + Sync>> {
         let url = format!("{}/", self.horizon_url);
         
         println!("🌐 Testing connection to Stellar network...");
@@ -828,7 +1392,7 @@ impl StellarClient {
 
     /// Cria conta de teste e financia automaticamente
     #[allow(dead_code)]
-    pub async fn create_test_account(&self) -> Result<StellarAccount, Box<dyn Error>> {
+    pub async fn create_test_account(&self) -> Result<StellarAccount, Box<dyn Error + Send + Sync>> {
         println!("🧪 Criando conta de teste...");
         
         // Gerar keypair
@@ -854,7 +1418,7 @@ impl StellarClient {
 
     /// Obtém saldo XLM de uma conta
     #[allow(dead_code)]
-    pub async fn get_xlm_balance(&self, account_id: &str) -> Result<String, Box<dyn Error>> {
+    pub async fn get_xlm_balance(&self, account_id: &str) -> Result<String, Box<dyn Error + Send + Sync>> {
         let account = self.get_account(account_id).await?;
         
         for balance in account.balances {
@@ -865,4 +1429,105 @@ impl StellarClient {
         
         Ok("0".to_string())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::Verifier;
+    use stellar_xdr::curr::ReadXdr;
+
+    #[test]
+    fn test_share_envelope_is_signed_for_network() {
+        let client = StellarClient::new_testnet();
+        let source = StellarClient::generate_keypair().unwrap();
+        let partner = StellarClient::generate_keypair().unwrap();
+        let keypair = StellarClient::keypair_from_secret(&source.secret_key).unwrap();
+
+        let memo = Memo::Hash(Hash([9u8; 32]));
+        let envelope_xdr = client
+            .build_share_envelope(&keypair, 42, &partner.public_key, memo.clone())
+            .unwrap();
+
+        let envelope = TransactionEnvelope::from_xdr_base64(envelope_xdr, Limits::none()).unwrap();
+        let TransactionEnvelope::Tx(envelope) = envelope else {
+            panic!("expected a v1 transaction envelope");
+        };
+
+        assert_eq!(envelope.tx.seq_num, SequenceNumber(42));
+        assert_eq!(envelope.tx.memo, memo);
+        assert_eq!(envelope.signatures.len(), 1);
+
+        let OperationBody::Payment(payment) = &envelope.tx.operations[0].body else {
+            panic!("expected a payment operation");
+        };
+        let partner_key = ed25519::PublicKey::from_string(&partner.public_key).unwrap();
+        assert_eq!(payment.destination, MuxedAccount::Ed25519(Uint256(partner_key.0)));
+
+        // Signature must verify against the network-specific transaction hash
+        let tx_hash = client.transaction_hash(&envelope.tx).unwrap();
+        let signature = ed25519_dalek::Signature::from_bytes(envelope.signatures[0].signature.0.as_slice()).unwrap();
+        assert!(keypair.public.verify(&tx_hash, &signature).is_ok());
+
+        // The same transaction hashes differently on another network
+        let mainnet_hash = StellarClient::new_mainnet().transaction_hash(&envelope.tx).unwrap();
+        assert_ne!(tx_hash, mainnet_hash);
+    }
+
+    #[test]
+    fn test_only_matching_hash_memo_contains_process() {
+        let client = StellarClient::new_testnet();
+        let commitment = [3u8; 32];
+        let record = |memo_type: &str, memo: &str| TransactionRecord {
+            id: "1".to_string(),
+            hash: "aa".to_string(),
+            ledger: 1,
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            source_account: "GA".to_string(),
+            memo: Some(memo.to_string()),
+            memo_type: Some(memo_type.to_string()),
+            successful: true,
+        };
+
+        let encoded = general_purpose::STANDARD.encode(commitment);
+        assert!(client.transaction_contains_process(&record("hash", &encoded), &commitment).unwrap());
+        assert!(!client.transaction_contains_process(&record("hash", &encoded), &[4u8; 32]).unwrap());
+        assert!(!client.transaction_contains_process(&record("text", &encoded), &commitment).unwrap());
+    }
+
+    #[test]
+    fn test_history_page_links_and_bounds() {
+        let page: TransactionsResponse = serde_json::from_str(r#"{
+            "_links": {
+                "next": { "href": "https://horizon-testnet.stellar.org/accounts/GA/transactions?cursor=123&limit=200&order=desc" }
+            },
+            "_embedded": {
+                "records": [
+                    { "id": "1", "hash": "aa", "ledger": 120, "created_at": "2024-03-01T00:00:00Z",
+                      "source_account": "GA", "memo_type": "none", "successful": true },
+                    { "id": "2", "hash": "bb", "ledger": 90, "created_at": "2024-01-01T00:00:00Z",
+                      "source_account": "GA", "memo_type": "none", "successful": true }
+                ]
+            }
+        }"#).unwrap();
+
+        let next = page.links.and_then(|links| links.next).unwrap();
+        assert!(next.href.contains("cursor=123"));
+
+        let [newer, older] = &page.embedded.records[..] else { panic!("expected two records") };
+
+        let unbounded = TransactionHistoryBounds::default();
+        assert!(unbounded.includes(newer) && unbounded.includes(older));
+
+        let by_ledger = TransactionHistoryBounds { min_ledger: Some(100), ..Default::default() };
+        assert!(by_ledger.includes(newer));
+        assert!(!by_ledger.includes(older));
+
+        let by_time = TransactionHistoryBounds {
+            since: Some("2024-02-01T00:00:00Z".parse().unwrap()),
+            ..Default::default()
+        };
+        assert!(by_time.includes(newer));
+        assert!(!by_time.includes(older));
+    }
+}
@@ -0,0 +1,607 @@
+//! # Background Tasks Module
+//!
+//! This module contains periodic jobs that run alongside the HTTP server.
+//!
+//! ## Share Expiry
+//!
+//! Shares can carry an `expires_at` date. Access checks reject expired shares
+//! immediately, but the share row is only marked (`expired_at`) by
+//! [`spawn_share_expiry_task`], which also notifies the process owner with a
+//! `share_expired` notification. Expired shares without a notification are
+//! picked up again on the next run.
+//!
+//! The interval is configured with `SHARE_EXPIRY_INTERVAL_SECS`
+//! (default [`DEFAULT_SHARE_EXPIRY_INTERVAL_SECS`]).
+//!
+//! ## Content Binding
+//!
+//! [`bind_process_content`] runs once at startup, before any request is
+//! served, and binds content written before process binding existed (legacy
+//! format or version 1 envelope) to its process as associated data. Every
+//! revision is bound to the process row holding it at that moment, so a
+//! content/key pair swapped between rows before the upgrade cannot be told
+//! apart; after the migration is recorded in `data_migrations`, unbound
+//! content is never bound again and is reported as an integrity error.
+//!
+//! ## Content Digests
+//!
+//! NDA terms cover the SHA-256 of the plaintext content, stored in
+//! `processes.content_sha256` when a revision is written (see
+//! [`crate::terms`]). [`digest_process_content`] computes it at startup for
+//! rows written before it was stored, so terms are never built by
+//! decrypting content while requests are served.
+//!
+//! ## Content Re-encryption
+//!
+//! [`spawn_reencryption_task`] upgrades bound process rows to the configured
+//! cipher: content under a cipher other than `CONTENT_CIPHER` is re-encrypted
+//! with the same data key and binding, and data keys wrapped in the legacy
+//! format are re-wrapped. The matching current revision in
+//! `process_revisions` is updated along with the process row. Rows are
+//! processed in small batches, each row updated only if it did not change
+//! since it was read. Unbound rows are logged as integrity errors and left
+//! untouched.
+//!
+//! The interval is configured with `REENCRYPTION_INTERVAL_SECS`
+//! (default [`DEFAULT_REENCRYPTION_INTERVAL_SECS`]).
+
+use chrono::{DateTime, Utc};
+use sqlx::{Row, SqlitePool};
+
+use crate::crypto::{decrypt_content, encrypt_content_with, envelope_header, is_bound, process_aad, ContentCipher};
+use crate::database::queries;
+use crate::keys::KeyRing;
+use crate::terms;
+
+/// Default interval between share expiry runs, in seconds.
+pub const DEFAULT_SHARE_EXPIRY_INTERVAL_SECS: u64 = 60;
+
+/// Notification kind emitted when a share expires.
+pub const SHARE_EXPIRED_NOTIFICATION: &str = "share_expired";
+
+/// Default interval between content re-encryption runs, in seconds.
+pub const DEFAULT_REENCRYPTION_INTERVAL_SECS: u64 = 3600;
+
+/// Number of process rows read per re-encryption batch.
+const REENCRYPTION_BATCH_SIZE: i64 = 100;
+
+/// Name of the content binding migration in `data_migrations`.
+pub const BIND_CONTENT_MIGRATION: &str = "bind_process_content";
+
+/// Reads a positive interval in seconds from `var`, falling back to `default`.
+fn interval_from_env(var: &str, default: u64) -> Result<u64, String> {
+    match std::env::var(var) {
+        Ok(value) => match value.trim().parse::<u64>() {
+            Ok(secs) if secs > 0 => Ok(secs),
+            _ => Err(format!(
+                "Invalid {} '{}' (expected a positive number of seconds)",
+                var, value
+            )),
+        },
+        Err(_) => Ok(default),
+    }
+}
+
+/// Reads the share expiry interval from `SHARE_EXPIRY_INTERVAL_SECS`.
+pub fn share_expiry_interval_from_env() -> Result<u64, String> {
+    interval_from_env("SHARE_EXPIRY_INTERVAL_SECS", DEFAULT_SHARE_EXPIRY_INTERVAL_SECS)
+}
+
+/// Reads the content re-encryption interval from `REENCRYPTION_INTERVAL_SECS`.
+pub fn reencryption_interval_from_env() -> Result<u64, String> {
+    interval_from_env("REENCRYPTION_INTERVAL_SECS", DEFAULT_REENCRYPTION_INTERVAL_SECS)
+}
+
+/// Marks shares expired at `now` and notifies the owners of their processes.
+///
+/// Notifications are sent for every expired share that does not have one yet,
+/// so a run that fails after marking shares is completed by the next run.
+///
+/// # Returns
+///
+/// Returns `Result` containing:
+/// - `Ok(usize)` - Number of expired shares whose owners were notified
+/// - `Err(sqlx::Error)` - Database connection or query error
+pub async fn expire_shares(pool: &SqlitePool, now: DateTime<Utc>) -> Result<usize, sqlx::Error> {
+    queries::expire_process_shares(pool, now).await?;
+
+    let expired = queries::list_unnotified_expired_shares(pool, SHARE_EXPIRED_NOTIFICATION).await?;
+    let mut notified = 0;
+
+    for share in &expired {
+        let process = match queries::find_process_by_id(pool, &share.process_id).await? {
+            Some(process) => process,
+            None => {
+                tracing::warn!("Expired share {} references missing process {}", share.id, share.process_id);
+                continue;
+            }
+        };
+
+        let message = format!(
+            "Share of '{}' with {} expired",
+            process.title, share.partner_public_key
+        );
+        queries::create_notification(
+            pool,
+            &process.client_id,
+            SHARE_EXPIRED_NOTIFICATION,
+            &share.process_id,
+            Some(&share.id),
+            &message,
+        )
+        .await?;
+
+        println!("⏰ Share {} expired", share.id);
+        notified += 1;
+    }
+
+    Ok(notified)
+}
+
+/// Starts a background task that periodically expires shares.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `interval_secs` - How often to check for expired shares (in seconds)
+///
+/// # Returns
+///
+/// Returns a tokio task handle that can be used to cancel the task.
+pub fn spawn_share_expiry_task(pool: SqlitePool, interval_secs: u64) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(interval_secs));
+        loop {
+            interval.tick().await;
+            match expire_shares(&pool, Utc::now()).await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!("Notified owners of {} expired shares", count),
+                Err(e) => tracing::error!("Share expiry task failed: {}", e),
+            }
+        }
+    })
+}
+
+/// Binds process content written before content binding to its process.
+///
+/// Runs once: every unbound revision, and the process row holding the
+/// current one, is decrypted without associated data and re-encrypted with
+/// [`process_aad`] of its process and version under the same data key. The
+/// migration is then recorded in `data_migrations`, and later calls return
+/// without reading any row. Rows that fail to decrypt are logged and left
+/// unbound, so reading them reports an integrity error.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `keyring` - Key ring able to unwrap the stored data keys
+/// * `cipher` - Cipher the bound content is encrypted with
+///
+/// # Returns
+///
+/// Returns `Result` containing:
+/// - `Ok(usize)` - Number of revisions bound
+/// - `Err(sqlx::Error)` - Database connection or query error
+pub async fn bind_process_content(
+    pool: &SqlitePool,
+    keyring: &KeyRing,
+    cipher: ContentCipher,
+) -> Result<usize, sqlx::Error> {
+    let applied: Option<String> = sqlx::query_scalar("SELECT applied_at FROM data_migrations WHERE name = ?1")
+        .bind(BIND_CONTENT_MIGRATION)
+        .fetch_optional(pool)
+        .await?;
+    if applied.is_some() {
+        return Ok(0);
+    }
+
+    let mut bound = 0;
+    let processes = sqlx::query("SELECT id, client_id, encrypted_content, encryption_key, key_id, content_version FROM processes")
+        .fetch_all(pool)
+        .await?;
+
+    for process in &processes {
+        let id: String = process.get("id");
+        let client_id: String = process.get("client_id");
+        let current_content: String = process.get("encrypted_content");
+        let content_version: i64 = process.get("content_version");
+        let stored_key: String = process.get("encryption_key");
+        let key_id: Option<String> = process.get("key_id");
+
+        let revisions = sqlx::query("SELECT version, encrypted_content FROM process_revisions WHERE process_id = ?1")
+            .bind(&id)
+            .fetch_all(pool)
+            .await?;
+        let unbound: Vec<(i64, String)> = revisions
+            .iter()
+            .map(|row| (row.get("version"), row.get("encrypted_content")))
+            .filter(|(_, content): &(i64, String)| !is_bound(content))
+            .collect();
+        if unbound.is_empty() && is_bound(&current_content) {
+            continue;
+        }
+
+        let data_key = match keyring.unwrap_key(key_id.as_deref(), &stored_key) {
+            Ok(data_key) => data_key,
+            Err(e) => {
+                tracing::error!("Cannot bind content of process {}: {}", id, e);
+                continue;
+            }
+        };
+        let bind = |version: i64, content: &str| -> Result<String, String> {
+            let plaintext = decrypt_content(content, &data_key, b"").map_err(|e| e.to_string())?;
+            encrypt_content_with(&plaintext, &data_key, cipher, &process_aad(&id, &client_id, version))
+                .map_err(|e| e.to_string())
+        };
+
+        let mut tx = pool.begin().await?;
+        for (version, content) in &unbound {
+            match bind(*version, content) {
+                Ok(bound_content) => {
+                    sqlx::query("UPDATE process_revisions SET encrypted_content = ?1 WHERE process_id = ?2 AND version = ?3")
+                        .bind(&bound_content)
+                        .bind(&id)
+                        .bind(version)
+                        .execute(&mut *tx)
+                        .await?;
+                    if *version == content_version && *content == current_content {
+                        sqlx::query("UPDATE processes SET encrypted_content = ?1 WHERE id = ?2")
+                            .bind(&bound_content)
+                            .bind(&id)
+                            .execute(&mut *tx)
+                            .await?;
+                    }
+                    bound += 1;
+                }
+                Err(e) => tracing::error!("Cannot bind revision {} of process {}: {}", version, id, e),
+            }
+        }
+        tx.commit().await?;
+    }
+
+    sqlx::query("INSERT INTO data_migrations (name, applied_at) VALUES (?1, ?2)")
+        .bind(BIND_CONTENT_MIGRATION)
+        .bind(Utc::now().to_rfc3339())
+        .execute(pool)
+        .await?;
+
+    Ok(bound)
+}
+
+/// Stores the plaintext digest of the current revision of older process rows.
+///
+/// Decrypts the current content of every process without `content_sha256`
+/// and stores its [`terms::content_sha256`], unless the process was amended
+/// in the meantime. Rows that fail to decrypt are logged and left without a
+/// digest, so their terms cannot be built.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `keyring` - Key ring able to unwrap the stored data keys
+///
+/// # Returns
+///
+/// Returns `Result` containing:
+/// - `Ok(usize)` - Number of process rows given a digest
+/// - `Err(sqlx::Error)` - Database connection or query error
+pub async fn digest_process_content(pool: &SqlitePool, keyring: &KeyRing) -> Result<usize, sqlx::Error> {
+    let rows = sqlx::query(
+        "SELECT id, client_id, encrypted_content, encryption_key, key_id, content_version FROM processes \
+         WHERE content_sha256 IS NULL",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut digested = 0;
+    for row in &rows {
+        let id: String = row.get("id");
+        let content_version: i64 = row.get("content_version");
+        let key_id: Option<String> = row.get("key_id");
+        let stored_key: String = row.get("encryption_key");
+        let encrypted_content: String = row.get("encrypted_content");
+
+        let content = keyring
+            .unwrap_key(key_id.as_deref(), &stored_key)
+            .map_err(|e| e.to_string())
+            .and_then(|data_key| {
+                let aad = process_aad(&id, row.get("client_id"), content_version);
+                decrypt_content(&encrypted_content, &data_key, &aad).map_err(|e| e.to_string())
+            });
+        let content = match content {
+            Ok(content) => content,
+            Err(e) => {
+                tracing::error!("Cannot compute the content digest of process {}: {}", id, e);
+                continue;
+            }
+        };
+
+        let result = sqlx::query(
+            "UPDATE processes SET content_sha256 = ?1 WHERE id = ?2 AND content_version = ?3 AND content_sha256 IS NULL",
+        )
+        .bind(hex::encode(terms::content_sha256(&content)))
+        .bind(&id)
+        .bind(content_version)
+        .execute(pool)
+        .await?;
+        digested += result.rows_affected() as usize;
+    }
+
+    Ok(digested)
+}
+
+/// Upgrades bound process rows to the configured cipher.
+///
+/// Rows that fail to decrypt are logged and skipped, so one damaged row
+/// does not block the upgrade of the others. Content that is not bound to
+/// its process is never bound here (see [`bind_process_content`]); it is
+/// logged as an integrity error and skipped.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `keyring` - Key ring able to unwrap the stored data keys
+/// * `cipher` - Cipher process content should be encrypted with
+///
+/// # Returns
+///
+/// Returns `Result` containing:
+/// - `Ok(usize)` - Number of process rows upgraded
+/// - `Err(sqlx::Error)` - Database connection or query error
+pub async fn reencrypt_processes(
+    pool: &SqlitePool,
+    keyring: &KeyRing,
+    cipher: ContentCipher,
+) -> Result<usize, sqlx::Error> {
+    let mut upgraded = 0;
+    let mut last_id = String::new();
+
+    loop {
+        let rows = sqlx::query(
+            "SELECT id, client_id, encrypted_content, encryption_key, key_id, content_version FROM processes \
+             WHERE id > ?1 ORDER BY id LIMIT ?2",
+        )
+        .bind(&last_id)
+        .bind(REENCRYPTION_BATCH_SIZE)
+        .fetch_all(pool)
+        .await?;
+
+        let Some(last) = rows.last() else { break };
+        last_id = last.get("id");
+
+        for row in &rows {
+            let id: String = row.get("id");
+            let encrypted_content: String = row.get("encrypted_content");
+            let stored_key: String = row.get("encryption_key");
+            let key_id: Option<String> = row.get("key_id");
+            let content_version: i64 = row.get("content_version");
+            let aad = process_aad(&id, row.get("client_id"), content_version);
+
+            if !is_bound(&encrypted_content) {
+                tracing::error!(
+                    target: "security",
+                    "Content of process {} is not bound to it; integrity check failed, row left untouched",
+                    id
+                );
+                continue;
+            }
+            let content_outdated = envelope_header(&encrypted_content).is_none_or(|header| header.cipher != cipher);
+            // Legacy plaintext keys (no key ID) are wrapped at startup instead
+            let key_outdated = key_id.is_some() && envelope_header(&stored_key).is_none();
+            if !content_outdated && !key_outdated {
+                continue;
+            }
+
+            let upgrade = (|| -> Result<(String, String, Option<String>), String> {
+                let data_key = keyring.unwrap_key(key_id.as_deref(), &stored_key).map_err(|e| e.to_string())?;
+                let content = if content_outdated {
+                    let plaintext = decrypt_content(&encrypted_content, &data_key, &aad).map_err(|e| e.to_string())?;
+                    encrypt_content_with(&plaintext, &data_key, cipher, &aad).map_err(|e| e.to_string())?
+                } else {
+                    encrypted_content.clone()
+                };
+                if key_outdated {
+                    let wrapped = keyring.wrap_key(&data_key).map_err(|e| e.to_string())?;
+                    Ok((content, wrapped.wrapped_key, Some(wrapped.key_id)))
+                } else {
+                    Ok((content, stored_key.clone(), key_id.clone()))
+                }
+            })();
+
+            let (content, wrapped_key, new_key_id) = match upgrade {
+                Ok(values) => values,
+                Err(e) => {
+                    tracing::warn!("Cannot re-encrypt process {}: {}", id, e);
+                    continue;
+                }
+            };
+
+            // Skip rows changed (e.g. rotated or amended) since they were read
+            let mut tx = pool.begin().await?;
+            let result = sqlx::query(
+                "UPDATE processes SET encrypted_content = ?1, encryption_key = ?2, key_id = ?3 \
+                 WHERE id = ?4 AND encrypted_content = ?5 AND encryption_key = ?6",
+            )
+            .bind(&content)
+            .bind(&wrapped_key)
+            .bind(&new_key_id)
+            .bind(&id)
+            .bind(&encrypted_content)
+            .bind(&stored_key)
+            .execute(&mut *tx)
+            .await?;
+
+            if result.rows_affected() > 0 {
+                // Keep the current revision in step with the process row
+                sqlx::query(
+                    "UPDATE process_revisions SET encrypted_content = ?1 \
+                     WHERE process_id = ?2 AND version = ?3 AND encrypted_content = ?4",
+                )
+                .bind(&content)
+                .bind(&id)
+                .bind(content_version)
+                .bind(&encrypted_content)
+                .execute(&mut *tx)
+                .await?;
+                upgraded += 1;
+            }
+            tx.commit().await?;
+        }
+    }
+
+    Ok(upgraded)
+}
+
+/// Starts a background task that periodically re-encrypts outdated process rows.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `keyring` - Key ring able to unwrap the stored data keys
+/// * `cipher` - Cipher process content should be encrypted with
+/// * `interval_secs` - How often to look for outdated rows (in seconds)
+///
+/// # Returns
+///
+/// Returns a tokio task handle that can be used to cancel the task.
+pub fn spawn_reencryption_task(
+    pool: SqlitePool,
+    keyring: KeyRing,
+    cipher: ContentCipher,
+    interval_secs: u64,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(interval_secs));
+        loop {
+            interval.tick().await;
+            match reencrypt_processes(&pool, &keyring, cipher).await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!("Re-encrypted {} process rows", count),
+                Err(e) => tracing::error!("Content re-encryption task failed: {}", e),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::generate_key;
+    use crate::database::run_migrations;
+    use crate::keys::KeyEncryptionKey;
+    use crate::models::ProcessStatus;
+    use aes_gcm::{aead::{Aead, KeyInit}, Aes256Gcm, Nonce};
+    use base64::{engine::general_purpose, Engine as _};
+
+    /// Encrypts in the `nonce || ciphertext` format written before the envelope existed.
+    fn encrypt_legacy(content: &str, key: &str) -> String {
+        let cipher = Aes256Gcm::new_from_slice(&general_purpose::STANDARD.decode(key).unwrap()).unwrap();
+        let nonce = [7u8; 12];
+        let mut data = nonce.to_vec();
+        data.extend(cipher.encrypt(Nonce::from_slice(&nonce), content.as_bytes()).unwrap());
+        general_purpose::STANDARD.encode(data)
+    }
+
+    #[tokio::test]
+    async fn test_legacy_rows_are_bound_once_then_reencrypted() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        run_migrations(&pool).await.unwrap();
+
+        let kek = KeyEncryptionKey::generate();
+        let keyring = KeyRing::new(kek.clone());
+        let data_key = generate_key();
+        let legacy_wrapped = encrypt_legacy(&data_key, kek.to_base64());
+        let legacy = queries::create_process(
+            &pool,
+            "process-1",
+            "client",
+            "Legacy",
+            "",
+            &encrypt_legacy("Confidential", &data_key),
+            &hex::encode(terms::content_sha256("Confidential")),
+            &legacy_wrapped,
+            Some(kek.id()),
+            ProcessStatus::Active,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let cipher = ContentCipher::XChaCha20Poly1305;
+        assert_eq!(bind_process_content(&pool, &keyring, cipher).await.unwrap(), 1);
+        assert_eq!(bind_process_content(&pool, &keyring, cipher).await.unwrap(), 0);
+        let revision = queries::find_process_revision(&pool, &legacy.id, 1).await.unwrap().unwrap().1;
+        assert!(is_bound(&revision));
+
+        // The legacy wrapped key is upgraded by the periodic task
+        assert_eq!(reencrypt_processes(&pool, &keyring, cipher).await.unwrap(), 1);
+        assert_eq!(reencrypt_processes(&pool, &keyring, cipher).await.unwrap(), 0);
+
+        let process = queries::find_process_by_id(&pool, &legacy.id).await.unwrap().unwrap();
+        assert_eq!(envelope_header(&process.encrypted_content).unwrap().cipher, cipher);
+        assert!(envelope_header(&process.encryption_key).is_some());
+        let unwrapped = keyring.unwrap_key(process.key_id.as_deref(), &process.encryption_key).unwrap();
+        assert_eq!(unwrapped, data_key);
+        let aad = process_aad("process-1", "client", 1);
+        assert!(is_bound(&process.encrypted_content));
+        assert_eq!(decrypt_content(&process.encrypted_content, &unwrapped, &aad).unwrap(), "Confidential");
+
+        // Rows from before content digests were stored get one computed from their plaintext
+        sqlx::query("UPDATE processes SET content_sha256 = NULL").execute(&pool).await.unwrap();
+        assert_eq!(digest_process_content(&pool, &keyring).await.unwrap(), 1);
+        assert_eq!(digest_process_content(&pool, &keyring).await.unwrap(), 0);
+        let process = queries::find_process_by_id(&pool, &legacy.id).await.unwrap().unwrap();
+        assert_eq!(process.content_sha256, Some(hex::encode(terms::content_sha256("Confidential"))));
+
+        // Switching the configured cipher upgrades the row again
+        assert_eq!(reencrypt_processes(&pool, &keyring, ContentCipher::Aes256Gcm).await.unwrap(), 1);
+
+        // Content copied to another process is neither re-bound nor readable there
+        let process = queries::find_process_by_id(&pool, &legacy.id).await.unwrap().unwrap();
+        queries::create_process(
+            &pool,
+            "process-2",
+            "client",
+            "Copy",
+            "",
+            &process.encrypted_content,
+            &hex::encode(terms::content_sha256("Confidential")),
+            &process.encryption_key,
+            process.key_id.as_deref(),
+            ProcessStatus::Active,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(reencrypt_processes(&pool, &keyring, cipher).await.unwrap(), 1);
+        let copy = queries::find_process_by_id(&pool, "process-2").await.unwrap().unwrap();
+        assert_eq!(copy.encrypted_content, process.encrypted_content);
+
+        // Unbound content appearing after the migration is never bound
+        let unbound = encrypt_legacy("Swapped in", &data_key);
+        queries::create_process(
+            &pool,
+            "process-3",
+            "client",
+            "Restored",
+            "",
+            &unbound,
+            &hex::encode(terms::content_sha256("Swapped in")),
+            &process.encryption_key,
+            process.key_id.as_deref(),
+            ProcessStatus::Active,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(bind_process_content(&pool, &keyring, cipher).await.unwrap(), 0);
+        assert_eq!(reencrypt_processes(&pool, &keyring, cipher).await.unwrap(), 0);
+        let restored = queries::find_process_by_id(&pool, "process-3").await.unwrap().unwrap();
+        assert_eq!(restored.encrypted_content, unbound);
+        assert!(decrypt_content(&restored.encrypted_content, &data_key, &process_aad("process-3", "client", 1)).is_err());
+    }
+}
@@ -0,0 +1,323 @@
+//! # NDA Templates Module
+//!
+//! Clients keep the NDAs they write over and over as templates whose body
+//! contains typed placeholders. A process created from a template gets its
+//! confidential content rendered from the template and a map of values, and
+//! records the template version it came from.
+//!
+//! ## Syntax
+//!
+//! ```text
+//! This Agreement is made on {{effective_date}} between {{disclosing_party}}
+//! and {{receiving_party}}, and remains in force for {{term_months}} months
+//! under the laws of {{jurisdiction}}.
+//! ```
+//!
+//! Placeholders are written `{{name}}` (surrounding spaces are allowed) and
+//! every name used in the body must be declared with a
+//! [`TemplatePlaceholder`]. Declared placeholders must also appear in the
+//! body, so a template cannot silently ask for values it ignores.
+//!
+//! ## Validation
+//!
+//! [`validate_template`] checks a template when it is saved; [`render`]
+//! checks the values against the placeholder types before substituting
+//! them, so invalid values never reach the encrypted content. Errors list
+//! every problem found, not just the first one.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::NaiveDate;
+use serde_json::Value;
+
+use crate::models::{PlaceholderKind, TemplatePlaceholder};
+
+/// Template or template values that cannot be used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateError(Vec<String>);
+
+impl TemplateError {
+    /// Problems found, one per entry.
+    #[allow(dead_code)]
+    pub fn problems(&self) -> &[String] {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0.join("; "))
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+/// Piece of a parsed template body.
+enum Segment<'a> {
+    Text(&'a str),
+    Placeholder(&'a str),
+}
+
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn parse(body: &str) -> Result<Vec<Segment<'_>>, TemplateError> {
+    let mut segments = Vec::new();
+    let mut rest = body;
+
+    while let Some(start) = rest.find("{{") {
+        segments.push(Segment::Text(&rest[..start]));
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| TemplateError(vec!["Unclosed '{{' in template body".to_string()]))?;
+        let name = after[..end].trim();
+        if !is_valid_name(name) {
+            return Err(TemplateError(vec![format!("Invalid placeholder name '{}'", name)]));
+        }
+        segments.push(Segment::Placeholder(name));
+        rest = &after[end + 2..];
+    }
+    segments.push(Segment::Text(rest));
+
+    Ok(segments)
+}
+
+/// Lists the placeholder names used in a template body, in order of first use.
+pub fn placeholder_names(body: &str) -> Result<Vec<String>, TemplateError> {
+    let mut names: Vec<String> = Vec::new();
+    for segment in parse(body)? {
+        if let Segment::Placeholder(name) = segment {
+            if !names.iter().any(|known| known == name) {
+                names.push(name.to_string());
+            }
+        }
+    }
+    Ok(names)
+}
+
+/// Checks that a template body and its placeholder declarations match.
+///
+/// # Parameters
+///
+/// * `body` - Template body with `{{name}}` placeholders
+/// * `placeholders` - Declared placeholders
+///
+/// # Returns
+///
+/// Returns `Ok(())` for a usable template, or a `TemplateError` listing
+/// malformed syntax, invalid or duplicate declarations, placeholders used
+/// but not declared and placeholders declared but not used.
+pub fn validate_template(body: &str, placeholders: &[TemplatePlaceholder]) -> Result<(), TemplateError> {
+    let used = placeholder_names(body)?;
+    let mut problems = Vec::new();
+    let mut declared = HashSet::new();
+
+    for placeholder in placeholders {
+        if !is_valid_name(&placeholder.name) {
+            problems.push(format!("Invalid placeholder name '{}'", placeholder.name));
+        }
+        if !declared.insert(placeholder.name.as_str()) {
+            problems.push(format!("Placeholder '{}' is declared twice", placeholder.name));
+        }
+        match placeholder.kind {
+            PlaceholderKind::Choice if placeholder.options.is_empty() => {
+                problems.push(format!("Choice placeholder '{}' has no options", placeholder.name));
+            }
+            PlaceholderKind::Choice => {}
+            _ if !placeholder.options.is_empty() => {
+                problems.push(format!("Only choice placeholders take options, '{}' does not", placeholder.name));
+            }
+            _ => {}
+        }
+        if !used.contains(&placeholder.name) {
+            problems.push(format!("Placeholder '{}' is not used in the body", placeholder.name));
+        }
+    }
+    for name in &used {
+        if !declared.contains(name.as_str()) {
+            problems.push(format!("Placeholder '{}' is used but not declared", name));
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(TemplateError(problems))
+    }
+}
+
+/// Checks a value against its placeholder and formats it for the body.
+fn render_value(placeholder: &TemplatePlaceholder, value: &Value) -> Result<String, String> {
+    let name = &placeholder.name;
+    match (placeholder.kind, value) {
+        (PlaceholderKind::Text, Value::String(text)) if !text.trim().is_empty() => Ok(text.clone()),
+        (PlaceholderKind::Text, _) => Err(format!("'{}' must be a non-empty string", name)),
+        (PlaceholderKind::Date, Value::String(date)) if NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok() => {
+            Ok(date.clone())
+        }
+        (PlaceholderKind::Date, _) => Err(format!("'{}' must be a date formatted as YYYY-MM-DD", name)),
+        (PlaceholderKind::Integer, Value::Number(number)) if number.is_i64() => Ok(number.to_string()),
+        (PlaceholderKind::Integer, _) => Err(format!("'{}' must be an integer", name)),
+        (PlaceholderKind::Choice, Value::String(choice)) if placeholder.options.contains(choice) => Ok(choice.clone()),
+        (PlaceholderKind::Choice, _) => Err(format!(
+            "'{}' must be one of: {}",
+            name,
+            placeholder.options.join(", ")
+        )),
+    }
+}
+
+/// Renders a template body with the given values.
+///
+/// # Parameters
+///
+/// * `body` - Template body, already checked by [`validate_template`]
+/// * `placeholders` - Placeholders of the template
+/// * `values` - Placeholder values by name; `null` counts as omitted
+///
+/// # Returns
+///
+/// Returns a `Result` containing:
+/// - `Ok(String)` - Rendered content
+/// - `Err(TemplateError)` - Missing required values, values of the wrong
+///   type and values for unknown placeholders
+pub fn render(
+    body: &str,
+    placeholders: &[TemplatePlaceholder],
+    values: &HashMap<String, Value>,
+) -> Result<String, TemplateError> {
+    let mut problems = Vec::new();
+    let mut rendered = HashMap::new();
+
+    for placeholder in placeholders {
+        match values.get(&placeholder.name).filter(|value| !value.is_null()) {
+            Some(value) => match render_value(placeholder, value) {
+                Ok(text) => {
+                    rendered.insert(placeholder.name.as_str(), text);
+                }
+                Err(problem) => problems.push(problem),
+            },
+            None if placeholder.optional => {
+                rendered.insert(placeholder.name.as_str(), String::new());
+            }
+            None => problems.push(format!("Missing value for '{}'", placeholder.name)),
+        }
+    }
+
+    let mut unknown: Vec<&String> = values
+        .keys()
+        .filter(|name| !placeholders.iter().any(|placeholder| &placeholder.name == *name))
+        .collect();
+    unknown.sort();
+    problems.extend(unknown.into_iter().map(|name| format!("Unknown placeholder '{}'", name)));
+
+    if !problems.is_empty() {
+        return Err(TemplateError(problems));
+    }
+
+    let mut content = String::with_capacity(body.len());
+    for segment in parse(body)? {
+        match segment {
+            Segment::Text(text) => content.push_str(text),
+            Segment::Placeholder(name) => {
+                let value = rendered
+                    .get(name)
+                    .ok_or_else(|| TemplateError(vec![format!("Placeholder '{}' is used but not declared", name)]))?;
+                content.push_str(value);
+            }
+        }
+    }
+
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn placeholder(name: &str, kind: PlaceholderKind) -> TemplatePlaceholder {
+        TemplatePlaceholder {
+            name: name.to_string(),
+            kind,
+            label: None,
+            optional: false,
+            options: vec![],
+        }
+    }
+
+    fn nda_placeholders() -> Vec<TemplatePlaceholder> {
+        vec![
+            placeholder("party", PlaceholderKind::Text),
+            placeholder("effective_date", PlaceholderKind::Date),
+            placeholder("term_months", PlaceholderKind::Integer),
+            TemplatePlaceholder {
+                options: vec!["Brazil".to_string(), "Delaware".to_string()],
+                ..placeholder("jurisdiction", PlaceholderKind::Choice)
+            },
+            TemplatePlaceholder {
+                optional: true,
+                ..placeholder("notes", PlaceholderKind::Text)
+            },
+        ]
+    }
+
+    const BODY: &str = "NDA with {{party}} from {{ effective_date }} for {{term_months}} months \
+                        under {{jurisdiction}} law.{{notes}} Signed by {{party}}.";
+
+    #[test]
+    fn test_render_validates_and_substitutes_values() {
+        let placeholders = nda_placeholders();
+        validate_template(BODY, &placeholders).unwrap();
+
+        let values: HashMap<String, Value> = serde_json::from_value(json!({
+            "party": "Acme Corp",
+            "effective_date": "2026-11-01",
+            "term_months": 24,
+            "jurisdiction": "Delaware"
+        }))
+        .unwrap();
+        assert_eq!(
+            render(BODY, &placeholders, &values).unwrap(),
+            "NDA with Acme Corp from 2026-11-01 for 24 months under Delaware law. Signed by Acme Corp."
+        );
+
+        let invalid: HashMap<String, Value> = serde_json::from_value(json!({
+            "party": "",
+            "effective_date": "01/11/2026",
+            "term_months": "24",
+            "jurisdiction": "Texas",
+            "court": "Supreme"
+        }))
+        .unwrap();
+        let error = render(BODY, &placeholders, &invalid).unwrap_err();
+        assert_eq!(error.problems().len(), 5);
+        assert!(error.to_string().contains("Unknown placeholder 'court'"));
+
+        let error = render(BODY, &placeholders, &HashMap::new()).unwrap_err();
+        assert_eq!(error.problems().len(), 4);
+    }
+
+    #[test]
+    fn test_validate_template_rejects_mismatched_declarations() {
+        let placeholders = nda_placeholders();
+
+        assert!(validate_template("Unclosed {{party", &placeholders).is_err());
+        assert!(validate_template("{{not a name}}", &placeholders).is_err());
+
+        let error = validate_template("{{party}} and {{witness}}", &placeholders[..1]).unwrap_err();
+        assert_eq!(error.problems(), ["Placeholder 'witness' is used but not declared"]);
+
+        let error = validate_template("{{party}}", &placeholders[..2]).unwrap_err();
+        assert_eq!(error.problems(), ["Placeholder 'effective_date' is not used in the body"]);
+
+        let choice = placeholder("jurisdiction", PlaceholderKind::Choice);
+        assert!(validate_template("{{jurisdiction}}", &[choice]).is_err());
+        let duplicated = vec![placeholders[0].clone(), placeholders[0].clone()];
+        assert!(validate_template("{{party}}", &duplicated).is_err());
+    }
+}
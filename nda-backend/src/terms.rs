@@ -0,0 +1,210 @@
+//! # NDA Terms Module
+//!
+//! Partners accept the terms of an NDA by signing a canonical digest of them
+//! with the ed25519 key of their Stellar account. `access_process` refuses
+//! to hand out content until a valid signature for the current terms exists.
+//!
+//! ## Terms Digest
+//!
+//! ```text
+//! terms_digest = SHA-256(
+//!     "NDA_TERMS_V1"
+//!     || len(process_id) || process_id
+//!     || len(owner_public_key) || owner_public_key
+//!     || len(partner_public_key) || partner_public_key
+//!     || len(title) || title
+//!     || len(description) || description
+//!     || content_version           (big-endian i64)
+//!     || content_sha256            (32 bytes, SHA-256 of the plaintext content)
+//! )
+//! ```
+//!
+//! Variable-length fields are prefixed with their length as a big-endian
+//! `u32`, like share commitments (see [`crate::commitment`]). The digest
+//! covers the plaintext rather than the ciphertext, so re-encryption and
+//! data key rotation do not invalidate signatures and the partner can check
+//! after decryption that the content is what they signed. Amending the
+//! content creates a new content version, which has to be signed again.
+//!
+//! `content_sha256` is computed from the plaintext once, when a revision is
+//! written, and stored in `processes.content_sha256` (rows from older
+//! versions get it at startup). Building terms never decrypts content, so
+//! the server does not touch the plaintext of zero-knowledge shares to
+//! serve terms or grant access.
+//!
+//! ## Signing
+//!
+//! The partner signs the raw 32 digest bytes (not the hex string) with the
+//! ed25519 key behind their Stellar secret seed; the signature is sent
+//! Base64-encoded. [`sign`] implements this for Rust clients.
+
+use base64::{engine::general_purpose, Engine as _};
+use ed25519_dalek::{PublicKey, SecretKey, Signature, Signer};
+use sha2::{Digest, Sha256};
+
+use crate::crypto::CryptoError;
+use crate::models::{NdaTerms, Process};
+
+/// Domain separation tag of the current terms format.
+const TERMS_DOMAIN: &[u8] = b"NDA_TERMS_V1";
+
+/// Computes the SHA-256 digest of plaintext process content.
+pub fn content_sha256(content: &str) -> [u8; 32] {
+    Sha256::digest(content.as_bytes()).into()
+}
+
+fn terms_digest(
+    process_id: &str,
+    owner_public_key: &str,
+    partner_public_key: &str,
+    title: &str,
+    description: &str,
+    content_version: i64,
+    content_sha256: &[u8; 32],
+) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(TERMS_DOMAIN);
+    for field in [process_id, owner_public_key, partner_public_key, title, description] {
+        hasher.update((field.len() as u32).to_be_bytes());
+        hasher.update(field.as_bytes());
+    }
+    hasher.update(content_version.to_be_bytes());
+    hasher.update(content_sha256);
+    hasher.finalize().into()
+}
+
+/// Builds the terms of the current revision of a process for a partner.
+///
+/// # Parameters
+///
+/// * `process` - Process whose terms to build
+/// * `owner_public_key` - Stellar public key of the process owner
+/// * `partner_public_key` - Stellar public key of the partner who will sign
+/// * `content_hash` - [`content_sha256`] of the process's current revision
+pub fn nda_terms(process: &Process, owner_public_key: &str, partner_public_key: &str, content_hash: &[u8; 32]) -> NdaTerms {
+    let digest = terms_digest(
+        &process.id,
+        owner_public_key,
+        partner_public_key,
+        &process.title,
+        &process.description,
+        process.content_version,
+        content_hash,
+    );
+
+    NdaTerms {
+        process_id: process.id.clone(),
+        owner_public_key: owner_public_key.to_string(),
+        partner_public_key: partner_public_key.to_string(),
+        title: process.title.clone(),
+        description: process.description.clone(),
+        content_version: process.content_version,
+        content_sha256: hex::encode(content_hash),
+        terms_digest: hex::encode(digest),
+    }
+}
+
+/// Verifies an ed25519 signature of a terms digest.
+///
+/// # Parameters
+///
+/// * `public_key` - Stellar public key (`G...`) of the signer
+/// * `terms_digest` - The 32 digest bytes that were signed
+/// * `signature` - Raw 64-byte signature
+///
+/// # Returns
+///
+/// Returns `Ok(())` for a valid signature, or a `CryptoError` for a malformed
+/// key or signature and for signatures that do not verify.
+pub fn verify_signature(public_key: &str, terms_digest: &[u8; 32], signature: &[u8]) -> Result<(), CryptoError> {
+    let key = stellar_strkey::ed25519::PublicKey::from_string(public_key)
+        .map_err(|e| CryptoError::new(format!("Invalid Stellar public key: {:?}", e)))?;
+    let key = PublicKey::from_bytes(&key.0)
+        .map_err(|e| CryptoError::new(format!("Invalid ed25519 public key: {}", e)))?;
+    let signature = Signature::from_bytes(signature)
+        .map_err(|e| CryptoError::new(format!("Invalid signature: {}", e)))?;
+
+    key.verify_strict(terms_digest, &signature)
+        .map_err(|_| CryptoError::new("Signature does not match the terms digest"))
+}
+
+/// Signs a terms digest with a Stellar secret key.
+///
+/// This is the partner-side counterpart of [`verify_signature`]; the server
+/// never signs terms on a partner's behalf.
+///
+/// # Parameters
+///
+/// * `secret_key` - Stellar secret key (`S...`) of the partner
+/// * `terms_digest` - The 32 digest bytes to sign
+///
+/// # Returns
+///
+/// Returns a `Result` containing:
+/// - `Ok(String)` - Base64-encoded signature
+/// - `Err(CryptoError)` - Invalid secret key
+#[allow(dead_code)]
+pub fn sign(secret_key: &str, terms_digest: &[u8; 32]) -> Result<String, CryptoError> {
+    let seed = stellar_strkey::ed25519::PrivateKey::from_string(secret_key)
+        .map_err(|e| CryptoError::new(format!("Invalid Stellar secret key: {:?}", e)))?;
+    let secret = SecretKey::from_bytes(&seed.0)
+        .map_err(|e| CryptoError::new(format!("Invalid ed25519 secret key: {}", e)))?;
+    let public = PublicKey::from(&secret);
+    let keypair = ed25519_dalek::Keypair { secret, public };
+
+    Ok(general_purpose::STANDARD.encode(keypair.sign(terms_digest).to_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ProcessStatus;
+    use crate::stellar_real::StellarClient;
+
+    fn process(title: &str, content_version: i64) -> Process {
+        Process {
+            id: "process-1".to_string(),
+            client_id: "client".to_string(),
+            title: title.to_string(),
+            description: "Terms".to_string(),
+            encrypted_content: String::new(),
+            encryption_key: String::new(),
+            key_id: None,
+            content_version,
+            content_sha256: None,
+            status: ProcessStatus::Active,
+            template_id: None,
+            template_version: None,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_signature_accepts_only_the_signed_terms() {
+        let owner = StellarClient::generate_keypair().unwrap();
+        let partner = StellarClient::generate_keypair().unwrap();
+        let other = StellarClient::generate_keypair().unwrap();
+
+        let terms = nda_terms(&process("NDA", 1), &owner.public_key, &partner.public_key, &content_sha256("Secret"));
+        let digest: [u8; 32] = hex::decode(&terms.terms_digest).unwrap().try_into().unwrap();
+        assert_eq!(terms.content_sha256, hex::encode(content_sha256("Secret")));
+
+        let signature = general_purpose::STANDARD.decode(sign(&partner.secret_key, &digest).unwrap()).unwrap();
+        assert!(verify_signature(&partner.public_key, &digest, &signature).is_ok());
+        assert!(verify_signature(&other.public_key, &digest, &signature).is_err());
+        assert!(verify_signature(&partner.public_key, &digest, &signature[..63]).is_err());
+
+        // Every field of the terms is covered by the digest
+        for changed in [
+            nda_terms(&process("NDA", 2), &owner.public_key, &partner.public_key, &content_sha256("Secret")),
+            nda_terms(&process("Other NDA", 1), &owner.public_key, &partner.public_key, &content_sha256("Secret")),
+            nda_terms(&process("NDA", 1), &other.public_key, &partner.public_key, &content_sha256("Secret")),
+            nda_terms(&process("NDA", 1), &owner.public_key, &other.public_key, &content_sha256("Secret")),
+            nda_terms(&process("NDA", 1), &owner.public_key, &partner.public_key, &content_sha256("Amended")),
+        ] {
+            assert_ne!(changed.terms_digest, terms.terms_digest);
+            let changed: [u8; 32] = hex::decode(&changed.terms_digest).unwrap().try_into().unwrap();
+            assert!(verify_signature(&partner.public_key, &changed, &signature).is_err());
+        }
+    }
+}